@@ -30,6 +30,10 @@ pub(crate) fn get_cache_path() -> PathBuf {
 }
 
 /// Open the cache, creating the directory if needed.
+///
+/// Known ticker changes and delistings are seeded the first time a cache is
+/// opened so cached quotes stitch across renames and delisted names keep
+/// their final return.
 pub(crate) fn open_cache() -> Result<SqliteCache, DataError> {
     let cache_path = get_cache_path();
 
@@ -38,5 +42,7 @@ pub(crate) fn open_cache() -> Result<SqliteCache, DataError> {
         std::fs::create_dir_all(parent)?;
     }
 
-    SqliteCache::new(&cache_path)
+    let cache = SqliteCache::new(&cache_path)?;
+    cache.seed_reference_data()?;
+    Ok(cache)
 }
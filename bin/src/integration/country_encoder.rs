@@ -0,0 +1,58 @@
+//! Country one-hot encoding.
+//!
+//! Converts each symbol's country of listing into dummy columns that sit
+//! alongside the style scores in the cross-sectional regression.
+
+use super::data_pipeline::{DataPipelineError, universe_countries};
+use perth::universe::{Country, Universe};
+use polars::prelude::*;
+
+/// Generate the dummy column name for a country.
+///
+/// The estimator treats every `_score` column as an unconstrained exposure,
+/// which is what a country dummy is next to the constrained industries.
+fn country_column_name(country: Country) -> String {
+    format!("country_{}_score", country.code())
+}
+
+/// The country that anchors the country factors: the one with most symbols.
+///
+/// Its dummy is left out, since the dummies of all countries would sum to
+/// the market factor. The other countries' factor returns are relative to it.
+pub(crate) fn base_country(universe: &dyn Universe) -> Option<Country> {
+    universe_countries(universe)
+        .into_iter()
+        .max_by_key(|country| {
+            let count = universe
+                .symbols()
+                .iter()
+                .filter(|s| universe.country(s) == *country)
+                .count();
+            // Ties go to the earlier country in enumeration order
+            (count, std::cmp::Reverse(*country))
+        })
+}
+
+/// Encode countries as one-hot columns, one per country except the base.
+///
+/// A single-country universe yields no dummy columns.
+///
+/// Returns DataFrame with columns: [symbol, country_JP_score, country_DE_score, ...]
+pub(crate) fn encode_countries(universe: &dyn Universe) -> Result<DataFrame, DataPipelineError> {
+    let base = base_country(universe);
+    let symbols = universe.symbols();
+
+    let mut columns = vec![Column::new("symbol".into(), &symbols)];
+    for country in universe_countries(universe) {
+        if Some(country) == base {
+            continue;
+        }
+        let dummy: Vec<f64> = symbols
+            .iter()
+            .map(|s| f64::from(u8::from(universe.country(s) == country)))
+            .collect();
+        columns.push(Column::new(country_column_name(country).into(), dummy));
+    }
+
+    Ok(DataFrame::new(columns)?)
+}
@@ -1,18 +1,28 @@
 //! Data pipeline for fetching and preparing universe data.
 //!
-//! Provides functions to fetch OHLCV data for the S&P 500 universe,
+//! Provides functions to fetch OHLCV data for a universe,
 //! compute returns, and prepare market cap data for factor model estimation.
+//! Listings outside the US are converted to US dollars with FX rates from the
+//! quote provider and benchmarked against their own country's index.
 //! Supports caching via SQLite to avoid repeated Yahoo Finance API calls.
 
 use super::cache_manager;
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use futures::stream::{self, StreamExt};
 use indicatif::ProgressBar;
-use perth::universe::SP500Universe;
-use perth_data::yahoo::quotes::YahooQuoteProvider;
+use perth::universe::{Country, Universe};
+use perth_data::cache::{FinancialStatement, SqliteCache, trailing_eps_diluted};
+use perth_data::calendar::{ExchangeCalendar, NyseCalendar};
+use perth_data::edgar::EdgarFundamentalsProvider;
+use perth_data::fx::{Currency, usd_rates};
+use perth_data::quality::QualityReport;
+use perth_data::yahoo::quotes::{DividendHistory, YahooQuoteProvider};
 use polars::prelude::*;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
+use tokio::time::sleep;
 
 /// Error type for data pipeline operations.
 #[derive(Debug, thiserror::Error)]
@@ -23,6 +33,17 @@ pub(crate) enum DataPipelineError {
     /// Polars DataFrame error.
     #[error("Polars error: {0}")]
     Polars(#[from] PolarsError),
+    /// Invalid attribution weights.
+    #[error("Attribution error: {0}")]
+    Attribution(#[from] perth::output::AttributionError),
+    /// A day a portfolio was held on without any attributed holding.
+    #[error("No holding of {portfolio} has an attribution on {date}")]
+    UnattributedDay {
+        /// Portfolio name.
+        portfolio: String,
+        /// Day held.
+        date: NaiveDate,
+    },
 }
 
 /// Configuration for data fetching.
@@ -32,6 +53,8 @@ pub(crate) struct FetchConfig {
     pub use_cache: bool,
     /// Whether to force refresh (ignore cache).
     pub force_refresh: bool,
+    /// Whether to refetch cached quotes whose dividends were never fetched.
+    pub dividends: bool,
 }
 
 impl Default for FetchConfig {
@@ -39,6 +62,7 @@ impl Default for FetchConfig {
         Self {
             use_cache: true,
             force_refresh: false,
+            dividends: false,
         }
     }
 }
@@ -51,25 +75,61 @@ fn to_naive_date(dt: DateTime<Utc>) -> NaiveDate {
 /// Default number of concurrent fetches.
 const DEFAULT_CONCURRENCY: usize = 10;
 
+/// Days from 0001-01-01 to the Unix epoch.
+const EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// Days cached EDGAR financial statements are used before being refetched.
+const FINANCIALS_MAX_AGE_DAYS: i64 = 7;
+
+/// Pause between EDGAR requests, inside the SEC's 10 requests per second.
+const EDGAR_REQUEST_DELAY: Duration = Duration::from_millis(250);
+
 /// Fetch OHLCV data for all symbols with custom configuration and optional progress bar.
+///
+/// Each symbol's quotes carry a `dividend_ttm` column: dividends per share
+/// that went ex in the trailing twelve months, zero for a symbol known to
+/// have paid none and null where its dividends are unknown.
 pub(crate) async fn fetch_universe_data_with_progress(
     provider: &YahooQuoteProvider,
-    universe: &SP500Universe,
+    universe: &dyn Universe,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
     config: FetchConfig,
     progress: Option<&ProgressBar>,
 ) -> Result<DataFrame, DataPipelineError> {
-    let symbols = universe.symbols();
-    let start_date = to_naive_date(start);
-    let end_date = to_naive_date(end);
-
     // Try to open cache if enabled
     let cache = if config.use_cache {
         cache_manager::open_cache().ok()
     } else {
         None
     };
+    fetch_symbols_with_cache(
+        provider,
+        universe.symbols(),
+        start,
+        end,
+        &config,
+        cache,
+        progress,
+    )
+    .await
+}
+
+/// Load `symbols` from `cache` where it covers the range and fetch the rest.
+///
+/// The cached range of a delisted symbol ends at its delisting, so symbols
+/// Yahoo no longer serves are read from the cache with their final quote.
+async fn fetch_symbols_with_cache(
+    provider: &YahooQuoteProvider,
+    symbols: Vec<String>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    config: &FetchConfig,
+    cache: Option<SqliteCache>,
+    progress: Option<&ProgressBar>,
+) -> Result<DataFrame, DataPipelineError> {
+    let start_date = to_naive_date(start);
+    let end_date = to_naive_date(end);
 
     let mut cached_dfs = Vec::new();
     let mut symbols_to_fetch = Vec::new();
@@ -78,13 +138,15 @@ pub(crate) async fn fetch_universe_data_with_progress(
     if let Some(ref cache) = cache {
         if !config.force_refresh {
             for symbol in &symbols {
+                let dividends = cache.get_dividend_history(symbol).ok().flatten();
                 if cache
-                    .has_quotes(symbol, start_date, end_date)
+                    .has_stitched_quotes(symbol, start_date, end_date)
                     .unwrap_or(false)
+                    && (dividends.is_some() || !config.dividends)
                 {
-                    // Try to get cached data
-                    if let Ok(df) = cache.get_quotes(symbol, start_date, end_date) {
-                        cached_dfs.push(df.lazy());
+                    // Try to get cached data, following earlier tickers
+                    if let Ok(df) = cache.get_stitched_quotes(symbol, start_date, end_date) {
+                        cached_dfs.push(with_trailing_dividends(&df, dividends.as_ref())?.lazy());
                         continue;
                     }
                 }
@@ -125,16 +187,32 @@ pub(crate) async fn fetch_universe_data_with_progress(
                 let results = Arc::clone(&results);
                 let cache = Arc::clone(&cache_arc);
                 async move {
-                    match provider.fetch_quotes(&symbol, start, end).await {
-                        Ok(df) => {
+                    match provider
+                        .fetch_quotes_with_dividends(&symbol, start, end)
+                        .await
+                    {
+                        Ok((df, dividends)) => {
                             // Store in cache if available
                             let cache_guard = cache.lock().await;
-                            if let Some(ref cache) = *cache_guard
-                                && let Err(e) = cache.put_quotes(&df)
-                            {
-                                eprintln!("Warning: Failed to cache quotes for {}: {}", symbol, e);
+                            if let Some(ref cache) = *cache_guard {
+                                if let Err(e) =
+                                    cache.put_fetched_quotes(&symbol, to_naive_date(start), &df)
+                                {
+                                    eprintln!(
+                                        "Warning: Failed to cache quotes for {}: {}",
+                                        symbol, e
+                                    );
+                                }
+                                if let Err(e) = cache.put_dividend_history(&symbol, &dividends) {
+                                    eprintln!(
+                                        "Warning: Failed to cache dividends for {}: {}",
+                                        symbol, e
+                                    );
+                                }
                             }
                             drop(cache_guard);
+                            let df = with_trailing_dividends(&df, Some(&dividends))
+                                .map_err(|e| (symbol.clone(), e.into()))?;
                             results.lock().await.push(df.lazy());
                             Ok(symbol)
                         }
@@ -206,9 +284,9 @@ pub(crate) async fn fetch_symbol_data(
         && !config.force_refresh
         && let Ok(cache) = cache_manager::open_cache()
         && cache
-            .has_quotes(symbol, start_date, end_date)
+            .has_stitched_quotes(symbol, start_date, end_date)
             .unwrap_or(false)
-        && let Ok(df) = cache.get_quotes(symbol, start_date, end_date)
+        && let Ok(df) = cache.get_stitched_quotes(symbol, start_date, end_date)
     {
         return Ok(df);
     }
@@ -219,7 +297,7 @@ pub(crate) async fn fetch_symbol_data(
     // Cache the result
     if config.use_cache
         && let Ok(cache) = cache_manager::open_cache()
-        && let Err(e) = cache.put_quotes(&df)
+        && let Err(e) = cache.put_fetched_quotes(symbol, start_date, &df)
     {
         eprintln!("Warning: Failed to cache quotes for {}: {}", symbol, e);
     }
@@ -227,23 +305,370 @@ pub(crate) async fn fetch_symbol_data(
     Ok(df)
 }
 
-/// Compute daily returns from adjusted close prices.
+/// Add a `dividend_ttm` column from a symbol's dividend history.
 ///
-/// Returns a LazyFrame with columns: [date, symbol, asset_returns]
-pub(crate) fn compute_returns(quotes: &DataFrame) -> Result<LazyFrame, DataPipelineError> {
-    let returns = quotes
+/// Dates the history does not cover, or every date without a history, get
+/// null rather than zero.
+fn with_trailing_dividends(
+    quotes: &DataFrame,
+    dividends: Option<&DividendHistory>,
+) -> Result<DataFrame, PolarsError> {
+    with_dated_column(quotes, "dividend_ttm", |_, date| {
+        dividends.and_then(|history| history.trailing_12m(date))
+    })
+}
+
+/// Attach each row's point-in-time trailing diluted EPS as `eps_ttm`.
+///
+/// Statements come from the cache while fresh and from EDGAR otherwise.
+/// Rows of symbols without EDGAR statements, or before their first annual
+/// report was public, get null. Returns the frame and the symbols with no
+/// statements at all.
+pub(crate) async fn attach_trailing_eps(
+    quotes: &DataFrame,
+    report_lag_days: u64,
+    config: &FetchConfig,
+    progress: Option<&ProgressBar>,
+) -> Result<(DataFrame, Vec<String>), DataPipelineError> {
+    let cache = if config.use_cache {
+        cache_manager::open_cache().ok()
+    } else {
+        None
+    };
+    let provider = EdgarFundamentalsProvider::new();
+
+    let symbols: BTreeSet<String> = quotes
+        .column("symbol")?
+        .str()?
+        .into_iter()
+        .flatten()
+        .map(str::to_string)
+        .collect();
+    if let Some(pb) = progress {
+        pb.set_length(symbols.len() as u64);
+    }
+
+    let mut statements = HashMap::new();
+    let mut missing = Vec::new();
+    for symbol in symbols {
+        let cached = cache.as_ref().filter(|cache| {
+            !config.force_refresh
+                && cache
+                    .has_recent_financials(&symbol, FINANCIALS_MAX_AGE_DAYS)
+                    .unwrap_or(false)
+        });
+        let fetched = match cached {
+            Some(cache) => cache.get_stitched_financial_statements(&symbol).ok(),
+            None => {
+                let fetched = provider.fetch_financials(&symbol).await.ok().map(|stmts| {
+                    stmts
+                        .into_iter()
+                        .map(FinancialStatement::from)
+                        .collect::<Vec<_>>()
+                });
+                if let (Some(cache), Some(stmts)) = (&cache, &fetched)
+                    && let Err(e) = cache.put_financial_statements_batch(stmts)
+                {
+                    eprintln!("Warning: Failed to cache financials for {}: {}", symbol, e);
+                }
+                sleep(EDGAR_REQUEST_DELAY).await;
+                fetched
+            }
+        };
+        match fetched {
+            Some(stmts) if !stmts.is_empty() => {
+                statements.insert(symbol, stmts);
+            }
+            _ => missing.push(symbol),
+        }
+        if let Some(pb) = progress {
+            pb.inc(1);
+        }
+    }
+
+    let df = with_dated_column(quotes, "eps_ttm", |symbol, date| {
+        statements
+            .get(symbol)
+            .and_then(|stmts| trailing_eps_diluted(stmts, date, report_lag_days))
+    })?;
+    Ok((df, missing))
+}
+
+/// Number of symbols whose most recent row has a null `column`.
+pub(crate) fn symbols_without_latest(
+    quotes: &DataFrame,
+    column: &str,
+) -> Result<usize, DataPipelineError> {
+    let latest = quotes
         .clone()
         .lazy()
         .sort(["symbol", "date"], SortMultipleOptions::default())
-        .with_column(
+        .group_by([col("symbol")])
+        .agg([col(column).last().is_null().alias("unknown")])
+        .filter(col("unknown"))
+        .collect()?;
+    Ok(latest.height())
+}
+
+/// Add a float column computed from each row's symbol and date.
+fn with_dated_column(
+    df: &DataFrame,
+    name: &str,
+    value: impl Fn(&str, NaiveDate) -> Option<f64>,
+) -> Result<DataFrame, PolarsError> {
+    let symbols = df.column("symbol")?.str()?.clone();
+    let days = df.column("date")?.cast(&DataType::Int32)?;
+    let values: Float64Chunked = symbols
+        .into_iter()
+        .zip(days.i32()?)
+        .map(|(symbol, day)| {
+            let date = NaiveDate::from_num_days_from_ce_opt(day? + EPOCH_DAYS_FROM_CE)?;
+            value(symbol?, date)
+        })
+        .collect();
+
+    let mut df = df.clone();
+    df.with_column(values.with_name(name.into()).into_column())?;
+    Ok(df)
+}
+
+/// Restrict quotes to NYSE trading days and attach a session index.
+///
+/// Rows dated on weekends or holidays are dropped. The added `trading_day`
+/// column counts sessions from the earliest quote, so two rows of a symbol
+/// are consecutive sessions exactly when their indices differ by one.
+pub(crate) fn resample_to_trading_days(quotes: &DataFrame) -> Result<LazyFrame, DataPipelineError> {
+    let days = quotes.column("date")?.cast(&DataType::Int32)?;
+    let days = days.i32()?;
+    let (Some(first), Some(last)) = (days.min(), days.max()) else {
+        return Ok(quotes
+            .clone()
+            .lazy()
+            .with_column(lit(NULL).cast(DataType::Int64).alias("trading_day")));
+    };
+
+    let to_date = |days: i32| {
+        NaiveDate::from_num_days_from_ce_opt(days + EPOCH_DAYS_FROM_CE).expect("valid date")
+    };
+    let calendar = NyseCalendar.trading_day_frame(to_date(first), to_date(last))?;
+
+    Ok(quotes.clone().lazy().join(
+        calendar.lazy(),
+        [col("date")],
+        [col("date")],
+        JoinArgs::new(JoinType::Inner),
+    ))
+}
+
+/// Compute daily returns from adjusted close prices.
+///
+/// Quotes are first aligned to the NYSE calendar. A return is only kept when
+/// the previous observation is the previous trading day, so returns never
+/// span a gap in the data.
+///
+/// `delistings` is a `symbol`, `date`, `delisting_return` frame as returned
+/// by [`cached_delisting_returns`]. A delisted symbol's final return is
+/// appended on its delisting date when its last quote is the session before.
+///
+/// Returns a LazyFrame with columns: [date, symbol, asset_returns]
+pub(crate) fn compute_returns(
+    quotes: &DataFrame,
+    delistings: Option<&DataFrame>,
+) -> Result<LazyFrame, DataPipelineError> {
+    let returns = resample_to_trading_days(quotes)?
+        .sort(["symbol", "date"], SortMultipleOptions::default())
+        .with_columns([
             (col("adjusted_close") / col("adjusted_close").shift(lit(1)).over([col("symbol")])
                 - lit(1.0))
             .alias("asset_returns"),
+            (col("trading_day") - col("trading_day").shift(lit(1)).over([col("symbol")]))
+                .alias("session_gap"),
+        ])
+        .filter(
+            col("asset_returns")
+                .is_not_null()
+                .and(col("session_gap").eq(lit(1))),
         )
-        .filter(col("asset_returns").is_not_null())
         .select([col("date"), col("symbol"), col("asset_returns")]);
 
-    Ok(returns)
+    let Some(delistings) = delistings else {
+        return Ok(returns);
+    };
+    Ok(concat(
+        [returns, final_delisting_returns(quotes, delistings)?.lazy()],
+        UnionArgs::default(),
+    )?
+    .sort(["symbol", "date"], SortMultipleOptions::default()))
+}
+
+/// Delisting returns of symbols last quoted the session before their
+/// delisting, with columns: [date, symbol, asset_returns]
+fn final_delisting_returns(
+    quotes: &DataFrame,
+    delistings: &DataFrame,
+) -> Result<DataFrame, DataPipelineError> {
+    let last = quotes
+        .clone()
+        .lazy()
+        .group_by([col("symbol")])
+        .agg([col("date").max().cast(DataType::Int32)])
+        .collect()?;
+    let last_quote: HashMap<&str, i32> = last
+        .column("symbol")?
+        .str()?
+        .iter()
+        .zip(last.column("date")?.i32()?.iter())
+        .filter_map(|(symbol, day)| Some((symbol?, day?)))
+        .collect();
+
+    let days = delistings.column("date")?.cast(&DataType::Int32)?;
+    let (mut dates, mut symbols, mut returns) = (Vec::new(), Vec::new(), Vec::new());
+    for ((symbol, day), delisting_return) in delistings
+        .column("symbol")?
+        .str()?
+        .iter()
+        .zip(days.i32()?.iter())
+        .zip(delistings.column("delisting_return")?.f64()?.iter())
+    {
+        let (Some(symbol), Some(day), Some(delisting_return)) = (symbol, day, delisting_return)
+        else {
+            continue;
+        };
+        let Some(date) = NaiveDate::from_num_days_from_ce_opt(day + EPOCH_DAYS_FROM_CE) else {
+            continue;
+        };
+        let last_session =
+            NyseCalendar.previous_trading_day(date).num_days_from_ce() - EPOCH_DAYS_FROM_CE;
+        if last_quote.get(symbol) == Some(&last_session) {
+            dates.push(day);
+            symbols.push(symbol);
+            returns.push(delisting_return);
+        }
+    }
+
+    Ok(df! {
+        "date" => dates,
+        "symbol" => symbols,
+        "asset_returns" => returns,
+    }?
+    .lazy()
+    .with_column(col("date").cast(DataType::Date))
+    .collect()?)
+}
+
+/// Cached delisting returns dated within a range, as a `symbol`, `date`,
+/// `delisting_return` frame.
+///
+/// Returns `None` when the cache is disabled or cannot be read.
+pub(crate) fn cached_delisting_returns(
+    start: NaiveDate,
+    end: NaiveDate,
+    config: &FetchConfig,
+) -> Option<DataFrame> {
+    if !config.use_cache {
+        return None;
+    }
+    match cache_manager::open_cache().and_then(|cache| cache.delisting_returns(start, end)) {
+        Ok(returns) => Some(returns),
+        Err(e) => {
+            eprintln!("Warning: Failed to read delistings from cache: {}", e);
+            None
+        }
+    }
+}
+
+/// Compound a symbol's daily returns over the whole sample.
+///
+/// Returns `None` when the symbol has no returns.
+pub(crate) fn cumulative_return(
+    quotes: &DataFrame,
+    symbol: &str,
+) -> Result<Option<f64>, DataPipelineError> {
+    let returns = compute_returns(quotes, None)?
+        .filter(col("symbol").eq(lit(symbol)))
+        .select([col("asset_returns")])
+        .collect()?;
+    let returns = returns.column("asset_returns")?.f64()?;
+    if returns.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(
+        returns
+            .into_iter()
+            .flatten()
+            .map(|r| 1.0 + r)
+            .product::<f64>()
+            - 1.0,
+    ))
+}
+
+/// Keep only rows whose symbol was a universe member on the row's date.
+///
+/// Universes without dated membership keep every current constituent on
+/// every date, so this only drops symbols outside the universe.
+pub(crate) fn filter_to_members(
+    universe: &dyn Universe,
+    frame: LazyFrame,
+) -> Result<LazyFrame, DataPipelineError> {
+    let df = frame.collect()?;
+    let days = df.column("date")?.cast(&DataType::Int32)?;
+    let symbols = df.column("symbol")?.str()?.clone();
+
+    let mut members: HashMap<i32, HashSet<String>> = HashMap::new();
+    let mask: BooleanChunked = days
+        .i32()?
+        .iter()
+        .zip(symbols.iter())
+        .map(|(day, symbol)| {
+            let (Some(day), Some(symbol)) = (day, symbol) else {
+                return false;
+            };
+            members
+                .entry(day)
+                .or_insert_with(|| {
+                    NaiveDate::from_num_days_from_ce_opt(day + EPOCH_DAYS_FROM_CE)
+                        .map(|date| universe.symbols_on(date).into_iter().collect())
+                        .unwrap_or_default()
+                })
+                .contains(symbol)
+        })
+        .collect();
+
+    Ok(df.filter(&mask)?.lazy())
+}
+
+/// Add each symbol's GICS sector name as a `sector` column, null for
+/// symbols the universe does not classify.
+///
+/// The factor engine imputes missing exposures from sector medians.
+pub(crate) fn with_sectors(
+    universe: &dyn Universe,
+    factor_data: DataFrame,
+) -> Result<DataFrame, DataPipelineError> {
+    let symbols: BTreeSet<&str> = factor_data
+        .column("symbol")?
+        .str()?
+        .iter()
+        .flatten()
+        .collect();
+    let sectors: Vec<Option<&str>> = symbols
+        .iter()
+        .map(|symbol| universe.sector(symbol).map(|sector| sector.name()))
+        .collect();
+    let lookup = df! {
+        "symbol" => symbols.into_iter().collect::<Vec<_>>(),
+        "sector" => sectors,
+    }?;
+
+    Ok(factor_data
+        .lazy()
+        .join(
+            lookup.lazy(),
+            [col("symbol")],
+            [col("symbol")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .collect()?)
 }
 
 /// Compute market cap proxy using volume * close.
@@ -263,26 +688,174 @@ pub(crate) fn compute_market_cap_proxy(quotes: &DataFrame) -> Result<LazyFrame,
     Ok(mkt_cap)
 }
 
-/// Fetch market benchmark (SPY) returns with custom configuration.
+/// Fetch each country's market benchmark returns, keyed by symbol.
+///
+/// Every country in the universe is benchmarked against its own USD-priced
+/// index ETF (SPY for the US), and each symbol gets its country's return.
+///
+/// Returns a LazyFrame with columns: [date, symbol, market_return]
 pub(crate) async fn fetch_market_benchmark_with_config(
     provider: &YahooQuoteProvider,
+    universe: &dyn Universe,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
     config: FetchConfig,
 ) -> Result<LazyFrame, DataPipelineError> {
-    let spy_quotes = fetch_symbol_data(provider, "SPY", start, end, &config).await?;
+    let mut benchmarks = Vec::new();
+    for country in universe_countries(universe) {
+        let quotes = fetch_symbol_data(provider, country.benchmark(), start, end, &config).await?;
+        benchmarks.push(compute_returns(&quotes, None)?.select([
+            col("date"),
+            lit(country.code()).alias("country"),
+            col("asset_returns").alias("market_return"),
+        ]));
+    }
+
+    let returns = concat(benchmarks, UnionArgs::default())?.join(
+        symbol_countries(universe)?.lazy(),
+        [col("country")],
+        [col("country")],
+        JoinArgs::new(JoinType::Inner),
+    );
+
+    Ok(returns.select([col("date"), col("symbol"), col("market_return")]))
+}
+
+/// Fetch USD rates for every non-USD currency in the universe.
+///
+/// Returns a DataFrame with columns: [date, currency, usd_rate]
+pub(crate) async fn fetch_fx_rates_with_config(
+    provider: &YahooQuoteProvider,
+    universe: &dyn Universe,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    config: FetchConfig,
+) -> Result<DataFrame, DataPipelineError> {
+    let mut rates = vec![
+        DataFrame::new(vec![
+            Column::new_empty("date".into(), &DataType::Date),
+            Column::new_empty("currency".into(), &DataType::String),
+            Column::new_empty("usd_rate".into(), &DataType::Float64),
+        ])?
+        .lazy(),
+    ];
+    for currency in universe_currencies(universe) {
+        let Some(fx_symbol) = currency.fx_symbol() else {
+            continue;
+        };
+        let quotes = fetch_symbol_data(provider, &fx_symbol, start, end, &config).await?;
+        rates.push(usd_rates(currency, &quotes)?.lazy());
+    }
+
+    Ok(concat(rates, UnionArgs::default())?.collect()?)
+}
+
+/// Countries of the universe's symbols, in order.
+pub(crate) fn universe_countries(universe: &dyn Universe) -> BTreeSet<Country> {
+    universe
+        .symbols()
+        .iter()
+        .map(|s| universe.country(s))
+        .collect()
+}
+
+/// Currencies of the universe's symbols, in order.
+pub(crate) fn universe_currencies(universe: &dyn Universe) -> BTreeSet<Currency> {
+    universe_countries(universe)
+        .into_iter()
+        .map(|c| c.currency())
+        .collect()
+}
+
+/// Country, currency and price scale of every symbol in the universe.
+///
+/// Returns a DataFrame with columns: [symbol, country, currency, price_scale]
+pub(crate) fn symbol_countries(universe: &dyn Universe) -> Result<DataFrame, DataPipelineError> {
+    let symbols = universe.symbols();
+    let countries: Vec<Country> = symbols.iter().map(|s| universe.country(s)).collect();
 
-    let spy_returns = spy_quotes
+    Ok(DataFrame::new(vec![
+        Column::new(
+            "country".into(),
+            countries.iter().map(|c| c.code()).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "currency".into(),
+            countries
+                .iter()
+                .map(|c| c.currency().code())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "price_scale".into(),
+            countries
+                .iter()
+                .map(|c| c.price_scale())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new("symbol".into(), symbols),
+    ])?)
+}
+
+/// Convert quoted prices to US dollars.
+///
+/// Prices are multiplied by the currency's USD rate on the same date and by
+/// the country's price scale (London quotes are in pence). Rows without an
+/// FX rate for their date are dropped. Trailing dividends are converted at
+/// the same rate as prices, so dividend yields are unchanged. Volumes are
+/// share counts and are left alone, so the market cap proxy comes out in
+/// dollars too.
+pub(crate) fn quotes_to_usd(
+    universe: &dyn Universe,
+    quotes: &DataFrame,
+    fx_rates: &DataFrame,
+) -> Result<DataFrame, DataPipelineError> {
+    let countries = symbol_countries(universe)?;
+    let all_usd = countries
+        .column("currency")?
+        .str()?
+        .iter()
+        .all(|c| c == Some(Currency::Usd.code()));
+    if all_usd {
+        return Ok(quotes.clone());
+    }
+
+    let columns: Vec<Expr> = quotes
+        .get_column_names()
+        .into_iter()
+        .map(|name| match name.as_str() {
+            "open" | "high" | "low" | "close" | "adjusted_close" | "dividend_ttm" => {
+                (col(name.as_str()) * col("usd_factor")).alias(name.as_str())
+            }
+            _ => col(name.as_str()),
+        })
+        .collect();
+
+    Ok(quotes
+        .clone()
         .lazy()
-        .sort(["date"], SortMultipleOptions::default())
+        .join(
+            countries.lazy(),
+            [col("symbol")],
+            [col("symbol")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .join(
+            fx_rates.clone().lazy(),
+            [col("date"), col("currency")],
+            [col("date"), col("currency")],
+            JoinArgs::new(JoinType::Left),
+        )
         .with_column(
-            (col("adjusted_close") / col("adjusted_close").shift(lit(1)) - lit(1.0))
-                .alias("market_return"),
+            (when(col("currency").eq(lit(Currency::Usd.code())))
+                .then(lit(1.0))
+                .otherwise(col("usd_rate"))
+                * col("price_scale"))
+            .alias("usd_factor"),
         )
-        .filter(col("market_return").is_not_null())
-        .select([col("date"), col("market_return")]);
-
-    Ok(spy_returns)
+        .filter(col("usd_factor").is_not_null())
+        .select(columns)
+        .collect()?)
 }
 
 /// Prepare combined data for factor computation.
@@ -291,13 +864,31 @@ pub(crate) async fn fetch_market_benchmark_with_config(
 /// for factor score computation.
 ///
 /// Returns DataFrame with columns:
-/// [date, symbol, adjusted_close, close, volume, asset_returns, market_return, market_cap]
+/// [date, symbol, adjusted_close, close, volume, asset_returns, market_return, market_cap],
+/// followed by the `dividend_ttm` and `eps_ttm` fundamentals the quotes carry.
 pub(crate) fn prepare_factor_data(
     quotes: &DataFrame,
     market_returns: &LazyFrame,
     market_cap: &LazyFrame,
 ) -> Result<DataFrame, DataPipelineError> {
-    let returns = compute_returns(quotes)?;
+    let returns = compute_returns(quotes, None)?;
+    let mut columns = vec![
+        col("date"),
+        col("symbol"),
+        col("adjusted_close"),
+        col("close"),
+        col("volume"),
+        col("asset_returns"),
+        col("market_return"),
+        col("market_cap"),
+    ];
+    let names = quotes.get_column_names();
+    columns.extend(
+        ["dividend_ttm", "eps_ttm"]
+            .into_iter()
+            .filter(|name| names.iter().any(|n| n.as_str() == *name))
+            .map(col),
+    );
 
     let combined = quotes
         .clone()
@@ -309,11 +900,11 @@ pub(crate) fn prepare_factor_data(
             [col("date"), col("symbol")],
             JoinArgs::new(JoinType::Inner),
         )
-        // Join with market returns
+        // Join with each symbol's country benchmark returns
         .join(
             market_returns.clone(),
-            [col("date")],
-            [col("date")],
+            [col("date"), col("symbol")],
+            [col("date"), col("symbol")],
             JoinArgs::new(JoinType::Inner),
         )
         // Join with market cap
@@ -323,16 +914,7 @@ pub(crate) fn prepare_factor_data(
             [col("date"), col("symbol")],
             JoinArgs::new(JoinType::Inner),
         )
-        .select([
-            col("date"),
-            col("symbol"),
-            col("adjusted_close"),
-            col("close"),
-            col("volume"),
-            col("asset_returns"),
-            col("market_return"),
-            col("market_cap"),
-        ])
+        .select(columns)
         .collect()?;
 
     Ok(combined)
@@ -354,3 +936,164 @@ pub(crate) fn print_cache_info() {
         println!("  Cached data: {} quotes for {} symbols", quotes, symbols);
     }
 }
+
+/// Maximum number of symbols listed in the printed quality report.
+const MAX_QUALITY_ROWS: usize = 20;
+
+/// Print the per-symbol data quality report.
+///
+/// Only symbols with at least one flagged row are listed.
+pub(crate) fn print_quality_report(report: &QualityReport) {
+    let with_issues = report.symbols_with_issues();
+    if with_issues.is_empty() {
+        println!("  Data quality: no issues found");
+        return;
+    }
+
+    println!(
+        "  Data quality: {} rows flagged across {} symbols",
+        report.total_flagged(),
+        with_issues.len()
+    );
+    println!(
+        "    {:<8} {:>6} {:>8} {:>7} {:>7} {:>6}  Status",
+        "Symbol", "Rows", "Invalid", "LowVol", "Spikes", "Stale"
+    );
+    for quality in with_issues.iter().take(MAX_QUALITY_ROWS) {
+        println!(
+            "    {:<8} {:>6} {:>8} {:>7} {:>7} {:>6}  {}",
+            quality.symbol,
+            quality.rows,
+            quality.invalid_prices,
+            quality.low_volume,
+            quality.return_spikes,
+            quality.stale_prices,
+            if quality.excluded { "excluded" } else { "kept" }
+        );
+    }
+    if with_issues.len() > MAX_QUALITY_ROWS {
+        println!("    ... and {} more", with_issues.len() - MAX_QUALITY_ROWS);
+    }
+
+    let excluded = report.excluded_symbols();
+    if !excluded.is_empty() {
+        println!("  Excluded from regression: {}", excluded.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dated(frame: DataFrame) -> DataFrame {
+        frame
+            .lazy()
+            .with_column(col("date").cast(DataType::Date))
+            .collect()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_delisted_symbol_is_read_from_cache() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2023, m, d).unwrap();
+        let at = |m, d| date(m, d).and_time(chrono::NaiveTime::MIN).and_utc();
+        let cache = SqliteCache::in_memory().unwrap();
+        cache.seed_reference_data().unwrap();
+        let close = [94.1, 94.2, 94.4];
+        let quotes = df! {
+            "symbol" => ["ATVI"; 3],
+            "date" => ["2023-10-10", "2023-10-11", "2023-10-12"],
+            "open" => close,
+            "high" => close,
+            "low" => close,
+            "close" => close,
+            "volume" => [10u64; 3],
+            "adjusted_close" => close,
+        }
+        .unwrap();
+        cache
+            .put_fetched_quotes("ATVI", date(10, 10), &quotes)
+            .unwrap();
+        let delistings = cache.delisting_returns(date(10, 10), date(12, 29)).unwrap();
+
+        // Yahoo no longer serves ATVI, and nothing listens on this port.
+        let provider = YahooQuoteProvider::new().with_base_url("http://127.0.0.1:9");
+        let fetched = fetch_symbols_with_cache(
+            &provider,
+            vec!["ATVI".to_string()],
+            at(10, 10),
+            at(12, 29),
+            &FetchConfig::default(),
+            Some(cache),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(fetched.height(), 3);
+
+        let returns = compute_returns(&fetched, Some(&delistings))
+            .unwrap()
+            .collect()
+            .unwrap();
+        let dates: Vec<_> = returns
+            .column("date")
+            .unwrap()
+            .date()
+            .unwrap()
+            .as_date_iter()
+            .flatten()
+            .collect();
+        assert_eq!(dates, [date(10, 11), date(10, 12), date(10, 13)]);
+        let last = returns.column("asset_returns").unwrap().f64().unwrap();
+        assert!((last.get(2).unwrap() - (95.0 / 94.4 - 1.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_delisting_return_is_appended() {
+        let quotes = dated(
+            df! {
+                "symbol" => ["TWTR", "TWTR", "TWTR", "STALE", "STALE"],
+                "date" => ["2022-10-25", "2022-10-26", "2022-10-27", "2022-10-20", "2022-10-21"],
+                "adjusted_close" => [50.0, 52.0, 53.7, 10.0, 11.0],
+            }
+            .unwrap(),
+        );
+        // STALE's last quote is a week before its delisting, so the return
+        // would span a gap.
+        let delistings = dated(
+            df! {
+                "symbol" => ["TWTR", "STALE", "GONE"],
+                "date" => ["2022-10-28", "2022-10-28", "2022-10-28"],
+                "delisting_return" => [54.2 / 53.7 - 1.0, -0.3, -0.3],
+            }
+            .unwrap(),
+        );
+
+        let returns = compute_returns(&quotes, Some(&delistings))
+            .unwrap()
+            .filter(col("symbol").eq(lit("TWTR")))
+            .collect()
+            .unwrap();
+        let dates: Vec<_> = returns
+            .column("date")
+            .unwrap()
+            .date()
+            .unwrap()
+            .as_date_iter()
+            .collect();
+        assert_eq!(
+            dates.last().copied().flatten(),
+            NaiveDate::from_ymd_opt(2022, 10, 28)
+        );
+        let values = returns.column("asset_returns").unwrap().f64().unwrap();
+        assert_eq!(values.len(), 3);
+        assert!((values.get(2).unwrap() - (54.2 / 53.7 - 1.0)).abs() < 1e-12);
+
+        let all = compute_returns(&quotes, Some(&delistings))
+            .unwrap()
+            .collect()
+            .unwrap();
+        let without = compute_returns(&quotes, None).unwrap().collect().unwrap();
+        assert_eq!(all.height(), without.height() + 1);
+    }
+}
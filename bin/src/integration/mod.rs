@@ -1,9 +1,11 @@
 //! Integration module for cross-sectional factor model.
 //!
-//! This module provides the data pipeline, factor computation, and sector encoding
-//! needed to run proper factor attribution using toraniko-model's FactorReturnsEstimator.
+//! This module provides the data pipeline, the sector and country encoding
+//! and the rolling exposures needed to run proper factor attribution with
+//! perth's cross-sectional regression.
 
 pub(crate) mod cache_manager;
+pub(crate) mod country_encoder;
 pub(crate) mod data_pipeline;
-pub(crate) mod factor_engine;
+pub(crate) mod rolling;
 pub(crate) mod sector_encoder;
@@ -0,0 +1,541 @@
+//! Rolling exposures and factor attribution of a single stock or a portfolio
+//! of dated holdings.
+//!
+//! Style scores are recomputed on rebalance dates, every trading day or the
+//! last trading day of each month or quarter, and each day's return is attributed with
+//! the scores of the latest rebalance date before it, so no day is explained
+//! by exposures computed from its own prices. Holdings likewise earn the
+//! returns of the days after their date.
+
+use super::data_pipeline::DataPipelineError;
+use chrono::NaiveDate;
+use indicatif::ProgressBar;
+use perth::output::{
+    AttributionFrequency, FactorAttribution, PortfolioAttribution, PortfolioExport,
+    SecurityAttribution,
+};
+use perth::style::FactorEngine;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// Trading days whose scores are computed: the last trading day of each
+/// period of `frequency`.
+fn rebalance_dates(dates: &[NaiveDate], frequency: AttributionFrequency) -> Vec<NaiveDate> {
+    dates
+        .iter()
+        .zip(dates.iter().skip(1).map(Some).chain([None]))
+        .filter(|(date, next)| {
+            next.is_none_or(|next| frequency.period_start(*next) != frequency.period_start(**date))
+        })
+        .map(|(date, _)| *date)
+        .collect()
+}
+
+/// Each trading day after the first scored rebalance with the latest scored
+/// rebalance strictly before it, whose scores it is attributed with.
+fn score_schedule(dates: &[NaiveDate], scored: &[NaiveDate]) -> Vec<(NaiveDate, NaiveDate)> {
+    let Some(&first) = scored.first() else {
+        return Vec::new();
+    };
+    let mut latest = 0;
+    dates
+        .iter()
+        .filter(|&&day| day > first)
+        .map(|&day| {
+            while latest + 1 < scored.len() && scored[latest + 1] < day {
+                latest += 1;
+            }
+            (day, scored[latest])
+        })
+        .collect()
+}
+
+/// Sorted distinct values of a frame's `date` column.
+fn distinct_dates(frame: &DataFrame) -> Result<Vec<NaiveDate>, DataPipelineError> {
+    let dates = frame.column("date")?.cast(&DataType::Date)?;
+    let mut dates: Vec<NaiveDate> = dates.date()?.as_date_iter().flatten().collect();
+    dates.sort_unstable();
+    dates.dedup();
+    Ok(dates)
+}
+
+/// Style scores for every trading day after the first rebalance, taken from
+/// the latest rebalance date before it.
+///
+/// Returns the columns of [`FactorEngine::compute_all_scores`] with `date`
+/// as a date. Rebalance dates within the engine's lookback of the start of
+/// `factor_data`, or whose scores fail, are skipped. `progress` advances
+/// once per rebalance date.
+pub(crate) fn rolling_scores(
+    engine: &FactorEngine,
+    factor_data: &DataFrame,
+    frequency: AttributionFrequency,
+    progress: Option<&ProgressBar>,
+) -> Result<DataFrame, DataPipelineError> {
+    let dates = distinct_dates(factor_data)?;
+    let warm_up = engine.lookback().min(dates.len());
+    let rebalances = rebalance_dates(&dates[warm_up..], frequency);
+    if let Some(pb) = progress {
+        pb.set_length(rebalances.len() as u64);
+    }
+
+    let mut scored = Vec::new();
+    let mut frames = Vec::new();
+    for rebalance in rebalances {
+        if let Some(pb) = progress {
+            pb.inc(1);
+        }
+        let Ok(scores) = engine.compute_all_scores(factor_data, rebalance) else {
+            continue;
+        };
+        if scores.height() == 0 {
+            continue;
+        }
+        scored.push(rebalance);
+        frames.push(
+            scores
+                .lazy()
+                .with_column(col("date").cast(DataType::Date).alias("score_date"))
+                .drop([col("date")]),
+        );
+    }
+    if scored.is_empty() {
+        return Err(PolarsError::NoData("no rebalance date could be scored".into()).into());
+    }
+
+    // Each later trading day takes the scores of the last rebalance before it
+    let (days, score_dates): (Vec<NaiveDate>, Vec<NaiveDate>) =
+        score_schedule(&dates, &scored).into_iter().unzip();
+    let schedule = DataFrame::new(vec![
+        Column::new("date".into(), days),
+        Column::new("score_date".into(), score_dates),
+    ])?;
+
+    Ok(concat(frames, UnionArgs::default())?
+        .join(
+            schedule.lazy(),
+            [col("score_date")],
+            [col("score_date")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .drop([col("score_date")])
+        .sort(["date", "symbol"], SortMultipleOptions::default())
+        .collect()?)
+}
+
+/// `symbol`'s numeric exposures by date in `frame`, keyed by column name.
+fn symbol_exposures(
+    frame: &DataFrame,
+    symbol: &str,
+) -> Result<HashMap<NaiveDate, HashMap<String, f64>>, DataPipelineError> {
+    let rows = frame
+        .clone()
+        .lazy()
+        .filter(col("symbol").eq(lit(symbol)))
+        .with_column(col("date").cast(DataType::Date))
+        .collect()?;
+    let dates: Vec<Option<NaiveDate>> = rows.column("date")?.date()?.as_date_iter().collect();
+
+    let mut exposures: HashMap<NaiveDate, HashMap<String, f64>> = HashMap::new();
+    for column in rows.get_columns() {
+        if matches!(column.name().as_str(), "date" | "symbol")
+            || !column.dtype().is_primitive_numeric()
+        {
+            continue;
+        }
+        let values = column.cast(&DataType::Float64)?;
+        for (date, value) in dates.iter().zip(values.f64()?) {
+            if let (Some(date), Some(value)) = (date, value) {
+                exposures
+                    .entry(*date)
+                    .or_default()
+                    .insert(column.name().to_string(), value);
+            }
+        }
+    }
+    Ok(exposures)
+}
+
+/// One attribution per day `symbol` has a return, style scores and
+/// estimated factor returns.
+///
+/// Each factor contributes its exposure times its factor return: one for
+/// `market`, the sector dummy for `sector_*` factors and the score for
+/// `*_score` factors. The specific return is what the factors leave of the
+/// day's return.
+pub(crate) fn daily_attributions(
+    symbol: &str,
+    returns: &DataFrame,
+    factor_returns: &DataFrame,
+    style_df: &DataFrame,
+    sector_df: &DataFrame,
+) -> Result<Vec<SecurityAttribution>, DataPipelineError> {
+    let styles = symbol_exposures(style_df, symbol)?;
+    let sectors = symbol_exposures(sector_df, symbol)?;
+    let totals: HashMap<NaiveDate, f64> = symbol_exposures(returns, symbol)?
+        .into_iter()
+        .filter_map(|(date, values)| Some((date, *values.get("asset_returns")?)))
+        .collect();
+
+    let dates: Vec<Option<NaiveDate>> = factor_returns
+        .column("date")?
+        .date()?
+        .as_date_iter()
+        .collect();
+    let names = factor_returns.column("factor")?.str()?;
+    let values = factor_returns.column("factor_return")?.f64()?;
+    let mut by_date: Vec<(NaiveDate, Vec<(&str, f64)>)> = Vec::new();
+    for ((date, name), value) in dates.into_iter().zip(names).zip(values) {
+        let (Some(date), Some(name), Some(value)) = (date, name, value) else {
+            continue;
+        };
+        match by_date.last_mut() {
+            Some((last, factors)) if *last == date => factors.push((name, value)),
+            _ => by_date.push((date, vec![(name, value)])),
+        }
+    }
+
+    Ok(by_date
+        .into_iter()
+        .filter_map(|(date, factors)| {
+            let total = *totals.get(&date)?;
+            let scores = styles.get(&date)?;
+            let dummies = sectors.get(&date);
+            let factors = factors
+                .into_iter()
+                .map(|(name, factor_return)| {
+                    let exposure = if name == "market" {
+                        1.0
+                    } else if name.starts_with("sector_") {
+                        dummies.and_then(|d| d.get(name)).copied().unwrap_or(0.0)
+                    } else {
+                        scores.get(name).copied().unwrap_or(0.0)
+                    };
+                    FactorAttribution::new(name.to_string(), exposure, factor_return, total)
+                })
+                .collect();
+            Some(SecurityAttribution::new(
+                symbol.to_string(),
+                date,
+                date,
+                total,
+                factors,
+            ))
+        })
+        .collect())
+}
+
+/// A portfolio's daily attributions and the weight each day left out.
+pub(crate) struct PortfolioDays {
+    /// One attribution per day the portfolio was held, sorted by date.
+    pub attributions: Vec<PortfolioAttribution>,
+    /// Gross weight of the holdings without an attribution on every day a
+    /// snapshot was held, sorted by date.
+    pub uncovered: Vec<(NaiveDate, f64)>,
+}
+
+/// One attribution per day of a portfolio holding `snapshots`, each day
+/// held as of the latest snapshot before it.
+///
+/// Holdings are attributed with [`daily_attributions`] and weighted as held,
+/// so each factor contributes the portfolio's weighted exposure times its
+/// factor return, and net-short or dollar-neutral books are attributed as
+/// they are. Holdings without an attribution on a day, outside the universe
+/// or without a return or scores, are left out without re-weighting the
+/// rest; the absolute weight left out is returned per day.
+///
+/// # Errors
+///
+/// Returns [`DataPipelineError::UnattributedDay`] for a held day on which
+/// none of the holdings has an attribution.
+pub(crate) fn portfolio_attributions(
+    snapshots: &[PortfolioExport],
+    returns: &DataFrame,
+    factor_returns: &DataFrame,
+    style_df: &DataFrame,
+    sector_df: &DataFrame,
+) -> Result<PortfolioDays, DataPipelineError> {
+    let mut symbols: Vec<&str> = snapshots
+        .iter()
+        .flat_map(|p| &p.holdings)
+        .map(|h| h.symbol.as_str())
+        .collect();
+    symbols.sort_unstable();
+    symbols.dedup();
+
+    let mut by_symbol: HashMap<&str, HashMap<NaiveDate, SecurityAttribution>> = HashMap::new();
+    for symbol in symbols {
+        let daily = daily_attributions(symbol, returns, factor_returns, style_df, sector_df)?;
+        by_symbol.insert(
+            symbol,
+            daily.into_iter().map(|a| (a.period_end, a)).collect(),
+        );
+    }
+    let mut dates: Vec<NaiveDate> = by_symbol
+        .values()
+        .flat_map(|days| days.keys().copied())
+        .collect();
+    dates.sort_unstable();
+    dates.dedup();
+
+    let mut attributions = Vec::new();
+    let mut uncovered = Vec::new();
+    for date in dates {
+        let Some(snapshot) = snapshots.iter().rev().find(|p| p.date < date) else {
+            continue;
+        };
+        let mut securities = Vec::new();
+        let mut weights = Vec::new();
+        let mut left_out = 0.0;
+        for holding in &snapshot.holdings {
+            match by_symbol
+                .get(holding.symbol.as_str())
+                .and_then(|days| days.get(&date))
+            {
+                Some(attribution) => {
+                    securities.push(attribution.clone());
+                    weights.push(holding.weight);
+                }
+                None => left_out += holding.weight.abs(),
+            }
+        }
+        if securities.is_empty() {
+            return Err(DataPipelineError::UnattributedDay {
+                portfolio: snapshot.name.clone(),
+                date,
+            });
+        }
+        uncovered.push((date, left_out));
+        attributions.push(PortfolioAttribution::from_holdings(
+            snapshot.name.clone(),
+            securities,
+            weights,
+        )?);
+    }
+    Ok(PortfolioDays {
+        attributions,
+        uncovered,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use perth::output::PortfolioHolding;
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, m, d).unwrap()
+    }
+
+    fn dated(frame: DataFrame) -> DataFrame {
+        frame
+            .lazy()
+            .with_column(col("date").cast(DataType::Date))
+            .collect()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rebalance_dates_are_period_ends() {
+        let dates = [
+            date(1, 30),
+            date(1, 31),
+            date(2, 1),
+            date(2, 29),
+            date(3, 28),
+            date(4, 1),
+            date(4, 2),
+        ];
+
+        assert_eq!(
+            rebalance_dates(&dates, AttributionFrequency::Monthly),
+            vec![date(1, 31), date(2, 29), date(3, 28), date(4, 2)]
+        );
+        assert_eq!(
+            rebalance_dates(&dates, AttributionFrequency::Quarterly),
+            vec![date(3, 28), date(4, 2)]
+        );
+        assert_eq!(
+            rebalance_dates(&dates, AttributionFrequency::Daily),
+            dates.to_vec()
+        );
+        assert!(rebalance_dates(&[], AttributionFrequency::Monthly).is_empty());
+    }
+
+    #[test]
+    fn test_score_schedule_has_no_look_ahead() {
+        let dates = [
+            date(1, 30),
+            date(1, 31),
+            date(2, 1),
+            date(2, 2),
+            date(2, 29),
+            date(3, 1),
+        ];
+        // The February rebalance could not be scored.
+        let scored = [date(1, 31), date(3, 1)];
+
+        let schedule = score_schedule(&dates, &scored);
+        assert_eq!(
+            schedule,
+            vec![
+                (date(2, 1), date(1, 31)),
+                (date(2, 2), date(1, 31)),
+                (date(2, 29), date(1, 31)),
+                (date(3, 1), date(1, 31)),
+            ]
+        );
+        assert!(schedule.iter().all(|(day, score_date)| score_date < day));
+        assert!(score_schedule(&dates, &[]).is_empty());
+    }
+
+    /// Two days of factor returns, with AAPL and MSFT exposures and returns
+    /// for both and XOM scored on the second day only.
+    fn model() -> (DataFrame, DataFrame, DataFrame, DataFrame) {
+        let returns = dated(
+            df! {
+                "date" => [date(1, 2), date(1, 2), date(1, 3), date(1, 3), date(1, 3)],
+                "symbol" => ["AAPL", "MSFT", "AAPL", "MSFT", "XOM"],
+                "asset_returns" => [0.02, -0.01, 0.015, 0.005, 0.03],
+            }
+            .unwrap(),
+        );
+        let factor_returns = dated(
+            df! {
+                "date" => [date(1, 2), date(1, 2), date(1, 2), date(1, 3), date(1, 3), date(1, 3)],
+                "factor" => ["market", "sector_it", "size_score", "market", "sector_it", "size_score"],
+                "factor_return" => [0.01, 0.002, -0.004, 0.005, -0.001, 0.003],
+            }
+            .unwrap(),
+        );
+        let style_df = dated(
+            df! {
+                "date" => [date(1, 2), date(1, 2), date(1, 3), date(1, 3), date(1, 3)],
+                "symbol" => ["AAPL", "MSFT", "AAPL", "MSFT", "XOM"],
+                "size_score" => [1.5, 1.2, 1.4, 1.3, 0.2],
+            }
+            .unwrap(),
+        );
+        let sector_df = dated(
+            df! {
+                "date" => [date(1, 2), date(1, 2), date(1, 3), date(1, 3), date(1, 3)],
+                "symbol" => ["AAPL", "MSFT", "AAPL", "MSFT", "XOM"],
+                "sector_it" => [1, 1, 1, 1, 0],
+            }
+            .unwrap(),
+        );
+        (returns, factor_returns, style_df, sector_df)
+    }
+
+    #[test]
+    fn test_daily_contributions_sum_to_total_return() {
+        let (returns, factor_returns, style_df, sector_df) = model();
+
+        let daily =
+            daily_attributions("AAPL", &returns, &factor_returns, &style_df, &sector_df).unwrap();
+        assert_eq!(daily.len(), 2);
+
+        let first = &daily[0];
+        assert_eq!(first.period_end, date(1, 2));
+        assert_eq!(first.total_return, 0.02);
+        let exposures: Vec<(&str, f64)> = first
+            .factors
+            .iter()
+            .map(|f| (f.factor_name.as_str(), f.exposure))
+            .collect();
+        assert_eq!(
+            exposures,
+            vec![("market", 1.0), ("sector_it", 1.0), ("size_score", 1.5)]
+        );
+        for day in &daily {
+            let contributions: f64 = day.factors.iter().map(|f| f.contribution).sum();
+            assert!((contributions - day.factor_return).abs() < 1e-12);
+            assert!((day.factor_return + day.specific_return - day.total_return).abs() < 1e-12);
+        }
+        assert!((first.factor_return - (0.01 + 0.002 - 1.5 * 0.004)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_portfolio_keeps_weights_and_reports_uncovered_weight() {
+        let (returns, factor_returns, style_df, sector_df) = model();
+        let holding =
+            |symbol: &str, weight| PortfolioHolding::new(symbol.into(), weight, None, None);
+        let snapshots = [PortfolioExport::new(
+            "Tech".into(),
+            date(1, 1),
+            vec![
+                holding("AAPL", 0.5),
+                holding("MSFT", 0.3),
+                holding("NVDA", 0.2),
+            ],
+        )];
+
+        let days =
+            portfolio_attributions(&snapshots, &returns, &factor_returns, &style_df, &sector_df)
+                .unwrap();
+        assert_eq!(days.attributions.len(), 2);
+        assert_eq!(days.uncovered.len(), 2);
+        for (_, weight) in &days.uncovered {
+            assert!((weight - 0.2).abs() < 1e-12);
+        }
+
+        // NVDA has no returns; AAPL and MSFT keep their weights.
+        let first = &days.attributions[0];
+        assert!((first.total_return - (0.5 * 0.02 - 0.3 * 0.01)).abs() < 1e-12);
+        assert!((first.factor_return + first.specific_return - first.total_return).abs() < 1e-12);
+
+        // Holdings dated on a day only earn the returns of the days after it.
+        let snapshots = [PortfolioExport::new(
+            "Tech".into(),
+            date(1, 2),
+            vec![holding("AAPL", 0.5), holding("XOM", 0.5)],
+        )];
+        let days =
+            portfolio_attributions(&snapshots, &returns, &factor_returns, &style_df, &sector_df)
+                .unwrap();
+        assert_eq!(days.uncovered, vec![(date(1, 3), 0.0)]);
+        assert_eq!(days.attributions.len(), 1);
+        assert_eq!(days.attributions[0].period_end, date(1, 3));
+        assert!((days.attributions[0].total_return - 0.5 * (0.015 + 0.03)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dollar_neutral_portfolio() {
+        let (returns, factor_returns, style_df, sector_df) = model();
+        let holding =
+            |symbol: &str, weight| PortfolioHolding::new(symbol.into(), weight, None, None);
+        let snapshots = [PortfolioExport::new(
+            "Pair".into(),
+            date(1, 1),
+            vec![holding("AAPL", 1.0), holding("MSFT", -1.0)],
+        )];
+
+        let days =
+            portfolio_attributions(&snapshots, &returns, &factor_returns, &style_df, &sector_df)
+                .unwrap();
+        assert_eq!(days.attributions.len(), 2);
+        let first = &days.attributions[0];
+        assert!((first.total_return - 0.03).abs() < 1e-12);
+        let market = first
+            .factors
+            .iter()
+            .find(|f| f.factor_name == "market")
+            .unwrap();
+        assert!(market.exposure.abs() < 1e-12);
+
+        // A held day without any attributed holding is an error naming it:
+        // XOM is not scored on the first day.
+        let snapshots = [
+            PortfolioExport::new("Energy".into(), date(1, 1), vec![holding("XOM", 1.0)]),
+            PortfolioExport::new("Energy".into(), date(1, 2), vec![holding("AAPL", 1.0)]),
+        ];
+        let error =
+            portfolio_attributions(&snapshots, &returns, &factor_returns, &style_df, &sector_df)
+                .err()
+                .unwrap();
+        assert!(matches!(
+            error,
+            DataPipelineError::UnattributedDay { date: day, .. } if day == date(1, 2)
+        ));
+    }
+}
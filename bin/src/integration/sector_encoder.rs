@@ -1,79 +1,129 @@
-//! GICS sector one-hot encoding.
+//! GICS industry one-hot encoding.
 //!
-//! Converts sector assignments from SP500Universe into one-hot encoded
-//! columns for use in cross-sectional factor regression.
+//! Converts classifications from a [`Universe`] into one-hot encoded
+//! columns for use in cross-sectional factor regression. Only rows where the
+//! symbol was a universe member on that date are kept.
 
-use perth::universe::{GicsSector, SP500Universe};
+use super::data_pipeline::{DataPipelineError, filter_to_members};
+use perth::universe::{GicsCode, GicsLevel, GicsSector, Universe};
 use polars::prelude::*;
+use std::collections::BTreeSet;
 
-/// Generate sector column name for a GICS sector.
-fn sector_column_name(sector: GicsSector) -> String {
-    format!("sector_{}", sector.name().replace(' ', "_"))
+/// Generate the dummy column name for a GICS classification.
+///
+/// Every level uses the `sector_` prefix, which is how the estimator and
+/// attribution recognise industry dummies.
+fn industry_column_name(code: GicsCode) -> String {
+    let name: String = code
+        .name()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let parts: Vec<&str> = name.split('_').filter(|p| !p.is_empty()).collect();
+    format!("sector_{}", parts.join("_"))
 }
 
-/// Encode GICS sectors as one-hot columns.
+/// Classify a symbol at `level`, falling back to coarser levels when the
+/// universe does not record one that fine.
+fn classify(universe: &dyn Universe, symbol: &str, level: GicsLevel) -> Option<GicsCode> {
+    GicsLevel::all()
+        .into_iter()
+        .filter(|l| *l <= level)
+        .rev()
+        .find_map(|l| universe.classification(symbol, l))
+}
+
+/// Encode GICS classifications as one-hot columns.
 ///
-/// For each symbol in the quotes DataFrame, looks up its sector from the
-/// SP500Universe and creates one-hot encoded columns for all 11 GICS sectors.
+/// For each symbol in the quotes DataFrame, looks up its classification at
+/// `level` from the universe and creates one-hot encoded columns. Sector
+/// granularity always yields all 11 GICS sectors; finer levels yield one
+/// column per classification present in the universe. Symbols without a
+/// sub-industry keep the finest level the universe knows for them, usually
+/// their sector. Rows where the symbol was not a member on that date are
+/// dropped.
 ///
 /// Returns DataFrame with columns: [date, symbol, sector_Information_Technology, sector_Health_Care, ...]
-pub(crate) fn encode_gics_sectors(
-    universe: &SP500Universe,
+pub(crate) fn encode_gics_industries(
+    universe: &dyn Universe,
     quotes: &DataFrame,
-) -> Result<DataFrame, PolarsError> {
-    // Start with date and symbol columns
-    let mut lf = quotes.clone().lazy().select([col("date"), col("symbol")]);
+    level: GicsLevel,
+) -> Result<DataFrame, DataPipelineError> {
+    // Start with date and symbol columns of point-in-time members
+    let mut lf = filter_to_members(
+        universe,
+        quotes.clone().lazy().select([col("date"), col("symbol")]),
+    )?;
 
-    // Add a sector column based on universe lookup
-    // First, we need to create a mapping DataFrame
+    // Map each symbol to its classification
     let symbols: Vec<String> = universe.symbols();
-    let sectors: Vec<String> = symbols
+    let codes: Vec<Option<GicsCode>> = symbols
+        .iter()
+        .map(|s| classify(universe, s, level))
+        .collect();
+
+    let columns: Vec<GicsCode> = match level {
+        GicsLevel::Sector => GicsSector::all().into_iter().map(GicsCode::from).collect(),
+        _ => codes
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+    };
+
+    // Fallback codes can share a name with a finer code (the Energy sector
+    // and industry group), so shared names get the code appended.
+    let names: Vec<String> = columns
         .iter()
-        .map(|s| {
-            universe
-                .sector(s)
-                .map(|sec| sec.name().to_string())
-                .unwrap_or_else(|| "Other".to_string())
+        .map(|code| {
+            let name = industry_column_name(*code);
+            let shared = columns
+                .iter()
+                .any(|other| other != code && other.name() == code.name());
+            if shared {
+                format!("{name}_{code}")
+            } else {
+                name
+            }
         })
         .collect();
 
-    let sector_map = DataFrame::new(vec![
+    let industry_map = DataFrame::new(vec![
         Column::new("symbol".into(), symbols),
-        Column::new("sector".into(), sectors),
+        Column::new(
+            "industry".into(),
+            codes
+                .iter()
+                .map(|c| c.map(|c| c.value()))
+                .collect::<Vec<_>>(),
+        ),
     ])?;
 
-    // Join to get sector for each row
+    // Join to get the classification for each row
     lf = lf.join(
-        sector_map.lazy(),
+        industry_map.lazy(),
         [col("symbol")],
         [col("symbol")],
         JoinArgs::new(JoinType::Left),
     );
 
-    // Create one-hot encoded columns for each GICS sector
-    for sector in GicsSector::all() {
-        let col_name = sector_column_name(sector);
-        let sector_name = sector.name();
-
+    // Create one-hot encoded columns for each classification
+    for (code, name) in columns.iter().zip(&names) {
         lf = lf.with_column(
-            when(col("sector").eq(lit(sector_name)))
+            when(col("industry").eq(lit(code.value())))
                 .then(lit(1.0))
                 .otherwise(lit(0.0))
-                .alias(&col_name),
+                .alias(name.as_str()),
         );
     }
 
-    // Remove the intermediate sector column, keep only one-hot columns
-    let sector_cols: Vec<Expr> = std::iter::once(col("date"))
-        .chain(std::iter::once(col("symbol")))
-        .chain(
-            GicsSector::all()
-                .into_iter()
-                .map(|s| col(sector_column_name(s))),
-        )
+    // Remove the intermediate industry column, keep only one-hot columns
+    let industry_cols: Vec<Expr> = [col("date"), col("symbol")]
+        .into_iter()
+        .chain(names.iter().map(|name| col(name.as_str())))
         .collect();
 
-    let result = lf.select(sector_cols).collect()?;
-
-    Ok(result)
+    Ok(lf.select(industry_cols).collect()?)
 }
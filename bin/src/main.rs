@@ -4,28 +4,50 @@
 
 mod integration;
 
-use chrono::{Duration, Utc};
+use chrono::{NaiveDate, NaiveTime, Utc};
 use clap::{Parser, Subcommand};
 use indicatif::{ProgressBar, ProgressStyle};
+use integration::cache_manager;
+use integration::country_encoder::{base_country, encode_countries};
 use integration::data_pipeline::{
-    FetchConfig, compute_market_cap_proxy, compute_returns, fetch_market_benchmark_with_config,
-    fetch_universe_data_with_progress, prepare_factor_data, print_cache_info,
+    FetchConfig, attach_trailing_eps, cached_delisting_returns, compute_market_cap_proxy,
+    compute_returns, cumulative_return, fetch_fx_rates_with_config,
+    fetch_market_benchmark_with_config, fetch_universe_data_with_progress, filter_to_members,
+    prepare_factor_data, print_cache_info, print_quality_report, quotes_to_usd,
+    symbols_without_latest, universe_countries, universe_currencies, with_sectors,
 };
-use integration::factor_engine::FactorEngine;
-use integration::sector_encoder::encode_gics_sectors;
+use integration::rolling::{
+    PortfolioDays, daily_attributions, portfolio_attributions, rolling_scores,
+};
+use integration::sector_encoder::encode_gics_industries;
 use ndarray::Array2;
-use perth::universe::{GicsSector, SP500Universe, Universe};
-use perth_data::yahoo::quotes::YahooQuoteProvider;
-use perth_risk::covariance::{
-    CovarianceEstimator, EwmaCovarianceEstimator, LedoitWolfConfig, LedoitWolfEstimator,
-    VolatilityRegimeDetector,
+use perth::ModelSpec;
+use perth::output::{
+    AttributionFrequency, AttributionSeries, ExportFormat, Exporter, LinkingMethod,
+    PortfolioAttribution, PortfolioExport, UniverseDiffExport,
+};
+use perth::regression::{
+    DOWNWEIGHT_REPORT_THRESHOLD, FactorReturns, MIN_EFFECTIVE_ASSETS_PER_FACTOR, RobustWeighting,
+    T_STAT_SIGNIFICANCE,
+};
+use perth::style::{CollinearityDiagnostics, Imputation, VIF_WARNING_THRESHOLD};
+use perth::universe::diff::history_diffs;
+use perth::universe::{
+    Country, EtfHoldings, ExtendedUniverse, FileUniverse, GicsLevel, GicsSector, MembershipHistory,
+    SP500Universe, SicClassification, SicClassifier, Universe, UniverseRules, UniverseSnapshot,
 };
+use perth_data::calendar::{ExchangeCalendar, NyseCalendar, TRADING_DAYS_PER_YEAR};
+use perth_data::edgar::EdgarClient;
+use perth_data::quality::{QualityConfig, QuoteValidator};
+use perth_data::yahoo::quotes::YahooQuoteProvider;
+use perth_risk::covariance::VolatilityRegimeDetector;
 use polars::prelude::*;
 use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::time::Duration as StdDuration;
-use toraniko_model::{EstimatorConfig, FactorReturnsEstimator, compute_attribution};
-use toraniko_traits::ReturnsEstimator;
+use toraniko_model::compute_attribution;
 
 #[derive(Parser)]
 #[command(name = "perth")]
@@ -34,6 +56,11 @@ use toraniko_traits::ReturnsEstimator;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Model specification file (TOML) with factor, industry, regression,
+    /// risk and universe settings
+    #[arg(long, global = true, value_name = "PATH")]
+    model_spec: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -54,10 +81,107 @@ enum Commands {
         /// Force refresh cached data
         #[arg(long)]
         refresh: bool,
+
+        /// Drop one-day jumps larger than this that reverse the next day
+        /// (0.5 = 50%)
+        #[arg(long, default_value = "0.5")]
+        max_daily_return: f64,
+
+        /// Drop closes repeated for more than this many consecutive days
+        #[arg(long, default_value = "5")]
+        max_stale_days: usize,
+
+        /// Drop rows traded below this volume
+        #[arg(long, default_value = "1")]
+        min_volume: u64,
+
+        /// GICS level for industry factors (sector, industry-group, industry,
+        /// sub-industry), overriding the model spec
+        #[arg(long, value_name = "LEVEL")]
+        industry_level: Option<GicsLevel>,
+
+        /// Universe file (CSV, JSON or TOML), overriding the model spec
+        #[arg(long, value_name = "PATH")]
+        universe: Option<PathBuf>,
+
+        /// Show exposures and factor contributions over time instead of one
+        /// attribution for the whole period
+        #[arg(long)]
+        rolling: bool,
+
+        /// Period of the rolling series (daily, monthly or quarterly);
+        /// exposures are recomputed at the end of each period
+        #[arg(long, default_value = "monthly", requires = "rolling")]
+        frequency: AttributionFrequency,
+
+        /// How daily contributions are linked into periods (carino or
+        /// menchero)
+        #[arg(long, default_value = "carino", requires = "rolling")]
+        linking: LinkingMethod,
+
+        /// Write the rolling series to this file, as JSON for a `.json`
+        /// extension and CSV otherwise
+        #[arg(long, value_name = "PATH", requires = "rolling")]
+        output: Option<PathBuf>,
+    },
+
+    /// Attribute a portfolio's returns from its dated holdings
+    Attribute {
+        /// Dated holdings (CSV or JSON), such as concatenated portfolio
+        /// exports or a file with date, symbol and weight columns
+        #[arg(long, value_name = "PATH")]
+        portfolio: PathBuf,
+
+        /// Analysis period in years
+        #[arg(long, default_value = "5")]
+        years: u32,
+
+        /// Disable caching (always fetch fresh data)
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Force refresh cached data
+        #[arg(long)]
+        refresh: bool,
+
+        /// Drop one-day jumps larger than this that reverse the next day
+        /// (0.5 = 50%)
+        #[arg(long, default_value = "0.5")]
+        max_daily_return: f64,
+
+        /// Drop closes repeated for more than this many consecutive days
+        #[arg(long, default_value = "5")]
+        max_stale_days: usize,
+
+        /// Drop rows traded below this volume
+        #[arg(long, default_value = "1")]
+        min_volume: u64,
+
+        /// Universe file (CSV, JSON or TOML), overriding the model spec
+        #[arg(long, value_name = "PATH")]
+        universe: Option<PathBuf>,
+
+        /// Period of the attribution series (daily, monthly or quarterly);
+        /// exposures are recomputed at the end of each period
+        #[arg(long, default_value = "monthly")]
+        frequency: AttributionFrequency,
+
+        /// How daily contributions are linked into periods (carino or
+        /// menchero)
+        #[arg(long, default_value = "carino")]
+        linking: LinkingMethod,
+
+        /// Write the attribution series to this file, as JSON for a `.json`
+        /// extension and CSV otherwise
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
     },
 
     /// Run full universe analysis
     Universe {
+        #[command(subcommand)]
+        action: Option<UniverseAction>,
+
         /// Filter by GICS sector
         #[arg(long)]
         sector: Option<String>,
@@ -65,6 +189,10 @@ enum Commands {
         /// List all sectors
         #[arg(long)]
         list_sectors: bool,
+
+        /// Universe file (CSV, JSON or TOML) instead of the built-in S&P 500
+        #[arg(long, value_name = "PATH")]
+        universe: Option<PathBuf>,
     },
 
     /// Update data cache
@@ -80,6 +208,18 @@ enum Commands {
         /// Update all data
         #[arg(long)]
         full: bool,
+
+        /// Record the dated memberships of a universe file (CSV, JSON or TOML)
+        #[arg(long, value_name = "PATH")]
+        universe: Option<PathBuf>,
+
+        /// Build dated memberships from a universe rules file (TOML)
+        #[arg(long, value_name = "PATH")]
+        rules: Option<PathBuf>,
+
+        /// Years of history to evaluate the universe rules over
+        #[arg(long, default_value = "5", requires = "rules")]
+        years: u32,
     },
 
     /// Risk analysis and covariance estimation
@@ -103,6 +243,67 @@ enum Commands {
         /// Output format (json or text)
         #[arg(long, default_value = "text")]
         format: String,
+
+        /// Universe file (CSV, JSON or TOML) instead of the built-in S&P 500
+        #[arg(long, value_name = "PATH")]
+        universe: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum UniverseAction {
+    /// Show what changed between two universes
+    Diff {
+        /// Earlier universe: a universe file, or `sp500` for the built-in list
+        from: String,
+
+        /// Later universe: a universe file, or `sp500` for the built-in list
+        to: String,
+
+        /// Compare memberships as of this date (YYYY-MM-DD, default today)
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+
+        /// Output format (text, json or csv)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
+    /// Load an ETF holdings file as a universe and benchmark portfolio
+    Etf {
+        /// Issuer holdings file (CSV)
+        holdings: PathBuf,
+
+        /// Benchmark ETF holdings file to compare against
+        #[arg(long, value_name = "PATH")]
+        benchmark: Option<PathBuf>,
+
+        /// Universe to take sectors from when the holdings file has none
+        #[arg(long, value_name = "PATH")]
+        universe: Option<PathBuf>,
+
+        /// Output format (text, json or csv)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
+    /// List the dated membership changes of a universe
+    History {
+        /// Universe file (CSV, JSON or TOML) instead of the cached S&P 500 history
+        #[arg(long, value_name = "PATH")]
+        universe: Option<PathBuf>,
+
+        /// First change date to include (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<chrono::NaiveDate>,
+
+        /// Last change date to include (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<chrono::NaiveDate>,
+
+        /// Output format (text, json or csv)
+        #[arg(long, default_value = "text")]
+        format: String,
     },
 }
 
@@ -116,6 +317,10 @@ async fn main() {
 
 async fn run() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    let mut spec = match &cli.model_spec {
+        Some(path) => ModelSpec::from_path(path)?,
+        None => ModelSpec::default(),
+    };
 
     match cli.command {
         Commands::Analyze {
@@ -123,29 +328,124 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
             years,
             no_cache,
             refresh,
+            max_daily_return,
+            max_stale_days,
+            min_volume,
+            industry_level,
+            universe,
+            rolling,
+            frequency,
+            linking,
+            output,
         } => {
             let config = FetchConfig {
                 use_cache: !no_cache,
                 force_refresh: refresh,
+                dividends: spec.factors.dividend_yield.enabled,
             };
-            analyze_symbol(&symbol, years, config).await?;
+            let quality = QualityConfig {
+                max_abs_return: max_daily_return,
+                max_stale_run: max_stale_days,
+                min_volume,
+                ..QualityConfig::default()
+            };
+            if let Some(level) = industry_level {
+                spec.industries.level = level;
+            }
+            if universe.is_some() {
+                spec.universe.path = universe;
+            }
+            let universe = load_universe(spec.universe.path.as_deref())?;
+            let rolling = rolling.then_some(RollingOptions {
+                frequency,
+                linking,
+                output,
+            });
+            analyze_symbol(
+                &symbol,
+                years,
+                universe.as_ref(),
+                &spec,
+                config,
+                quality,
+                rolling,
+            )
+            .await?;
+        }
+        Commands::Attribute {
+            portfolio,
+            years,
+            no_cache,
+            refresh,
+            max_daily_return,
+            max_stale_days,
+            min_volume,
+            universe,
+            frequency,
+            linking,
+            output,
+        } => {
+            let config = FetchConfig {
+                use_cache: !no_cache,
+                force_refresh: refresh,
+                dividends: spec.factors.dividend_yield.enabled,
+            };
+            let quality = QualityConfig {
+                max_abs_return: max_daily_return,
+                max_stale_run: max_stale_days,
+                min_volume,
+                ..QualityConfig::default()
+            };
+            if universe.is_some() {
+                spec.universe.path = universe;
+            }
+            let universe = load_universe(spec.universe.path.as_deref())?;
+            let options = RollingOptions {
+                frequency,
+                linking,
+                output,
+            };
+            attribute_portfolio(
+                &portfolio,
+                years,
+                universe.as_ref(),
+                &spec,
+                config,
+                quality,
+                options,
+            )
+            .await?;
         }
         Commands::Universe {
+            action,
             sector,
             list_sectors,
+            universe,
         } => {
-            if list_sectors {
+            if let Some(action) = action {
+                run_universe_action(action)?;
+            } else if list_sectors {
                 list_all_sectors();
             } else {
-                run_universe_analysis(sector).await?;
+                let universe = load_universe(universe.or(spec.universe.path).as_deref())?;
+                run_universe_analysis(universe.as_ref(), sector).await?;
             }
         }
         Commands::Update {
             quotes,
             fundamentals,
             full,
+            universe,
+            rules,
+            years,
         } => {
-            update_data(quotes, fundamentals, full).await?;
+            if let Some(path) = rules {
+                build_rules_universe(&path, years)?;
+                if !quotes && !fundamentals && !full && universe.is_none() {
+                    return Ok(());
+                }
+            }
+            update_data(quotes, fundamentals, full, universe.as_deref()).await?;
         }
         Commands::Risk {
             covariance,
@@ -153,21 +453,76 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
             regime,
             symbol,
             format,
+            universe,
         } => {
-            risk_analysis(covariance, specific, regime, symbol, &format).await?;
+            if universe.is_some() {
+                spec.universe.path = universe;
+            }
+            let universe = load_universe(spec.universe.path.as_deref())?;
+            risk_analysis(
+                covariance,
+                specific,
+                regime,
+                symbol,
+                &format,
+                universe.as_ref(),
+                &spec,
+            )
+            .await?;
         }
     }
 
     Ok(())
 }
 
+/// Load the universe from a file, or fall back to the built-in S&P 500 list.
+///
+/// The S&P 500 uses the dated memberships recorded in the cache, if any.
+fn load_universe(path: Option<&Path>) -> Result<Box<dyn Universe>, Box<dyn std::error::Error>> {
+    match path {
+        Some(path) => Ok(Box::new(load_file_universe(path)?)),
+        None => {
+            let universe = SP500Universe::new();
+            let history = cache_manager::open_cache()
+                .and_then(|cache| MembershipHistory::load(&cache))
+                .ok()
+                .filter(MembershipHistory::is_dated);
+            Ok(Box::new(match history {
+                Some(history) => universe.with_history(history),
+                None => universe,
+            }))
+        }
+    }
+}
+
+/// Load a universe file, naming the file in errors.
+fn load_file_universe(path: &Path) -> Result<FileUniverse, Box<dyn std::error::Error>> {
+    FileUniverse::from_path(path)
+        .map_err(|e| format!("Failed to load universe {}: {}", path.display(), e).into())
+}
+
+/// Options of `analyze --rolling` and `attribute`.
+struct RollingOptions {
+    /// Period of the series.
+    frequency: AttributionFrequency,
+    /// Linking of the daily contributions within each period.
+    linking: LinkingMethod,
+    /// File the series is exported to.
+    output: Option<PathBuf>,
+}
+
 async fn analyze_symbol(
     symbol: &str,
     years: u32,
+    universe: &dyn Universe,
+    spec: &ModelSpec,
     config: FetchConfig,
+    quality_config: QualityConfig,
+    rolling: Option<RollingOptions>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let symbol = symbol.to_uppercase();
-    let universe = SP500Universe::new();
+    let industry_level = spec.industries.level;
+    let factor_engine = spec.factor_engine();
 
     println!("\n╔══════════════════════════════════════════════════════════════╗");
     println!(
@@ -176,10 +531,26 @@ async fn analyze_symbol(
     );
     println!("╚══════════════════════════════════════════════════════════════╝\n");
 
+    // Symbols outside the universe are classified from their EDGAR SIC code
+    // and analyzed alongside it
+    let extended;
+    let universe: &dyn Universe = if !universe.contains(&symbol)
+        && let Some(classification) = classify_from_edgar(&symbol, config.use_cache).await
+    {
+        match classification.sic {
+            Some(sic) => println!("Classified from EDGAR SIC code {}", sic),
+            None => println!("Classified from cached EDGAR SIC code"),
+        }
+        extended = ExtendedUniverse::new(universe).with_constituent(classification.constituent());
+        &extended
+    } else {
+        universe
+    };
+
     // Determine sector
     let sector_name = universe.sector(&symbol).map_or_else(
         || {
-            println!("Sector: Unknown (not in S&P 500 universe)");
+            println!("Sector: Unknown (not in {} universe)", universe.name());
             "Other".to_string()
         },
         |s| {
@@ -188,8 +559,288 @@ async fn analyze_symbol(
         },
     );
 
+    if let Some(sub_industry) = universe.sub_industry(&symbol) {
+        println!("GICS Sub-Industry: {}", sub_industry.name());
+    }
+
+    let country = universe.country(&symbol);
+    if country != Country::UnitedStates {
+        println!(
+            "Country: {} ({}, benchmark {})",
+            country,
+            country.currency(),
+            country.benchmark()
+        );
+    }
+
+    let ModelRun {
+        quotes,
+        delistings,
+        style_df,
+        sector_df,
+        industry_count,
+        country_count,
+        estimate,
+    } = estimate_model(
+        universe,
+        years,
+        spec,
+        config,
+        quality_config,
+        std::slice::from_ref(&symbol),
+        rolling.as_ref().map(|r| r.frequency),
+    )
+    .await?;
+    print_model_quality(
+        &estimate,
+        &spec.regression.robust,
+        std::slice::from_ref(&symbol),
+    )?;
+    let FactorReturns {
+        factor_returns,
+        residuals,
+        ..
+    } = estimate;
+
+    if let Some(rolling) = rolling {
+        let returns = compute_returns(&quotes, delistings.as_ref())?
+            .filter(col("symbol").eq(lit(symbol.as_str())))
+            .collect()?;
+        let daily = daily_attributions(&symbol, &returns, &factor_returns, &style_df, &sector_df)?;
+        if daily.is_empty() {
+            return Err(format!("No days to attribute for {}", symbol).into());
+        }
+        let series = AttributionSeries {
+            model_fingerprint: Some(spec.fingerprint()),
+            ..AttributionSeries::new(symbol.clone(), rolling.frequency, rolling.linking, &daily)
+        };
+        print_rolling_attribution(&series);
+        if let Some(path) = &rolling.output {
+            write_series(&series, path)?;
+        }
+        print_model_spec(spec)?;
+        return Ok(());
+    }
+
+    // Compute attribution for target symbol
+    print!("Computing attribution for {}...", symbol);
+    std::io::Write::flush(&mut std::io::stdout())?;
+
+    let attribution =
+        match compute_attribution(&symbol, &factor_returns, &residuals, &style_df, &sector_df) {
+            Ok(attr) => {
+                println!(" ✓");
+                attr
+            }
+            Err(e) => {
+                println!(" ✗");
+                return Err(format!("Attribution failed for {}: {}", symbol, e).into());
+            }
+        };
+
+    println!();
+
+    // Print results using toraniko-model's formatted output
+    attribution.print_summary();
+
+    // Print additional Perth-specific summary
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("PERTH MODEL DETAILS");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+
+    println!(
+        "  Style Factors:   {} (vs toraniko-rs baseline: 3)",
+        factor_engine.factor_names().len()
+    );
+    println!(
+        "  GICS Factors:    {} at {} level (vs toraniko-rs baseline: 3)",
+        industry_count, industry_level
+    );
+    println!(
+        "  Universe Size:   {} stocks (vs toraniko-rs baseline: 30)",
+        universe.size()
+    );
+    if country_count > 0 {
+        println!("  Country Factors: {}", country_count);
+    }
+    println!("  Target Sector:   {}", sector_name);
+    println!();
+
+    print_model_spec(spec)?;
+
+    println!("════════════════════════════════════════════════════════════════\n");
+
+    Ok(())
+}
+
+/// Attribute a portfolio's daily returns from its dated holdings and the
+/// estimated factor returns, linked into periods of `options.frequency`.
+async fn attribute_portfolio(
+    path: &Path,
+    years: u32,
+    universe: &dyn Universe,
+    spec: &ModelSpec,
+    config: FetchConfig,
+    quality_config: QualityConfig,
+    options: RollingOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let snapshots = PortfolioExport::read_series(path)
+        .map_err(|e| format!("Failed to load holdings {}: {}", path.display(), e))?;
+    let (Some(first), Some(last)) = (snapshots.first(), snapshots.last()) else {
+        return Err(format!("No holdings in {}", path.display()).into());
+    };
+    let name = last.name.clone();
+    let mut symbols: Vec<String> = snapshots
+        .iter()
+        .flat_map(|p| &p.holdings)
+        .map(|h| h.symbol.clone())
+        .collect();
+    symbols.sort_unstable();
+    symbols.dedup();
+
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║{:^62}║", format!("PORTFOLIO ATTRIBUTION: {}", name));
+    println!("╚══════════════════════════════════════════════════════════════╝\n");
+    println!(
+        "Holdings: {} snapshots from {} to {} ({} symbols)",
+        snapshots.len(),
+        first.date,
+        last.date,
+        symbols.len()
+    );
+
+    // Holdings outside the universe are classified from their EDGAR SIC
+    // code and analyzed alongside it
+    let mut extended = ExtendedUniverse::new(universe);
+    for symbol in symbols.iter().filter(|s| !universe.contains(s)) {
+        if let Some(classification) = classify_from_edgar(symbol, config.use_cache).await {
+            println!("Classified {} from EDGAR", symbol);
+            extended = extended.with_constituent(classification.constituent());
+        }
+    }
+
+    let ModelRun {
+        quotes,
+        delistings,
+        style_df,
+        sector_df,
+        estimate,
+        ..
+    } = estimate_model(
+        &extended,
+        years,
+        spec,
+        config,
+        quality_config,
+        &symbols,
+        Some(options.frequency),
+    )
+    .await?;
+    print_model_quality(&estimate, &spec.regression.robust, &symbols)?;
+
+    let returns = compute_returns(&quotes, delistings.as_ref())?.collect()?;
+    let PortfolioDays {
+        attributions: days,
+        uncovered,
+    } = portfolio_attributions(
+        &snapshots,
+        &returns,
+        &estimate.factor_returns,
+        &style_df,
+        &sector_df,
+    )?;
+    print_uncovered_weight(&uncovered);
+    if days.is_empty() {
+        return Err(format!(
+            "No days to attribute for {}: holdings must be dated before the last scored day",
+            name
+        )
+        .into());
+    }
+    let daily: Vec<_> = days
+        .iter()
+        .map(PortfolioAttribution::to_security_attribution)
+        .collect();
+    let series = AttributionSeries {
+        model_fingerprint: Some(spec.fingerprint()),
+        ..AttributionSeries::new(name.clone(), options.frequency, options.linking, &daily)
+    };
+    print_rolling_attribution(&series);
+    if let Some(total) = PortfolioAttribution::linked(name, &days, options.linking) {
+        print_holdings_attribution(&total, &symbols);
+    }
+    if let Some(path) = &options.output {
+        write_series(&series, path)?;
+    }
+    print_model_spec(spec)?;
+    Ok(())
+}
+
+/// Export an attribution series, as JSON for a `.json` path and CSV
+/// otherwise.
+fn write_series(series: &AttributionSeries, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let format = if path.extension().is_some_and(|ext| ext == "json") {
+        ExportFormat::PrettyJson
+    } else {
+        ExportFormat::Csv
+    };
+    series.export_to_file(path, format)?;
+    println!(
+        "Wrote {} {} periods to {}\n",
+        series.periods.len(),
+        series.frequency,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Data and factor returns of one model estimation.
+struct ModelRun {
+    /// Quotes of the universe, in US dollars.
+    quotes: DataFrame,
+    /// Cached delisting returns within the sample, if the cache is enabled.
+    delistings: Option<DataFrame>,
+    /// Style scores and country dummies by date and symbol.
+    style_df: DataFrame,
+    /// GICS industry dummies by date and symbol.
+    sector_df: DataFrame,
+    /// Number of GICS industry factors.
+    industry_count: usize,
+    /// Number of country factors.
+    country_count: usize,
+    /// Estimated factor returns and residuals.
+    estimate: FactorReturns,
+}
+
+/// Fetch and validate the universe's data, score it and estimate the daily
+/// factor returns, printing each step.
+///
+/// `targets` are the symbols being attributed: they must pass the quality
+/// checks, and non-US ones have their local and USD returns printed. With a
+/// `frequency` the scores are recomputed at the end of each period and
+/// applied to the following days, otherwise they are computed for the
+/// latest date only.
+async fn estimate_model(
+    universe: &dyn Universe,
+    years: u32,
+    spec: &ModelSpec,
+    config: FetchConfig,
+    quality_config: QualityConfig,
+    targets: &[String],
+    frequency: Option<AttributionFrequency>,
+) -> Result<ModelRun, Box<dyn std::error::Error>> {
+    let industry_level = spec.industries.level;
+    let factor_engine = spec.factor_engine();
+
+    println!("Universe: {} ({} stocks)", universe.name(), universe.size());
     println!("Analysis Period: {} year(s)", years);
-    println!("Model: Cross-sectional factor regression (5 style factors, 11 GICS sectors)");
+    println!(
+        "Model: {} [{}] - cross-sectional factor regression ({} style factors, GICS {} factors)",
+        spec.name,
+        spec.fingerprint(),
+        factor_engine.factor_names().len(),
+        industry_level
+    );
 
     // Print cache status
     if config.use_cache {
@@ -204,7 +855,10 @@ async fn analyze_symbol(
 
     let provider = YahooQuoteProvider::new();
     let end = Utc::now();
-    let start = end - Duration::days(years as i64 * 252);
+    let start = NyseCalendar
+        .n_trading_days_back(end.date_naive(), years * TRADING_DAYS_PER_YEAR)
+        .and_time(NaiveTime::MIN)
+        .and_utc();
 
     // Create progress bar for data fetching (the slow step)
     let pb = ProgressBar::new(universe.size() as u64);
@@ -220,7 +874,7 @@ async fn analyze_symbol(
     // Fetch universe data with progress reporting
     let quotes = match fetch_universe_data_with_progress(
         &provider,
-        &universe,
+        universe,
         start,
         end,
         config.clone(),
@@ -248,39 +902,175 @@ async fn analyze_symbol(
         }
     };
 
-    // Fetch market benchmark (SPY) - quick operation, no progress bar needed
-    print!("Fetching market benchmark (SPY)...");
+    // Validate quotes before they enter the model
+    print!("Validating data quality...");
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let (quotes, quality) = QuoteValidator::new(quality_config).validate(&quotes)?;
+    println!(" ✓");
+    print_quality_report(&quality);
+    let excluded: Vec<&str> = targets
+        .iter()
+        .map(String::as_str)
+        .filter(|symbol| quality.get(symbol).is_some_and(|q| q.excluded))
+        .collect();
+    if !excluded.is_empty() {
+        return Err(format!("{} excluded by data quality checks", excluded.join(", ")).into());
+    }
+
+    // Convert non-US listings to US dollars
+    let currencies: Vec<String> = universe_currencies(universe)
+        .into_iter()
+        .filter(|c| c.fx_symbol().is_some())
+        .map(|c| c.to_string())
+        .collect();
+    let local_quotes = quotes;
+    let quotes = if currencies.is_empty() {
+        local_quotes.clone()
+    } else {
+        print!("Fetching FX rates ({})...", currencies.join(", "));
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let fx_rates =
+            match fetch_fx_rates_with_config(&provider, universe, start, end, config.clone()).await
+            {
+                Ok(rates) => {
+                    println!(" ✓");
+                    rates
+                }
+                Err(e) => {
+                    println!(" ✗");
+                    return Err(format!("Failed to fetch FX rates: {}", e).into());
+                }
+            };
+        quotes_to_usd(universe, &local_quotes, &fx_rates)?
+    };
+    for symbol in targets {
+        let country = universe.country(symbol);
+        if country != Country::UnitedStates
+            && let (Some(local), Some(usd)) = (
+                cumulative_return(&local_quotes, symbol)?,
+                cumulative_return(&quotes, symbol)?,
+            )
+        {
+            println!(
+                "  {} return: {:.2}% in {}, {:.2}% in USD",
+                symbol,
+                local * 100.0,
+                country.currency(),
+                usd * 100.0
+            );
+        }
+    }
+
+    // Fundamentals for the yield factors; symbols whose fundamentals are
+    // unknown get an imputed yield score rather than a zero one
+    let quotes = if spec.factors.earnings_yield.enabled {
+        let pb = ProgressBar::new(0);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+                .expect("valid template")
+                .progress_chars("█▓░"),
+        );
+        pb.set_message("Fetching EDGAR financial statements...");
+        let (quotes, missing) = attach_trailing_eps(
+            &quotes,
+            spec.factors.earnings_yield.report_lag_days,
+            &config,
+            Some(&pb),
+        )
+        .await?;
+        pb.finish_with_message(format!(
+            "Fetched financial statements ({} symbols without EDGAR data)",
+            missing.len()
+        ));
+        quotes
+    } else {
+        quotes
+    };
+    for (enabled, factor, column) in [
+        (
+            spec.factors.dividend_yield.enabled,
+            "dividend_yield",
+            "dividend_ttm",
+        ),
+        (
+            spec.factors.earnings_yield.enabled,
+            "earnings_yield",
+            "eps_ttm",
+        ),
+    ] {
+        if enabled {
+            let unknown = symbols_without_latest(&quotes, column)?;
+            if unknown > 0 {
+                let treatment = match spec.factors.standardization.imputation {
+                    Imputation::None => "excluded",
+                    Imputation::SectorMedian | Imputation::Regression => "imputed",
+                };
+                println!(
+                    "  {}: {} symbols with unknown {} {}",
+                    factor, unknown, column, treatment
+                );
+            }
+        }
+    }
+
+    // Delisted names keep their final return
+    let delistings = cached_delisting_returns(start.date_naive(), end.date_naive(), &config);
+
+    // Fetch each country's market benchmark - quick operation, no progress bar needed
+    let benchmarks: Vec<&str> = universe_countries(universe)
+        .into_iter()
+        .map(|c| c.benchmark())
+        .collect();
+    print!("Fetching market benchmarks ({})...", benchmarks.join(", "));
     std::io::Write::flush(&mut std::io::stdout())?;
     let market_returns =
-        match fetch_market_benchmark_with_config(&provider, start, end, config).await {
+        match fetch_market_benchmark_with_config(&provider, universe, start, end, config).await {
             Ok(mr) => {
                 println!(" ✓");
                 mr
             }
             Err(e) => {
                 println!(" ✗");
-                return Err(format!("Failed to fetch SPY: {}", e).into());
+                return Err(format!("Failed to fetch market benchmarks: {}", e).into());
             }
         };
 
     // Compute returns and market cap
     print!("Computing returns and market cap...");
     std::io::Write::flush(&mut std::io::stdout())?;
-    let returns_df = compute_returns(&quotes)?;
+    let returns_df = compute_returns(&quotes, delistings.as_ref())?;
     let mkt_cap_df = compute_market_cap_proxy(&quotes)?;
     println!(" ✓");
 
+    // Restrict the regression cross-sections to point-in-time members
+    let returns_df = filter_to_members(universe, returns_df)?;
+    let mkt_cap_df = filter_to_members(universe, mkt_cap_df)?;
+
     // Prepare factor data (joins all necessary columns)
     print!("Preparing factor data...");
     std::io::Write::flush(&mut std::io::stdout())?;
     let factor_data = prepare_factor_data(&quotes, &market_returns, &mkt_cap_df)?;
+    let factor_data = with_sectors(universe, factor_data)?;
     println!(" ✓ ({} observations)", factor_data.height());
 
-    // Encode GICS sectors
-    print!("Encoding GICS sectors...");
+    // Encode GICS industry dummies at the chosen granularity
+    print!("Encoding GICS {} factors...", industry_level);
     std::io::Write::flush(&mut std::io::stdout())?;
-    let sector_df = encode_gics_sectors(&universe, &quotes)?;
-    println!(" ✓ (11 sectors)");
+    let sector_df = encode_gics_industries(universe, &quotes, industry_level)?;
+    let industry_count = sector_df.width() - 2;
+    println!(" ✓ ({} {} factors)", industry_count, industry_level);
+
+    // Country dummies, relative to the country with most listings
+    let country_df = encode_countries(universe)?;
+    let country_count = country_df.width() - 1;
+    if country_count > 0 {
+        println!(
+            "Encoded {} country factors (relative to {})",
+            country_count,
+            base_country(universe).map_or("-", |c| c.name())
+        );
+    }
 
     // Compute factor scores
     // Determine the most recent date in the factor data for point-in-time computation
@@ -293,42 +1083,87 @@ async fn analyze_symbol(
         .and_then(|s| chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok())
         .ok_or("Failed to determine target date from factor data")?;
 
-    print!("Computing factor scores for {}...", target_date);
-    std::io::Write::flush(&mut std::io::stdout())?;
-    let factor_engine = FactorEngine::new();
-    let style_df = match factor_engine.compute_all_scores(&factor_data, target_date) {
-        Ok(df) => {
-            println!(" ✓ ({} factors)", factor_engine.available_factors().len());
-            // Convert date column back from String to Date type for consistency with other DataFrames
-            df.lazy()
-                .with_column(col("date").cast(DataType::Date))
-                .collect()?
+    let scores = if let Some(frequency) = frequency {
+        // Scores at the end of each period, applied to the days after it
+        let pb = ProgressBar::new(0);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+                .expect("valid template")
+                .progress_chars("█▓░"),
+        );
+        pb.set_message(format!("Computing {} factor scores...", frequency));
+        match rolling_scores(&factor_engine, &factor_data, frequency, Some(&pb)) {
+            Ok(df) => {
+                pb.finish_with_message(format!(
+                    "Computed {} factor scores ({} factors)",
+                    frequency,
+                    factor_engine.factor_names().len()
+                ));
+                Ok(df)
+            }
+            Err(e) => {
+                pb.finish_with_message("Failed!");
+                Err(e.to_string())
+            }
         }
-        Err(e) => {
-            println!(" ✗");
-            return Err(format!("Failed to compute factor scores: {}", e).into());
+    } else {
+        print!("Computing factor scores for {}...", target_date);
+        std::io::Write::flush(&mut std::io::stdout())?;
+        match factor_engine.compute_all_scores(&factor_data, target_date) {
+            Ok(df) => {
+                println!(" ✓ ({} factors)", factor_engine.factor_names().len());
+                // Convert date column back from String to Date type for consistency with other DataFrames
+                Ok(df
+                    .lazy()
+                    .with_column(col("date").cast(DataType::Date))
+                    .collect()?)
+            }
+            Err(e) => {
+                println!(" ✗");
+                Err(e.to_string())
+            }
         }
     };
+    // Add the country dummies next to the style scores
+    let style_df = scores
+        .map_err(|e| format!("Failed to compute factor scores: {}", e))?
+        .lazy()
+        .join(
+            country_df.lazy(),
+            [col("symbol")],
+            [col("symbol")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .collect()?;
+
+    // Collinear exposures make the regression split returns arbitrarily; a
+    // rolling run reports the latest scores only
+    let collinearity = CollinearityDiagnostics::by_date(&style_df)?;
+    let shown = if frequency.is_some() {
+        1
+    } else {
+        collinearity.len()
+    };
+    for diagnostics in &collinearity[collinearity.len().saturating_sub(shown)..] {
+        print_collinearity(diagnostics);
+    }
 
     // Run factor returns estimation via WLS regression
     print!("Running cross-sectional regression...");
     std::io::Write::flush(&mut std::io::stdout())?;
 
-    let estimator_config = EstimatorConfig {
-        winsor_factor: Some(0.05),
-        residualize_styles: true,
-    };
-    let estimator = FactorReturnsEstimator::with_config(estimator_config);
+    let estimator = spec.regression_estimator();
 
-    let (factor_returns, residuals) = match estimator.estimate(
+    let estimate = match estimator.estimate(
         returns_df,
         mkt_cap_df,
         sector_df.clone().lazy(),
         style_df.clone().lazy(),
     ) {
-        Ok((fr, res)) => {
+        Ok(estimate) => {
             println!(" ✓");
-            (fr, res)
+            estimate
         }
         Err(e) => {
             println!(" ✗");
@@ -336,66 +1171,60 @@ async fn analyze_symbol(
         }
     };
 
-    // Compute attribution for target symbol
-    print!("Computing attribution for {}...", symbol);
-    std::io::Write::flush(&mut std::io::stdout())?;
-
-    let attribution =
-        match compute_attribution(&symbol, &factor_returns, &residuals, &style_df, &sector_df) {
-            Ok(attr) => {
-                println!(" ✓");
-                attr
-            }
-            Err(e) => {
-                println!(" ✗");
-                return Err(format!("Attribution failed for {}: {}", symbol, e).into());
-            }
-        };
-
-    println!();
-
-    // Print results using toraniko-model's formatted output
-    attribution.print_summary();
-
-    // Print additional Perth-specific summary
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("PERTH MODEL DETAILS");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-
-    println!(
-        "  Style Factors:   {} (vs toraniko-rs baseline: 3)",
-        factor_engine.available_factors().len()
-    );
-    println!("  GICS Sectors:    11 (vs toraniko-rs baseline: 3)");
-    println!(
-        "  Universe Size:   {} stocks (vs toraniko-rs baseline: 30)",
-        universe.size()
-    );
-    println!("  Target Sector:   {}", sector_name);
+    Ok(ModelRun {
+        quotes,
+        delistings,
+        style_df,
+        sector_df,
+        industry_count,
+        country_count,
+        estimate,
+    })
+}
 
-    println!("\n════════════════════════════════════════════════════════════════\n");
+/// Classify a symbol from its EDGAR SIC code, using the cache when enabled.
+///
+/// Failures are reported and treated as unclassified.
+async fn classify_from_edgar(symbol: &str, use_cache: bool) -> Option<SicClassification> {
+    let cache = use_cache
+        .then(cache_manager::open_cache)
+        .and_then(Result::ok);
+    let client = EdgarClient::new().ok()?;
+    let classifier = match &cache {
+        Some(cache) => SicClassifier::new(client).with_cache(cache),
+        None => SicClassifier::new(client),
+    };
 
-    Ok(())
+    match classifier.classify(symbol).await {
+        Ok(classification) => classification,
+        Err(e) => {
+            println!("Could not classify {} from EDGAR: {}", symbol, e);
+            None
+        }
+    }
 }
 
 async fn run_universe_analysis(
+    universe: &dyn Universe,
     sector_filter: Option<String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let universe = SP500Universe::new();
-
-    println!("S&P 500 Universe Analysis");
-    println!("=========================\n");
+    let title = format!("{} Universe Analysis", universe.name());
+    println!("{}", title);
+    println!("{}\n", "=".repeat(title.chars().count()));
 
     if let Some(sector_name) = sector_filter {
         // Filter by sector
-        let sector = parse_sector(&sector_name)?;
+        let sector: GicsSector = sector_name.parse()?;
         let symbols = universe.symbols_in_sector(sector);
 
         println!("Sector: {}", sector);
         println!("Constituents: {}\n", symbols.len());
 
         for symbol in symbols {
-            println!("  {}", symbol);
+            match universe.weight(&symbol) {
+                Some(weight) => println!("  {:8} {:>7.2}%", symbol, weight * 100.0),
+                None => println!("  {}", symbol),
+            }
         }
     } else {
         // Show all sectors
@@ -406,13 +1235,218 @@ async fn run_universe_analysis(
 
         for sector in GicsSector::all() {
             let count = sector_counts.get(&sector).unwrap_or(&0);
-            println!("  {:30} {:3} stocks", sector.name(), count);
+            let weight: f64 = universe
+                .symbols_in_sector(sector)
+                .iter()
+                .filter_map(|s| universe.weight(s))
+                .sum();
+            if weight > 0.0 {
+                println!(
+                    "  {:30} {:3} stocks {:>7.2}%",
+                    sector.name(),
+                    count,
+                    weight * 100.0
+                );
+            } else {
+                println!("  {:30} {:3} stocks", sector.name(), count);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run_universe_action(action: UniverseAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        UniverseAction::Diff {
+            from,
+            to,
+            date,
+            format,
+        } => {
+            let date = date.unwrap_or_else(|| Utc::now().date_naive());
+            let from = UniverseSnapshot::of(load_universe_spec(&from)?.as_ref(), date);
+            let to = UniverseSnapshot::of(load_universe_spec(&to)?.as_ref(), date);
+            let diff = from.diff(&to);
+
+            match parse_export_format(&format)? {
+                Some(format) => println!("{}", diff.export_to_string(format)?),
+                None => print!("{}", diff),
+            }
+        }
+        UniverseAction::Etf {
+            holdings,
+            benchmark,
+            universe,
+            format,
+        } => {
+            let base = load_universe(universe.as_deref())?;
+            let fund = load_etf_holdings(&holdings)?;
+            let benchmark = benchmark.as_deref().map(load_etf_holdings).transpose()?;
+            let date = fund.date.unwrap_or_else(|| Utc::now().date_naive());
+
+            let portfolio = fund.to_portfolio(date);
+            let benchmark_portfolio = benchmark.as_ref().map(|b| b.to_portfolio(date));
+            let output = match &benchmark_portfolio {
+                Some(benchmark) => portfolio.active_against(benchmark),
+                None => portfolio,
+            };
+
+            match parse_export_format(&format)? {
+                Some(format) => println!("{}", output.export_to_string(format)?),
+                None => print_etf_holdings(&fund, benchmark.as_ref(), base.as_ref(), &output)?,
+            }
+        }
+        UniverseAction::History {
+            universe,
+            since,
+            until,
+            format,
+        } => {
+            let (universe, history): (Box<dyn Universe>, MembershipHistory) = match universe {
+                Some(path) => {
+                    let universe = load_file_universe(&path)?;
+                    let history = universe.history().clone();
+                    (Box::new(universe), history)
+                }
+                None => {
+                    let history = MembershipHistory::load(&cache_manager::open_cache()?)?;
+                    (Box::new(SP500Universe::new()), history)
+                }
+            };
+            if !history.is_dated() {
+                return Err(format!(
+                    "{} has no dated membership history; record one with `perth update --universe` or `--rules`",
+                    universe.name()
+                )
+                .into());
+            }
+
+            let diffs = history_diffs(universe.as_ref(), &history, since, until);
+            match parse_export_format(&format)? {
+                Some(format) => println!("{}", diffs.export_to_string(format)?),
+                None if diffs.is_empty() => println!("No membership changes in range"),
+                None => {
+                    let text: Vec<String> = diffs.iter().map(UniverseDiffExport::to_text).collect();
+                    print!("{}", text.join("\n"));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Load an ETF holdings file, naming the file in errors.
+fn load_etf_holdings(path: &Path) -> Result<EtfHoldings, Box<dyn std::error::Error>> {
+    EtfHoldings::from_path(path)
+        .map_err(|e| format!("Failed to load ETF holdings {}: {}", path.display(), e).into())
+}
+
+/// Print an ETF's sector weights and largest positions, active against the
+/// benchmark when one is given.
+fn print_etf_holdings(
+    fund: &EtfHoldings,
+    benchmark: Option<&EtfHoldings>,
+    base: &dyn Universe,
+    portfolio: &PortfolioExport,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let title = format!("{} Holdings", portfolio.name);
+    println!("{}", title);
+    println!("{}\n", "=".repeat(title.chars().count()));
+
+    let mut sector_weights = Vec::new();
+    for etf in std::iter::once(fund).chain(benchmark) {
+        let unmapped = etf.unmapped(base);
+        print!(
+            "{}: {} equity holdings as of {}",
+            etf.name,
+            etf.len(),
+            portfolio.date
+        );
+        if unmapped.is_empty() {
+            println!();
+        } else {
+            let shown: Vec<&str> = unmapped.iter().take(5).map(String::as_str).collect();
+            println!(
+                " ({} without a sector: {}{})",
+                unmapped.len(),
+                shown.join(", "),
+                if unmapped.len() > shown.len() {
+                    ", ..."
+                } else {
+                    ""
+                }
+            );
+        }
+        sector_weights.push(etf.sector_weights(base));
+    }
+
+    println!("\nSector weights:");
+    let sectors = GicsSector::all()
+        .into_iter()
+        .map(|s| s.name())
+        .chain(["Unknown"]);
+    for sector in sectors {
+        let weights: Vec<f64> = sector_weights
+            .iter()
+            .map(|w| w.get(sector).copied().unwrap_or(0.0))
+            .collect();
+        match weights.as_slice() {
+            [fund, benchmark] if *fund > 0.0 || *benchmark > 0.0 => println!(
+                "  {:30} {:>7.2}% {:>7.2}% {:>+8.2}%",
+                sector,
+                fund * 100.0,
+                benchmark * 100.0,
+                (fund - benchmark) * 100.0
+            ),
+            [fund] if *fund > 0.0 => println!("  {:30} {:>7.2}%", sector, fund * 100.0),
+            _ => {}
+        }
+    }
+
+    let mut holdings = portfolio.holdings.clone();
+    holdings.sort_by(|a, b| b.weight.abs().total_cmp(&a.weight.abs()));
+    let heading = if benchmark.is_some() {
+        "Largest active weights"
+    } else {
+        "Largest holdings"
+    };
+    println!("\n{}:", heading);
+    for holding in holdings.iter().take(10) {
+        if benchmark.is_some() {
+            println!("  {:8} {:>+8.2}%", holding.symbol, holding.weight * 100.0);
+        } else {
+            println!("  {:8} {:>7.2}%", holding.symbol, holding.weight * 100.0);
         }
     }
 
     Ok(())
 }
 
+/// Load a universe named on the command line: `sp500` or a universe file.
+fn load_universe_spec(spec: &str) -> Result<Box<dyn Universe>, Box<dyn std::error::Error>> {
+    if spec.eq_ignore_ascii_case("sp500") {
+        load_universe(None)
+    } else {
+        load_universe(Some(Path::new(spec)))
+    }
+}
+
+/// Parse an output format option; `None` means text.
+fn parse_export_format(format: &str) -> Result<Option<ExportFormat>, Box<dyn std::error::Error>> {
+    match format.to_lowercase().as_str() {
+        "text" => Ok(None),
+        "json" => Ok(Some(ExportFormat::PrettyJson)),
+        "csv" => Ok(Some(ExportFormat::Csv)),
+        other => Err(format!(
+            "Unknown output format: {} (expected text, json or csv)",
+            other
+        )
+        .into()),
+    }
+}
+
 fn list_all_sectors() {
     println!("GICS Sectors:");
     println!("=============\n");
@@ -422,32 +1456,49 @@ fn list_all_sectors() {
     }
 }
 
-fn parse_sector(name: &str) -> Result<GicsSector, Box<dyn std::error::Error>> {
-    let normalized = name.to_lowercase().replace(' ', "");
-
-    let sector = match normalized.as_str() {
-        "informationtechnology" | "it" | "tech" => GicsSector::InformationTechnology,
-        "healthcare" | "health" => GicsSector::HealthCare,
-        "financials" | "finance" => GicsSector::Financials,
-        "consumerdiscretionary" | "discretionary" => GicsSector::ConsumerDiscretionary,
-        "communicationservices" | "communication" | "comms" => GicsSector::CommunicationServices,
-        "industrials" | "industrial" => GicsSector::Industrials,
-        "consumerstaples" | "staples" => GicsSector::ConsumerStaples,
-        "energy" => GicsSector::Energy,
-        "utilities" | "utility" => GicsSector::Utilities,
-        "realestate" | "estate" => GicsSector::RealEstate,
-        "materials" => GicsSector::Materials,
-        _ => return Err(format!("Unknown sector: {}", name).into()),
-    };
-
-    Ok(sector)
+/// Evaluate a universe rules file over the cached data and record the
+/// resulting dated memberships in the cache.
+fn build_rules_universe(path: &Path, years: u32) -> Result<(), Box<dyn std::error::Error>> {
+    let rules = UniverseRules::from_path(path)
+        .map_err(|e| format!("Failed to load universe rules {}: {}", path.display(), e))?;
+    let cache = cache_manager::open_cache()?;
+    let end = Utc::now().date_naive();
+    let start = NyseCalendar.n_trading_days_back(end, years * TRADING_DAYS_PER_YEAR);
+
+    let history = rules.build(&cache, start, end)?;
+    history.save(&cache)?;
+    println!(
+        "Recorded {} membership spells for {} ({} symbols, {} on {})",
+        history.memberships().count(),
+        rules.name,
+        history.symbols().len(),
+        history.symbols_on(end).len(),
+        end
+    );
+    Ok(())
 }
 
 async fn update_data(
     quotes: bool,
     fundamentals: bool,
     full: bool,
+    universe: Option<&Path>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(path) = universe {
+        let universe = load_file_universe(path)?;
+        let cache = cache_manager::open_cache()?;
+        universe.history().save(&cache)?;
+        println!(
+            "Recorded {} membership spells for {} ({} symbols)",
+            universe.history().memberships().count(),
+            universe.name(),
+            universe.size()
+        );
+        if !quotes && !fundamentals && !full {
+            return Ok(());
+        }
+    }
+
     if full {
         println!("Updating all data...");
         println!("  - Quotes: [Not yet implemented]");
@@ -469,18 +1520,40 @@ async fn update_data(
     Ok(())
 }
 
+/// Sections of the risk report to show.
+#[derive(Debug, Clone, Copy)]
+struct RiskSections {
+    covariance: bool,
+    specific: bool,
+    regime: bool,
+}
+
 async fn risk_analysis(
     show_covariance: bool,
     show_specific: bool,
     show_regime: bool,
     symbol: Option<String>,
     format: &str,
+    universe: &dyn Universe,
+    spec: &ModelSpec,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let symbol = symbol.map(|s| s.to_uppercase());
+    if let Some(sym) = &symbol
+        && !universe.contains(sym)
+    {
+        return Err(format!("{} is not in the {} universe", sym, universe.name()).into());
+    }
+
     // If no flags are set, show everything
     let show_all = !show_covariance && !show_specific && !show_regime;
     let do_covariance = show_all || show_covariance;
     let do_specific = show_all || show_specific;
     let do_regime = show_all || show_regime;
+    let sections = RiskSections {
+        covariance: do_covariance,
+        specific: do_specific,
+        regime: do_regime,
+    };
 
     // Generate synthetic factor returns for demonstration
     // In production, this would come from real data
@@ -500,9 +1573,8 @@ async fn risk_analysis(
             &factor_names,
             specific_volatility,
             symbol.as_deref(),
-            do_covariance,
-            do_specific,
-            do_regime,
+            sections,
+            spec,
         )?;
     } else {
         output_risk_text(
@@ -510,9 +1582,8 @@ async fn risk_analysis(
             &factor_names,
             specific_volatility,
             symbol.as_deref(),
-            do_covariance,
-            do_specific,
-            do_regime,
+            sections,
+            spec,
         )?;
     }
 
@@ -580,10 +1651,14 @@ fn output_risk_text(
     factor_names: &[String],
     specific_vol: Option<f64>,
     symbol: Option<&str>,
-    show_covariance: bool,
-    show_specific: bool,
-    show_regime: bool,
+    sections: RiskSections,
+    spec: &ModelSpec,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let RiskSections {
+        covariance: show_covariance,
+        specific: show_specific,
+        regime: show_regime,
+    } = sections;
     println!("\n╔══════════════════════════════════════════════════════════════╗");
     println!("║              Perth Risk Analysis (Demo Mode)                 ║");
     println!("╚══════════════════════════════════════════════════════════════╝\n");
@@ -598,16 +1673,11 @@ fn output_risk_text(
         println!("FACTOR COVARIANCE MATRIX");
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
-        // Estimate covariance using EWMA
-        let _ewma_estimator = EwmaCovarianceEstimator::try_default()?;
-        // Note: In production, you might want to compare EWMA vs Ledoit-Wolf
-        // let _ewma_cov = _ewma_estimator.estimate(factor_returns)?;
-
-        // Apply Ledoit-Wolf shrinkage
-        let lw_estimator = LedoitWolfEstimator::new(LedoitWolfConfig::default());
-        let lw_cov = lw_estimator.estimate(factor_returns)?;
+        // Estimate covariance with the estimator the model spec selects
+        let estimator = spec.risk.covariance.estimator()?;
+        let cov = estimator.estimate(factor_returns)?;
 
-        println!("Method: EWMA (λ=0.95) with Ledoit-Wolf Shrinkage");
+        println!("Method: {}", spec.risk.covariance.description());
         println!("Estimation Period: {} days\n", factor_returns.nrows());
 
         // Display correlation matrix (easier to read than covariance)
@@ -623,13 +1693,13 @@ fn output_risk_text(
 
         // Convert covariance to correlation
         let std_devs: Vec<f64> = (0..factor_names.len())
-            .map(|i| lw_cov[[i, i]].sqrt())
+            .map(|i| cov[[i, i]].sqrt())
             .collect();
 
         for i in 0..factor_names.len() {
             print!("{:<12}", &factor_names[i][..factor_names[i].len().min(11)]);
             for j in 0..factor_names.len() {
-                let corr = lw_cov[[i, j]] / (std_devs[i] * std_devs[j]);
+                let corr = cov[[i, j]] / (std_devs[i] * std_devs[j]);
                 print!("{:>10.3}", corr);
             }
             println!();
@@ -683,10 +1753,10 @@ fn output_risk_text(
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
         if let Some(vol) = specific_vol {
-            // Note: In production, use SpecificRiskEstimator to compute from residuals
-            // let _estimator = SpecificRiskEstimator::new(SpecificRiskConfig::default());
+            // Note: In production, use spec.risk.specific_estimator() to
+            // compute from residuals
 
-            println!("Method: EWMA (λ=0.95)");
+            println!("Method: {}", spec.risk.specific_description());
             println!("Estimation Period: {} days\n", factor_returns.nrows());
 
             if let Some(sym) = symbol {
@@ -701,6 +1771,8 @@ fn output_risk_text(
         println!();
     }
 
+    print_model_spec(spec)?;
+
     println!("Note: Using synthetic data for demonstration purposes.");
     println!("      Production system will use real market data.\n");
 
@@ -712,14 +1784,19 @@ fn output_risk_json(
     factor_names: &[String],
     specific_vol: Option<f64>,
     symbol: Option<&str>,
-    show_covariance: bool,
-    show_specific: bool,
-    show_regime: bool,
+    sections: RiskSections,
+    spec: &ModelSpec,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let RiskSections {
+        covariance: show_covariance,
+        specific: show_specific,
+        regime: show_regime,
+    } = sections;
     let mut output = json!({
         "analysis_type": "risk",
         "demo_mode": true,
         "estimation_period_days": factor_returns.nrows(),
+        "model_spec": model_spec_json(spec)?,
     });
 
     if let Some(sym) = symbol {
@@ -728,11 +1805,11 @@ fn output_risk_json(
 
     // Covariance estimation
     if show_covariance {
-        let lw_estimator = LedoitWolfEstimator::new(LedoitWolfConfig::default());
-        let lw_cov = lw_estimator.estimate(factor_returns)?;
+        let estimator = spec.risk.covariance.estimator()?;
+        let cov = estimator.estimate(factor_returns)?;
 
         let std_devs: Vec<f64> = (0..factor_names.len())
-            .map(|i| lw_cov[[i, i]].sqrt())
+            .map(|i| cov[[i, i]].sqrt())
             .collect();
 
         // Build correlation matrix
@@ -740,7 +1817,7 @@ fn output_risk_json(
         for i in 0..factor_names.len() {
             let mut row = Vec::new();
             for j in 0..factor_names.len() {
-                let corr = lw_cov[[i, j]] / (std_devs[i] * std_devs[j]);
+                let corr = cov[[i, j]] / (std_devs[i] * std_devs[j]);
                 row.push(format!("{:.4}", corr));
             }
             correlation.push(row);
@@ -758,8 +1835,7 @@ fn output_risk_json(
             .collect();
 
         output["covariance"] = json!({
-            "method": "EWMA with Ledoit-Wolf Shrinkage",
-            "ewma_decay": 0.95,
+            "method": spec.risk.covariance.description(),
             "factors": factor_names,
             "correlation_matrix": correlation,
             "volatilities": volatilities,
@@ -789,8 +1865,7 @@ fn output_risk_json(
     // Specific risk
     if show_specific && let Some(vol) = specific_vol {
         output["specific_risk"] = json!({
-            "method": "EWMA",
-            "ewma_decay": 0.95,
+            "method": spec.risk.specific_description(),
             "annualized_volatility": format!("{:.4}", vol),
         });
     }
@@ -798,3 +1873,376 @@ fn output_risk_json(
     println!("{}", serde_json::to_string_pretty(&output)?);
     Ok(())
 }
+
+/// Minimum absolute correlation for a pair of exposures to be listed.
+const CORRELATION_REPORT_THRESHOLD: f64 = 0.7;
+
+/// Print one cross-section's exposure collinearity, warning about factors
+/// with large variance inflation factors.
+fn print_collinearity(diagnostics: &CollinearityDiagnostics) {
+    println!(
+        "Exposure collinearity on {} ({} assets): condition number {:.1}",
+        diagnostics.date, diagnostics.n_assets, diagnostics.condition_number
+    );
+    let vifs: Vec<String> = diagnostics
+        .factors
+        .iter()
+        .zip(&diagnostics.vif)
+        .map(|(name, vif)| format!("{} {:.1}", name, vif))
+        .collect();
+    println!("  VIF: {}", vifs.join(", "));
+
+    let pairs = diagnostics.correlated_pairs(CORRELATION_REPORT_THRESHOLD);
+    if !pairs.is_empty() {
+        let pairs: Vec<String> = pairs
+            .iter()
+            .map(|(a, b, rho)| format!("{}/{} {:+.2}", a, b, rho))
+            .collect();
+        println!(
+            "  Correlated (|ρ| ≥ {}): {}",
+            CORRELATION_REPORT_THRESHOLD,
+            pairs.join(", ")
+        );
+    }
+
+    let high = diagnostics.high_vif(VIF_WARNING_THRESHOLD);
+    for (name, vif) in &high {
+        println!(
+            "  ⚠ {} has VIF {:.1} (> {}): {:.0}% of its exposure is explained by the other style factors",
+            name,
+            vif,
+            VIF_WARNING_THRESHOLD,
+            100.0 * (1.0 - 1.0 / vif)
+        );
+    }
+    if !high.is_empty() {
+        println!(
+            "    Their factor returns may offset each other; consider [factors.orthogonalization] in the model spec"
+        );
+    }
+}
+
+/// Most recent periods listed by `analyze --rolling`; `--output` has all.
+const ROLLING_PERIODS_SHOWN: usize = 24;
+
+/// Print the latest periods of a rolling attribution, then how each
+/// factor's exposure moved from the first period to the last and its linked
+/// contribution over the whole series.
+fn print_rolling_attribution(series: &AttributionSeries) {
+    let (Some(first), Some(last), Some(total)) = (
+        series.periods.first(),
+        series.periods.last(),
+        series.total(),
+    ) else {
+        return;
+    };
+    println!(
+        "\nRolling attribution for {} ({}, {} to {}, {} linking)",
+        series.symbol, series.frequency, first.period_start, last.period_end, series.linking
+    );
+    println!(
+        "  {:<12} {:>10} {:>10} {:>10} {:>14}",
+        "Period end", "Return", "Factor", "Specific", "Cum. specific"
+    );
+    let shown = series.periods.len().saturating_sub(ROLLING_PERIODS_SHOWN);
+    if shown > 0 {
+        println!("  ... {} earlier periods", shown);
+    }
+    for period in &series.periods[shown..] {
+        println!(
+            "  {:<12} {:>9.2}% {:>9.2}% {:>9.2}% {:>13.2}%",
+            period.period_end.to_string(),
+            100.0 * period.total_return,
+            100.0 * period.factor_return,
+            100.0 * period.specific_return,
+            100.0 * period.cumulative_specific_return
+        );
+    }
+
+    println!(
+        "\n  {:<32} {:>10} {:>10} {:>13}",
+        "Factor", "First exp.", "Last exp.", "Contribution"
+    );
+    let exposure = |period: &perth::output::AttributionPeriod, name: &str| {
+        period
+            .factors
+            .iter()
+            .find(|f| f.factor_name == name)
+            .map(|f| f.exposure)
+    };
+    for name in series.factor_names() {
+        let (start, end) = (exposure(first, name), exposure(last, name));
+        // Sectors the stock never belonged to
+        if name.starts_with("sector_")
+            && series
+                .periods
+                .iter()
+                .all(|p| exposure(p, name).is_none_or(|e| e == 0.0))
+        {
+            continue;
+        }
+        let contribution = total
+            .factors
+            .iter()
+            .find(|f| f.factor_name == name)
+            .map_or(0.0, |f| f.contribution);
+        let format_exposure =
+            |e: Option<f64>| e.map_or_else(|| "-".to_string(), |e| format!("{:.3}", e));
+        println!(
+            "  {:<32} {:>10} {:>10} {:>12.2}%",
+            name,
+            format_exposure(start),
+            format_exposure(end),
+            100.0 * contribution
+        );
+    }
+    println!(
+        "  Total {:+.2}% = factors {:+.2}% + specific {:+.2}%",
+        100.0 * total.total_return,
+        100.0 * total.factor_return,
+        100.0 * total.specific_return
+    );
+    println!();
+}
+
+/// Holdings listed by `attribute`, largest linked return first.
+const HOLDINGS_SHOWN: usize = 20;
+
+/// Print each holding's linked return over the days it was held, split into
+/// factor and specific return, and the held symbols that could not be
+/// attributed.
+fn print_holdings_attribution(portfolio: &PortfolioAttribution, symbols: &[String]) {
+    let mut securities: Vec<_> = portfolio.securities.iter().collect();
+    securities.sort_by(|a, b| b.total_return.abs().total_cmp(&a.total_return.abs()));
+    println!(
+        "Holdings ({} of {} attributed)",
+        securities.len(),
+        symbols.len()
+    );
+    println!(
+        "  {:<10} {:>10} {:>10} {:>10}",
+        "Symbol", "Return", "Factor", "Specific"
+    );
+    for security in securities.iter().take(HOLDINGS_SHOWN) {
+        println!(
+            "  {:<10} {:>9.2}% {:>9.2}% {:>9.2}%",
+            security.symbol,
+            100.0 * security.total_return,
+            100.0 * security.factor_return,
+            100.0 * security.specific_return
+        );
+    }
+    if securities.len() > HOLDINGS_SHOWN {
+        println!("  ... and {} more", securities.len() - HOLDINGS_SHOWN);
+    }
+    let missing: Vec<&str> = symbols
+        .iter()
+        .map(String::as_str)
+        .filter(|s| !securities.iter().any(|a| a.symbol == *s))
+        .collect();
+    if !missing.is_empty() {
+        println!(
+            "  ⚠ Without returns or scores, left out: {}",
+            missing.join(", ")
+        );
+    }
+    println!();
+}
+
+/// Uncovered weight above which a day of a portfolio attribution is flagged.
+const UNCOVERED_WEIGHT_WARNING: f64 = 0.05;
+
+/// Days with a large uncovered weight listed individually.
+const UNCOVERED_DAYS_SHOWN: usize = 10;
+
+/// Print how much of the portfolio's weight had no attribution, and flag
+/// the days whose attributed holdings leave out more than
+/// [`UNCOVERED_WEIGHT_WARNING`].
+fn print_uncovered_weight(uncovered: &[(NaiveDate, f64)]) {
+    let Some(&(worst_date, worst)) = uncovered.iter().max_by(|a, b| a.1.total_cmp(&b.1)) else {
+        return;
+    };
+    if worst <= 0.0 {
+        return;
+    }
+    let mean = uncovered.iter().map(|(_, w)| w).sum::<f64>() / uncovered.len() as f64;
+    println!(
+        "Uncovered weight: {:.2}% on average, {:.2}% at most ({})",
+        100.0 * mean,
+        100.0 * worst,
+        worst_date
+    );
+
+    let flagged: Vec<_> = uncovered
+        .iter()
+        .filter(|(_, w)| *w > UNCOVERED_WEIGHT_WARNING)
+        .collect();
+    if !flagged.is_empty() {
+        println!(
+            "  ⚠ {} days leave out more than {:.0}% of the weight:",
+            flagged.len(),
+            100.0 * UNCOVERED_WEIGHT_WARNING
+        );
+        for (date, weight) in flagged.iter().take(UNCOVERED_DAYS_SHOWN) {
+            println!("    {}  {:>6.2}%", date, 100.0 * weight);
+        }
+        if flagged.len() > UNCOVERED_DAYS_SHOWN {
+            println!("    ... and {} more", flagged.len() - UNCOVERED_DAYS_SHOWN);
+        }
+    }
+    println!();
+}
+
+/// Degenerate cross-sections listed individually before summarizing.
+const DEGENERATE_DAYS_SHOWN: usize = 10;
+
+/// Most frequently down-weighted symbols listed under robust regression.
+const DOWNWEIGHTED_SYMBOLS_SHOWN: usize = 5;
+
+/// Print fit statistics of the daily regressions, flag degenerate days and
+/// summarize robust down-weighting, including the target symbols'.
+fn print_model_quality(
+    estimate: &FactorReturns,
+    robust: &RobustWeighting,
+    targets: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let estimated: Vec<_> = estimate
+        .diagnostics
+        .iter()
+        .filter(|d| d.is_estimated())
+        .collect();
+    let mean = |values: Vec<f64>| {
+        let finite: Vec<f64> = values.into_iter().filter(|v| v.is_finite()).collect();
+        finite.iter().sum::<f64>() / finite.len().max(1) as f64
+    };
+
+    println!(
+        "\nModel quality ({} of {} cross-sections estimated)",
+        estimated.len(),
+        estimate.diagnostics.len()
+    );
+    println!(
+        "  Mean R²: {:.3}   Mean adjusted R²: {:.3}",
+        mean(estimated.iter().map(|d| d.r_squared).collect()),
+        mean(estimated.iter().map(|d| d.adjusted_r_squared).collect())
+    );
+    println!(
+        "  Mean assets: {:.0}   Mean effective assets: {:.1}",
+        mean(estimated.iter().map(|d| d.n_assets as f64).collect()),
+        mean(estimated.iter().map(|d| d.effective_n_assets).collect())
+    );
+    println!(
+        "  Constraint: {} (sector returns are relative to the market)",
+        estimate.sector_constraint
+    );
+
+    let significance = estimate.significance()?;
+    if !significance.is_empty() {
+        println!(
+            "  {:<32} {:>8} {:>8}",
+            "Factor",
+            "Mean |t|",
+            format!("|t|>{}", T_STAT_SIGNIFICANCE)
+        );
+        for factor in &significance {
+            println!(
+                "  {:<32} {:>8.2} {:>7.0}%",
+                factor.factor,
+                factor.mean_abs_t,
+                100.0 * factor.significant_share
+            );
+        }
+    }
+
+    let degenerate: Vec<_> = estimate.degenerate().collect();
+    if !degenerate.is_empty() {
+        println!(
+            "  ⚠ {} degenerate cross-sections (fewer than {} effective assets per parameter flags concentrated weights):",
+            degenerate.len(),
+            MIN_EFFECTIVE_ASSETS_PER_FACTOR
+        );
+        for day in degenerate.iter().take(DEGENERATE_DAYS_SHOWN) {
+            let issues: Vec<String> = day.issues.iter().map(ToString::to_string).collect();
+            println!(
+                "    {}: {} assets ({:.1} effective), {}",
+                day.date,
+                day.n_assets,
+                day.effective_n_assets,
+                issues.join("; ")
+            );
+        }
+        if degenerate.len() > DEGENERATE_DAYS_SHOWN {
+            println!(
+                "    ... and {} more",
+                degenerate.len() - DEGENERATE_DAYS_SHOWN
+            );
+        }
+    }
+
+    if robust.is_enabled() {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for day in &estimate.diagnostics {
+            for (downweighted, _) in &day.downweighted {
+                *counts.entry(downweighted.as_str()).or_default() += 1;
+            }
+        }
+        println!(
+            "  Robust weighting ({:?}, c = {}): {} asset-days weighted below {}",
+            robust.method,
+            robust.tuning_constant(),
+            counts.values().sum::<usize>(),
+            DOWNWEIGHT_REPORT_THRESHOLD
+        );
+        let mut frequent: Vec<(&str, usize)> = counts.iter().map(|(s, n)| (*s, *n)).collect();
+        frequent.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        if !frequent.is_empty() {
+            let listed: Vec<String> = frequent
+                .iter()
+                .take(DOWNWEIGHTED_SYMBOLS_SHOWN)
+                .map(|(s, n)| format!("{} ({} days)", s, n))
+                .collect();
+            println!("    Most often: {}", listed.join(", "));
+        }
+        for symbol in targets {
+            let target_days: Vec<String> = estimate
+                .diagnostics
+                .iter()
+                .filter_map(|day| {
+                    day.downweighted
+                        .iter()
+                        .find(|(s, _)| s == symbol)
+                        .map(|(_, weight)| format!("{} ({:.2})", day.date, weight))
+                })
+                .collect();
+            if !target_days.is_empty() {
+                println!(
+                    "    {} down-weighted on {} days: {}",
+                    symbol,
+                    target_days.len(),
+                    target_days.join(", ")
+                );
+            }
+        }
+    }
+    println!();
+    Ok(())
+}
+
+/// Print the model spec a run used, so it can be reproduced.
+fn print_model_spec(spec: &ModelSpec) -> Result<(), Box<dyn std::error::Error>> {
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("MODEL SPECIFICATION");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+
+    println!("# fingerprint {}", spec.fingerprint());
+    println!("{}", spec.to_toml_string()?);
+    Ok(())
+}
+
+/// The model spec a run used, with its fingerprint, for JSON outputs.
+fn model_spec_json(spec: &ModelSpec) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    Ok(json!({
+        "fingerprint": spec.fingerprint(),
+        "spec": serde_json::to_value(spec)?,
+    }))
+}
@@ -2,4 +2,7 @@
 
 pub mod sqlite;
 
-pub use sqlite::{CacheStats, FinancialStatement, PeriodType, SqliteCache};
+pub use sqlite::{
+    CacheStats, FinancialStatement, PeriodType, SqliteCache, SymbolClassification,
+    UniverseMembership, trailing_eps_diluted,
+};
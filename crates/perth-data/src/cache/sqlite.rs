@@ -1,12 +1,24 @@
 //! SQLite caching layer for market data.
 
+use crate::calendar::{ExchangeCalendar, NyseCalendar};
+use crate::edgar;
 use crate::error::{DataError, Result};
-use chrono::{DateTime, NaiveDate, Utc};
+use crate::security::{
+    Delisting, DelistingReason, SecurityId, TickerChange, TickerPeriod, known_delistings,
+    known_ticker_changes,
+};
+use crate::yahoo::{Dividend, DividendHistory};
+use chrono::{DateTime, Days, NaiveDate, Utc};
 use polars::prelude::*;
 use rusqlite::{Connection, OptionalExtension, params};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
+/// Version of the reference data written by
+/// [`SqliteCache::seed_reference_data`]; bump it when the known lists change.
+const REFERENCE_DATA_VERSION: i32 = 3;
+
 /// SQLite cache for market data.
 #[derive(Debug)]
 pub struct SqliteCache {
@@ -105,6 +117,68 @@ pub struct FinancialStatement {
     pub cached_at: DateTime<Utc>,
 }
 
+impl From<edgar::FinancialStatement> for FinancialStatement {
+    /// A freshly fetched statement, stamped as cached now.
+    fn from(stmt: edgar::FinancialStatement) -> Self {
+        Self {
+            symbol: stmt.symbol,
+            cik: stmt.cik,
+            period_end: stmt.period_end,
+            period_type: match stmt.period_type {
+                edgar::PeriodType::Quarterly => PeriodType::Quarterly,
+                edgar::PeriodType::Annual => PeriodType::Annual,
+            },
+            fiscal_year: stmt.fiscal_year,
+            fiscal_quarter: stmt.fiscal_quarter,
+            total_assets: stmt.total_assets,
+            total_liabilities: stmt.total_liabilities,
+            stockholders_equity: stmt.stockholders_equity,
+            long_term_debt: stmt.long_term_debt,
+            current_assets: stmt.current_assets,
+            current_liabilities: stmt.current_liabilities,
+            cash_and_equivalents: stmt.cash_and_equivalents,
+            revenue: stmt.revenue,
+            net_income: stmt.net_income,
+            operating_income: stmt.operating_income,
+            gross_profit: stmt.gross_profit,
+            eps_basic: stmt.eps_basic,
+            eps_diluted: stmt.eps_diluted,
+            operating_cash_flow: stmt.operating_cash_flow,
+            capital_expenditures: stmt.capital_expenditures,
+            free_cash_flow: stmt.free_cash_flow,
+            shares_outstanding: stmt.shares_outstanding,
+            shares_outstanding_diluted: stmt.shares_outstanding_diluted,
+            cached_at: Utc::now(),
+        }
+    }
+}
+
+/// Trailing diluted EPS as it was known on `date`.
+///
+/// Statements carry no filing date, so an annual report is taken to be
+/// public `report_lag_days` after its fiscal year end. The trailing figure is
+/// the diluted EPS of the latest annual report public by `date`. It is `None`
+/// when no such report has a diluted EPS, or when the latest one ended more
+/// than eighteen months before `date` and a newer report is overdue.
+pub fn trailing_eps_diluted(
+    statements: &[FinancialStatement],
+    date: NaiveDate,
+    report_lag_days: u64,
+) -> Option<f64> {
+    let stale_before = date.checked_sub_months(chrono::Months::new(18))?;
+    statements
+        .iter()
+        .filter(|s| s.period_type == PeriodType::Annual && s.eps_diluted.is_some())
+        .filter(|s| {
+            s.period_end
+                .checked_add_days(Days::new(report_lag_days))
+                .is_some_and(|public| public <= date)
+        })
+        .max_by_key(|s| s.period_end)
+        .filter(|s| s.period_end >= stale_before)
+        .and_then(|s| s.eps_diluted)
+}
+
 impl SqliteCache {
     /// Create a new SQLite cache.
     ///
@@ -127,6 +201,8 @@ impl SqliteCache {
 
     /// Initialize the database schema.
     fn initialize_schema(&self) -> Result<()> {
+        self.migrate_universe_table()?;
+
         // Quotes table
         self.conn.execute(
             "CREATE TABLE IF NOT EXISTS quotes (
@@ -150,15 +226,20 @@ impl SqliteCache {
             [],
         )?;
 
-        // Universe table (list of symbols to track)
+        // Universe table (symbols to track, one row per membership spell).
+        // An empty start_date means the spell predates recorded history.
+        // Inactive rows without an end_date only cache a classification.
         self.conn.execute(
             "CREATE TABLE IF NOT EXISTS universe (
-                symbol TEXT PRIMARY KEY,
+                symbol TEXT NOT NULL,
                 name TEXT,
                 sector TEXT,
                 industry TEXT,
                 added_at TEXT NOT NULL,
-                active INTEGER NOT NULL DEFAULT 1
+                active INTEGER NOT NULL DEFAULT 1,
+                start_date TEXT NOT NULL DEFAULT '',
+                end_date TEXT,
+                PRIMARY KEY (symbol, start_date)
             )",
             [],
         )?;
@@ -187,6 +268,40 @@ impl SqliteCache {
             [],
         )?;
 
+        // Dividends by ex-date
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS dividends (
+                symbol TEXT NOT NULL,
+                ex_date TEXT NOT NULL,
+                amount REAL NOT NULL,
+                cached_at TEXT NOT NULL,
+                PRIMARY KEY (symbol, ex_date)
+            )",
+            [],
+        )?;
+
+        // Date range over which each symbol's dividends are complete
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS dividend_coverage (
+                symbol TEXT PRIMARY KEY,
+                start_date TEXT NOT NULL,
+                end_date TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Date range each symbol's quotes were fetched over, so sessions
+        // without a quote inside it (before listing, halts, provider holes)
+        // are known to be missing rather than uncached
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS quote_coverage (
+                symbol TEXT PRIMARY KEY,
+                start_date TEXT NOT NULL,
+                end_date TEXT NOT NULL
+            )",
+            [],
+        )?;
+
         // Company CIK mappings
         self.conn.execute(
             "CREATE TABLE IF NOT EXISTS company_ciks (
@@ -247,24 +362,131 @@ impl SqliteCache {
             [],
         )?;
 
+        // Tickers each security has traded under, with inclusive dates
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS ticker_history (
+                security_id TEXT NOT NULL,
+                ticker TEXT NOT NULL,
+                start_date TEXT,
+                end_date TEXT,
+                PRIMARY KEY (security_id, ticker)
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_ticker_history_ticker ON ticker_history(ticker)",
+            [],
+        )?;
+
+        // Delisted securities and their final returns
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS delistings (
+                security_id TEXT PRIMARY KEY,
+                ticker TEXT NOT NULL,
+                date TEXT NOT NULL,
+                reason TEXT NOT NULL,
+                delisting_return REAL
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// Upgrade a universe table created before membership dates existed.
+    ///
+    /// Rows keep their data and become undated spells. Inactive rows are
+    /// closed as of the migration, since their removal date was not recorded.
+    fn migrate_universe_table(&self) -> Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name FROM pragma_table_info('universe')")?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<HashSet<_>, _>>()?;
+        if columns.is_empty() || columns.contains("start_date") {
+            return Ok(());
+        }
+
+        let today = Utc::now().date_naive().to_string();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("ALTER TABLE universe RENAME TO universe_v1", [])?;
+        tx.execute(
+            "CREATE TABLE universe (
+                symbol TEXT NOT NULL,
+                name TEXT,
+                sector TEXT,
+                industry TEXT,
+                added_at TEXT NOT NULL,
+                active INTEGER NOT NULL DEFAULT 1,
+                start_date TEXT NOT NULL DEFAULT '',
+                end_date TEXT,
+                PRIMARY KEY (symbol, start_date)
+            )",
+            [],
+        )?;
+        tx.execute(
+            "INSERT INTO universe (symbol, name, sector, industry, added_at, active, end_date)
+             SELECT symbol, name, sector, industry, added_at, active,
+                    CASE WHEN active = 1 THEN NULL ELSE ?1 END
+             FROM universe_v1",
+            params![today],
+        )?;
+        tx.execute("DROP TABLE universe_v1", [])?;
+        tx.commit()?;
         Ok(())
     }
 
     /// Check if quotes are cached for a symbol and date range.
+    ///
+    /// Every NYSE trading day in the range must be cached or lie inside the
+    /// symbol's [fetched coverage](Self::get_quote_coverage), where a missing
+    /// day is one the provider had no quote for: before the listing, during
+    /// a halt or in a hole in its data. The last day may also be missing,
+    /// since its bar may not be final yet.
     pub fn has_quotes(&self, symbol: &str, start: NaiveDate, end: NaiveDate) -> Result<bool> {
-        let count: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM quotes
-             WHERE symbol = ?1 AND date >= ?2 AND date <= ?3",
-            params![symbol, start.to_string(), end.to_string()],
-            |row| row.get(0),
-        )?;
+        let missing = self.uncovered_quote_dates(symbol, start, end)?;
+        Ok(quotes_complete(&missing, start, end))
+    }
 
-        // Check if we have data for most of the expected trading days
-        // Roughly 252 trading days per year, so ~21 per month
-        let days = (end - start).num_days();
-        let expected_count = (days as f64 * 0.7) as i64; // 70% of calendar days
+    /// Trading days in a date range with neither a cached quote nor fetched
+    /// coverage for a symbol.
+    fn uncovered_quote_dates(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<NaiveDate>> {
+        let mut missing = self.missing_quote_dates(symbol, start, end)?;
+        if let Some((from, to)) = self.get_quote_coverage(symbol)? {
+            missing.retain(|day| *day < from || *day > to);
+        }
+        Ok(missing)
+    }
 
-        Ok(count >= expected_count)
+    /// NYSE trading days in a date range with no cached quote for a symbol.
+    pub fn missing_quote_dates(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<NaiveDate>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT date FROM quotes
+             WHERE symbol = ?1 AND date >= ?2 AND date <= ?3",
+        )?;
+        let cached = stmt
+            .query_map(params![symbol, start.to_string(), end.to_string()], |row| {
+                row.get::<_, String>(0)
+            })?
+            .collect::<std::result::Result<HashSet<_>, _>>()?;
+
+        Ok(NyseCalendar
+            .trading_days(start, end)
+            .into_iter()
+            .filter(|day| !cached.contains(&day.to_string()))
+            .collect())
     }
 
     /// Get cached quotes for a symbol and date range.
@@ -337,6 +559,17 @@ impl SqliteCache {
         Ok(df)
     }
 
+    /// Every symbol with cached quotes, sorted.
+    pub fn get_quote_symbols(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT symbol FROM quotes ORDER BY symbol")?;
+        let symbols = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(symbols)
+    }
+
     /// Store quotes in the cache.
     pub fn put_quotes(&self, df: &DataFrame) -> Result<()> {
         let cached_at = Utc::now().to_rfc3339();
@@ -395,6 +628,58 @@ impl SqliteCache {
         Ok(())
     }
 
+    /// Store quotes fetched for `symbol` from `start`, recording that the
+    /// provider has no other quotes between `start` and the last quote.
+    ///
+    /// The coverage ends at the last quote rather than the requested end so
+    /// a session whose bar was not published yet is fetched again. A range
+    /// that overlaps or adjoins the covered one extends it; a disjoint range
+    /// replaces it.
+    pub fn put_fetched_quotes(&self, symbol: &str, start: NaiveDate, df: &DataFrame) -> Result<()> {
+        self.put_quotes(df)?;
+
+        let dates = df.column("date")?.cast(&DataType::String)?;
+        let Some(last) = dates.str()?.iter().flatten().max() else {
+            return Ok(());
+        };
+        let end = parse_date(last)?;
+        self.put_quote_coverage(symbol, start.min(end), end)
+    }
+
+    /// Record that `symbol`'s quotes were fetched over a date range.
+    pub fn put_quote_coverage(&self, symbol: &str, start: NaiveDate, end: NaiveDate) -> Result<()> {
+        let (mut start, mut end) = (start, end);
+        if let Some((from, to)) = self.get_quote_coverage(symbol)?
+            && from <= NyseCalendar.next_trading_day(end)
+            && start <= NyseCalendar.next_trading_day(to)
+        {
+            start = start.min(from);
+            end = end.max(to);
+        }
+        self.conn.execute(
+            "INSERT OR REPLACE INTO quote_coverage (symbol, start_date, end_date)
+             VALUES (?1, ?2, ?3)",
+            params![symbol, start.to_string(), end.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// The date range `symbol`'s quotes were fetched over, if recorded.
+    pub fn get_quote_coverage(&self, symbol: &str) -> Result<Option<(NaiveDate, NaiveDate)>> {
+        let coverage: Option<(String, String)> = self
+            .conn
+            .query_row(
+                "SELECT start_date, end_date FROM quote_coverage WHERE symbol = ?1",
+                params![symbol],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((start, end)) = coverage else {
+            return Ok(None);
+        };
+        Ok(Some((parse_date(&start)?, parse_date(&end)?)))
+    }
+
     /// Add a symbol to the universe.
     pub fn add_to_universe(
         &self,
@@ -418,7 +703,7 @@ impl SqliteCache {
     pub fn get_universe(&self) -> Result<Vec<String>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT symbol FROM universe WHERE active = 1 ORDER BY symbol")?;
+            .prepare("SELECT DISTINCT symbol FROM universe WHERE active = 1 ORDER BY symbol")?;
 
         let symbols = stmt
             .query_map([], |row| row.get(0))?
@@ -428,14 +713,153 @@ impl SqliteCache {
     }
 
     /// Remove a symbol from the universe (mark as inactive).
+    ///
+    /// Open membership spells end yesterday, so the symbol is no longer a
+    /// member as of today.
     pub fn remove_from_universe(&self, symbol: &str) -> Result<()> {
+        let yesterday = Utc::now().date_naive().pred_opt().map(|d| d.to_string());
         self.conn.execute(
-            "UPDATE universe SET active = 0 WHERE symbol = ?1",
-            params![symbol],
+            "UPDATE universe SET active = 0, end_date = COALESCE(end_date, ?2)
+             WHERE symbol = ?1 AND active = 1",
+            params![symbol, yesterday],
+        )?;
+        Ok(())
+    }
+
+    /// Store a dated membership spell, updating a spell with the same start.
+    pub fn put_universe_membership(&self, membership: &UniverseMembership) -> Result<()> {
+        self.put_universe_memberships(std::slice::from_ref(membership))
+    }
+
+    /// Store several membership spells in one transaction.
+    pub fn put_universe_memberships(&self, memberships: &[UniverseMembership]) -> Result<()> {
+        let added_at = Utc::now().to_rfc3339();
+        let tx = self.conn.unchecked_transaction()?;
+
+        for m in memberships {
+            tx.execute(
+                "INSERT INTO universe
+                 (symbol, sector, industry, added_at, active, start_date, end_date)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (symbol, start_date) DO UPDATE SET
+                    sector = excluded.sector,
+                    industry = excluded.industry,
+                    active = excluded.active,
+                    end_date = excluded.end_date",
+                params![
+                    m.symbol,
+                    m.sector,
+                    m.industry,
+                    added_at,
+                    m.end.is_none(),
+                    m.start.map(|d| d.to_string()).unwrap_or_default(),
+                    m.end.map(|d| d.to_string()),
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// All membership spells, ordered by symbol and start date.
+    pub fn get_universe_memberships(&self) -> Result<Vec<UniverseMembership>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT symbol, start_date, end_date, sector, industry FROM universe
+             WHERE active = 1 OR end_date IS NOT NULL
+             ORDER BY symbol, start_date",
+        )?;
+
+        let memberships = stmt
+            .query_map([], |row| {
+                let start: String = row.get(1)?;
+                Ok(UniverseMembership {
+                    symbol: row.get(0)?,
+                    start: optional_date((!start.is_empty()).then_some(start))?,
+                    end: optional_date(row.get(2)?)?,
+                    sector: row.get(3)?,
+                    industry: row.get(4)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(memberships)
+    }
+
+    /// Symbols that were universe members on `date`.
+    pub fn get_universe_on(&self, date: NaiveDate) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT symbol FROM universe
+             WHERE (active = 1 OR end_date IS NOT NULL)
+               AND (start_date = '' OR start_date <= ?1)
+               AND (end_date IS NULL OR end_date >= ?1)
+             ORDER BY symbol",
+        )?;
+
+        let symbols = stmt
+            .query_map(params![date.to_string()], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+
+        Ok(symbols)
+    }
+
+    /// Cache the sector and industry of a symbol.
+    ///
+    /// Updates every row of a universe member. Other symbols get a
+    /// classification-only row that does not make them members.
+    pub fn put_classification(&self, classification: &SymbolClassification) -> Result<()> {
+        let updated = self.conn.execute(
+            "UPDATE universe SET name = COALESCE(?2, name), sector = ?3, industry = ?4
+             WHERE symbol = ?1",
+            params![
+                classification.symbol,
+                classification.name,
+                classification.sector,
+                classification.industry
+            ],
         )?;
+
+        if updated == 0 {
+            self.conn.execute(
+                "INSERT INTO universe
+                    (symbol, name, sector, industry, added_at, active, start_date, end_date)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 0, '', NULL)",
+                params![
+                    classification.symbol,
+                    classification.name,
+                    classification.sector,
+                    classification.industry,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+        }
+
         Ok(())
     }
 
+    /// Get the cached sector and industry of a symbol, member or not.
+    pub fn get_classification(&self, symbol: &str) -> Result<Option<SymbolClassification>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT symbol, name, sector, industry FROM universe
+             WHERE symbol = ?1 AND sector IS NOT NULL
+             ORDER BY start_date DESC
+             LIMIT 1",
+        )?;
+
+        let classification = stmt
+            .query_row(params![symbol], |row| {
+                Ok(SymbolClassification {
+                    symbol: row.get(0)?,
+                    name: row.get(1)?,
+                    sector: row.get(2)?,
+                    industry: row.get(3)?,
+                })
+            })
+            .optional()?;
+
+        Ok(classification)
+    }
+
     /// Store market cap data.
     pub fn put_market_cap(&self, symbol: &str, date: NaiveDate, market_cap: f64) -> Result<()> {
         let cached_at = Utc::now().to_rfc3339();
@@ -463,6 +887,111 @@ impl SqliteCache {
         Ok(result)
     }
 
+    /// Most recent market cap for a symbol on or before `date`.
+    pub fn get_market_cap_on_or_before(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+    ) -> Result<Option<f64>> {
+        let result = self
+            .conn
+            .query_row(
+                "SELECT market_cap FROM market_caps
+                 WHERE symbol = ?1 AND date <= ?2
+                 ORDER BY date DESC LIMIT 1",
+                params![symbol, date.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(result)
+    }
+
+    /// Store a symbol's dividend history.
+    ///
+    /// The stored dividends over the history's range are replaced. A range
+    /// that overlaps or adjoins the covered one extends it; a disjoint range
+    /// replaces it, since the gap between them is unknown.
+    pub fn put_dividend_history(&self, symbol: &str, history: &DividendHistory) -> Result<()> {
+        let (mut start, mut end) = (history.start, history.end);
+        if let Some(covered) = self.get_dividend_history(symbol)?
+            && covered.start <= end.succ_opt().unwrap_or(end)
+            && start <= covered.end.succ_opt().unwrap_or(covered.end)
+        {
+            start = start.min(covered.start);
+            end = end.max(covered.end);
+        }
+
+        let cached_at = Utc::now().to_rfc3339();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM dividends
+             WHERE symbol = ?1 AND ((ex_date >= ?2 AND ex_date <= ?3) OR ex_date < ?4 OR ex_date > ?5)",
+            params![
+                symbol,
+                history.start.to_string(),
+                history.end.to_string(),
+                start.to_string(),
+                end.to_string()
+            ],
+        )?;
+        for dividend in &history.dividends {
+            tx.execute(
+                "INSERT OR REPLACE INTO dividends (symbol, ex_date, amount, cached_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    symbol,
+                    dividend.ex_date.to_string(),
+                    dividend.amount,
+                    cached_at
+                ],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO dividend_coverage (symbol, start_date, end_date)
+             VALUES (?1, ?2, ?3)",
+            params![symbol, start.to_string(), end.to_string()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// A symbol's cached dividend history, or `None` if its dividends were
+    /// never fetched.
+    pub fn get_dividend_history(&self, symbol: &str) -> Result<Option<DividendHistory>> {
+        let coverage: Option<(String, String)> = self
+            .conn
+            .query_row(
+                "SELECT start_date, end_date FROM dividend_coverage WHERE symbol = ?1",
+                params![symbol],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((start, end)) = coverage else {
+            return Ok(None);
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT ex_date, amount FROM dividends
+             WHERE symbol = ?1 AND ex_date >= ?2 AND ex_date <= ?3
+             ORDER BY ex_date",
+        )?;
+        let dividends = stmt
+            .query_map(params![symbol, start, end], |row| {
+                Ok(Dividend {
+                    ex_date: parse_date(&row.get::<_, String>(0)?)?,
+                    amount: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(Some(DividendHistory {
+            start: parse_date(&start)?,
+            end: parse_date(&end)?,
+            dividends,
+        }))
+    }
+
     /// Store fundamental data (as JSON).
     pub fn put_fundamentals(&self, symbol: &str, date: NaiveDate, data: &str) -> Result<()> {
         let cached_at = Utc::now().to_rfc3339();
@@ -741,51 +1270,386 @@ impl SqliteCache {
         Ok(count > 0)
     }
 
-    /// Clear all cached data.
-    pub fn clear_all(&self) -> Result<()> {
-        self.conn.execute("DELETE FROM quotes", [])?;
-        self.conn.execute("DELETE FROM market_caps", [])?;
-        self.conn.execute("DELETE FROM fundamentals", [])?;
-        self.conn.execute("DELETE FROM financial_statements", [])?;
-        self.conn.execute("DELETE FROM company_ciks", [])?;
-        Ok(())
-    }
-
-    /// Clear cached data for a specific symbol.
-    pub fn clear_symbol(&self, symbol: &str) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM quotes WHERE symbol = ?1", params![symbol])?;
-        self.conn
-            .execute("DELETE FROM market_caps WHERE symbol = ?1", params![symbol])?;
-        self.conn.execute(
-            "DELETE FROM fundamentals WHERE symbol = ?1",
-            params![symbol],
-        )?;
-        self.conn.execute(
-            "DELETE FROM financial_statements WHERE symbol = ?1",
-            params![symbol],
-        )?;
+    /// Store a ticker period, replacing any earlier record of the same ticker
+    /// for the same security.
+    pub fn put_ticker_period(&self, period: &TickerPeriod) -> Result<()> {
         self.conn.execute(
-            "DELETE FROM company_ciks WHERE symbol = ?1",
-            params![symbol],
+            "INSERT OR REPLACE INTO ticker_history (security_id, ticker, start_date, end_date)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                period.security_id.to_string(),
+                period.ticker,
+                period.start.map(|d| d.to_string()),
+                period.end.map(|d| d.to_string()),
+            ],
         )?;
         Ok(())
     }
 
-    /// Get cache statistics.
-    pub fn get_stats(&self) -> Result<CacheStats> {
-        let quotes_count: i64 = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM quotes", [], |row| row.get(0))?;
+    /// Record that a security switched from `old_ticker` to `new_ticker` on
+    /// `effective`.
+    ///
+    /// The old ticker's period is closed the day before, keeping its known
+    /// start date, and an open-ended period starts for the new ticker.
+    pub fn record_ticker_change(
+        &self,
+        security_id: &SecurityId,
+        old_ticker: &str,
+        new_ticker: &str,
+        effective: NaiveDate,
+    ) -> Result<()> {
+        let history = self.ticker_history(security_id)?;
+        let old_start = history
+            .iter()
+            .find(|p| p.ticker == old_ticker)
+            .and_then(|p| p.start);
+        let new_end = history
+            .iter()
+            .find(|p| p.ticker == new_ticker)
+            .and_then(|p| p.end);
+
+        self.put_ticker_period(&TickerPeriod {
+            security_id: security_id.clone(),
+            ticker: old_ticker.to_string(),
+            start: old_start,
+            end: effective.pred_opt(),
+        })?;
+        self.put_ticker_period(&TickerPeriod {
+            security_id: security_id.clone(),
+            ticker: new_ticker.to_string(),
+            start: Some(effective),
+            end: new_end,
+        })
+    }
 
-        let symbols_count: i64 =
-            self.conn
-                .query_row("SELECT COUNT(DISTINCT symbol) FROM quotes", [], |row| {
-                    row.get(0)
-                })?;
+    /// Record the [known ticker changes](known_ticker_changes) and
+    /// [delistings](known_delistings) once per version of the lists.
+    ///
+    /// The seeded version is kept in the database's `user_version`, so
+    /// reopening a seeded cache writes nothing.
+    pub fn seed_reference_data(&self) -> Result<()> {
+        let version: i32 = self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version >= REFERENCE_DATA_VERSION {
+            return Ok(());
+        }
 
-        let universe_count: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM universe WHERE active = 1",
+        let tx = self.conn.unchecked_transaction()?;
+        self.put_ticker_changes(&known_ticker_changes())?;
+        for delisting in known_delistings() {
+            self.put_delisting(&delisting)?;
+        }
+        tx.pragma_update(None, "user_version", REFERENCE_DATA_VERSION)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Record a list of ticker changes, e.g. from
+    /// [`known_ticker_changes`](crate::security::known_ticker_changes).
+    pub fn put_ticker_changes(&self, changes: &[TickerChange]) -> Result<()> {
+        for change in changes {
+            self.record_ticker_change(
+                &change.security_id,
+                change.old_ticker,
+                change.new_ticker,
+                change.effective,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// All tickers a security has traded under, oldest first.
+    pub fn ticker_history(&self, security_id: &SecurityId) -> Result<Vec<TickerPeriod>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT security_id, ticker, start_date, end_date FROM ticker_history
+             WHERE security_id = ?1
+             ORDER BY start_date IS NOT NULL, start_date ASC",
+        )?;
+        let rows = stmt.query_map(params![security_id.to_string()], ticker_period_from_row)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// The security that traded under `ticker` on `date`.
+    pub fn resolve_ticker(&self, ticker: &str, date: NaiveDate) -> Result<Option<SecurityId>> {
+        Ok(self
+            .ticker_periods(ticker)?
+            .into_iter()
+            .find(|p| p.contains(date))
+            .map(|p| p.security_id))
+    }
+
+    /// The most recent security to have used `ticker`.
+    pub fn security_for_ticker(&self, ticker: &str) -> Result<Option<SecurityId>> {
+        Ok(self
+            .ticker_periods(ticker)?
+            .into_iter()
+            .next()
+            .map(|p| p.security_id))
+    }
+
+    /// The ticker a security traded under on `date`.
+    pub fn ticker_on(&self, security_id: &SecurityId, date: NaiveDate) -> Result<Option<String>> {
+        Ok(self
+            .ticker_history(security_id)?
+            .into_iter()
+            .find(|p| p.contains(date))
+            .map(|p| p.ticker))
+    }
+
+    /// Periods recorded for a ticker across securities, most recent first.
+    fn ticker_periods(&self, ticker: &str) -> Result<Vec<TickerPeriod>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT security_id, ticker, start_date, end_date FROM ticker_history
+             WHERE ticker = ?1
+             ORDER BY end_date IS NULL DESC, end_date DESC",
+        )?;
+        let rows = stmt.query_map(params![ticker], ticker_period_from_row)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Split a date range into the tickers the security behind `ticker`
+    /// traded under, stopping at its delisting date.
+    ///
+    /// Tickers without recorded history map to a single segment.
+    fn quote_segments(
+        &self,
+        ticker: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(String, NaiveDate, NaiveDate)>> {
+        let Some(security_id) = self.security_for_ticker(ticker)? else {
+            return Ok(vec![(ticker.to_string(), start, end)]);
+        };
+
+        let end = match self.get_delisting(&security_id)? {
+            Some(delisting) => delisting.date.pred_opt().unwrap_or(delisting.date).min(end),
+            None => end,
+        };
+
+        Ok(self
+            .ticker_history(&security_id)?
+            .into_iter()
+            .filter(|p| p.overlaps(start, end))
+            .map(|p| {
+                let from = p.start.map_or(start, |s| s.max(start));
+                let to = p.end.map_or(end, |e| e.min(end));
+                (p.ticker, from, to)
+            })
+            .collect())
+    }
+
+    /// Like [`has_quotes`](Self::has_quotes), but follows the security behind
+    /// `ticker` through earlier tickers and ends the range at its delisting.
+    pub fn has_stitched_quotes(
+        &self,
+        ticker: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<bool> {
+        let mut missing = Vec::new();
+        for (segment, from, to) in self.quote_segments(ticker, start, end)? {
+            missing.extend(self.uncovered_quote_dates(&segment, from, to)?);
+        }
+        Ok(quotes_complete(&missing, start, end))
+    }
+
+    /// Quotes for the security behind `ticker`, stitched across ticker
+    /// changes.
+    ///
+    /// Rows cached under earlier tickers are relabelled with `ticker`, so the
+    /// result reads as one continuous history. Quotes after a recorded
+    /// delisting are excluded.
+    pub fn get_stitched_quotes(
+        &self,
+        ticker: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        let mut frames = Vec::new();
+        for (segment, from, to) in self.quote_segments(ticker, start, end)? {
+            match self.get_quotes(&segment, from, to) {
+                Ok(df) => frames.push(df.lazy()),
+                Err(DataError::MissingData { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        if frames.is_empty() {
+            return Err(DataError::MissingData {
+                symbol: ticker.to_string(),
+                reason: "No cached data found".to_string(),
+            });
+        }
+
+        Ok(concat(frames, UnionArgs::default())?
+            .with_column(lit(ticker).alias("symbol"))
+            .sort(["date"], SortMultipleOptions::default())
+            .collect()?)
+    }
+
+    /// Financial statements for the security behind `ticker`, stitched across
+    /// ticker changes and relabelled with `ticker`, newest first.
+    ///
+    /// When the same period was cached under several tickers, the copy under
+    /// the most recent ticker wins.
+    pub fn get_stitched_financial_statements(
+        &self,
+        ticker: &str,
+    ) -> Result<Vec<FinancialStatement>> {
+        let Some(security_id) = self.security_for_ticker(ticker)? else {
+            return self.get_financial_statements(ticker);
+        };
+
+        let mut by_period = HashMap::new();
+        // Oldest ticker first so later tickers overwrite shared periods.
+        for period in self.ticker_history(&security_id)? {
+            for mut stmt in self.get_financial_statements(&period.ticker)? {
+                stmt.symbol = ticker.to_string();
+                by_period.insert((stmt.period_end, stmt.period_type.to_db_str()), stmt);
+            }
+        }
+
+        let mut statements: Vec<_> = by_period.into_values().collect();
+        statements.sort_by_key(|s| std::cmp::Reverse(s.period_end));
+        Ok(statements)
+    }
+
+    /// Record a delisting.
+    ///
+    /// A security without ticker history gets its delisted ticker, ending
+    /// the day before the delisting, so its quotes are looked up until then.
+    pub fn put_delisting(&self, delisting: &Delisting) -> Result<()> {
+        if self.ticker_history(&delisting.security_id)?.is_empty() {
+            self.put_ticker_period(&TickerPeriod {
+                security_id: delisting.security_id.clone(),
+                ticker: delisting.ticker.clone(),
+                start: None,
+                end: delisting.date.pred_opt(),
+            })?;
+        }
+        self.conn.execute(
+            "INSERT OR REPLACE INTO delistings (security_id, ticker, date, reason, delisting_return)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                delisting.security_id.to_string(),
+                delisting.ticker,
+                delisting.date.to_string(),
+                delisting.reason.to_db_str(),
+                delisting.delisting_return,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The delisting of a security, if it was delisted.
+    pub fn get_delisting(&self, security_id: &SecurityId) -> Result<Option<Delisting>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT security_id, ticker, date, reason, delisting_return FROM delistings
+                 WHERE security_id = ?1",
+                params![security_id.to_string()],
+                delisting_from_row,
+            )
+            .optional()?)
+    }
+
+    /// Delistings dated within a range, oldest first.
+    pub fn get_delistings(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<Delisting>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT security_id, ticker, date, reason, delisting_return FROM delistings
+             WHERE date >= ?1 AND date <= ?2
+             ORDER BY date ASC, ticker ASC",
+        )?;
+        let rows = stmt.query_map(
+            params![start.to_string(), end.to_string()],
+            delisting_from_row,
+        )?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Delisting returns within a range as a `symbol`, `date`,
+    /// `delisting_return` frame.
+    ///
+    /// Missing returns are filled with the default for the delisting reason,
+    /// so the rows can be appended to daily returns.
+    pub fn delisting_returns(&self, start: NaiveDate, end: NaiveDate) -> Result<DataFrame> {
+        let delistings = self.get_delistings(start, end)?;
+        let symbols: Vec<&str> = delistings.iter().map(|d| d.ticker.as_str()).collect();
+        let dates: Vec<String> = delistings.iter().map(|d| d.date.to_string()).collect();
+        let returns: Vec<f64> = delistings.iter().map(Delisting::effective_return).collect();
+
+        Ok(df! {
+            "symbol" => symbols,
+            "date" => dates,
+            "delisting_return" => returns,
+        }?
+        .lazy()
+        .with_column(col("date").cast(DataType::Date))
+        .collect()?)
+    }
+
+    /// Clear all cached data.
+    pub fn clear_all(&self) -> Result<()> {
+        self.conn.execute("DELETE FROM quotes", [])?;
+        self.conn.execute("DELETE FROM market_caps", [])?;
+        self.conn.execute("DELETE FROM fundamentals", [])?;
+        self.conn.execute("DELETE FROM dividends", [])?;
+        self.conn.execute("DELETE FROM dividend_coverage", [])?;
+        self.conn.execute("DELETE FROM quote_coverage", [])?;
+        self.conn.execute("DELETE FROM financial_statements", [])?;
+        self.conn.execute("DELETE FROM company_ciks", [])?;
+        self.conn.execute("DELETE FROM ticker_history", [])?;
+        self.conn.execute("DELETE FROM delistings", [])?;
+        Ok(())
+    }
+
+    /// Clear cached data for a specific symbol.
+    pub fn clear_symbol(&self, symbol: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM quotes WHERE symbol = ?1", params![symbol])?;
+        self.conn
+            .execute("DELETE FROM market_caps WHERE symbol = ?1", params![symbol])?;
+        self.conn.execute(
+            "DELETE FROM fundamentals WHERE symbol = ?1",
+            params![symbol],
+        )?;
+        self.conn
+            .execute("DELETE FROM dividends WHERE symbol = ?1", params![symbol])?;
+        self.conn.execute(
+            "DELETE FROM dividend_coverage WHERE symbol = ?1",
+            params![symbol],
+        )?;
+        self.conn.execute(
+            "DELETE FROM quote_coverage WHERE symbol = ?1",
+            params![symbol],
+        )?;
+        self.conn.execute(
+            "DELETE FROM financial_statements WHERE symbol = ?1",
+            params![symbol],
+        )?;
+        self.conn.execute(
+            "DELETE FROM company_ciks WHERE symbol = ?1",
+            params![symbol],
+        )?;
+        Ok(())
+    }
+
+    /// Get cache statistics.
+    pub fn get_stats(&self) -> Result<CacheStats> {
+        let quotes_count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM quotes", [], |row| row.get(0))?;
+
+        let symbols_count: i64 =
+            self.conn
+                .query_row("SELECT COUNT(DISTINCT symbol) FROM quotes", [], |row| {
+                    row.get(0)
+                })?;
+
+        let universe_count: i64 = self.conn.query_row(
+            "SELECT COUNT(DISTINCT symbol) FROM universe WHERE active = 1",
             [],
             |row| row.get(0),
         )?;
@@ -810,6 +1674,52 @@ impl SqliteCache {
     }
 }
 
+/// A spell of universe membership for one symbol, with inclusive dates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UniverseMembership {
+    /// Ticker symbol
+    pub symbol: String,
+    /// First day of membership (`None` if it predates recorded history)
+    pub start: Option<NaiveDate>,
+    /// Last day of membership (`None` while still a member)
+    pub end: Option<NaiveDate>,
+    /// Sector label, if known
+    pub sector: Option<String>,
+    /// Industry label, if known
+    pub industry: Option<String>,
+}
+
+impl UniverseMembership {
+    /// An open-ended membership starting on `start`.
+    pub fn new(symbol: impl Into<String>, start: Option<NaiveDate>) -> Self {
+        Self {
+            symbol: symbol.into(),
+            start,
+            end: None,
+            sector: None,
+            industry: None,
+        }
+    }
+
+    /// Whether the symbol was a member on `date`.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start.is_none_or(|start| start <= date) && self.end.is_none_or(|end| date <= end)
+    }
+}
+
+/// Sector and industry labels for a symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolClassification {
+    /// Ticker symbol
+    pub symbol: String,
+    /// Company name, if known
+    pub name: Option<String>,
+    /// Sector label
+    pub sector: String,
+    /// Industry label, if known
+    pub industry: Option<String>,
+}
+
 /// Cache statistics.
 #[derive(Debug, Clone)]
 pub struct CacheStats {
@@ -825,10 +1735,62 @@ pub struct CacheStats {
     pub cik_mappings: usize,
 }
 
+/// Whether quotes with these missing trading days count as cached for a
+/// date range: none may be missing except the last session.
+fn quotes_complete(missing: &[NaiveDate], start: NaiveDate, end: NaiveDate) -> bool {
+    match missing {
+        [] => true,
+        [only] => *only == NyseCalendar.trading_day_on_or_before(end) && *only > start,
+        _ => false,
+    }
+}
+
+/// Parse a `YYYY-MM-DD` column.
+fn parse_date(value: &str) -> rusqlite::Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// Parse an optional `YYYY-MM-DD` column.
+fn optional_date(value: Option<String>) -> rusqlite::Result<Option<NaiveDate>> {
+    value
+        .map(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d"))
+        .transpose()
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// Parse a security id column.
+fn security_id_column(row: &rusqlite::Row<'_>, idx: usize) -> rusqlite::Result<SecurityId> {
+    row.get::<_, String>(idx)?
+        .parse()
+        .map_err(|e: DataError| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+fn ticker_period_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<TickerPeriod> {
+    Ok(TickerPeriod {
+        security_id: security_id_column(row, 0)?,
+        ticker: row.get(1)?,
+        start: optional_date(row.get(2)?)?,
+        end: optional_date(row.get(3)?)?,
+    })
+}
+
+fn delisting_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Delisting> {
+    Ok(Delisting {
+        security_id: security_id_column(row, 0)?,
+        ticker: row.get(1)?,
+        date: NaiveDate::parse_from_str(&row.get::<_, String>(2)?, "%Y-%m-%d")
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?,
+        reason: DelistingReason::from_db_str(&row.get::<_, String>(3)?)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?,
+        delisting_return: row.get(4)?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use chrono::{Datelike, NaiveDate};
 
     #[test]
     fn test_cache_initialization() {
@@ -861,6 +1823,135 @@ mod tests {
         assert!(!universe.contains(&"AAPL".to_string()));
     }
 
+    #[test]
+    fn test_universe_memberships() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        let spell = |symbol: &str, start, end| UniverseMembership {
+            end,
+            sector: Some("Energy".to_string()),
+            ..UniverseMembership::new(symbol, start)
+        };
+        cache
+            .put_universe_memberships(&[
+                spell("XOM", None, None),
+                spell("OLD", Some(date(2015, 1, 2)), Some(date(2019, 12, 31))),
+                spell("OLD", Some(date(2021, 6, 1)), None),
+                spell("NEW", Some(date(2020, 3, 2)), None),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            cache.get_universe_on(date(2018, 1, 2)).unwrap(),
+            vec!["OLD", "XOM"]
+        );
+        assert_eq!(
+            cache.get_universe_on(date(2020, 6, 1)).unwrap(),
+            vec!["NEW", "XOM"]
+        );
+        assert_eq!(
+            cache.get_universe_on(date(2022, 1, 3)).unwrap(),
+            vec!["NEW", "OLD", "XOM"]
+        );
+        assert_eq!(cache.get_universe().unwrap(), vec!["NEW", "OLD", "XOM"]);
+
+        let memberships = cache.get_universe_memberships().unwrap();
+        assert_eq!(memberships.len(), 4);
+        assert_eq!(memberships[0].symbol, "NEW");
+        assert!(memberships[1].contains(date(2016, 1, 4)));
+        assert!(!memberships[1].contains(date(2020, 1, 2)));
+        assert_eq!(memberships[3].start, None);
+        assert_eq!(memberships[3].sector.as_deref(), Some("Energy"));
+
+        // Removing closes the open spells.
+        cache.remove_from_universe("OLD").unwrap();
+        assert!(
+            cache
+                .get_universe_memberships()
+                .unwrap()
+                .iter()
+                .all(|m| m.symbol != "OLD" || m.end.is_some())
+        );
+        assert_eq!(cache.get_universe().unwrap(), vec!["NEW", "XOM"]);
+    }
+
+    #[test]
+    fn test_classifications() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let classification = |symbol: &str, industry: &str| SymbolClassification {
+            symbol: symbol.to_string(),
+            name: None,
+            sector: "Information Technology".to_string(),
+            industry: Some(industry.to_string()),
+        };
+
+        // A classified non-member is not part of the universe.
+        cache
+            .put_classification(&classification("SMCI", "Technology Hardware"))
+            .unwrap();
+        assert_eq!(
+            cache.get_classification("SMCI").unwrap(),
+            Some(classification("SMCI", "Technology Hardware"))
+        );
+        assert!(cache.get_universe().unwrap().is_empty());
+        assert!(cache.get_universe_memberships().unwrap().is_empty());
+        assert!(
+            cache
+                .get_universe_on(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())
+                .unwrap()
+                .is_empty()
+        );
+
+        // Members keep their spells and pick up the classification.
+        cache
+            .add_to_universe("AAPL", Some("Apple Inc."), None, None)
+            .unwrap();
+        cache
+            .put_classification(&classification("AAPL", "Semiconductors"))
+            .unwrap();
+        let cached = cache.get_classification("AAPL").unwrap().unwrap();
+        assert_eq!(cached.name.as_deref(), Some("Apple Inc."));
+        assert_eq!(cached.industry.as_deref(), Some("Semiconductors"));
+        assert_eq!(cache.get_universe().unwrap(), vec!["AAPL"]);
+
+        assert_eq!(cache.get_classification("MSFT").unwrap(), None);
+    }
+
+    #[test]
+    fn test_universe_table_migration() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE universe (
+                symbol TEXT PRIMARY KEY,
+                name TEXT,
+                sector TEXT,
+                industry TEXT,
+                added_at TEXT NOT NULL,
+                active INTEGER NOT NULL DEFAULT 1
+            )",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO universe VALUES
+                ('AAPL', 'Apple Inc.', 'Technology', NULL, '2024-01-01T00:00:00+00:00', 1),
+                ('GONE', NULL, NULL, NULL, '2024-01-01T00:00:00+00:00', 0)",
+            [],
+        )
+        .unwrap();
+
+        let cache = SqliteCache { conn };
+        cache.initialize_schema().unwrap();
+
+        assert_eq!(cache.get_universe().unwrap(), vec!["AAPL"]);
+        let memberships = cache.get_universe_memberships().unwrap();
+        assert_eq!(memberships.len(), 2);
+        assert_eq!(memberships[0].sector.as_deref(), Some("Technology"));
+        assert_eq!(memberships[0].end, None);
+        assert!(memberships[1].end.is_some());
+    }
+
     #[test]
     fn test_market_cap_operations() {
         let cache = SqliteCache::in_memory().unwrap();
@@ -878,6 +1969,19 @@ mod tests {
         // Get non-existent market cap
         let market_cap = cache.get_market_cap("MSFT", date).unwrap();
         assert_eq!(market_cap, None);
+
+        // The latest value on or before a date carries forward
+        let later = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!(cache.get_market_cap("AAPL", later).unwrap(), None);
+        assert_eq!(
+            cache.get_market_cap_on_or_before("AAPL", later).unwrap(),
+            Some(3_000_000_000_000.0)
+        );
+        let earlier = NaiveDate::from_ymd_opt(2023, 12, 29).unwrap();
+        assert_eq!(
+            cache.get_market_cap_on_or_before("AAPL", earlier).unwrap(),
+            None
+        );
     }
 
     #[test]
@@ -892,6 +1996,497 @@ mod tests {
         assert_eq!(stats.cik_mappings, 0);
     }
 
+    #[test]
+    fn test_quote_gap_detection() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+
+        // Friday, then Tuesday and Wednesday around the MLK Day holiday.
+        let df = df! {
+            "symbol" => ["AAPL", "AAPL", "AAPL"],
+            "date" => ["2024-01-12", "2024-01-16", "2024-01-17"],
+            "open" => [1.0, 1.0, 1.0],
+            "high" => [1.0, 1.0, 1.0],
+            "low" => [1.0, 1.0, 1.0],
+            "close" => [1.0, 1.0, 1.0],
+            "volume" => [10u64, 10, 10],
+            "adjusted_close" => [1.0, 1.0, 1.0],
+        }
+        .unwrap();
+        cache.put_quotes(&df).unwrap();
+
+        assert!(cache.has_quotes("AAPL", date(12), date(17)).unwrap());
+        assert!(
+            cache
+                .missing_quote_dates("AAPL", date(12), date(17))
+                .unwrap()
+                .is_empty()
+        );
+
+        // The final session may still be missing.
+        assert!(cache.has_quotes("AAPL", date(12), date(18)).unwrap());
+
+        // A gap inside the range is not.
+        assert_eq!(
+            cache
+                .missing_quote_dates("AAPL", date(10), date(17))
+                .unwrap(),
+            vec![date(10), date(11)]
+        );
+        assert!(!cache.has_quotes("AAPL", date(10), date(17)).unwrap());
+        assert_eq!(cache.get_quote_symbols().unwrap(), vec!["AAPL"]);
+    }
+
+    fn flat_quotes(symbol: &str, dates: &[&str], close: f64) -> DataFrame {
+        let n = dates.len();
+        df! {
+            "symbol" => vec![symbol; n],
+            "date" => dates,
+            "open" => vec![close; n],
+            "high" => vec![close; n],
+            "low" => vec![close; n],
+            "close" => vec![close; n],
+            "volume" => vec![10u64; n],
+            "adjusted_close" => vec![close; n],
+        }
+        .unwrap()
+    }
+
+    #[test]
+    fn test_late_listing_stays_cached() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+
+        // Fetched from 2 Jan, but the first quote is the 10 Jan listing.
+        let df = flat_quotes("NEWCO", &["2024-01-10", "2024-01-11", "2024-01-12"], 1.0);
+        cache.put_quotes(&df).unwrap();
+        assert!(!cache.has_quotes("NEWCO", date(2), date(12)).unwrap());
+
+        cache.put_fetched_quotes("NEWCO", date(2), &df).unwrap();
+        assert_eq!(
+            cache.get_quote_coverage("NEWCO").unwrap(),
+            Some((date(2), date(12)))
+        );
+        assert!(cache.has_quotes("NEWCO", date(2), date(12)).unwrap());
+        assert!(
+            cache
+                .has_stitched_quotes("NEWCO", date(2), date(12))
+                .unwrap()
+        );
+
+        // Earlier than the fetch is still unknown, as are sessions after
+        // the last quote.
+        assert!(
+            !cache
+                .has_quotes("NEWCO", date(2) - Days::new(7), date(12))
+                .unwrap()
+        );
+        assert!(!cache.has_quotes("NEWCO", date(2), date(19)).unwrap());
+
+        cache.clear_symbol("NEWCO").unwrap();
+        assert_eq!(cache.get_quote_coverage("NEWCO").unwrap(), None);
+    }
+
+    #[test]
+    fn test_halt_stays_cached() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+
+        // Halted 4 and 5 Jan.
+        let df = flat_quotes(
+            "HALT",
+            &["2024-01-02", "2024-01-03", "2024-01-08", "2024-01-09"],
+            1.0,
+        );
+        cache.put_fetched_quotes("HALT", date(2), &df).unwrap();
+        assert_eq!(
+            cache.missing_quote_dates("HALT", date(2), date(9)).unwrap(),
+            vec![date(4), date(5)]
+        );
+        assert!(cache.has_quotes("HALT", date(2), date(9)).unwrap());
+
+        // A later fetch that adjoins the coverage extends it.
+        let later = flat_quotes("HALT", &["2024-01-10", "2024-01-11"], 1.0);
+        cache.put_fetched_quotes("HALT", date(10), &later).unwrap();
+        assert_eq!(
+            cache.get_quote_coverage("HALT").unwrap(),
+            Some((date(2), date(11)))
+        );
+        assert!(cache.has_quotes("HALT", date(2), date(11)).unwrap());
+    }
+
+    #[test]
+    fn test_reference_data_seeded_once() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let meta = SecurityId::from_cik("1326801");
+
+        cache.seed_reference_data().unwrap();
+        assert_eq!(cache.ticker_history(&meta).unwrap().len(), 2);
+        assert_eq!(
+            cache
+                .get_delisting(&SecurityId::from_cik("1418091"))
+                .unwrap()
+                .map(|d| d.date),
+            NaiveDate::from_ymd_opt(2022, 10, 28)
+        );
+
+        // A seeded cache is left alone.
+        cache
+            .conn
+            .execute("DELETE FROM ticker_history", [])
+            .unwrap();
+        cache.seed_reference_data().unwrap();
+        assert!(cache.ticker_history(&meta).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_seeded_delisting_ends_quote_lookups() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let date = |m, d| NaiveDate::from_ymd_opt(2023, m, d).unwrap();
+        cache.seed_reference_data().unwrap();
+
+        // Quotes fetched while ATVI traded cover every day it can be quoted.
+        let df = flat_quotes("ATVI", &["2023-10-10", "2023-10-11", "2023-10-12"], 94.4);
+        cache.put_fetched_quotes("ATVI", date(10, 10), &df).unwrap();
+        assert!(
+            cache
+                .has_stitched_quotes("ATVI", date(10, 10), date(12, 29))
+                .unwrap()
+        );
+        assert_eq!(
+            cache
+                .get_stitched_quotes("ATVI", date(10, 10), date(12, 29))
+                .unwrap()
+                .height(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_ticker_history() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let meta = SecurityId::from_cik("1326801");
+        let date = |m, d| NaiveDate::from_ymd_opt(2022, m, d).unwrap();
+
+        cache
+            .put_ticker_changes(&crate::security::known_ticker_changes())
+            .unwrap();
+
+        let history = cache.ticker_history(&meta).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].ticker, "FB");
+        assert_eq!(history[0].end, Some(date(6, 8)));
+        assert_eq!(history[1].ticker, "META");
+        assert_eq!(history[1].start, Some(date(6, 9)));
+        assert_eq!(history[1].end, None);
+
+        assert_eq!(
+            cache.resolve_ticker("FB", date(1, 3)).unwrap(),
+            Some(meta.clone())
+        );
+        assert_eq!(cache.resolve_ticker("FB", date(6, 9)).unwrap(), None);
+        assert_eq!(
+            cache.ticker_on(&meta, date(1, 3)).unwrap().as_deref(),
+            Some("FB")
+        );
+        assert_eq!(cache.security_for_ticker("META").unwrap(), Some(meta));
+
+        // Re-recording the same change is idempotent.
+        cache
+            .put_ticker_changes(&crate::security::known_ticker_changes())
+            .unwrap();
+        assert_eq!(
+            cache
+                .ticker_history(&SecurityId::from_cik("1326801"))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_stitched_quotes_across_ticker_change() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2022, 6, d).unwrap();
+        cache
+            .record_ticker_change(&SecurityId::from_cik("1326801"), "FB", "META", date(9))
+            .unwrap();
+
+        cache
+            .put_quotes(&flat_quotes("FB", &["2022-06-07", "2022-06-08"], 190.0))
+            .unwrap();
+        cache
+            .put_quotes(&flat_quotes("META", &["2022-06-09", "2022-06-10"], 185.0))
+            .unwrap();
+
+        assert!(!cache.has_quotes("META", date(7), date(10)).unwrap());
+        assert!(
+            cache
+                .has_stitched_quotes("META", date(7), date(10))
+                .unwrap()
+        );
+
+        let stitched = cache
+            .get_stitched_quotes("META", date(7), date(10))
+            .unwrap();
+        assert_eq!(stitched.height(), 4);
+        let symbols = stitched.column("symbol").unwrap().str().unwrap();
+        assert!(symbols.into_iter().all(|s| s == Some("META")));
+        let closes = stitched.column("close").unwrap().f64().unwrap();
+        assert_eq!(closes.get(0), Some(190.0));
+        assert_eq!(closes.get(3), Some(185.0));
+
+        // Tickers without history behave like plain lookups.
+        cache
+            .put_quotes(&flat_quotes("AAPL", &["2022-06-07"], 150.0))
+            .unwrap();
+        assert_eq!(
+            cache
+                .get_stitched_quotes("AAPL", date(7), date(10))
+                .unwrap()
+                .height(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_stitched_financial_statements() {
+        let cache = SqliteCache::in_memory().unwrap();
+        cache
+            .record_ticker_change(
+                &SecurityId::from_cik("1326801"),
+                "FB",
+                "META",
+                NaiveDate::from_ymd_opt(2022, 6, 9).unwrap(),
+            )
+            .unwrap();
+
+        let statement = |symbol: &str, year| FinancialStatement {
+            symbol: symbol.to_string(),
+            cik: "0001326801".to_string(),
+            period_end: NaiveDate::from_ymd_opt(year, 12, 31).unwrap(),
+            period_type: PeriodType::Annual,
+            fiscal_year: year,
+            fiscal_quarter: None,
+            total_assets: Some(1.0),
+            total_liabilities: None,
+            stockholders_equity: None,
+            long_term_debt: None,
+            current_assets: None,
+            current_liabilities: None,
+            cash_and_equivalents: None,
+            revenue: None,
+            net_income: None,
+            operating_income: None,
+            gross_profit: None,
+            eps_basic: None,
+            eps_diluted: None,
+            operating_cash_flow: None,
+            capital_expenditures: None,
+            free_cash_flow: None,
+            shares_outstanding: None,
+            shares_outstanding_diluted: None,
+            cached_at: Utc::now(),
+        };
+        cache
+            .put_financial_statements_batch(&[
+                statement("FB", 2020),
+                statement("FB", 2021),
+                statement("META", 2021),
+                statement("META", 2022),
+            ])
+            .unwrap();
+
+        let statements = cache.get_stitched_financial_statements("META").unwrap();
+        let years: Vec<i32> = statements.iter().map(|s| s.fiscal_year).collect();
+        assert_eq!(years, vec![2022, 2021, 2020]);
+        assert!(statements.iter().all(|s| s.symbol == "META"));
+    }
+
+    #[test]
+    fn test_dividend_history() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let dividend = |ex_date, amount| Dividend { ex_date, amount };
+
+        // Never fetched is unknown, fetched without dividends is a non-payer
+        assert_eq!(cache.get_dividend_history("AAPL").unwrap(), None);
+        let none_paid = DividendHistory {
+            start: date(2024, 1, 1),
+            end: date(2024, 6, 30),
+            dividends: Vec::new(),
+        };
+        cache.put_dividend_history("BRK-B", &none_paid).unwrap();
+        assert_eq!(
+            cache.get_dividend_history("BRK-B").unwrap(),
+            Some(none_paid)
+        );
+
+        cache
+            .put_dividend_history(
+                "AAPL",
+                &DividendHistory {
+                    start: date(2024, 1, 1),
+                    end: date(2024, 6, 30),
+                    dividends: vec![dividend(date(2024, 2, 9), 0.24)],
+                },
+            )
+            .unwrap();
+
+        // An overlapping fetch extends the coverage
+        cache
+            .put_dividend_history(
+                "AAPL",
+                &DividendHistory {
+                    start: date(2024, 6, 1),
+                    end: date(2024, 12, 31),
+                    dividends: vec![dividend(date(2024, 8, 12), 0.25)],
+                },
+            )
+            .unwrap();
+        let history = cache.get_dividend_history("AAPL").unwrap().unwrap();
+        assert_eq!(
+            (history.start, history.end),
+            (date(2024, 1, 1), date(2024, 12, 31))
+        );
+        assert_eq!(history.dividends.len(), 2);
+
+        // A disjoint fetch replaces it
+        cache
+            .put_dividend_history(
+                "AAPL",
+                &DividendHistory {
+                    start: date(2025, 6, 1),
+                    end: date(2025, 12, 31),
+                    dividends: vec![dividend(date(2025, 8, 11), 0.26)],
+                },
+            )
+            .unwrap();
+        let history = cache.get_dividend_history("AAPL").unwrap().unwrap();
+        assert_eq!(history.start, date(2025, 6, 1));
+        assert_eq!(history.dividends, vec![dividend(date(2025, 8, 11), 0.26)]);
+
+        cache.clear_symbol("AAPL").unwrap();
+        assert_eq!(cache.get_dividend_history("AAPL").unwrap(), None);
+    }
+
+    #[test]
+    fn test_trailing_eps_diluted() {
+        let statement = |period_end: NaiveDate, period_type, eps| FinancialStatement {
+            symbol: "AAPL".to_string(),
+            cik: "0000320193".to_string(),
+            period_end,
+            period_type,
+            fiscal_year: period_end.year(),
+            fiscal_quarter: None,
+            total_assets: None,
+            total_liabilities: None,
+            stockholders_equity: None,
+            long_term_debt: None,
+            current_assets: None,
+            current_liabilities: None,
+            cash_and_equivalents: None,
+            revenue: None,
+            net_income: None,
+            operating_income: None,
+            gross_profit: None,
+            eps_basic: None,
+            eps_diluted: eps,
+            operating_cash_flow: None,
+            capital_expenditures: None,
+            free_cash_flow: None,
+            shares_outstanding: None,
+            shares_outstanding_diluted: None,
+            cached_at: Utc::now(),
+        };
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let statements = [
+            statement(date(2023, 9, 30), PeriodType::Annual, Some(6.13)),
+            statement(date(2023, 12, 30), PeriodType::Quarterly, Some(2.18)),
+            statement(date(2024, 9, 28), PeriodType::Annual, Some(6.08)),
+        ];
+
+        // The 2024 report is not public until 90 days after year end
+        assert_eq!(
+            trailing_eps_diluted(&statements, date(2024, 12, 1), 90),
+            Some(6.13)
+        );
+        assert_eq!(
+            trailing_eps_diluted(&statements, date(2024, 12, 27), 90),
+            Some(6.08)
+        );
+        // Before the first report, and long after the last, EPS is unknown
+        assert_eq!(
+            trailing_eps_diluted(&statements, date(2023, 12, 1), 90),
+            None
+        );
+        assert_eq!(
+            trailing_eps_diluted(&statements, date(2026, 6, 1), 90),
+            None
+        );
+    }
+
+    #[test]
+    fn test_delistings() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let date = |m, d| NaiveDate::from_ymd_opt(2022, m, d).unwrap();
+        let twitter = SecurityId::from_cik("1418091");
+
+        cache
+            .put_ticker_period(&TickerPeriod {
+                security_id: twitter.clone(),
+                ticker: "TWTR".to_string(),
+                start: None,
+                end: None,
+            })
+            .unwrap();
+        cache
+            .put_quotes(&flat_quotes("TWTR", &["2022-10-26", "2022-10-27"], 53.7))
+            .unwrap();
+        cache
+            .put_delisting(&Delisting {
+                security_id: twitter.clone(),
+                ticker: "TWTR".to_string(),
+                date: date(10, 28),
+                reason: DelistingReason::Merger,
+                delisting_return: Some(54.20 / 53.70 - 1.0),
+            })
+            .unwrap();
+        cache
+            .put_delisting(&Delisting {
+                security_id: SecurityId::from_cik("1"),
+                ticker: "GONE".to_string(),
+                date: date(11, 1),
+                reason: DelistingReason::Performance,
+                delisting_return: None,
+            })
+            .unwrap();
+
+        // No quotes are expected after the delisting date.
+        assert!(
+            cache
+                .has_stitched_quotes("TWTR", date(10, 26), date(11, 30))
+                .unwrap()
+        );
+        assert_eq!(
+            cache.get_delisting(&twitter).unwrap().unwrap().reason,
+            DelistingReason::Merger
+        );
+
+        let returns = cache.delisting_returns(date(10, 1), date(11, 30)).unwrap();
+        assert_eq!(returns.height(), 2);
+        let values = returns.column("delisting_return").unwrap().f64().unwrap();
+        assert!((values.get(0).unwrap() - 0.00931).abs() < 1e-4);
+        assert_eq!(values.get(1), Some(-0.30));
+
+        assert!(
+            cache
+                .get_delistings(date(11, 2), date(11, 30))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_cik_operations() {
         let cache = SqliteCache::in_memory().unwrap();
@@ -0,0 +1,387 @@
+//! Exchange trading calendars.
+//!
+//! The [`ExchangeCalendar`] trait provides business-day arithmetic on top of
+//! two exchange-specific rules: which weekdays are holidays and which sessions
+//! close early. [`NyseCalendar`] implements the NYSE rules, including
+//! observed-holiday shifts, Good Friday and one-off closures such as national
+//! days of mourning.
+//!
+//! # Example
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use perth_data::calendar::{ExchangeCalendar, NyseCalendar};
+//!
+//! let nyse = NyseCalendar;
+//! let july_4 = NaiveDate::from_ymd_opt(2024, 7, 4).unwrap();
+//! assert!(!nyse.is_trading_day(july_4));
+//! assert!(nyse.is_early_close(NaiveDate::from_ymd_opt(2024, 7, 3).unwrap()));
+//! assert_eq!(
+//!     nyse.n_trading_days_back(NaiveDate::from_ymd_opt(2024, 7, 8).unwrap(), 3),
+//!     NaiveDate::from_ymd_opt(2024, 7, 2).unwrap()
+//! );
+//! ```
+
+use crate::error::Result;
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
+use polars::prelude::*;
+
+/// Trading days in a typical NYSE year, used to convert years to sessions.
+pub const TRADING_DAYS_PER_YEAR: u32 = 252;
+
+/// An exchange's trading calendar.
+///
+/// Implementors define holidays and early closes; every other method is
+/// derived from those and the Monday–Friday trading week.
+pub trait ExchangeCalendar {
+    /// Whether the exchange is closed for a holiday on this weekday.
+    fn is_holiday(&self, date: NaiveDate) -> bool;
+
+    /// Closing time for an early-close session, if `date` is one.
+    fn early_close(&self, date: NaiveDate) -> Option<NaiveTime>;
+
+    /// Regular closing time in exchange-local time.
+    fn regular_close(&self) -> NaiveTime;
+
+    /// Whether the exchange holds a session on `date`.
+    fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(date)
+    }
+
+    /// Whether `date` is a trading day with a shortened session.
+    fn is_early_close(&self, date: NaiveDate) -> bool {
+        self.is_trading_day(date) && self.early_close(date).is_some()
+    }
+
+    /// Closing time on `date`, or `None` when the exchange is closed.
+    fn close_time(&self, date: NaiveDate) -> Option<NaiveTime> {
+        self.is_trading_day(date).then(|| {
+            self.early_close(date)
+                .unwrap_or_else(|| self.regular_close())
+        })
+    }
+
+    /// First trading day strictly after `date`.
+    fn next_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut day = date.succ_opt().expect("date in range");
+        while !self.is_trading_day(day) {
+            day = day.succ_opt().expect("date in range");
+        }
+        day
+    }
+
+    /// Last trading day strictly before `date`.
+    fn previous_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut day = date.pred_opt().expect("date in range");
+        while !self.is_trading_day(day) {
+            day = day.pred_opt().expect("date in range");
+        }
+        day
+    }
+
+    /// `date` itself if it is a trading day, otherwise the previous one.
+    fn trading_day_on_or_before(&self, date: NaiveDate) -> NaiveDate {
+        if self.is_trading_day(date) {
+            date
+        } else {
+            self.previous_trading_day(date)
+        }
+    }
+
+    /// The trading day `n` sessions before `date`.
+    ///
+    /// Counting starts from the last trading day on or before `date`, so
+    /// `n = 0` returns that day.
+    fn n_trading_days_back(&self, date: NaiveDate, n: u32) -> NaiveDate {
+        (0..n).fold(self.trading_day_on_or_before(date), |day, _| {
+            self.previous_trading_day(day)
+        })
+    }
+
+    /// The trading day `n` sessions after `date`.
+    fn n_trading_days_forward(&self, date: NaiveDate, n: u32) -> NaiveDate {
+        (0..n).fold(date, |day, _| self.next_trading_day(day))
+    }
+
+    /// All trading days in `[start, end]`, in order.
+    fn trading_days(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        start
+            .iter_days()
+            .take_while(|day| *day <= end)
+            .filter(|day| self.is_trading_day(*day))
+            .collect()
+    }
+
+    /// Number of trading days in `[start, end]`.
+    fn count_trading_days(&self, start: NaiveDate, end: NaiveDate) -> usize {
+        self.trading_days(start, end).len()
+    }
+
+    /// Trading days in `[start, end]` as a DataFrame.
+    ///
+    /// Columns are `date` (Date) and `trading_day` (Int64), a running session
+    /// index. Consecutive sessions differ by exactly one, so joining quotes
+    /// onto this frame both drops non-trading dates and exposes gaps.
+    fn trading_day_frame(&self, start: NaiveDate, end: NaiveDate) -> Result<DataFrame> {
+        let days = self.trading_days(start, end);
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date");
+        let dates: Vec<i32> = days
+            .iter()
+            .map(|day| (*day - epoch).num_days() as i32)
+            .collect();
+        let index: Vec<i64> = (0..days.len() as i64).collect();
+
+        let df = DataFrame::new(vec![
+            Series::new("date".into(), dates)
+                .cast(&DataType::Date)?
+                .into(),
+            Series::new("trading_day".into(), index).into(),
+        ])?;
+        Ok(df)
+    }
+}
+
+/// New York Stock Exchange calendar.
+///
+/// Holidays: New Year's Day, Martin Luther King Jr. Day, Washington's
+/// Birthday, Good Friday, Memorial Day, Juneteenth (from 2022), Independence
+/// Day, Labor Day, Thanksgiving and Christmas. Saturday holidays are observed
+/// the Friday before (except New Year's Day) and Sunday holidays the Monday
+/// after. Early closes at 13:00 fall on July 3, the day after Thanksgiving
+/// and Christmas Eve when those are trading days.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NyseCalendar;
+
+/// Unscheduled full-day NYSE closures.
+const NYSE_SPECIAL_CLOSURES: &[(i32, u32, u32)] = &[
+    (2001, 9, 11),
+    (2001, 9, 12),
+    (2001, 9, 13),
+    (2001, 9, 14),
+    (2004, 6, 11),
+    (2007, 1, 2),
+    (2012, 10, 29),
+    (2012, 10, 30),
+    (2018, 12, 5),
+    (2025, 1, 9),
+];
+
+impl NyseCalendar {
+    fn observed(date: NaiveDate) -> NaiveDate {
+        match date.weekday() {
+            Weekday::Sat => date.pred_opt().expect("date in range"),
+            Weekday::Sun => date.succ_opt().expect("date in range"),
+            _ => date,
+        }
+    }
+
+    fn holidays(year: i32) -> Vec<NaiveDate> {
+        let ymd = |m, d| NaiveDate::from_ymd_opt(year, m, d).expect("valid date");
+
+        let mut holidays = vec![
+            nth_weekday(year, 1, Weekday::Mon, 3),
+            nth_weekday(year, 2, Weekday::Mon, 3),
+            easter_sunday(year) - chrono::Duration::days(2),
+            last_weekday(year, 5, Weekday::Mon),
+            Self::observed(ymd(7, 4)),
+            nth_weekday(year, 9, Weekday::Mon, 1),
+            nth_weekday(year, 11, Weekday::Thu, 4),
+            Self::observed(ymd(12, 25)),
+        ];
+
+        // New Year's Day falling on a Saturday is not observed on Friday.
+        let new_year = ymd(1, 1);
+        if new_year.weekday() != Weekday::Sat {
+            holidays.push(Self::observed(new_year));
+        }
+
+        if year >= 2022 {
+            holidays.push(Self::observed(ymd(6, 19)));
+        }
+
+        holidays.extend(
+            NYSE_SPECIAL_CLOSURES
+                .iter()
+                .filter(|(y, _, _)| *y == year)
+                .map(|&(_, m, d)| ymd(m, d)),
+        );
+        holidays
+    }
+}
+
+impl ExchangeCalendar for NyseCalendar {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        Self::holidays(date.year()).contains(&date)
+    }
+
+    fn early_close(&self, date: NaiveDate) -> Option<NaiveTime> {
+        let year = date.year();
+        let ymd = |m, d| NaiveDate::from_ymd_opt(year, m, d).expect("valid date");
+
+        let day_after_thanksgiving = nth_weekday(year, 11, Weekday::Thu, 4)
+            .succ_opt()
+            .expect("date in range");
+        let early = date == ymd(7, 3) || date == day_after_thanksgiving || date == ymd(12, 24);
+
+        if early && self.is_trading_day(date) {
+            NaiveTime::from_hms_opt(13, 0, 0)
+        } else {
+            None
+        }
+    }
+
+    fn regular_close(&self) -> NaiveTime {
+        NaiveTime::from_hms_opt(16, 0, 0).expect("valid time")
+    }
+}
+
+/// The `n`-th (1-based) `weekday` of a month.
+const fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n).expect("valid date")
+}
+
+/// The last `weekday` of a month.
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, 5)
+        .unwrap_or_else(|| nth_weekday(year, month, weekday, 4))
+}
+
+/// Western Easter Sunday (anonymous Gregorian algorithm).
+const fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).expect("valid date")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_easter() {
+        assert_eq!(easter_sunday(2024), date(2024, 3, 31));
+        assert_eq!(easter_sunday(2025), date(2025, 4, 20));
+        assert_eq!(easter_sunday(2019), date(2019, 4, 21));
+    }
+
+    #[test]
+    fn test_nyse_holidays_2024() {
+        let nyse = NyseCalendar;
+        let expected = [
+            date(2024, 1, 1),
+            date(2024, 1, 15),
+            date(2024, 2, 19),
+            date(2024, 3, 29),
+            date(2024, 5, 27),
+            date(2024, 6, 19),
+            date(2024, 7, 4),
+            date(2024, 9, 2),
+            date(2024, 11, 28),
+            date(2024, 12, 25),
+        ];
+        for day in expected {
+            assert!(!nyse.is_trading_day(day), "{day} should be a holiday");
+        }
+        // 2024 has 252 sessions.
+        assert_eq!(
+            nyse.count_trading_days(date(2024, 1, 1), date(2024, 12, 31)),
+            252
+        );
+    }
+
+    #[test]
+    fn test_observed_holidays() {
+        let nyse = NyseCalendar;
+        // Independence Day 2026 is a Saturday, observed Friday.
+        assert!(nyse.is_holiday(date(2026, 7, 3)));
+        // Christmas 2022 is a Sunday, observed Monday.
+        assert!(nyse.is_holiday(date(2022, 12, 26)));
+        // New Year's Day 2022 is a Saturday and is not observed.
+        assert!(nyse.is_trading_day(date(2021, 12, 31)));
+        // Juneteenth only from 2022.
+        assert!(nyse.is_trading_day(date(2021, 6, 18)));
+        assert!(nyse.is_holiday(date(2023, 6, 19)));
+    }
+
+    #[test]
+    fn test_special_closures() {
+        let nyse = NyseCalendar;
+        assert!(!nyse.is_trading_day(date(2012, 10, 29)));
+        assert!(!nyse.is_trading_day(date(2025, 1, 9)));
+    }
+
+    #[test]
+    fn test_early_closes() {
+        let nyse = NyseCalendar;
+        assert!(nyse.is_early_close(date(2024, 7, 3)));
+        assert!(nyse.is_early_close(date(2024, 11, 29)));
+        assert!(nyse.is_early_close(date(2024, 12, 24)));
+        assert_eq!(
+            nyse.close_time(date(2024, 12, 24)),
+            NaiveTime::from_hms_opt(13, 0, 0)
+        );
+        assert_eq!(
+            nyse.close_time(date(2024, 12, 23)),
+            NaiveTime::from_hms_opt(16, 0, 0)
+        );
+        // July 3, 2026 is the observed Independence Day, not an early close.
+        assert!(!nyse.is_early_close(date(2026, 7, 3)));
+        assert_eq!(nyse.close_time(date(2024, 12, 25)), None);
+    }
+
+    #[test]
+    fn test_trading_day_arithmetic() {
+        let nyse = NyseCalendar;
+        // Friday before a Monday holiday.
+        assert_eq!(nyse.next_trading_day(date(2024, 1, 12)), date(2024, 1, 16));
+        assert_eq!(
+            nyse.previous_trading_day(date(2024, 1, 16)),
+            date(2024, 1, 12)
+        );
+        // Counting back from a weekend starts at Friday.
+        assert_eq!(
+            nyse.n_trading_days_back(date(2024, 1, 14), 0),
+            date(2024, 1, 12)
+        );
+        assert_eq!(
+            nyse.n_trading_days_back(date(2024, 1, 17), 2),
+            date(2024, 1, 12)
+        );
+        assert_eq!(
+            nyse.n_trading_days_forward(date(2024, 1, 12), 2),
+            date(2024, 1, 17)
+        );
+        assert_eq!(
+            nyse.n_trading_days_back(date(2024, 12, 31), TRADING_DAYS_PER_YEAR - 1),
+            date(2024, 1, 2)
+        );
+    }
+
+    #[test]
+    fn test_trading_day_frame() {
+        let nyse = NyseCalendar;
+        let frame = nyse
+            .trading_day_frame(date(2024, 1, 12), date(2024, 1, 17))
+            .unwrap();
+        assert_eq!(frame.height(), 3);
+        assert_eq!(frame.column("date").unwrap().dtype(), &DataType::Date);
+        let index = frame.column("trading_day").unwrap().i64().unwrap();
+        assert_eq!(index.into_no_null_iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+}
@@ -1,18 +1,20 @@
 //! SEC EDGAR API client with rate limiting.
 
 use crate::error::{DataError, Result};
+use crate::retry::{RateLimiter, RetryConfig, RetryPolicy, transient_status_error};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Mutex;
-use tokio::time::{Instant, sleep};
 
 /// SEC EDGAR API base URL
 const EDGAR_BASE_URL: &str = "https://data.sec.gov";
 
+/// SEC website base URL (ticker list and filing archives)
+const SEC_WWW_URL: &str = "https://www.sec.gov";
+
 /// Default rate limit: 10 requests per second (SEC requirement)
-const DEFAULT_RATE_LIMIT: Duration = Duration::from_millis(100);
+pub(crate) const DEFAULT_RATE_LIMIT: Duration = Duration::from_millis(100);
 
 /// User agent for SEC EDGAR requests (SEC requires identifying information)
 const USER_AGENT: &str = "Perth-FactorModel/0.1 (contact@example.com)";
@@ -37,10 +39,23 @@ pub struct CompanyFilings {
     pub cik: String,
     /// Company name
     pub name: String,
+    /// Standard Industrial Classification code, e.g. `"3571"`
+    #[serde(default)]
+    pub sic: Option<String>,
+    /// Description of the SIC code, e.g. `"Electronic Computers"`
+    #[serde(default, rename = "sicDescription")]
+    pub sic_description: Option<String>,
     /// Filing history container
     pub filings: FilingsContainer,
 }
 
+impl CompanyFilings {
+    /// The SIC code as a number, if the filer has one.
+    pub fn sic_code(&self) -> Option<u16> {
+        self.sic.as_deref().and_then(|sic| sic.trim().parse().ok())
+    }
+}
+
 /// Container for filings data
 #[derive(Debug, Clone, Deserialize)]
 pub struct FilingsContainer {
@@ -65,34 +80,13 @@ pub struct FilingsRecent {
     pub primary_document: Vec<String>,
 }
 
-/// Rate limiter to ensure we don't exceed SEC's rate limits
-struct RateLimiter {
-    last_request: Instant,
-    min_interval: Duration,
-}
-
-impl RateLimiter {
-    fn new(min_interval: Duration) -> Self {
-        Self {
-            last_request: Instant::now() - min_interval,
-            min_interval,
-        }
-    }
-
-    async fn wait(&mut self) {
-        let elapsed = self.last_request.elapsed();
-        if elapsed < self.min_interval {
-            sleep(self.min_interval - elapsed).await;
-        }
-        self.last_request = Instant::now();
-    }
-}
-
-/// SEC EDGAR API client with rate limiting
+/// SEC EDGAR API client with rate limiting and retries
 pub struct EdgarClient {
     client: reqwest::Client,
-    rate_limiter: Arc<Mutex<RateLimiter>>,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
     base_url: String,
+    www_url: String,
 }
 
 impl EdgarClient {
@@ -124,13 +118,63 @@ impl EdgarClient {
             .build()
             .map_err(DataError::Network)?;
 
+        let rate_limiter = Arc::new(RateLimiter::new(min_interval));
+
         Ok(Self {
             client,
-            rate_limiter: Arc::new(Mutex::new(RateLimiter::new(min_interval))),
+            retry_policy: RetryPolicy::new(RetryConfig::default())
+                .with_rate_limiter(Arc::clone(&rate_limiter)),
+            rate_limiter,
             base_url: EDGAR_BASE_URL.to_string(),
+            www_url: SEC_WWW_URL.to_string(),
         })
     }
 
+    /// Replace the retry configuration used for CIK and filings lookups
+    ///
+    /// # Example
+    /// ```no_run
+    /// use perth_data::edgar::EdgarClient;
+    /// use perth_data::retry::RetryConfig;
+    ///
+    /// # fn example() -> perth_data::Result<()> {
+    /// let client = EdgarClient::new()?.with_retry_config(RetryConfig {
+    ///     max_attempts: 6,
+    ///     ..RetryConfig::default()
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_retry_config(mut self, config: RetryConfig) -> Self {
+        self.retry_policy =
+            RetryPolicy::new(config).with_rate_limiter(Arc::clone(&self.rate_limiter));
+        self
+    }
+
+    /// Send `data.sec.gov` requests (submissions) to a different host
+    ///
+    /// # Example
+    /// ```no_run
+    /// use perth_data::edgar::EdgarClient;
+    ///
+    /// # fn example() -> perth_data::Result<()> {
+    /// let client = EdgarClient::new()?
+    ///     .with_base_url("http://127.0.0.1:8080")
+    ///     .with_www_url("http://127.0.0.1:8080");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Send `www.sec.gov` requests (company tickers, filing archives) to a different host
+    pub fn with_www_url(mut self, www_url: impl Into<String>) -> Self {
+        self.www_url = www_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     /// Look up a company's CIK number from its ticker symbol
     ///
     /// # Arguments
@@ -161,30 +205,38 @@ impl EdgarClient {
 
         let ticker_upper = ticker.to_uppercase();
 
-        // Rate limit
-        self.rate_limiter.lock().await.wait().await;
-
         // Fetch company tickers JSON (note: hosted at www.sec.gov, not data.sec.gov)
-        let url = "https://www.sec.gov/files/company_tickers.json".to_string();
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(DataError::Network)?;
-
-        if !response.status().is_success() {
-            return Err(DataError::EdgarApi(format!(
-                "Failed to fetch company tickers: HTTP {}",
-                response.status()
-            )));
-        }
-
-        // Parse as a map of index -> CompanyInfo
-        let data: HashMap<String, CompanyInfo> = response
-            .json()
-            .await
-            .map_err(|e| DataError::EdgarApi(format!("Failed to parse company tickers: {}", e)))?;
+        let url = format!("{}/files/company_tickers.json", self.www_url);
+        let data: HashMap<String, CompanyInfo> = self
+            .retry_policy
+            .execute("SEC company tickers", || async {
+                // Rate limit
+                self.rate_limiter.wait().await;
+
+                let response = self
+                    .client
+                    .get(&url)
+                    .send()
+                    .await
+                    .map_err(DataError::Network)?;
+
+                if let Some(err) = transient_status_error(&response) {
+                    return Err(err);
+                }
+                if !response.status().is_success() {
+                    return Err(DataError::EdgarApi(format!(
+                        "Failed to fetch company tickers: HTTP {}",
+                        response.status()
+                    )));
+                }
+
+                // Parse as a map of index -> CompanyInfo
+                let body = response.bytes().await.map_err(DataError::Network)?;
+                serde_json::from_slice(&body).map_err(|e| {
+                    DataError::EdgarApi(format!("Failed to parse company tickers: {}", e))
+                })
+            })
+            .await?;
 
         // Search for ticker in the response
         for company in data.values() {
@@ -227,33 +279,38 @@ impl EdgarClient {
         // Ensure CIK is zero-padded to 10 digits
         let cik_padded = format!("{:0>10}", cik);
 
-        // Rate limit
-        self.rate_limiter.lock().await.wait().await;
-
         // Fetch company filings JSON
         let url = format!("{}/submissions/CIK{}.json", self.base_url, cik_padded);
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
+        self.retry_policy
+            .execute("SEC submissions", || async {
+                // Rate limit
+                self.rate_limiter.wait().await;
+
+                let response = self
+                    .client
+                    .get(&url)
+                    .send()
+                    .await
+                    .map_err(DataError::Network)?;
+
+                if let Some(err) = transient_status_error(&response) {
+                    return Err(err);
+                }
+                if !response.status().is_success() {
+                    return Err(DataError::EdgarApi(format!(
+                        "Failed to fetch company filings for CIK {}: HTTP {}",
+                        cik_padded,
+                        response.status()
+                    )));
+                }
+
+                let body = response.bytes().await.map_err(DataError::Network)?;
+                serde_json::from_slice::<CompanyFilings>(&body).map_err(|e| {
+                    DataError::EdgarApi(format!("Failed to parse company filings: {}", e))
+                })
+            })
             .await
-            .map_err(DataError::Network)?;
-
-        if !response.status().is_success() {
-            return Err(DataError::EdgarApi(format!(
-                "Failed to fetch company filings for CIK {}: HTTP {}",
-                cik_padded,
-                response.status()
-            )));
-        }
-
-        let filings: CompanyFilings = response
-            .json()
-            .await
-            .map_err(|e| DataError::EdgarApi(format!("Failed to parse company filings: {}", e)))?;
-
-        Ok(filings)
     }
 
     /// Fetch a raw filing document
@@ -308,12 +365,12 @@ impl EdgarClient {
         let accession_no_dash = accession.replace('-', "");
 
         // Rate limit
-        self.rate_limiter.lock().await.wait().await;
+        self.rate_limiter.wait().await;
 
         // Construct document URL
         let url = format!(
             "{}/Archives/edgar/data/{}/{}/{}",
-            self.base_url, cik_padded, accession_no_dash, document
+            self.www_url, cik_padded, accession_no_dash, document
         );
 
         let response = self
@@ -349,6 +406,7 @@ impl std::fmt::Debug for EdgarClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("EdgarClient")
             .field("base_url", &self.base_url)
+            .field("www_url", &self.www_url)
             .finish_non_exhaustive()
     }
 }
@@ -356,6 +414,7 @@ impl std::fmt::Debug for EdgarClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::time::Instant;
 
     #[tokio::test]
     async fn test_get_company_cik() {
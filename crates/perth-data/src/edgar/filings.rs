@@ -6,6 +6,7 @@
 //! - Filter and extract specific filing types (10-K, 10-Q)
 
 use crate::error::{DataError, Result};
+use crate::security::{SecurityId, share_class_from_ticker};
 use chrono::NaiveDate;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -88,6 +89,27 @@ impl CikLookup {
         self.ticker_to_cik.get(&ticker.to_uppercase())
     }
 
+    /// Stable security identifier for a ticker.
+    ///
+    /// The share class comes from a ticker suffix (`BRK-B`). When several
+    /// unsuffixed tickers share a CIK (`GOOG`/`GOOGL`), the ticker itself is
+    /// used as the share class so each listing keeps a distinct identifier.
+    pub fn security_id(&self, ticker: &str) -> Option<SecurityId> {
+        let ticker = ticker.to_uppercase();
+        let (cik, _) = self.ticker_to_cik.get(&ticker)?;
+
+        let share_class = share_class_from_ticker(&ticker).or_else(|| {
+            let listings = self
+                .ticker_to_cik
+                .values()
+                .filter(|(other, _)| other == cik)
+                .count();
+            (listings > 1).then_some(ticker.as_str())
+        });
+
+        Some(SecurityId::new(cik, share_class))
+    }
+
     /// Get all tickers in the lookup table.
     ///
     /// # Returns
@@ -124,6 +146,12 @@ pub struct CompanyFilings {
     pub cik: String,
     /// Company name
     pub name: String,
+    /// Standard Industrial Classification code, e.g. `"3571"`
+    #[serde(default)]
+    pub sic: Option<String>,
+    /// Description of the SIC code, e.g. `"Electronic Computers"`
+    #[serde(default, rename = "sicDescription")]
+    pub sic_description: Option<String>,
     /// Filing history
     pub filings: FilingHistory,
 }
@@ -200,6 +228,11 @@ impl CompanyFilings {
         Ok(filings)
     }
 
+    /// The SIC code as a number, if the filer has one.
+    pub fn sic_code(&self) -> Option<u16> {
+        self.sic.as_deref().and_then(|sic| sic.trim().parse().ok())
+    }
+
     /// Get the most recent 10-K filing.
     ///
     /// 10-K forms are annual reports that provide comprehensive overview
@@ -328,6 +361,35 @@ mod tests {
         assert_eq!(CikLookup::pad_cik("1234567890"), "1234567890");
     }
 
+    #[test]
+    fn test_security_id() {
+        let lookup = CikLookup {
+            ticker_to_cik: [
+                ("AAPL", "320193"),
+                ("GOOGL", "1652044"),
+                ("GOOG", "1652044"),
+                ("BRK-B", "1067983"),
+            ]
+            .into_iter()
+            .map(|(ticker, cik)| (ticker.to_string(), (cik.to_string(), String::new())))
+            .collect(),
+        };
+
+        assert_eq!(
+            lookup.security_id("aapl").unwrap().to_string(),
+            "0000320193"
+        );
+        assert_eq!(
+            lookup.security_id("GOOG").unwrap().to_string(),
+            "0001652044.GOOG"
+        );
+        assert_eq!(
+            lookup.security_id("BRK-B").unwrap().to_string(),
+            "0001067983.B"
+        );
+        assert!(lookup.security_id("NOPE").is_none());
+    }
+
     #[test]
     fn test_document_url() {
         let filing = FilingInfo {
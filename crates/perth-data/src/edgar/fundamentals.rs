@@ -327,6 +327,10 @@ pub struct EdgarFundamentalsProvider {
     client: reqwest::Client,
     /// XBRL tag mapper
     tag_mapper: XbrlTagMapper,
+    /// Base URL for `data.sec.gov` endpoints
+    base_url: String,
+    /// Base URL for `www.sec.gov` endpoints
+    www_url: String,
 }
 
 impl EdgarFundamentalsProvider {
@@ -342,18 +346,32 @@ impl EdgarFundamentalsProvider {
                 .build()
                 .expect("Failed to create HTTP client"),
             tag_mapper: XbrlTagMapper::new(),
+            base_url: "https://data.sec.gov".to_string(),
+            www_url: "https://www.sec.gov".to_string(),
         }
     }
 
+    /// Send company facts requests to a different `data.sec.gov` host.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Send the company tickers request to a different `www.sec.gov` host.
+    pub fn with_www_url(mut self, www_url: impl Into<String>) -> Self {
+        self.www_url = www_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     /// Fetch company CIK from symbol.
     ///
     /// The SEC uses CIK (Central Index Key) to identify companies.
     /// This method looks up the CIK for a given ticker symbol.
     async fn fetch_cik(&self, symbol: &str) -> Result<String> {
         // The SEC provides a company tickers JSON file that maps symbols to CIKs
-        let url = "https://www.sec.gov/files/company_tickers.json";
+        let url = format!("{}/files/company_tickers.json", self.www_url);
 
-        let response = self.client.get(url).send().await?;
+        let response = self.client.get(&url).send().await?;
 
         if !response.status().is_success() {
             return Err(DataError::Http(format!(
@@ -389,7 +407,7 @@ impl EdgarFundamentalsProvider {
     /// This uses the SEC's Company Facts API which returns all XBRL facts
     /// for a company in a single JSON response.
     async fn fetch_company_facts(&self, cik: &str) -> Result<CompanyFactsResponse> {
-        let url = format!("https://data.sec.gov/api/xbrl/companyfacts/CIK{}.json", cik);
+        let url = format!("{}/api/xbrl/companyfacts/CIK{}.json", self.base_url, cik);
 
         let response = self.client.get(&url).send().await?;
 
@@ -551,7 +569,7 @@ impl EdgarFundamentalsProvider {
         }
 
         // Sort by period end date (most recent first)
-        statements.sort_by(|a, b| b.period_end.cmp(&a.period_end));
+        statements.sort_by_key(|s| std::cmp::Reverse(s.period_end));
 
         Ok(statements)
     }
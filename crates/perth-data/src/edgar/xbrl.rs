@@ -25,10 +25,13 @@
 //! }
 //! ```
 
+use crate::edgar::client::DEFAULT_RATE_LIMIT;
 use crate::error::{DataError, Result};
+use crate::retry::{RateLimiter, RetryConfig, RetryPolicy, transient_status_error};
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Represents a single XBRL fact (data point).
 ///
@@ -203,7 +206,7 @@ impl XbrlDocument {
     pub fn get_facts_by_concept(&self, concept: &str) -> Vec<&XbrlFact> {
         let mut facts: Vec<&XbrlFact> =
             self.facts.iter().filter(|f| f.concept == concept).collect();
-        facts.sort_by(|a, b| b.period_end.cmp(&a.period_end));
+        facts.sort_by_key(|f| std::cmp::Reverse(f.period_end));
         facts
     }
 
@@ -238,11 +241,24 @@ impl XbrlDocument {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct SecApiResponse {
+    #[serde(deserialize_with = "deserialize_cik")]
     cik: String,
     entity_name: String,
     facts: HashMap<String, TaxonomyFacts>,
 }
 
+/// The SEC serves `cik` as a JSON number; accept strings as well.
+fn deserialize_cik<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Number(n) => Ok(n.to_string()),
+        serde_json::Value::String(s) => Ok(s),
+        other => Err(serde::de::Error::custom(format!("invalid CIK: {}", other))),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct TaxonomyFacts(HashMap<String, ConceptData>);
 
@@ -277,10 +293,15 @@ struct FactData {
 }
 
 /// Client for fetching XBRL data from SEC EDGAR
+///
+/// Requests are spaced by an adaptive [`RateLimiter`] within the SEC's
+/// 10 requests per second.
 #[derive(Debug)]
 pub struct XbrlClient {
     client: reqwest::Client,
     base_url: String,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
 }
 
 impl XbrlClient {
@@ -302,12 +323,33 @@ impl XbrlClient {
             .build()
             .expect("Failed to build HTTP client");
 
+        let rate_limiter = Arc::new(RateLimiter::new(DEFAULT_RATE_LIMIT));
+
         Self {
             client,
             base_url: "https://data.sec.gov/api/xbrl".to_string(),
+            retry_policy: RetryPolicy::new(RetryConfig::default())
+                .with_rate_limiter(Arc::clone(&rate_limiter)),
+            rate_limiter,
         }
     }
 
+    /// Send requests to a different API root
+    ///
+    /// `base_url` replaces `https://data.sec.gov/api/xbrl`, so company facts
+    /// are fetched from `{base_url}/companyfacts/CIK##########.json`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Replace the retry configuration used for company facts requests
+    pub fn with_retry_config(mut self, config: RetryConfig) -> Self {
+        self.retry_policy =
+            RetryPolicy::new(config).with_rate_limiter(Arc::clone(&self.rate_limiter));
+        self
+    }
+
     /// Fetches all company facts for a given CIK
     ///
     /// # Arguments
@@ -334,17 +376,27 @@ impl XbrlClient {
         let cik_padded = format!("{:0>10}", cik);
         let url = format!("{}/companyfacts/CIK{}.json", self.base_url, cik_padded);
 
-        let response = self.client.get(&url).send().await?;
+        let json = self
+            .retry_policy
+            .execute("SEC company facts", || async {
+                self.rate_limiter.wait().await;
+                let response = self.client.get(&url).send().await?;
 
-        if !response.status().is_success() {
-            return Err(DataError::Http(format!(
-                "SEC API returned status {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
-        }
+                if let Some(err) = transient_status_error(&response) {
+                    return Err(err);
+                }
+                if !response.status().is_success() {
+                    return Err(DataError::Http(format!(
+                        "SEC API returned status {}: {}",
+                        response.status(),
+                        response.text().await.unwrap_or_default()
+                    )));
+                }
+
+                Ok(response.text().await?)
+            })
+            .await?;
 
-        let json = response.text().await?;
         XbrlDocument::parse_json(&json)
     }
 
@@ -381,6 +433,7 @@ impl XbrlClient {
             self.base_url, cik_padded, taxonomy, concept
         );
 
+        self.rate_limiter.wait().await;
         let response = self.client.get(&url).send().await?;
 
         if !response.status().is_success() {
@@ -96,10 +96,57 @@ pub enum DataError {
     /// Filing not found
     #[error("Filing not found: {0}")]
     FilingNotFound(String),
+
+    /// Transient server-side HTTP error (5xx)
+    #[error("Server error: HTTP {status}")]
+    ServerError {
+        /// HTTP status code returned by the server
+        status: u16,
+    },
+
+    /// Circuit breaker is open for an endpoint
+    #[error("Circuit breaker open for {0}; too many consecutive failures")]
+    CircuitOpen(String),
+}
+
+impl DataError {
+    /// Whether the error is transient and the request may succeed if retried.
+    ///
+    /// Rate limits, 5xx responses, timeouts and connection failures are
+    /// transient; everything else (bad input, missing data, parse errors) is not.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::RateLimit { .. } | Self::ServerError { .. } => true,
+            Self::Network(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err.status().is_some_and(|status| {
+                        status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    /// Server-requested delay before retrying, if any.
+    pub const fn retry_after(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::RateLimit { retry_after_ms } if *retry_after_ms > 0 => {
+                Some(std::time::Duration::from_millis(*retry_after_ms))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl From<yahoo_finance_api::YahooError> for DataError {
     fn from(err: yahoo_finance_api::YahooError) -> Self {
-        Self::YahooApi(err.to_string())
+        match err {
+            yahoo_finance_api::YahooError::TooManyRequests(_) => {
+                Self::RateLimit { retry_after_ms: 0 }
+            }
+            yahoo_finance_api::YahooError::ConnectionFailed(err) => Self::Network(err),
+            err => Self::YahooApi(err.to_string()),
+        }
     }
 }
@@ -0,0 +1,160 @@
+//! Currencies and FX rates.
+//!
+//! Listings outside the US are quoted in their local currency. [`Currency`]
+//! names the Yahoo Finance FX pair for each supported currency, quoted as US
+//! dollars per unit (`EURUSD=X`), so the pair's quotes fetched and cached like
+//! any other symbol give the rate for converting local prices to USD.
+//! [`usd_rates`] turns those quotes into a `[date, currency, usd_rate]` series.
+
+use crate::error::{DataError, Result};
+use polars::prelude::*;
+use std::fmt;
+use std::str::FromStr;
+
+/// Currencies that listings can be quoted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Currency {
+    /// US dollar
+    Usd,
+    /// Canadian dollar
+    Cad,
+    /// Pound sterling
+    Gbp,
+    /// Euro
+    Eur,
+    /// Swiss franc
+    Chf,
+    /// Swedish krona
+    Sek,
+    /// Danish krone
+    Dkk,
+    /// Norwegian krone
+    Nok,
+    /// Japanese yen
+    Jpy,
+    /// Hong Kong dollar
+    Hkd,
+    /// Australian dollar
+    Aud,
+}
+
+impl Currency {
+    /// Returns all supported currencies.
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Usd,
+            Self::Cad,
+            Self::Gbp,
+            Self::Eur,
+            Self::Chf,
+            Self::Sek,
+            Self::Dkk,
+            Self::Nok,
+            Self::Jpy,
+            Self::Hkd,
+            Self::Aud,
+        ]
+    }
+
+    /// ISO 4217 code.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Usd => "USD",
+            Self::Cad => "CAD",
+            Self::Gbp => "GBP",
+            Self::Eur => "EUR",
+            Self::Chf => "CHF",
+            Self::Sek => "SEK",
+            Self::Dkk => "DKK",
+            Self::Nok => "NOK",
+            Self::Jpy => "JPY",
+            Self::Hkd => "HKD",
+            Self::Aud => "AUD",
+        }
+    }
+
+    /// Yahoo Finance symbol of the USD-per-unit FX pair, `None` for USD.
+    pub fn fx_symbol(&self) -> Option<String> {
+        match self {
+            Self::Usd => None,
+            _ => Some(format!("{}USD=X", self.code())),
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = DataError;
+
+    /// Parse an ISO 4217 code, ignoring case.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::all()
+            .into_iter()
+            .find(|c| c.code().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| DataError::Parse(format!("Unknown currency: {s}")))
+    }
+}
+
+/// Convert quotes of a currency's FX pair to `[date, currency, usd_rate]`.
+///
+/// The rate is the pair's close: US dollars per unit of `currency`. Rows
+/// without a positive close are dropped.
+pub fn usd_rates(currency: Currency, fx_quotes: &DataFrame) -> Result<DataFrame> {
+    Ok(fx_quotes
+        .clone()
+        .lazy()
+        .select([
+            col("date"),
+            lit(currency.code()).alias("currency"),
+            col("close").cast(DataType::Float64).alias("usd_rate"),
+        ])
+        .filter(col("usd_rate").gt(lit(0.0)))
+        .unique(Some(vec!["date".into()]), UniqueKeepStrategy::Last)
+        .sort(["date"], SortMultipleOptions::default())
+        .collect()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fx_symbols() {
+        assert_eq!(Currency::Usd.fx_symbol(), None);
+        assert_eq!(Currency::Eur.fx_symbol().as_deref(), Some("EURUSD=X"));
+        assert_eq!(Currency::Jpy.fx_symbol().as_deref(), Some("JPYUSD=X"));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("eur".parse::<Currency>().unwrap(), Currency::Eur);
+        assert_eq!(" JPY ".parse::<Currency>().unwrap(), Currency::Jpy);
+        assert!("XYZ".parse::<Currency>().is_err());
+    }
+
+    #[test]
+    fn test_usd_rates() {
+        let quotes = df! {
+            "symbol" => ["EURUSD=X", "EURUSD=X", "EURUSD=X"],
+            "date" => ["2024-01-02", "2024-01-03", "2024-01-04"],
+            "close" => [1.10, 0.0, 1.09],
+        }
+        .unwrap();
+
+        let rates = usd_rates(Currency::Eur, &quotes).unwrap();
+        assert_eq!(rates.height(), 2);
+        assert_eq!(
+            rates.column("currency").unwrap().str().unwrap().get(0),
+            Some("EUR")
+        );
+        assert_eq!(
+            rates.column("usd_rate").unwrap().f64().unwrap().get(1),
+            Some(1.09)
+        );
+    }
+}
@@ -5,8 +5,13 @@
 #![forbid(unsafe_code)]
 
 pub mod cache;
+pub mod calendar;
 pub mod edgar;
 pub mod error;
+pub mod fx;
+pub mod quality;
+pub mod retry;
+pub mod security;
 pub mod yahoo;
 
 pub use error::{DataError, Result};
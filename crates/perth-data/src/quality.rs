@@ -0,0 +1,388 @@
+//! Data quality checks for fetched quotes.
+//!
+//! [`QuoteValidator`] scans OHLCV rows symbol by symbol and flags:
+//!
+//! - **Invalid prices**: non-finite, zero or negative open/high/low/close/adjusted close
+//! - **Low volume**: volume below [`QualityConfig::min_volume`]
+//! - **Return spikes**: adjusted-close jumps larger than [`QualityConfig::max_abs_return`]
+//!   that reverse on the next row; a move that lasts is a real price change
+//! - **Stale prices**: closes repeated for more than [`QualityConfig::max_stale_run`] days
+//!
+//! With [`QualityConfig::repair`] set, flagged rows are dropped. Symbols whose
+//! share of flagged rows exceeds [`QualityConfig::max_flagged_fraction`] are
+//! excluded entirely. The resulting [`QualityReport`] lists what was found
+//! per symbol.
+
+use crate::error::Result;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Thresholds for quote validation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityConfig {
+    /// Largest plausible absolute daily return of the adjusted close (0.5 = 50%);
+    /// larger jumps are spikes when the next row reverses them
+    pub max_abs_return: f64,
+    /// Longest allowed run of identical consecutive closes
+    pub max_stale_run: usize,
+    /// Minimum daily volume for a row to count as traded
+    pub min_volume: u64,
+    /// Drop flagged rows instead of only reporting them
+    pub repair: bool,
+    /// Exclude a symbol when more than this fraction of its rows is flagged
+    pub max_flagged_fraction: f64,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            max_abs_return: 0.5,
+            max_stale_run: 5,
+            min_volume: 1,
+            repair: true,
+            max_flagged_fraction: 0.1,
+        }
+    }
+}
+
+/// Quality findings for one symbol.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SymbolQuality {
+    /// Ticker symbol
+    pub symbol: String,
+    /// Rows received
+    pub rows: usize,
+    /// Rows with non-finite or non-positive prices
+    pub invalid_prices: usize,
+    /// Rows below the minimum volume
+    pub low_volume: usize,
+    /// Rows whose return exceeded the spike threshold
+    pub return_spikes: usize,
+    /// Rows inside an over-long run of unchanged closes
+    pub stale_prices: usize,
+    /// Rows flagged by at least one rule
+    pub flagged: usize,
+    /// Whether the symbol was excluded from the output
+    pub excluded: bool,
+}
+
+impl SymbolQuality {
+    /// Fraction of rows flagged by at least one rule.
+    pub fn flagged_fraction(&self) -> f64 {
+        if self.rows == 0 {
+            0.0
+        } else {
+            self.flagged as f64 / self.rows as f64
+        }
+    }
+
+    /// Whether any rule fired for this symbol.
+    pub const fn has_issues(&self) -> bool {
+        self.flagged > 0
+    }
+}
+
+/// Per-symbol quality report produced by [`QuoteValidator::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QualityReport {
+    /// Findings per symbol, in symbol order
+    pub symbols: Vec<SymbolQuality>,
+}
+
+impl QualityReport {
+    /// Findings for a single symbol.
+    pub fn get(&self, symbol: &str) -> Option<&SymbolQuality> {
+        self.symbols.iter().find(|s| s.symbol == symbol)
+    }
+
+    /// Symbols removed from the validated quotes.
+    pub fn excluded_symbols(&self) -> Vec<&str> {
+        self.symbols
+            .iter()
+            .filter(|s| s.excluded)
+            .map(|s| s.symbol.as_str())
+            .collect()
+    }
+
+    /// Symbols with at least one flagged row.
+    pub fn symbols_with_issues(&self) -> Vec<&SymbolQuality> {
+        self.symbols.iter().filter(|s| s.has_issues()).collect()
+    }
+
+    /// Total number of flagged rows across all symbols.
+    pub fn total_flagged(&self) -> usize {
+        self.symbols.iter().map(|s| s.flagged).sum()
+    }
+}
+
+/// Validates quote DataFrames against a [`QualityConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct QuoteValidator {
+    config: QualityConfig,
+}
+
+impl QuoteValidator {
+    /// Create a validator with the given thresholds.
+    pub const fn new(config: QualityConfig) -> Self {
+        Self { config }
+    }
+
+    /// The thresholds in use.
+    pub const fn config(&self) -> &QualityConfig {
+        &self.config
+    }
+
+    /// Validate quotes and return the cleaned frame with its report.
+    ///
+    /// Expects the columns produced by
+    /// [`YahooQuoteProvider`](crate::yahoo::YahooQuoteProvider): `symbol`,
+    /// `date`, `open`, `high`, `low`, `close`, `volume` and `adjusted_close`.
+    /// The output is sorted by symbol and date and keeps the input schema.
+    pub fn validate(&self, quotes: &DataFrame) -> Result<(DataFrame, QualityReport)> {
+        let sorted = quotes
+            .clone()
+            .lazy()
+            .sort(["symbol", "date"], SortMultipleOptions::default())
+            .collect()?;
+
+        let symbols: Vec<Option<String>> = sorted
+            .column("symbol")?
+            .str()?
+            .into_iter()
+            .map(|s| s.map(str::to_string))
+            .collect();
+        let prices = ["open", "high", "low", "close", "adjusted_close"]
+            .iter()
+            .map(|name| float_column(&sorted, name))
+            .collect::<Result<Vec<_>>>()?;
+        let volumes = float_column(&sorted, "volume")?;
+        let closes = &prices[3];
+        let adj_closes = &prices[4];
+
+        let mut keep = vec![true; sorted.height()];
+        let mut report = QualityReport::default();
+
+        let mut start = 0;
+        while start < symbols.len() {
+            let mut end = start + 1;
+            while end < symbols.len() && symbols[end] == symbols[start] {
+                end += 1;
+            }
+
+            let mut quality = SymbolQuality {
+                symbol: symbols[start].clone().unwrap_or_default(),
+                rows: end - start,
+                ..SymbolQuality::default()
+            };
+
+            let mut last_adj_close: Option<f64> = None;
+            let mut last_close: Option<f64> = None;
+            let mut stale_run = 0;
+
+            for row in start..end {
+                let invalid = prices
+                    .iter()
+                    .any(|column| column[row].is_none_or(|p| !p.is_finite() || p <= 0.0));
+                let low_volume = volumes[row]
+                    .is_none_or(|v| !v.is_finite() || v < self.config.min_volume as f64);
+
+                let mut spike = false;
+                let mut stale = false;
+                if !invalid {
+                    let adj_close = adj_closes[row].unwrap_or_default();
+                    let close = closes[row].unwrap_or_default();
+
+                    // A bad tick jumps away and comes straight back; a crash or
+                    // a takeover bid stays, and dropping it would bias returns
+                    let jumps = |price: f64, from: f64| {
+                        (price / from - 1.0).abs() > self.config.max_abs_return
+                    };
+                    let next_adj_close = (row + 1..end)
+                        .filter_map(|next| adj_closes[next])
+                        .find(|p| p.is_finite() && *p > 0.0);
+                    spike = last_adj_close.is_some_and(|prev| {
+                        jumps(adj_close, prev)
+                            && next_adj_close.is_some_and(|next| !jumps(next, prev))
+                    });
+
+                    stale_run = if last_close == Some(close) {
+                        stale_run + 1
+                    } else {
+                        0
+                    };
+                    stale = stale_run > self.config.max_stale_run;
+                    last_close = Some(close);
+                }
+
+                quality.invalid_prices += usize::from(invalid);
+                quality.low_volume += usize::from(low_volume);
+                quality.return_spikes += usize::from(spike);
+                quality.stale_prices += usize::from(stale);
+
+                let flagged = invalid || low_volume || spike || stale;
+                quality.flagged += usize::from(flagged);
+
+                if flagged && self.config.repair {
+                    keep[row] = false;
+                } else if !invalid {
+                    // Spikes are measured against the last row that survives.
+                    last_adj_close = adj_closes[row];
+                }
+            }
+
+            if quality.flagged_fraction() > self.config.max_flagged_fraction {
+                quality.excluded = true;
+                keep[start..end].fill(false);
+            }
+
+            report.symbols.push(quality);
+            start = end;
+        }
+
+        let mask = BooleanChunked::from_slice("keep".into(), &keep);
+        let cleaned = sorted.filter(&mask)?;
+
+        Ok((cleaned, report))
+    }
+}
+
+/// Read a numeric column as `f64` values, keeping nulls.
+fn float_column(df: &DataFrame, name: &str) -> Result<Vec<Option<f64>>> {
+    let column = df.column(name)?.cast(&DataType::Float64)?;
+    Ok(column.f64()?.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quotes(symbol: &str, closes: &[f64], volumes: &[u64]) -> DataFrame {
+        let n = closes.len();
+        let dates: Vec<i32> = (0..n as i32).map(|d| 19_723 + d).collect();
+        df! {
+            "symbol" => vec![symbol; n],
+            "date" => dates,
+            "open" => closes,
+            "high" => closes,
+            "low" => closes,
+            "close" => closes,
+            "volume" => volumes,
+            "adjusted_close" => closes,
+        }
+        .unwrap()
+        .lazy()
+        .with_column(col("date").cast(DataType::Date))
+        .collect()
+        .unwrap()
+    }
+
+    fn lenient() -> QualityConfig {
+        QualityConfig {
+            max_flagged_fraction: 1.0,
+            ..QualityConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_clean_quotes_pass_through() {
+        let df = quotes("AAA", &[10.0, 10.1, 10.2, 10.0], &[100; 4]);
+        let (cleaned, report) = QuoteValidator::default().validate(&df).unwrap();
+
+        assert_eq!(cleaned.height(), 4);
+        assert_eq!(cleaned.get_column_names(), df.get_column_names());
+        assert_eq!(report.total_flagged(), 0);
+        assert!(report.excluded_symbols().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_prices_are_dropped() {
+        let df = quotes("AAA", &[10.0, -1.0, f64::NAN, 10.2, 0.0], &[100; 5]);
+        let (cleaned, report) = QuoteValidator::new(lenient()).validate(&df).unwrap();
+
+        assert_eq!(report.get("AAA").unwrap().invalid_prices, 3);
+        assert_eq!(cleaned.height(), 2);
+    }
+
+    #[test]
+    fn test_zero_volume_is_flagged() {
+        let df = quotes("AAA", &[10.0, 10.1, 10.2], &[100, 0, 100]);
+        let (cleaned, report) = QuoteValidator::new(lenient()).validate(&df).unwrap();
+
+        assert_eq!(report.get("AAA").unwrap().low_volume, 1);
+        assert_eq!(cleaned.height(), 2);
+    }
+
+    #[test]
+    fn test_spike_measured_against_last_kept_row() {
+        // The 50.0 print is a bad tick; the following row returns to normal.
+        let df = quotes("AAA", &[10.0, 50.0, 10.1, 10.2], &[100; 4]);
+        let (cleaned, report) = QuoteValidator::new(lenient()).validate(&df).unwrap();
+
+        let quality = report.get("AAA").unwrap();
+        assert_eq!(quality.return_spikes, 1);
+        assert_eq!(cleaned.height(), 3);
+    }
+
+    #[test]
+    fn test_lasting_moves_are_not_spikes() {
+        // A crash that holds, then a rally that holds
+        let df = quotes(
+            "AAA",
+            &[10.0, 10.1, 4.0, 4.1, 4.0, 3.9, 8.0, 8.1, 8.2, 8.0],
+            &[100; 10],
+        );
+        let (cleaned, report) = QuoteValidator::default().validate(&df).unwrap();
+
+        let quality = report.get("AAA").unwrap();
+        assert_eq!(quality.return_spikes, 0);
+        assert!(!quality.excluded);
+        assert_eq!(cleaned.height(), 10);
+
+        // The last row has nothing to reverse against
+        let df = quotes("AAA", &[10.0, 10.1, 10.2, 3.0], &[100; 4]);
+        let (_, report) = QuoteValidator::new(lenient()).validate(&df).unwrap();
+        assert_eq!(report.get("AAA").unwrap().return_spikes, 0);
+    }
+
+    #[test]
+    fn test_stale_run_beyond_limit() {
+        let config = QualityConfig {
+            max_stale_run: 2,
+            ..lenient()
+        };
+        let df = quotes("AAA", &[10.0, 10.0, 10.0, 10.0, 10.0, 10.5], &[100; 6]);
+        let (cleaned, report) = QuoteValidator::new(config).validate(&df).unwrap();
+
+        // Two repeats are allowed; the third and fourth are stale.
+        assert_eq!(report.get("AAA").unwrap().stale_prices, 2);
+        assert_eq!(cleaned.height(), 4);
+    }
+
+    #[test]
+    fn test_flag_only_keeps_rows() {
+        let config = QualityConfig {
+            repair: false,
+            ..lenient()
+        };
+        let df = quotes("AAA", &[10.0, 10.1, 10.2], &[100, 0, 100]);
+        let (cleaned, report) = QuoteValidator::new(config).validate(&df).unwrap();
+
+        assert_eq!(report.get("AAA").unwrap().flagged, 1);
+        assert_eq!(cleaned.height(), 3);
+    }
+
+    #[test]
+    fn test_symbols_over_threshold_are_excluded() {
+        let good = quotes("AAA", &[10.0, 10.1, 10.2, 10.3], &[100; 4]);
+        let bad = quotes("BBB", &[10.0, 10.1, 10.2, 10.3], &[0, 0, 100, 100]);
+        let df = good.vstack(&bad).unwrap();
+
+        let (cleaned, report) = QuoteValidator::default().validate(&df).unwrap();
+
+        assert_eq!(report.excluded_symbols(), vec!["BBB"]);
+        assert!(!report.get("AAA").unwrap().excluded);
+        assert_eq!(cleaned.height(), 4);
+        let symbols = cleaned.column("symbol").unwrap().str().unwrap();
+        assert!(symbols.into_iter().all(|s| s == Some("AAA")));
+    }
+}
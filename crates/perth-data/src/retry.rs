@@ -0,0 +1,665 @@
+//! Retry, backoff and circuit breaking for remote data sources.
+//!
+//! Yahoo Finance and SEC EDGAR both fail transiently under load: HTTP 429
+//! responses, 5xx errors and dropped connections. This module provides a
+//! shared [`RetryPolicy`] that retries those failures with exponential backoff
+//! and jitter, honours `Retry-After` headers, and trips a [`CircuitBreaker`]
+//! when an endpoint keeps failing. An adaptive [`RateLimiter`] slows requests
+//! down after rate-limit responses and recovers as requests succeed.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use perth_data::retry::{RetryConfig, RetryPolicy};
+//! use perth_data::DataError;
+//!
+//! # async fn example() -> perth_data::Result<()> {
+//! let policy = RetryPolicy::new(RetryConfig::default());
+//! let body = policy
+//!     .execute("example", || async {
+//!         let response = reqwest::get("https://example.com").await?;
+//!         Ok::<_, DataError>(response.text().await?)
+//!     })
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{DataError, Result};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{Instant, sleep};
+
+/// Configuration for retrying transient failures.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryConfig {
+    /// Maximum number of attempts, including the first request.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound on the computed backoff between attempts.
+    pub max_backoff: Duration,
+    /// Multiplier applied to the backoff after each failed attempt.
+    pub backoff_multiplier: f64,
+    /// Fraction of each backoff that is randomized (0.0 disables jitter).
+    pub jitter: f64,
+    /// Consecutive transient failures that open the circuit breaker.
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a trial request is allowed.
+    pub cooldown: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            backoff_multiplier: 2.0,
+            jitter: 0.25,
+            failure_threshold: 10,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// A configuration that never retries and never opens the circuit.
+    pub const fn no_retry() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            backoff_multiplier: 1.0,
+            jitter: 0.0,
+            failure_threshold: u32::MAX,
+            cooldown: Duration::ZERO,
+        }
+    }
+}
+
+/// State of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow normally.
+    Closed,
+    /// Requests are rejected until the cooldown elapses.
+    Open,
+    /// The cooldown elapsed; a single trial request decides whether to close
+    /// or re-open.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// When the half-open trial request was let through, until it succeeds
+    /// or fails.
+    trial_started: Option<Instant>,
+}
+
+/// Circuit breaker that stops hammering an endpoint that keeps failing.
+///
+/// After `failure_threshold` consecutive transient failures the circuit opens
+/// and requests fail fast with [`DataError::CircuitOpen`]. Once `cooldown` has
+/// elapsed a single trial request is let through while concurrent callers
+/// keep failing fast; success closes the circuit and failure re-opens it. A
+/// trial whose outcome is never recorded, such as a cancelled request, is
+/// given up after another cooldown so the circuit cannot stay half-open.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    inner: Mutex<BreakerInner>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    /// Create a new circuit breaker in the closed state.
+    pub const fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            inner: Mutex::new(BreakerInner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                trial_started: None,
+            }),
+            failure_threshold,
+            cooldown,
+        }
+    }
+
+    /// Current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        self.lock().state
+    }
+
+    /// Check whether a request may proceed, moving from open to half-open
+    /// once the cooldown has elapsed.
+    ///
+    /// While half-open only the trial request is admitted; the caller must
+    /// record its outcome with [`record_success`](Self::record_success) or
+    /// [`record_failure`](Self::record_failure).
+    pub fn allow_request(&self) -> bool {
+        let mut inner = self.lock();
+        let admit_trial = match inner.state {
+            CircuitState::Closed => return true,
+            CircuitState::HalfOpen => inner
+                .trial_started
+                .is_none_or(|started| started.elapsed() >= self.cooldown),
+            CircuitState::Open => inner
+                .opened_at
+                .is_none_or(|opened| opened.elapsed() >= self.cooldown),
+        };
+        if admit_trial {
+            inner.state = CircuitState::HalfOpen;
+            inner.trial_started = Some(Instant::now());
+        }
+        admit_trial
+    }
+
+    /// Record a request that reached the endpoint successfully.
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        inner.state = CircuitState::Closed;
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        inner.trial_started = None;
+    }
+
+    /// Record a transient failure.
+    pub fn record_failure(&self) {
+        let mut inner = self.lock();
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        inner.trial_started = None;
+        if inner.state == CircuitState::HalfOpen
+            || inner.consecutive_failures >= self.failure_threshold
+        {
+            inner.state = CircuitState::Open;
+            inner.opened_at = Some(Instant::now());
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Adaptive rate limiter shared by the HTTP clients.
+///
+/// Enforces a minimum interval between requests. Rate-limit responses double
+/// the interval (up to 64x the base), and each success shrinks it back toward
+/// the configured base.
+#[derive(Debug)]
+pub struct RateLimiter {
+    base_interval: Duration,
+    state: tokio::sync::Mutex<Instant>,
+    current_interval: Mutex<Duration>,
+}
+
+impl RateLimiter {
+    /// Maximum factor by which the interval may grow above the base.
+    const MAX_SLOWDOWN: u32 = 64;
+
+    /// Create a rate limiter with the given minimum interval between requests.
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            base_interval: min_interval,
+            state: tokio::sync::Mutex::new(Instant::now() - min_interval),
+            current_interval: Mutex::new(min_interval),
+        }
+    }
+
+    /// The configured minimum interval.
+    pub const fn base_interval(&self) -> Duration {
+        self.base_interval
+    }
+
+    /// The current (possibly slowed-down) interval between requests.
+    pub fn interval(&self) -> Duration {
+        *self.interval_lock()
+    }
+
+    /// Wait until the next request is allowed.
+    pub async fn wait(&self) {
+        let mut last_request = self.state.lock().await;
+        let interval = self.interval();
+        let elapsed = last_request.elapsed();
+        if elapsed < interval {
+            sleep(interval - elapsed).await;
+        }
+        *last_request = Instant::now();
+    }
+
+    /// Slow down after a rate-limit response.
+    pub fn penalize(&self) {
+        let mut interval = self.interval_lock();
+        let floor = self.base_interval.max(Duration::from_millis(10));
+        let ceiling = floor * Self::MAX_SLOWDOWN;
+        *interval = (interval.max(floor) * 2).min(ceiling);
+    }
+
+    /// Recover toward the base interval after a successful request.
+    pub fn reward(&self) {
+        let mut interval = self.interval_lock();
+        *interval = interval.mul_f64(0.9).max(self.base_interval);
+    }
+
+    fn interval_lock(&self) -> std::sync::MutexGuard<'_, Duration> {
+        self.current_interval
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Retry policy with exponential backoff, jitter and a circuit breaker.
+#[derive(Debug)]
+pub struct RetryPolicy {
+    config: RetryConfig,
+    breaker: CircuitBreaker,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(RetryConfig::default())
+    }
+}
+
+impl RetryPolicy {
+    /// Create a retry policy from a configuration.
+    pub const fn new(config: RetryConfig) -> Self {
+        let breaker = CircuitBreaker::new(config.failure_threshold, config.cooldown);
+        Self {
+            config,
+            breaker,
+            rate_limiter: None,
+        }
+    }
+
+    /// Attach a rate limiter that is slowed down on rate-limit responses and
+    /// sped up again on success.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// The retry configuration.
+    pub const fn config(&self) -> &RetryConfig {
+        &self.config
+    }
+
+    /// The circuit breaker guarding this policy's endpoint.
+    pub const fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
+    /// Backoff before retry number `retry` (1-based), without jitter.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(32) as i32;
+        let scaled = self.config.initial_backoff.as_secs_f64()
+            * self.config.backoff_multiplier.powi(exponent);
+        Duration::from_secs_f64(scaled.min(self.config.max_backoff.as_secs_f64()))
+    }
+
+    /// Run `operation`, retrying transient failures.
+    ///
+    /// Errors for which [`DataError::is_transient`] is false are returned
+    /// immediately. When the server supplies a `Retry-After` delay it is used
+    /// if it is longer than the computed backoff, capped at `max_backoff` so
+    /// a server cannot stall the client indefinitely.
+    ///
+    /// # Errors
+    /// Returns the last error once attempts are exhausted or the failure
+    /// opens the circuit breaker, and [`DataError::CircuitOpen`] if the
+    /// breaker was already open when the request was made.
+    pub async fn execute<T, F, Fut>(&self, endpoint: &str, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let max_attempts = self.config.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            if !self.breaker.allow_request() {
+                return Err(DataError::CircuitOpen(endpoint.to_string()));
+            }
+
+            let err = match operation().await {
+                Ok(value) => {
+                    self.breaker.record_success();
+                    if let Some(limiter) = &self.rate_limiter {
+                        limiter.reward();
+                    }
+                    return Ok(value);
+                }
+                Err(err) if !err.is_transient() => {
+                    // The endpoint answered; the failure is ours, not theirs.
+                    self.breaker.record_success();
+                    return Err(err);
+                }
+                Err(err) => err,
+            };
+
+            self.breaker.record_failure();
+            if let (Some(limiter), DataError::RateLimit { .. }) = (&self.rate_limiter, &err) {
+                limiter.penalize();
+            }
+
+            // An open circuit would reject the retry, so report the failure
+            // that opened it instead
+            if attempt >= max_attempts || self.breaker.state() == CircuitState::Open {
+                return Err(err);
+            }
+
+            let delay = self.jittered(self.backoff(attempt));
+            let delay = err
+                .retry_after()
+                .map_or(delay, |after| after.min(self.config.max_backoff).max(delay));
+            sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    fn jittered(&self, delay: Duration) -> Duration {
+        let jitter = self.config.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 || delay.is_zero() {
+            return delay;
+        }
+        let factor = rand::thread_rng().gen_range((1.0 - jitter)..=(1.0 + jitter));
+        delay.mul_f64(factor)
+    }
+}
+
+/// Convert a retryable HTTP status into an error.
+///
+/// Returns [`DataError::RateLimit`] for 429 (using the `Retry-After` header
+/// when present) and [`DataError::ServerError`] for 5xx responses. Other
+/// statuses return `None` and are left to the caller.
+pub(crate) fn transient_status_error(response: &reqwest::Response) -> Option<DataError> {
+    let status = response.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after_ms = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, Utc::now()))
+            .map_or(0, |delay| delay.as_millis() as u64);
+        Some(DataError::RateLimit { retry_after_ms })
+    } else if status.is_server_error() {
+        Some(DataError::ServerError {
+            status: status.as_u16(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Parse a `Retry-After` header given either as delay-seconds or an HTTP date.
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((at - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn fast_config(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            jitter: 0.0,
+            ..RetryConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let policy = RetryPolicy::new(RetryConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            backoff_multiplier: 2.0,
+            ..RetryConfig::default()
+        });
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 27, 0).unwrap();
+
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() {
+        let policy = RetryPolicy::new(fast_config(4));
+        let calls = AtomicU32::new(0);
+
+        let result = policy
+            .execute("test", || async {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(DataError::ServerError { status: 503 })
+                } else {
+                    Ok(42)
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(policy.circuit_breaker().state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let policy = RetryPolicy::new(fast_config(3));
+        let calls = AtomicU32::new(0);
+
+        let result: Result<()> = policy
+            .execute("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(DataError::RateLimit { retry_after_ms: 1 })
+            })
+            .await;
+
+        assert!(matches!(result, Err(DataError::RateLimit { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_permanent_errors_are_not_retried() {
+        let policy = RetryPolicy::new(fast_config(5));
+        let calls = AtomicU32::new(0);
+
+        let result: Result<()> = policy
+            .execute("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(DataError::CikNotFound("NOPE".to_string()))
+            })
+            .await;
+
+        assert!(matches!(result, Err(DataError::CikNotFound(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_and_fails_fast() {
+        let policy = RetryPolicy::new(RetryConfig {
+            failure_threshold: 2,
+            cooldown: Duration::from_secs(60),
+            ..fast_config(1)
+        });
+
+        for _ in 0..2 {
+            let result: Result<()> = policy
+                .execute("test", || async {
+                    Err(DataError::ServerError { status: 500 })
+                })
+                .await;
+            assert!(matches!(result, Err(DataError::ServerError { .. })));
+        }
+        assert_eq!(policy.circuit_breaker().state(), CircuitState::Open);
+
+        let calls = AtomicU32::new(0);
+        let result = policy
+            .execute("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .await;
+        assert!(matches!(result, Err(DataError::CircuitOpen(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tripping_the_circuit_keeps_the_error() {
+        let policy = RetryPolicy::new(RetryConfig {
+            failure_threshold: 2,
+            cooldown: Duration::from_secs(60),
+            ..fast_config(5)
+        });
+        let calls = AtomicU32::new(0);
+
+        let result: Result<()> = policy
+            .execute("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(DataError::ServerError { status: 502 })
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(DataError::ServerError { status: 502 })
+        ));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(policy.circuit_breaker().state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_circuit_half_open_after_cooldown() {
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        assert!(breaker.allow_request());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_is_capped() {
+        let policy = RetryPolicy::new(fast_config(2));
+        let calls = AtomicU32::new(0);
+
+        // An hour-long Retry-After waits max_backoff (5ms) instead
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            policy.execute("test", || async {
+                if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(DataError::RateLimit {
+                        retry_after_ms: 3_600_000,
+                    })
+                } else {
+                    Ok(())
+                }
+            }),
+        )
+        .await;
+
+        assert!(matches!(result, Ok(Ok(()))));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_circuit_half_open_admits_one_trial() {
+        let cooldown = Duration::from_millis(20);
+        let breaker = CircuitBreaker::new(1, cooldown);
+
+        breaker.record_failure();
+        assert!(!breaker.allow_request());
+        std::thread::sleep(cooldown);
+
+        // One trial at a time while half-open
+        assert!(breaker.allow_request());
+        assert!(!breaker.allow_request());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.allow_request());
+        std::thread::sleep(cooldown);
+
+        assert!(breaker.allow_request());
+        assert!(!breaker.allow_request());
+        breaker.record_success();
+        assert!(breaker.allow_request());
+        assert!(breaker.allow_request());
+    }
+
+    #[test]
+    fn test_circuit_abandoned_trial_expires() {
+        let cooldown = Duration::from_millis(20);
+        let breaker = CircuitBreaker::new(1, cooldown);
+
+        breaker.record_failure();
+        std::thread::sleep(cooldown);
+        assert!(breaker.allow_request());
+        assert!(!breaker.allow_request());
+
+        // The trial never reported back
+        std::thread::sleep(cooldown);
+        assert!(breaker.allow_request());
+    }
+
+    #[test]
+    fn test_rate_limiter_adapts() {
+        let limiter = RateLimiter::new(Duration::from_millis(100));
+
+        limiter.penalize();
+        assert_eq!(limiter.interval(), Duration::from_millis(200));
+        limiter.penalize();
+        assert_eq!(limiter.interval(), Duration::from_millis(400));
+
+        for _ in 0..100 {
+            limiter.reward();
+        }
+        assert_eq!(limiter.interval(), limiter.base_interval());
+    }
+}
@@ -0,0 +1,309 @@
+//! Stable security identifiers and ticker history.
+//!
+//! Tickers are reused and renamed (FB became META in 2022), so they make a
+//! poor key for long histories. A [`SecurityId`] identifies a security by the
+//! issuer's SEC CIK plus an optional share class, which survives renames.
+//! [`TickerPeriod`] records which ticker a security traded under over a date
+//! range, and [`Delisting`] records how a security left the market so that
+//! backtests can book its final return instead of silently dropping it.
+
+use crate::error::{DataError, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Separator between CIK and share class in the string form of a [`SecurityId`].
+const SHARE_CLASS_SEPARATOR: char = '.';
+
+/// Stable identifier for a listed security: issuer CIK plus share class.
+///
+/// The string form is the zero-padded CIK, followed by `.` and the share class
+/// when there is one, e.g. `0000320193` or `0001067983.B`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SecurityId {
+    /// Issuer CIK, zero-padded to 10 digits
+    pub cik: String,
+    /// Share class for issuers with several listed classes
+    pub share_class: Option<String>,
+}
+
+impl SecurityId {
+    /// Create an identifier from a CIK (padded to 10 digits) and share class.
+    pub fn new(cik: &str, share_class: Option<&str>) -> Self {
+        Self {
+            cik: format!("{:0>10}", cik.trim()),
+            share_class: share_class
+                .map(|class| class.trim().to_uppercase())
+                .filter(|class| !class.is_empty()),
+        }
+    }
+
+    /// Identifier for an issuer with a single listed share class.
+    pub fn from_cik(cik: &str) -> Self {
+        Self::new(cik, None)
+    }
+}
+
+impl fmt::Display for SecurityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.share_class {
+            Some(class) => write!(f, "{}{SHARE_CLASS_SEPARATOR}{class}", self.cik),
+            None => write!(f, "{}", self.cik),
+        }
+    }
+}
+
+impl FromStr for SecurityId {
+    type Err = DataError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (cik, class) = match s.split_once(SHARE_CLASS_SEPARATOR) {
+            Some((cik, class)) => (cik, Some(class)),
+            None => (s, None),
+        };
+        if cik.is_empty() || !cik.chars().all(|c| c.is_ascii_digit()) {
+            return Err(DataError::Parse(format!("Invalid security id: {s}")));
+        }
+        Ok(Self::new(cik, class))
+    }
+}
+
+/// Share class encoded in a ticker suffix, e.g. `B` for `BRK-B` or `BRK.B`.
+pub fn share_class_from_ticker(ticker: &str) -> Option<&str> {
+    ticker
+        .rsplit_once(['-', '.'])
+        .map(|(_, class)| class)
+        .filter(|class| !class.is_empty())
+}
+
+/// A ticker a security traded under, with inclusive validity dates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickerPeriod {
+    /// Security the ticker belonged to
+    pub security_id: SecurityId,
+    /// Ticker symbol
+    pub ticker: String,
+    /// First day under this ticker (`None` if unknown, i.e. since listing)
+    pub start: Option<NaiveDate>,
+    /// Last day under this ticker (`None` while still in use)
+    pub end: Option<NaiveDate>,
+}
+
+impl TickerPeriod {
+    /// Whether the ticker was in use on `date`.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start.is_none_or(|start| start <= date) && self.end.is_none_or(|end| date <= end)
+    }
+
+    /// Whether the period overlaps the inclusive range `start..=end`.
+    pub fn overlaps(&self, start: NaiveDate, end: NaiveDate) -> bool {
+        self.start.is_none_or(|s| s <= end) && self.end.is_none_or(|e| start <= e)
+    }
+}
+
+/// A ticker change: `old_ticker` was last used the day before `effective`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TickerChange {
+    /// Security that changed ticker
+    pub security_id: SecurityId,
+    /// Ticker before the change
+    pub old_ticker: &'static str,
+    /// Ticker from `effective` onwards
+    pub new_ticker: &'static str,
+    /// First trading day under the new ticker
+    pub effective: NaiveDate,
+}
+
+/// Ticker changes among large US companies, used to seed the cache.
+pub fn known_ticker_changes() -> Vec<TickerChange> {
+    let change = |cik: &str, old_ticker, new_ticker, (y, m, d)| TickerChange {
+        security_id: SecurityId::from_cik(cik),
+        old_ticker,
+        new_ticker,
+        effective: NaiveDate::from_ymd_opt(y, m, d).expect("valid date"),
+    };
+
+    vec![
+        change("101829", "UTX", "RTX", (2020, 4, 3)),
+        change("1326801", "FB", "META", (2022, 6, 9)),
+        change("1156039", "ANTM", "ELV", (2022, 6, 28)),
+    ]
+}
+
+/// Why a security stopped trading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DelistingReason {
+    /// Acquired or merged into another company
+    Merger,
+    /// Moved to another exchange
+    Exchange,
+    /// Liquidated or dissolved
+    Liquidation,
+    /// Delisted for failing listing standards (bankruptcy, low price, ...)
+    Performance,
+    /// Any other reason
+    Other,
+}
+
+impl DelistingReason {
+    /// Return assumed when the actual delisting return is unknown.
+    ///
+    /// Follows Shumway (1997): performance-related delistings lose about 30%
+    /// after the last quoted price, while mergers and exchange moves are
+    /// assumed to be fully paid out at that price.
+    pub const fn default_return(&self) -> f64 {
+        match self {
+            Self::Merger | Self::Exchange => 0.0,
+            Self::Liquidation | Self::Performance | Self::Other => -0.30,
+        }
+    }
+
+    /// Convert to database string representation.
+    pub const fn to_db_str(&self) -> &'static str {
+        match self {
+            Self::Merger => "merger",
+            Self::Exchange => "exchange",
+            Self::Liquidation => "liquidation",
+            Self::Performance => "performance",
+            Self::Other => "other",
+        }
+    }
+
+    /// Parse from database string representation.
+    pub fn from_db_str(s: &str) -> Result<Self> {
+        match s {
+            "merger" => Ok(Self::Merger),
+            "exchange" => Ok(Self::Exchange),
+            "liquidation" => Ok(Self::Liquidation),
+            "performance" => Ok(Self::Performance),
+            "other" => Ok(Self::Other),
+            _ => Err(DataError::Parse(format!("Invalid delisting reason: {s}"))),
+        }
+    }
+}
+
+/// How and when a security left the market.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Delisting {
+    /// Security that was delisted
+    pub security_id: SecurityId,
+    /// Last ticker the security traded under
+    pub ticker: String,
+    /// Delisting date (first day without a regular quote)
+    pub date: NaiveDate,
+    /// Reason for the delisting
+    pub reason: DelistingReason,
+    /// Return from the last quoted price to the delisting proceeds, if known
+    pub delisting_return: Option<f64>,
+}
+
+impl Delisting {
+    /// The recorded delisting return, or the default for the reason.
+    pub fn effective_return(&self) -> f64 {
+        self.delisting_return
+            .unwrap_or_else(|| self.reason.default_return())
+    }
+}
+
+/// Delistings among large US companies, used to seed the cache.
+///
+/// Cash deals record the return from the last close to the deal price;
+/// stock deals are left to the reason's default. Yahoo no longer serves
+/// these symbols, so the return is only booked for a symbol whose quotes
+/// are cached through its last session.
+pub fn known_delistings() -> Vec<Delisting> {
+    let delisting = |cik: &str, ticker: &str, (y, m, d), reason, delisting_return| Delisting {
+        security_id: SecurityId::from_cik(cik),
+        ticker: ticker.to_string(),
+        date: NaiveDate::from_ymd_opt(y, m, d).expect("valid date"),
+        reason,
+        delisting_return,
+    };
+
+    vec![
+        delisting(
+            "743988",
+            "XLNX",
+            (2022, 2, 14),
+            DelistingReason::Merger,
+            None,
+        ),
+        delisting(
+            "1418091",
+            "TWTR",
+            (2022, 10, 28),
+            DelistingReason::Merger,
+            Some(54.20 / 53.70 - 1.0),
+        ),
+        delisting(
+            "718877",
+            "ATVI",
+            (2023, 10, 13),
+            DelistingReason::Merger,
+            Some(95.00 / 94.40 - 1.0),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_security_id_round_trip() {
+        let id = SecurityId::new("1067983", Some("b"));
+        assert_eq!(id.to_string(), "0001067983.B");
+        assert_eq!("0001067983.B".parse::<SecurityId>().unwrap(), id);
+
+        let id: SecurityId = "320193".parse().unwrap();
+        assert_eq!(id, SecurityId::from_cik("0000320193"));
+        assert_eq!(id.to_string(), "0000320193");
+
+        assert!("AAPL".parse::<SecurityId>().is_err());
+    }
+
+    #[test]
+    fn test_share_class_from_ticker() {
+        assert_eq!(share_class_from_ticker("BRK-B"), Some("B"));
+        assert_eq!(share_class_from_ticker("BF.A"), Some("A"));
+        assert_eq!(share_class_from_ticker("AAPL"), None);
+    }
+
+    #[test]
+    fn test_ticker_period_bounds() {
+        let period = TickerPeriod {
+            security_id: SecurityId::from_cik("1326801"),
+            ticker: "FB".to_string(),
+            start: None,
+            end: Some(date(2022, 6, 8)),
+        };
+        assert!(period.contains(date(2015, 1, 2)));
+        assert!(period.contains(date(2022, 6, 8)));
+        assert!(!period.contains(date(2022, 6, 9)));
+        assert!(period.overlaps(date(2022, 6, 1), date(2022, 7, 1)));
+        assert!(!period.overlaps(date(2022, 6, 9), date(2022, 7, 1)));
+    }
+
+    #[test]
+    fn test_delisting_default_return() {
+        let mut delisting = Delisting {
+            security_id: SecurityId::from_cik("1"),
+            ticker: "GONE".to_string(),
+            date: date(2020, 1, 2),
+            reason: DelistingReason::Performance,
+            delisting_return: None,
+        };
+        assert_eq!(delisting.effective_return(), -0.30);
+
+        delisting.reason = DelistingReason::Merger;
+        assert_eq!(delisting.effective_return(), 0.0);
+
+        delisting.delisting_return = Some(0.12);
+        assert_eq!(delisting.effective_return(), 0.12);
+    }
+}
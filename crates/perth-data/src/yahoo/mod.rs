@@ -4,4 +4,4 @@ pub mod fundamentals;
 pub mod quotes;
 
 pub use fundamentals::{FundamentalData, YahooFundamentalsProvider};
-pub use quotes::YahooQuoteProvider;
+pub use quotes::{Dividend, DividendHistory, YahooQuoteProvider};
@@ -1,22 +1,88 @@
 //! Quote data fetching from Yahoo Finance.
 
 use crate::error::{DataError, Result};
-use chrono::{DateTime, Utc};
+use crate::retry::{RateLimiter, RetryConfig, RetryPolicy, transient_status_error};
+use chrono::{DateTime, Months, NaiveDate, Utc};
 use polars::prelude::*;
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
 use yahoo_finance_api as yahoo;
 
-/// Yahoo Finance quote provider with rate limiting.
+/// Yahoo Finance chart endpoint.
+const YAHOO_CHART_URL: &str = "https://query1.finance.yahoo.com/v8/finance/chart";
+
+/// Browser user agent; the chart endpoint rejects requests without one.
+const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36";
+
+/// A cash dividend per share, in the listing's currency.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dividend {
+    /// Ex-dividend date
+    pub ex_date: NaiveDate,
+    /// Amount per share
+    pub amount: f64,
+}
+
+/// Every dividend a symbol went ex over an inclusive date range.
+///
+/// A history without dividends means the symbol is known to have paid none
+/// over the range. Dates outside the range are unknown rather than zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DividendHistory {
+    /// First date covered
+    pub start: NaiveDate,
+    /// Last date covered
+    pub end: NaiveDate,
+    /// Dividends by ex-date, oldest first
+    pub dividends: Vec<Dividend>,
+}
+
+impl DividendHistory {
+    /// Whether `date` lies in the covered range.
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        (self.start..=self.end).contains(&date)
+    }
+
+    /// Dividends that went ex in the twelve months ending on `date`, or
+    /// `None` when `date` is not covered.
+    ///
+    /// Near the start of the range the window is truncated to the covered
+    /// dates.
+    pub fn trailing_12m(&self, date: NaiveDate) -> Option<f64> {
+        if !self.covers(date) {
+            return None;
+        }
+        let after = date.checked_sub_months(Months::new(12))?;
+        Some(
+            self.dividends
+                .iter()
+                .filter(|d| d.ex_date > after && d.ex_date <= date)
+                .map(|d| d.amount)
+                .sum(),
+        )
+    }
+}
+
+/// Yahoo Finance quote provider with rate limiting and retries.
+///
+/// Each request, retries included, waits until the rate limiter's current
+/// interval has passed since the previous one. Rate-limit responses lengthen
+/// that interval and successes shorten it back toward the configured delay.
+/// Transient failures are retried according to the provider's
+/// [`RetryPolicy`].
 pub struct YahooQuoteProvider {
-    provider: yahoo::YahooConnector,
-    rate_limit_delay: Duration,
+    client: reqwest::Client,
+    base_url: String,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
 }
 
 impl std::fmt::Debug for YahooQuoteProvider {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("YahooQuoteProvider")
-            .field("rate_limit_delay", &self.rate_limit_delay)
+            .field("base_url", &self.base_url)
+            .field("rate_limit_delay", &self.rate_limiter.interval())
+            .field("retry_policy", &self.retry_policy)
             .finish_non_exhaustive()
     }
 }
@@ -24,20 +90,42 @@ impl std::fmt::Debug for YahooQuoteProvider {
 impl YahooQuoteProvider {
     /// Create a new Yahoo Finance quote provider with default rate limiting (1 req/sec).
     pub fn new() -> Self {
-        Self {
-            provider: yahoo::YahooConnector::new().expect("Failed to create Yahoo connector"),
-            rate_limit_delay: Duration::from_millis(1000),
-        }
+        Self::with_rate_limit(Duration::from_millis(1000))
     }
 
     /// Create a new Yahoo Finance quote provider with custom rate limiting.
     pub fn with_rate_limit(rate_limit_delay: Duration) -> Self {
+        let rate_limiter = Arc::new(RateLimiter::new(rate_limit_delay));
         Self {
-            provider: yahoo::YahooConnector::new().expect("Failed to create Yahoo connector"),
-            rate_limit_delay,
+            client: reqwest::Client::builder()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build()
+                .expect("Failed to create HTTP client"),
+            base_url: YAHOO_CHART_URL.to_string(),
+            retry_policy: RetryPolicy::new(RetryConfig::default())
+                .with_rate_limiter(Arc::clone(&rate_limiter)),
+            rate_limiter,
         }
     }
 
+    /// Replace the retry configuration used for quote requests.
+    pub fn with_retry_config(mut self, config: RetryConfig) -> Self {
+        self.retry_policy =
+            RetryPolicy::new(config).with_rate_limiter(Arc::clone(&self.rate_limiter));
+        self
+    }
+
+    /// Send chart requests to a different endpoint.
+    ///
+    /// `base_url` replaces `https://query1.finance.yahoo.com/v8/finance/chart`;
+    /// requests go to `{base_url}/{symbol}`. Useful for proxies and local
+    /// test servers.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     /// Fetch OHLCV data for a single symbol.
     ///
     /// # Arguments
@@ -53,6 +141,21 @@ impl YahooQuoteProvider {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> Result<DataFrame> {
+        let (quotes, _) = self.fetch_quotes_with_dividends(symbol, start, end).await?;
+        Ok(quotes)
+    }
+
+    /// Fetch OHLCV data for a single symbol together with its dividends.
+    ///
+    /// Both come from the same chart response. The dividend history covers
+    /// `start` to `end`, so a symbol without dividends in it is a known
+    /// non-payer over the period.
+    pub async fn fetch_quotes_with_dividends(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<(DataFrame, DividendHistory)> {
         // Validate date range
         if start > end {
             return Err(DataError::InvalidDateRange {
@@ -66,22 +169,21 @@ impl YahooQuoteProvider {
             return Err(DataError::InvalidSymbol("Empty symbol".to_string()));
         }
 
-        // Convert chrono DateTime to time::OffsetDateTime
-        let start_time = time::OffsetDateTime::from_unix_timestamp(start.timestamp())
-            .map_err(|e| DataError::TimeConversion(e.to_string()))?;
-        let end_time = time::OffsetDateTime::from_unix_timestamp(end.timestamp())
-            .map_err(|e| DataError::TimeConversion(e.to_string()))?;
-
-        // Fetch data from Yahoo Finance
-        let response = self
-            .provider
-            .get_quote_history(symbol, start_time, end_time)
+        // Fetch data from Yahoo Finance, retrying transient failures
+        let url = format!(
+            "{}/{symbol}?symbol={symbol}&period1={}&period2={}&interval=1d&events=div|split|capitalGains",
+            self.base_url,
+            start.timestamp(),
+            end.timestamp(),
+        );
+        let (quotes, dividends) = self
+            .retry_policy
+            .execute("Yahoo Finance chart", || async {
+                self.rate_limiter.wait().await;
+                self.fetch_chart(&url).await
+            })
             .await?;
 
-        let quotes = response
-            .quotes()
-            .map_err(|e| DataError::YahooApi(e.to_string()))?;
-
         if quotes.is_empty() {
             return Err(DataError::MissingData {
                 symbol: symbol.to_string(),
@@ -133,10 +235,62 @@ impl YahooQuoteProvider {
             ])
             .collect()?;
 
-        // Apply rate limiting
-        sleep(self.rate_limit_delay).await;
+        let dividends = DividendHistory {
+            start: start.date_naive(),
+            end: end.date_naive(),
+            dividends: dividends
+                .into_iter()
+                .filter_map(|d| {
+                    Some(Dividend {
+                        ex_date: DateTime::from_timestamp(d.date, 0)?.date_naive(),
+                        amount: d.amount,
+                    })
+                })
+                .collect(),
+        };
+
+        Ok((df, dividends))
+    }
+
+    /// Request a chart and decode its quotes and dividends.
+    async fn fetch_chart(&self, url: &str) -> Result<(Vec<yahoo::Quote>, Vec<yahoo::Dividend>)> {
+        let response = self.client.get(url).send().await?;
+        if let Some(err) = transient_status_error(&response) {
+            return Err(err);
+        }
+
+        let status = response.status();
+        let body = response.text().await?;
+        let chart = match serde_json::from_str::<yahoo::YResponse>(&body) {
+            Ok(chart) => chart,
+            Err(_) if body.to_lowercase().contains("too many requests") => {
+                return Err(DataError::RateLimit { retry_after_ms: 0 });
+            }
+            Err(_) if !status.is_success() => {
+                return Err(DataError::YahooApi(format!("HTTP {status}")));
+            }
+            Err(e) => return Err(DataError::YahooApi(e.to_string())),
+        };
+
+        if chart.chart.result.is_none()
+            && let Some(error) = &chart.chart.error
+        {
+            return Err(DataError::YahooApi(
+                error
+                    .description
+                    .clone()
+                    .or_else(|| error.code.clone())
+                    .unwrap_or_else(|| format!("HTTP {status}")),
+            ));
+        }
 
-        Ok(df)
+        let quotes = chart
+            .quotes()
+            .map_err(|e| DataError::YahooApi(e.to_string()))?;
+        let dividends = chart
+            .dividends()
+            .map_err(|e| DataError::YahooApi(e.to_string()))?;
+        Ok((quotes, dividends))
     }
 
     /// Fetch OHLCV data for multiple symbols.
@@ -217,6 +371,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_trailing_dividends() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let history = DividendHistory {
+            start: date(1, 1),
+            end: date(12, 31),
+            dividends: vec![
+                Dividend {
+                    ex_date: date(2, 9),
+                    amount: 0.5,
+                },
+                Dividend {
+                    ex_date: date(5, 10),
+                    amount: 0.25,
+                },
+            ],
+        };
+        assert_eq!(history.trailing_12m(date(2, 8)), Some(0.0));
+        assert_eq!(history.trailing_12m(date(6, 30)), Some(0.75));
+        assert_eq!(history.trailing_12m(date(12, 31)), Some(0.75));
+        assert_eq!(
+            history.trailing_12m(NaiveDate::from_ymd_opt(2025, 1, 2).unwrap()),
+            None
+        );
+
+        let non_payer = DividendHistory {
+            dividends: Vec::new(),
+            ..history
+        };
+        assert_eq!(non_payer.trailing_12m(date(6, 30)), Some(0.0));
+    }
+
     #[tokio::test]
     async fn test_invalid_date_range() {
         let provider = YahooQuoteProvider::new();
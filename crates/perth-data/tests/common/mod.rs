@@ -0,0 +1,220 @@
+//! Local HTTP stand-in for the Yahoo Finance and SEC endpoints.
+//!
+//! [`MockServer`] binds an ephemeral port on 127.0.0.1 and answers `GET`
+//! requests from a table of routes keyed by path (query strings are ignored).
+//! Responses registered for the same path are served in order, and the last
+//! one keeps being served, so a test can script "fail twice, then succeed".
+//! Unknown paths answer 404.
+//!
+//! Fixture bodies live under `tests/fixtures/` and were trimmed from real
+//! responses of the corresponding endpoints.
+
+#![allow(dead_code, unreachable_pub)]
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Apple's CIK, the company covered by the SEC fixtures.
+pub const AAPL_CIK: &str = "0000320193";
+
+/// A canned HTTP response.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl MockResponse {
+    /// A response with the given status and JSON body.
+    pub fn json(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: body.into(),
+        }
+    }
+
+    /// A 200 response whose body is read from `tests/fixtures/<name>`.
+    pub fn fixture(name: &str) -> Self {
+        Self::json(200, load_fixture(name))
+    }
+
+    /// An empty response with the given status.
+    pub fn status(status: u16) -> Self {
+        Self::json(status, "")
+    }
+
+    /// Add a response header.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Routes {
+    responses: HashMap<String, VecDeque<MockResponse>>,
+    hits: HashMap<String, usize>,
+}
+
+impl Routes {
+    fn respond(&mut self, path: &str) -> MockResponse {
+        *self.hits.entry(path.to_string()).or_default() += 1;
+        match self.responses.get_mut(path) {
+            Some(queue) if queue.len() > 1 => queue.pop_front().expect("queue is non-empty"),
+            Some(queue) => queue.front().cloned().expect("routes are never empty"),
+            None => MockResponse::status(404),
+        }
+    }
+}
+
+/// Minimal HTTP/1.1 server serving recorded responses.
+#[derive(Debug)]
+pub struct MockServer {
+    base_url: String,
+    routes: Arc<Mutex<Routes>>,
+}
+
+impl MockServer {
+    /// Start a server with no routes.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock server");
+        let base_url = format!("http://{}", listener.local_addr().expect("local addr"));
+        let routes = Arc::new(Mutex::new(Routes::default()));
+
+        let accept_routes = Arc::clone(&routes);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle(stream, Arc::clone(&accept_routes)));
+            }
+        });
+
+        Self { base_url, routes }
+    }
+
+    /// Start a server with the SEC and Yahoo fixtures mounted at their real paths.
+    pub async fn with_fixtures() -> Self {
+        let server = Self::start().await;
+        server.mock(
+            "/files/company_tickers.json",
+            MockResponse::fixture("sec/company_tickers.json"),
+        );
+        server.mock(
+            &format!("/submissions/CIK{AAPL_CIK}.json"),
+            MockResponse::fixture(&format!("sec/submissions/CIK{AAPL_CIK}.json")),
+        );
+        server.mock(
+            &format!("/api/xbrl/companyfacts/CIK{AAPL_CIK}.json"),
+            MockResponse::fixture(&format!("sec/companyfacts/CIK{AAPL_CIK}.json")),
+        );
+        server.mock(
+            "/v8/finance/chart/AAPL",
+            MockResponse::fixture("yahoo/chart/AAPL.json"),
+        );
+        server
+    }
+
+    /// Queue a response for `path`.
+    pub fn mock(&self, path: &str, response: MockResponse) {
+        self.routes
+            .lock()
+            .expect("routes lock")
+            .responses
+            .entry(path.to_string())
+            .or_default()
+            .push_back(response);
+    }
+
+    /// Replace every queued response for `path`.
+    pub fn reset(&self, path: &str, responses: impl IntoIterator<Item = MockResponse>) {
+        let mut routes = self.routes.lock().expect("routes lock");
+        routes
+            .responses
+            .insert(path.to_string(), responses.into_iter().collect());
+    }
+
+    /// Number of requests received for `path`.
+    pub fn hits(&self, path: &str) -> usize {
+        self.routes
+            .lock()
+            .expect("routes lock")
+            .hits
+            .get(path)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Root URL of the server, e.g. `http://127.0.0.1:49152`.
+    pub fn url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Root of the Yahoo chart endpoint on this server.
+    pub fn chart_url(&self) -> String {
+        format!("{}/v8/finance/chart", self.base_url)
+    }
+
+    /// Root of the XBRL API on this server.
+    pub fn xbrl_url(&self) -> String {
+        format!("{}/api/xbrl", self.base_url)
+    }
+}
+
+/// Read a fixture file from `tests/fixtures/`.
+pub fn load_fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name);
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read fixture {}: {e}", path.display()))
+}
+
+async fn handle(mut stream: TcpStream, routes: Arc<Mutex<Routes>>) {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let target = request.split_whitespace().nth(1).unwrap_or("/");
+    let path = target.split('?').next().unwrap_or(target);
+    let response = routes.lock().expect("routes lock").respond(path);
+
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason(response.status),
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(response.body.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+const fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
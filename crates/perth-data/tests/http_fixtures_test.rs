@@ -0,0 +1,298 @@
+//! End-to-end tests of the HTTP clients against a local fixture server.
+
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use common::{AAPL_CIK, MockResponse, MockServer};
+use perth_data::DataError;
+use perth_data::cache::SqliteCache;
+use perth_data::edgar::{EdgarClient, EdgarFundamentalsProvider, PeriodType, XbrlClient};
+use perth_data::retry::RetryConfig;
+use perth_data::yahoo::YahooQuoteProvider;
+use std::time::Duration;
+
+const CHART_PATH: &str = "/v8/finance/chart/AAPL";
+const SUBMISSIONS_PATH: &str = "/submissions/CIK0000320193.json";
+
+/// Retries with negligible backoff so failure paths run quickly.
+fn fast_retries() -> RetryConfig {
+    RetryConfig {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+        jitter: 0.0,
+        ..RetryConfig::default()
+    }
+}
+
+fn edgar_client(server: &MockServer) -> EdgarClient {
+    EdgarClient::with_rate_limit(Duration::ZERO)
+        .unwrap()
+        .with_base_url(server.url())
+        .with_www_url(server.url())
+        .with_retry_config(fast_retries())
+}
+
+fn quote_provider(server: &MockServer) -> YahooQuoteProvider {
+    YahooQuoteProvider::with_rate_limit(Duration::ZERO)
+        .with_base_url(server.chart_url())
+        .with_retry_config(fast_retries())
+}
+
+fn january_2024() -> (chrono::DateTime<Utc>, chrono::DateTime<Utc>) {
+    (
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2024, 1, 9, 0, 0, 0).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_edgar_cik_lookup() {
+    let server = MockServer::with_fixtures().await;
+    let client = edgar_client(&server);
+
+    assert_eq!(client.get_company_cik("AAPL").await.unwrap(), AAPL_CIK);
+    assert_eq!(client.get_company_cik("msft").await.unwrap(), "0000789019");
+    assert!(matches!(
+        client.get_company_cik("NOTAREALTICKER").await,
+        Err(DataError::CikNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_edgar_company_filings() {
+    let server = MockServer::with_fixtures().await;
+    let client = edgar_client(&server);
+
+    let filings = client.get_company_filings("320193").await.unwrap();
+    assert_eq!(filings.name, "Apple Inc.");
+    assert_eq!(filings.sic_code(), Some(3571));
+    assert_eq!(
+        filings.sic_description.as_deref(),
+        Some("Electronic Computers")
+    );
+    let recent = &filings.filings.recent;
+    assert_eq!(recent.accession_number.len(), 4);
+    assert_eq!(recent.form[0], "10-K");
+    assert_eq!(recent.primary_document[0], "aapl-20240928.htm");
+    assert_eq!(server.hits(SUBMISSIONS_PATH), 1);
+}
+
+#[tokio::test]
+async fn test_edgar_missing_submissions_is_not_retried() {
+    let server = MockServer::with_fixtures().await;
+    let client = edgar_client(&server);
+
+    let result = client.get_company_filings("789019").await;
+    assert!(matches!(result, Err(DataError::EdgarApi(_))));
+    assert_eq!(server.hits("/submissions/CIK0000789019.json"), 1);
+}
+
+#[tokio::test]
+async fn test_edgar_server_errors_are_retried() {
+    let server = MockServer::with_fixtures().await;
+    server.reset(
+        SUBMISSIONS_PATH,
+        [
+            MockResponse::status(503),
+            MockResponse::status(502),
+            MockResponse::fixture("sec/submissions/CIK0000320193.json"),
+        ],
+    );
+    let client = edgar_client(&server);
+
+    let filings = client.get_company_filings(AAPL_CIK).await.unwrap();
+    assert_eq!(filings.filings.recent.form.len(), 4);
+    assert_eq!(server.hits(SUBMISSIONS_PATH), 3);
+}
+
+#[tokio::test]
+async fn test_xbrl_company_facts() {
+    let server = MockServer::with_fixtures().await;
+    let client = XbrlClient::new()
+        .with_base_url(server.xbrl_url())
+        .with_retry_config(fast_retries());
+
+    let doc = client.fetch_company_facts("320193").await.unwrap();
+    assert_eq!(doc.entity_name.as_deref(), Some("Apple Inc."));
+    assert_eq!(doc.cik.as_deref(), Some("320193"));
+
+    let assets = doc.get_latest_fact("us-gaap:Assets").unwrap();
+    assert_eq!(
+        assets.period_end,
+        NaiveDate::from_ymd_opt(2024, 9, 28).unwrap()
+    );
+    assert_eq!(assets.value, 364_980_000_000.0);
+}
+
+#[tokio::test]
+async fn test_xbrl_requests_are_rate_limited() {
+    let server = MockServer::with_fixtures().await;
+    let client = XbrlClient::new()
+        .with_base_url(server.xbrl_url())
+        .with_retry_config(fast_retries());
+
+    // Three requests at no more than 10 per second
+    let started = std::time::Instant::now();
+    for _ in 0..3 {
+        client.fetch_company_facts("320193").await.unwrap();
+    }
+    assert!(started.elapsed() >= Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn test_xbrl_unknown_company() {
+    let server = MockServer::with_fixtures().await;
+    let client = XbrlClient::new()
+        .with_base_url(server.xbrl_url())
+        .with_retry_config(fast_retries());
+
+    let result = client.fetch_company_facts("789019").await;
+    assert!(matches!(result, Err(DataError::Http(_))));
+}
+
+#[tokio::test]
+async fn test_fundamentals_provider_statements() {
+    let server = MockServer::with_fixtures().await;
+    let provider = EdgarFundamentalsProvider::new()
+        .with_base_url(server.url())
+        .with_www_url(server.url());
+
+    let statements = provider.fetch_financials("AAPL").await.unwrap();
+    assert_eq!(statements.len(), 3);
+
+    let latest = &statements[0];
+    assert_eq!(latest.cik, AAPL_CIK);
+    assert_eq!(latest.period_type, PeriodType::Annual);
+    assert_eq!(latest.fiscal_year, 2024);
+    assert_eq!(latest.total_assets, Some(364_980_000_000.0));
+    assert_eq!(latest.net_income, Some(93_736_000_000.0));
+}
+
+#[tokio::test]
+async fn test_yahoo_fetch_quotes() {
+    let server = MockServer::with_fixtures().await;
+    let provider = quote_provider(&server);
+    let (start, end) = january_2024();
+
+    let df = provider.fetch_quotes("AAPL", start, end).await.unwrap();
+    assert_eq!(df.height(), 5);
+    assert_eq!(
+        df.get_column_names(),
+        vec![
+            "symbol",
+            "date",
+            "open",
+            "high",
+            "low",
+            "close",
+            "volume",
+            "adjusted_close"
+        ]
+    );
+    let closes = df.column("close").unwrap().f64().unwrap();
+    assert_eq!(closes.get(0), Some(185.64));
+    assert_eq!(closes.get(4), Some(185.56));
+}
+
+#[tokio::test]
+async fn test_yahoo_quotes_round_trip_through_cache() {
+    let server = MockServer::with_fixtures().await;
+    let provider = quote_provider(&server);
+    let cache = SqliteCache::in_memory().unwrap();
+    let (start, end) = january_2024();
+
+    let fetched = provider.fetch_quotes("AAPL", start, end).await.unwrap();
+    cache.put_quotes(&fetched).unwrap();
+
+    let first = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+    let last = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+    assert!(cache.has_quotes("AAPL", first, last).unwrap());
+
+    let cached = cache.get_quotes("AAPL", first, last).unwrap();
+    assert_eq!(cached.height(), fetched.height());
+    assert_eq!(server.hits(CHART_PATH), 1);
+}
+
+#[tokio::test]
+async fn test_yahoo_unknown_symbol() {
+    let server = MockServer::with_fixtures().await;
+    server.mock(
+        "/v8/finance/chart/ZZZZ",
+        MockResponse::json(404, common::load_fixture("yahoo/chart/not_found.json")),
+    );
+    let provider = quote_provider(&server);
+    let (start, end) = january_2024();
+
+    match provider.fetch_quotes("ZZZZ", start, end).await {
+        Err(DataError::YahooApi(message)) => assert!(message.contains("No data found")),
+        other => panic!("expected YahooApi error, got {other:?}"),
+    }
+    assert_eq!(server.hits("/v8/finance/chart/ZZZZ"), 1);
+}
+
+#[tokio::test]
+async fn test_yahoo_rate_limit_honours_retry_after() {
+    let server = MockServer::with_fixtures().await;
+    server.reset(
+        CHART_PATH,
+        [
+            MockResponse::status(429).with_header("Retry-After", "0"),
+            MockResponse::fixture("yahoo/chart/AAPL.json"),
+        ],
+    );
+    let provider = quote_provider(&server);
+    let (start, end) = january_2024();
+
+    let df = provider.fetch_quotes("AAPL", start, end).await.unwrap();
+    assert_eq!(df.height(), 5);
+    assert_eq!(server.hits(CHART_PATH), 2);
+}
+
+#[tokio::test]
+async fn test_yahoo_retry_waits_for_slowed_rate_limit() {
+    let server = MockServer::with_fixtures().await;
+    server.reset(
+        CHART_PATH,
+        [
+            MockResponse::status(429).with_header("Retry-After", "0"),
+            MockResponse::fixture("yahoo/chart/AAPL.json"),
+        ],
+    );
+    let provider = YahooQuoteProvider::with_rate_limit(Duration::from_millis(50))
+        .with_base_url(server.chart_url())
+        .with_retry_config(fast_retries());
+    let (start, end) = january_2024();
+
+    // The 429 doubles the interval, which the retry waits out
+    let started = std::time::Instant::now();
+    provider.fetch_quotes("AAPL", start, end).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    assert_eq!(server.hits(CHART_PATH), 2);
+}
+
+#[tokio::test]
+async fn test_yahoo_circuit_opens_after_repeated_failures() {
+    let server = MockServer::with_fixtures().await;
+    server.reset(CHART_PATH, [MockResponse::status(500)]);
+    let provider = YahooQuoteProvider::with_rate_limit(Duration::ZERO)
+        .with_base_url(server.chart_url())
+        .with_retry_config(RetryConfig {
+            failure_threshold: 2,
+            cooldown: Duration::from_secs(60),
+            ..fast_retries()
+        });
+    let (start, end) = january_2024();
+
+    // The failure that opens the circuit is reported, later calls fail fast
+    let result = provider.fetch_quotes("AAPL", start, end).await;
+    assert!(matches!(
+        result,
+        Err(DataError::ServerError { status: 500 })
+    ));
+    assert_eq!(server.hits(CHART_PATH), 2);
+
+    let result = provider.fetch_quotes("AAPL", start, end).await;
+    assert!(matches!(result, Err(DataError::CircuitOpen(_))));
+    assert_eq!(server.hits(CHART_PATH), 2);
+}
@@ -52,7 +52,8 @@ fn main() {
         "Tech Leaders Portfolio".to_string(),
         vec![aapl_attribution, msft_attribution],
         vec![0.6, 0.4], // 60% AAPL, 40% MSFT
-    );
+    )
+    .expect("weights sum to one");
 
     println!("\n{}", portfolio.to_ascii_table());
 
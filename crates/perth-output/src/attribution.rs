@@ -1,11 +1,50 @@
 //! Factor attribution analysis for portfolio returns.
 //!
 //! This module provides structures and utilities for decomposing security and portfolio
-//! returns into factor contributions and specific returns.
+//! returns into factor contributions and specific returns, either over a
+//! single period or as an [`AttributionSeries`] over consecutive periods.
 
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Tolerance on portfolio weights summing to one.
+pub const WEIGHT_SUM_TOLERANCE: f64 = 1e-6;
+
+/// Errors that can occur building an attribution.
+#[derive(Debug, Error, PartialEq)]
+pub enum AttributionError {
+    /// Securities and weights differ in number.
+    #[error("{securities} securities but {weights} weights")]
+    WeightCount {
+        /// Number of securities.
+        securities: usize,
+        /// Number of weights.
+        weights: usize,
+    },
+
+    /// A weight is NaN or infinite.
+    #[error("weight {0} is not finite")]
+    NonFiniteWeight(f64),
+
+    /// Weights do not sum to one.
+    #[error("weights sum to {0}, not 1")]
+    WeightSum(f64),
+}
+
+/// Check that `weights` are finite and sum to one.
+pub(crate) fn check_weights(weights: &[f64]) -> Result<(), AttributionError> {
+    if let Some(&weight) = weights.iter().find(|w| !w.is_finite()) {
+        return Err(AttributionError::NonFiniteWeight(weight));
+    }
+    let sum: f64 = weights.iter().sum();
+    if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+        return Err(AttributionError::WeightSum(sum));
+    }
+    Ok(())
+}
 
 /// Factor attribution for a single factor.
 ///
@@ -169,6 +208,52 @@ impl SecurityAttribution {
         }
     }
 
+    /// Link consecutive sub-period attributions of one security into an
+    /// attribution over their whole span.
+    ///
+    /// The total return is compounded and the factor contributions are
+    /// linked with `method`, so they and the specific return add up to the
+    /// compounded total exactly. `None` if `periods` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use perth_output::{FactorAttribution, LinkingMethod, SecurityAttribution};
+    /// use chrono::NaiveDate;
+    ///
+    /// let day = |d: u32, total: f64, market: f64| {
+    ///     let date = NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+    ///     let factors = vec![FactorAttribution::new("market".to_string(), 1.0, market, total)];
+    ///     SecurityAttribution::new("AAPL".to_string(), date, date, total, factors)
+    /// };
+    ///
+    /// let linked = SecurityAttribution::linked(
+    ///     "AAPL".to_string(),
+    ///     &[day(2, 0.10, 0.08), day(3, 0.10, 0.05)],
+    ///     LinkingMethod::Carino,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!((linked.total_return - 0.21).abs() < 1e-12);
+    /// assert!((linked.factor_return + linked.specific_return - 0.21).abs() < 1e-12);
+    /// ```
+    pub fn linked(symbol: String, periods: &[Self], method: LinkingMethod) -> Option<Self> {
+        let period_start = periods.iter().map(|p| p.period_start).min()?;
+        let period_end = periods.iter().map(|p| p.period_end).max()?;
+        let pairs: Vec<(f64, &[FactorAttribution])> = periods
+            .iter()
+            .map(|p| (p.total_return, p.factors.as_slice()))
+            .collect();
+        let (total_return, factors) = link_factors(&pairs, method);
+        Some(Self::new(
+            symbol,
+            period_start,
+            period_end,
+            total_return,
+            factors,
+        ))
+    }
+
     /// Get the R-squared (proportion of variance explained by factors).
     ///
     /// # Returns
@@ -463,26 +548,49 @@ impl PortfolioAttribution {
     /// * `securities` - Vector of security attributions
     /// * `weights` - Vector of weights (must sum to 1.0)
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if weights and securities have different lengths or weights don't sum to ~1.0.
+    /// Returns an error if weights and securities have different lengths, a
+    /// weight is not finite or the weights don't sum to 1.0 within
+    /// [`WEIGHT_SUM_TOLERANCE`].
     pub fn new_weighted(
         portfolio_name: String,
         securities: Vec<SecurityAttribution>,
         weights: Vec<f64>,
-    ) -> Self {
-        assert_eq!(
-            securities.len(),
-            weights.len(),
-            "Securities and weights must have same length"
-        );
-        assert!(
-            (weights.iter().sum::<f64>() - 1.0).abs() < 1e-6,
-            "Weights must sum to 1.0"
-        );
+    ) -> Result<Self, AttributionError> {
+        if !securities.is_empty() && securities.len() == weights.len() {
+            check_weights(&weights)?;
+        }
+        Self::from_holdings(portfolio_name, securities, weights)
+    }
 
+    /// Create a portfolio attribution from holdings weights as held.
+    ///
+    /// Unlike [`new_weighted`](Self::new_weighted), the weights need not sum
+    /// to one: net-short and dollar-neutral books, or a book of which only
+    /// some holdings are attributed, contribute their weighted returns
+    /// without being scaled to a fully invested portfolio.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if weights and securities have different lengths or
+    /// a weight is not finite.
+    pub fn from_holdings(
+        portfolio_name: String,
+        securities: Vec<SecurityAttribution>,
+        weights: Vec<f64>,
+    ) -> Result<Self, AttributionError> {
+        if securities.len() != weights.len() {
+            return Err(AttributionError::WeightCount {
+                securities: securities.len(),
+                weights: weights.len(),
+            });
+        }
         if securities.is_empty() {
-            return Self::new(portfolio_name, securities);
+            return Ok(Self::new(portfolio_name, securities));
+        }
+        if let Some(&weight) = weights.iter().find(|w| !w.is_finite()) {
+            return Err(AttributionError::NonFiniteWeight(weight));
         }
 
         let period_start = securities[0].period_start;
@@ -545,7 +653,7 @@ impl PortfolioAttribution {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        Self {
+        Ok(Self {
             portfolio_name,
             period_start,
             period_end,
@@ -554,6 +662,68 @@ impl PortfolioAttribution {
             specific_return,
             factors,
             securities,
+        })
+    }
+
+    /// Link consecutive sub-period attributions of one portfolio into an
+    /// attribution over their whole span.
+    ///
+    /// Portfolio factor contributions are linked with `method` so they and
+    /// the specific return add up to the compounded portfolio return
+    /// exactly; each security is linked over the sub-periods it appears in.
+    /// `None` if `periods` is empty.
+    pub fn linked(portfolio_name: String, periods: &[Self], method: LinkingMethod) -> Option<Self> {
+        let period_start = periods.iter().map(|p| p.period_start).min()?;
+        let period_end = periods.iter().map(|p| p.period_end).max()?;
+        let pairs: Vec<(f64, &[FactorAttribution])> = periods
+            .iter()
+            .map(|p| (p.total_return, p.factors.as_slice()))
+            .collect();
+        let (total_return, factors) = link_factors(&pairs, method);
+        let factor_return: f64 = factors.iter().map(|f| f.contribution).sum();
+
+        let mut symbols: Vec<&str> = Vec::new();
+        for security in periods.iter().flat_map(|p| &p.securities) {
+            if !symbols.contains(&security.symbol.as_str()) {
+                symbols.push(&security.symbol);
+            }
+        }
+        let securities = symbols
+            .into_iter()
+            .filter_map(|symbol| {
+                let history: Vec<SecurityAttribution> = periods
+                    .iter()
+                    .flat_map(|p| &p.securities)
+                    .filter(|s| s.symbol == symbol)
+                    .cloned()
+                    .collect();
+                SecurityAttribution::linked(symbol.to_string(), &history, method)
+            })
+            .collect();
+
+        Some(Self {
+            portfolio_name,
+            period_start,
+            period_end,
+            total_return,
+            factor_return,
+            specific_return: total_return - factor_return,
+            factors,
+            securities,
+        })
+    }
+
+    /// The portfolio's own attribution as a security named after it, for
+    /// building an [`AttributionSeries`] of the portfolio.
+    pub fn to_security_attribution(&self) -> SecurityAttribution {
+        SecurityAttribution {
+            symbol: self.portfolio_name.clone(),
+            period_start: self.period_start,
+            period_end: self.period_end,
+            total_return: self.total_return,
+            factor_return: self.factor_return,
+            specific_return: self.specific_return,
+            factors: self.factors.clone(),
         }
     }
 
@@ -743,6 +913,404 @@ impl fmt::Display for PortfolioAttribution {
     }
 }
 
+/// How the contributions of consecutive sub-periods are linked into a
+/// multi-period attribution.
+///
+/// Returns compound while contributions add, so summed daily contributions
+/// drift away from the compounded total return over long windows. Both
+/// methods scale sub-period `t`'s contributions by a coefficient `kₜ` with
+/// `Σₜ kₜ rₜ = Π(1 + rₜ) - 1 = R`. Since each sub-period's contributions sum
+/// to its return `rₜ`, the linked contributions sum to `R` exactly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkingMethod {
+    /// Carino (1999): `kₜ = [ln(1 + rₜ) / rₜ] / [ln(1 + R) / R]`, scaling
+    /// each sub-period by its log return.
+    ///
+    /// A return of -100% or less has no log return, so such an `rₜ` or `R`
+    /// gets the undamped ratio one instead. The coefficients stay finite,
+    /// but the linked contributions no longer sum to `R` exactly and the
+    /// difference is left in the specific return.
+    #[default]
+    Carino,
+    /// Menchero (2000): `kₜ = M + α rₜ`, with `M = (R / T) / ((1 + R)^(1/T) - 1)`
+    /// the average scaling and `α = (R - M Σrₜ) / Σrₜ²` the smallest
+    /// correction that reconciles exactly.
+    Menchero,
+}
+
+impl LinkingMethod {
+    /// Linking coefficient of each sub-period return in `returns`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use perth_output::LinkingMethod;
+    ///
+    /// let returns = [0.10, -0.05, 0.20];
+    /// let total = returns.iter().map(|r| 1.0 + r).product::<f64>() - 1.0;
+    /// for method in [LinkingMethod::Carino, LinkingMethod::Menchero] {
+    ///     let k = method.coefficients(&returns);
+    ///     let linked: f64 = k.iter().zip(&returns).map(|(k, r)| k * r).sum();
+    ///     assert!((linked - total).abs() < 1e-12);
+    /// }
+    /// ```
+    pub fn coefficients(self, returns: &[f64]) -> Vec<f64> {
+        let total = compound(returns.iter().copied());
+        match self {
+            Self::Carino => {
+                let scale = log_ratio(total);
+                returns.iter().map(|&r| log_ratio(r) / scale).collect()
+            }
+            Self::Menchero => {
+                let n = returns.len() as f64;
+                let mean_growth = (1.0 + total).powf(1.0 / n) - 1.0;
+                let m = if mean_growth.abs() > 1e-12 {
+                    (total / n) / mean_growth
+                } else {
+                    1.0
+                };
+                let sum: f64 = returns.iter().sum();
+                let sum_sq: f64 = returns.iter().map(|r| r * r).sum();
+                let alpha = if sum_sq > 1e-24 {
+                    (total - m * sum) / sum_sq
+                } else {
+                    0.0
+                };
+                returns.iter().map(|r| m + alpha * r).collect()
+            }
+        }
+    }
+}
+
+impl FromStr for LinkingMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "carino" => Ok(Self::Carino),
+            "menchero" => Ok(Self::Menchero),
+            other => Err(format!(
+                "Unknown linking method: {} (expected carino or menchero)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LinkingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Carino => write!(f, "carino"),
+            Self::Menchero => write!(f, "menchero"),
+        }
+    }
+}
+
+/// `Π(1 + rₜ) - 1`, accumulated without adding and removing the one so a
+/// single return comes back unchanged.
+fn compound(returns: impl IntoIterator<Item = f64>) -> f64 {
+    returns
+        .into_iter()
+        .fold(0.0, |total, r| total + r + total * r)
+}
+
+/// `ln(1 + r) / r`, tending to one as `r` goes to zero, and one for
+/// `r <= -1` where the log return is undefined.
+fn log_ratio(r: f64) -> f64 {
+    if r <= -1.0 {
+        1.0
+    } else if r.abs() < 1e-9 {
+        1.0 - r / 2.0
+    } else {
+        r.ln_1p() / r
+    }
+}
+
+/// Link sub-periods given as `(total_return, factors)` pairs.
+///
+/// Returns the compounded total return and one attribution per factor, in
+/// the order factors first appear: the mean exposure and the compounded
+/// factor return over the sub-periods it appears in, and the linked
+/// contribution. Percentages are of the compounded total.
+fn link_factors(
+    periods: &[(f64, &[FactorAttribution])],
+    method: LinkingMethod,
+) -> (f64, Vec<FactorAttribution>) {
+    let returns: Vec<f64> = periods.iter().map(|(total, _)| *total).collect();
+    let total_return = compound(returns.iter().copied());
+    let coefficients = method.coefficients(&returns);
+
+    let mut factors: Vec<FactorAttribution> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+    for ((_, period_factors), k) in periods.iter().zip(&coefficients) {
+        for factor in *period_factors {
+            let i = factors
+                .iter()
+                .position(|f| f.factor_name == factor.factor_name)
+                .unwrap_or_else(|| {
+                    factors.push(FactorAttribution {
+                        factor_name: factor.factor_name.clone(),
+                        exposure: 0.0,
+                        factor_return: 0.0,
+                        contribution: 0.0,
+                        contribution_pct: 0.0,
+                    });
+                    counts.push(0);
+                    factors.len() - 1
+                });
+            let entry = &mut factors[i];
+            counts[i] += 1;
+            entry.exposure += (factor.exposure - entry.exposure) / counts[i] as f64;
+            entry.factor_return = compound([entry.factor_return, factor.factor_return]);
+            entry.contribution += k * factor.contribution;
+        }
+    }
+    for factor in &mut factors {
+        factor.contribution_pct = if total_return.abs() > 1e-10 {
+            (factor.contribution / total_return) * 100.0
+        } else {
+            0.0
+        };
+    }
+    (total_return, factors)
+}
+
+/// Length of the periods in an [`AttributionSeries`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributionFrequency {
+    /// One period per trading day.
+    Daily,
+    /// One period per calendar month.
+    #[default]
+    Monthly,
+    /// One period per calendar quarter.
+    Quarterly,
+}
+
+impl AttributionFrequency {
+    /// First calendar day of the period containing `date`.
+    pub fn period_start(self, date: NaiveDate) -> NaiveDate {
+        let first_month = match self {
+            Self::Daily => return date,
+            Self::Monthly => date.month(),
+            Self::Quarterly => date.month0() / 3 * 3 + 1,
+        };
+        NaiveDate::from_ymd_opt(date.year(), first_month, 1).unwrap_or(date)
+    }
+}
+
+impl FromStr for AttributionFrequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" => Ok(Self::Daily),
+            "monthly" => Ok(Self::Monthly),
+            "quarterly" => Ok(Self::Quarterly),
+            other => Err(format!(
+                "Unknown frequency: {} (expected daily, monthly or quarterly)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AttributionFrequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Daily => write!(f, "daily"),
+            Self::Monthly => write!(f, "monthly"),
+            Self::Quarterly => write!(f, "quarterly"),
+        }
+    }
+}
+
+/// Attribution of a security's return over one period of an
+/// [`AttributionSeries`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttributionPeriod {
+    /// First trading day in the period.
+    pub period_start: NaiveDate,
+
+    /// Last trading day in the period.
+    pub period_end: NaiveDate,
+
+    /// Trading days in the period.
+    pub days: usize,
+
+    /// Daily total returns compounded over the period.
+    pub total_return: f64,
+
+    /// Sum of the linked factor contributions.
+    pub factor_return: f64,
+
+    /// Linked specific return; with `factor_return` it adds up to
+    /// `total_return`.
+    pub specific_return: f64,
+
+    /// Daily specific returns compounded from the first day of the series
+    /// through `period_end`.
+    pub cumulative_specific_return: f64,
+
+    /// Per-factor attribution: the mean daily exposure, the compounded
+    /// factor return and the linked contribution.
+    pub factors: Vec<FactorAttribution>,
+}
+
+impl AttributionPeriod {
+    /// The period's attribution as a [`SecurityAttribution`] of `symbol`.
+    pub fn to_security_attribution(&self, symbol: &str) -> SecurityAttribution {
+        SecurityAttribution {
+            symbol: symbol.to_string(),
+            period_start: self.period_start,
+            period_end: self.period_end,
+            total_return: self.total_return,
+            factor_return: self.factor_return,
+            specific_return: self.specific_return,
+            factors: self.factors.clone(),
+        }
+    }
+}
+
+/// A security's attribution over consecutive periods, for plotting how its
+/// exposures and factor contributions evolved.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttributionSeries {
+    /// Security symbol or identifier.
+    pub symbol: String,
+
+    /// Length of each period.
+    pub frequency: AttributionFrequency,
+
+    /// How daily contributions are linked within each period.
+    pub linking: LinkingMethod,
+
+    /// Periods in date order.
+    pub periods: Vec<AttributionPeriod>,
+
+    /// Fingerprint of the model specification the series was estimated
+    /// with, if known.
+    pub model_fingerprint: Option<String>,
+}
+
+impl AttributionSeries {
+    /// Build a series from daily attributions.
+    ///
+    /// Each of `daily` covers one trading day, its `period_end`. Days are
+    /// grouped into periods of `frequency`, whose contributions are linked
+    /// with `linking` so they compound to the period's return.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use perth_output::{
+    ///     AttributionFrequency, AttributionSeries, FactorAttribution, LinkingMethod,
+    ///     SecurityAttribution,
+    /// };
+    /// use chrono::NaiveDate;
+    ///
+    /// let day = |d: u32, total: f64, market: f64| {
+    ///     let date = NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+    ///     let factors = vec![FactorAttribution::new("market".to_string(), 1.0, market, total)];
+    ///     SecurityAttribution::new("AAPL".to_string(), date, date, total, factors)
+    /// };
+    ///
+    /// let series = AttributionSeries::new(
+    ///     "AAPL".to_string(),
+    ///     AttributionFrequency::Monthly,
+    ///     LinkingMethod::Carino,
+    ///     &[day(2, 0.02, 0.01), day(3, -0.01, -0.02)],
+    /// );
+    ///
+    /// let january = &series.periods[0];
+    /// assert!((january.total_return - (1.02 * 0.99 - 1.0)).abs() < 1e-12);
+    /// assert!((january.factor_return + january.specific_return - january.total_return).abs() < 1e-12);
+    /// ```
+    pub fn new(
+        symbol: String,
+        frequency: AttributionFrequency,
+        linking: LinkingMethod,
+        daily: &[SecurityAttribution],
+    ) -> Self {
+        let mut days: Vec<&SecurityAttribution> = daily.iter().collect();
+        days.sort_by_key(|day| day.period_end);
+
+        let mut groups: Vec<Vec<&SecurityAttribution>> = Vec::new();
+        for day in days {
+            match groups.last_mut() {
+                Some(group)
+                    if group.last().is_some_and(|last| {
+                        frequency.period_start(last.period_end)
+                            == frequency.period_start(day.period_end)
+                    }) =>
+                {
+                    group.push(day)
+                }
+                _ => groups.push(vec![day]),
+            }
+        }
+
+        let mut cumulative_specific = 1.0;
+        let periods = groups
+            .into_iter()
+            .filter_map(|group| {
+                let (first, last) = (group.first()?, group.last()?);
+                for day in &group {
+                    cumulative_specific *= 1.0 + day.specific_return;
+                }
+                let pairs: Vec<(f64, &[FactorAttribution])> = group
+                    .iter()
+                    .map(|day| (day.total_return, day.factors.as_slice()))
+                    .collect();
+                let (total_return, factors) = link_factors(&pairs, linking);
+                let factor_return: f64 = factors.iter().map(|f| f.contribution).sum();
+                Some(AttributionPeriod {
+                    period_start: first.period_end,
+                    period_end: last.period_end,
+                    days: group.len(),
+                    total_return,
+                    factor_return,
+                    specific_return: total_return - factor_return,
+                    cumulative_specific_return: cumulative_specific - 1.0,
+                    factors,
+                })
+            })
+            .collect();
+
+        Self {
+            symbol,
+            frequency,
+            linking,
+            periods,
+            model_fingerprint: None,
+        }
+    }
+
+    /// Attribution over the whole series, linking the periods with the
+    /// series' method. `None` for an empty series.
+    pub fn total(&self) -> Option<SecurityAttribution> {
+        let periods: Vec<SecurityAttribution> = self
+            .periods
+            .iter()
+            .map(|p| p.to_security_attribution(&self.symbol))
+            .collect();
+        SecurityAttribution::linked(self.symbol.clone(), &periods, self.linking)
+    }
+
+    /// Names of the factors in any period, in the order they first appear.
+    pub fn factor_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for factor in self.periods.iter().flat_map(|p| &p.factors) {
+            if !names.contains(&factor.factor_name.as_str()) {
+                names.push(&factor.factor_name);
+            }
+        }
+        names
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -887,25 +1455,80 @@ mod tests {
             "Tech Portfolio".to_string(),
             vec![sec1, sec2],
             vec![0.6, 0.4],
-        );
+        )
+        .unwrap();
 
         // 0.6 * 0.15 + 0.4 * 0.20 = 0.17
         assert!((portfolio.total_return - 0.17).abs() < 1e-6);
     }
 
     #[test]
-    #[should_panic(expected = "Weights must sum to 1.0")]
     fn test_portfolio_attribution_invalid_weights() {
         let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
         let end = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
 
         let sec1 = SecurityAttribution::new("AAPL".to_string(), start, end, 0.15, vec![]);
+        let weighted = |weights: Vec<f64>| {
+            PortfolioAttribution::new_weighted("Portfolio".to_string(), vec![sec1.clone()], weights)
+        };
+
+        // Doesn't sum to 1.0
+        assert_eq!(weighted(vec![0.5]), Err(AttributionError::WeightSum(0.5)));
+        assert_eq!(
+            weighted(vec![0.5, 0.5]),
+            Err(AttributionError::WeightCount {
+                securities: 1,
+                weights: 2
+            })
+        );
+        assert!(matches!(
+            weighted(vec![f64::NAN]),
+            Err(AttributionError::NonFiniteWeight(_))
+        ));
+        assert!(weighted(vec![1.0]).is_ok());
+    }
+
+    #[test]
+    fn test_portfolio_attribution_from_holdings() {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let security = |symbol: &str, total_return: f64, exposure: f64| {
+            SecurityAttribution::new(
+                symbol.to_string(),
+                start,
+                end,
+                total_return,
+                vec![FactorAttribution::new(
+                    "Market".to_string(),
+                    exposure,
+                    0.10,
+                    total_return,
+                )],
+            )
+        };
 
-        PortfolioAttribution::new_weighted(
-            "Portfolio".to_string(),
-            vec![sec1],
-            vec![0.5], // Doesn't sum to 1.0
+        // Long AAPL, short MSFT: the weights sum to zero and stay as held
+        let portfolio = PortfolioAttribution::from_holdings(
+            "Pair".to_string(),
+            vec![security("AAPL", 0.15, 1.2), security("MSFT", 0.20, 1.0)],
+            vec![0.5, -0.5],
+        )
+        .unwrap();
+        assert!((portfolio.total_return + 0.025).abs() < 1e-12);
+        assert!((portfolio.factors[0].exposure - 0.1).abs() < 1e-12);
+        assert!(
+            (portfolio.factor_return + portfolio.specific_return - portfolio.total_return).abs()
+                < 1e-12
         );
+
+        assert!(matches!(
+            PortfolioAttribution::from_holdings(
+                "Pair".to_string(),
+                vec![security("AAPL", 0.15, 1.2)],
+                vec![f64::INFINITY],
+            ),
+            Err(AttributionError::NonFiniteWeight(_))
+        ));
     }
 
     #[test]
@@ -957,4 +1580,203 @@ mod tests {
         assert!(md.contains("# Portfolio Factor Attribution"));
         assert!(md.contains("## Individual Securities"));
     }
+
+    /// One security's daily attribution with a market and a size factor.
+    fn daily(
+        month: u32,
+        d: u32,
+        total: f64,
+        exposure: f64,
+        factor_return: f64,
+    ) -> SecurityAttribution {
+        let date = NaiveDate::from_ymd_opt(2024, month, d).unwrap();
+        SecurityAttribution::new(
+            "AAPL".to_string(),
+            date,
+            date,
+            total,
+            vec![
+                FactorAttribution::new("market".to_string(), 1.0, 0.01, total),
+                FactorAttribution::new("size".to_string(), exposure, factor_return, total),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_linking_reconciles_compounded_return() {
+        // A year of large daily moves, where summed contributions drift far
+        // from the compounded return
+        let days: Vec<SecurityAttribution> = (0..250)
+            .map(|t| {
+                let t = t as f64;
+                let total = 0.03 * (t * 0.37).sin() + 0.004;
+                let date =
+                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Days::new(t as u64);
+                SecurityAttribution::new(
+                    "AAPL".to_string(),
+                    date,
+                    date,
+                    total,
+                    vec![
+                        FactorAttribution::new("market".to_string(), 1.0, 0.6 * total, total),
+                        FactorAttribution::new(
+                            "size".to_string(),
+                            0.5,
+                            0.01 * (t * 0.11).cos(),
+                            total,
+                        ),
+                    ],
+                )
+            })
+            .collect();
+        let compounded = days.iter().map(|d| 1.0 + d.total_return).product::<f64>() - 1.0;
+        let summed: f64 = days.iter().map(|d| d.total_return).sum();
+        assert!((compounded - summed).abs() > 0.01);
+
+        for method in [LinkingMethod::Carino, LinkingMethod::Menchero] {
+            let linked = SecurityAttribution::linked("AAPL".to_string(), &days, method).unwrap();
+            assert!((linked.total_return - compounded).abs() < 1e-12);
+            let contributions: f64 = linked.factors.iter().map(|f| f.contribution).sum();
+            assert!((contributions + linked.specific_return - compounded).abs() < 1e-12);
+            assert_eq!(linked.factors[1].exposure, 0.5);
+            assert_eq!(
+                linked.period_end - linked.period_start,
+                chrono::Duration::days(249)
+            );
+        }
+        assert!(
+            SecurityAttribution::linked("AAPL".to_string(), &[], LinkingMethod::Carino).is_none()
+        );
+
+        // Zero returns link with unit coefficients
+        assert_eq!(LinkingMethod::Carino.coefficients(&[0.0, 0.0]), [1.0, 1.0]);
+        assert_eq!(
+            LinkingMethod::Menchero.coefficients(&[0.0, 0.0]),
+            [1.0, 1.0]
+        );
+        assert_eq!("Menchero".parse(), Ok(LinkingMethod::Menchero));
+    }
+
+    #[test]
+    fn test_carino_total_loss() {
+        let returns = [0.10, -1.0, 0.05];
+        let k = LinkingMethod::Carino.coefficients(&returns);
+        assert!(k.iter().all(|k| k.is_finite()));
+        assert_eq!(k[1], 1.0);
+
+        // A short position can lose more than everything
+        assert!(
+            LinkingMethod::Carino
+                .coefficients(&[-1.5, 0.2])
+                .iter()
+                .all(|k| k.is_finite())
+        );
+
+        let days = [daily(1, 2, 0.10, 0.5, 0.02), daily(1, 3, -1.0, 0.5, -0.3)];
+        let linked =
+            SecurityAttribution::linked("AAPL".to_string(), &days, LinkingMethod::Carino).unwrap();
+        assert_eq!(linked.total_return, -1.0);
+        assert!(linked.factors.iter().all(|f| f.contribution.is_finite()));
+        assert!(linked.specific_return.is_finite());
+        assert!(
+            (linked.factor_return + linked.specific_return - linked.total_return).abs() < 1e-12
+        );
+    }
+
+    #[test]
+    fn test_portfolio_linked() {
+        let security = |symbol: &str, d: u32, total: f64| SecurityAttribution {
+            symbol: symbol.to_string(),
+            ..daily(1, d, total, 1.0, 0.01)
+        };
+        let periods = [
+            PortfolioAttribution::new("Tech".to_string(), vec![security("AAPL", 2, 0.04)]),
+            PortfolioAttribution::new(
+                "Tech".to_string(),
+                vec![security("AAPL", 3, -0.02), security("MSFT", 3, 0.06)],
+            ),
+        ];
+
+        let linked =
+            PortfolioAttribution::linked("Tech".to_string(), &periods, LinkingMethod::Menchero)
+                .unwrap();
+        // Equal weights: 4% on the first day, 2% on the second
+        let compounded = 1.04 * 1.02 - 1.0;
+        assert!((linked.total_return - compounded).abs() < 1e-12);
+        assert!((linked.factor_return + linked.specific_return - compounded).abs() < 1e-12);
+
+        // AAPL is linked over both days, MSFT over the one it was held
+        let symbols: Vec<&str> = linked
+            .securities
+            .iter()
+            .map(|s| s.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, ["AAPL", "MSFT"]);
+        assert!((linked.securities[0].total_return - (1.04 * 0.98 - 1.0)).abs() < 1e-12);
+        assert!((linked.securities[1].total_return - 0.06).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_attribution_series_periods() {
+        // Out of order on purpose
+        let days = [
+            daily(4, 1, 0.03, 1.0, 0.01),
+            daily(1, 30, 0.02, 0.5, 0.02),
+            daily(1, 31, 0.00, 1.5, -0.01),
+            daily(2, 1, -0.01, 1.0, 0.00),
+        ];
+
+        let monthly = AttributionSeries::new(
+            "AAPL".to_string(),
+            AttributionFrequency::Monthly,
+            LinkingMethod::Carino,
+            &days,
+        );
+        assert_eq!(monthly.factor_names(), ["market", "size"]);
+        assert_eq!(monthly.periods.len(), 3);
+
+        let january = &monthly.periods[0];
+        assert_eq!(january.days, 2);
+        assert_eq!(
+            january.period_start,
+            NaiveDate::from_ymd_opt(2024, 1, 30).unwrap()
+        );
+        assert_eq!(
+            january.period_end,
+            NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+        );
+        assert!((january.total_return - 0.02).abs() < 1e-12);
+        let size = &january.factors[1];
+        assert!((size.exposure - 1.0).abs() < 1e-12);
+        assert!((size.factor_return - (1.02 * 0.99 - 1.0)).abs() < 1e-12);
+        for period in &monthly.periods {
+            let explained = period.factor_return + period.specific_return;
+            assert!((explained - period.total_return).abs() < 1e-12);
+        }
+
+        // Specific returns of 0.0, 0.005, -0.02 and 0.01 compound
+        let cumulative = 1.005 * 0.98 * 1.01 - 1.0;
+        assert!((monthly.periods[2].cumulative_specific_return - cumulative).abs() < 1e-12);
+
+        let quarterly = AttributionSeries::new(
+            "AAPL".to_string(),
+            AttributionFrequency::Quarterly,
+            LinkingMethod::Carino,
+            &days,
+        );
+        assert_eq!(quarterly.periods.len(), 2);
+        assert_eq!(quarterly.periods[0].days, 3);
+        let total = quarterly.total().unwrap();
+        let compounded = 1.02 * 0.99 * 1.03 - 1.0;
+        assert!((total.total_return - compounded).abs() < 1e-12);
+        assert!((total.factor_return + total.specific_return - compounded).abs() < 1e-12);
+
+        assert_eq!(
+            AttributionFrequency::Quarterly
+                .period_start(NaiveDate::from_ymd_opt(2024, 9, 30).unwrap()),
+            NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()
+        );
+        assert_eq!("Quarterly".parse(), Ok(AttributionFrequency::Quarterly));
+        assert!("weekly".parse::<AttributionFrequency>().is_err());
+    }
 }
@@ -1,8 +1,10 @@
 //! Export functionality for Perth factor model data.
 //!
 //! This module provides comprehensive CSV and JSON export capabilities for
-//! factor exposures, risk decomposition, and portfolio analysis.
+//! factor exposures, risk decomposition, portfolio analysis and attribution
+//! time series.
 
+use crate::attribution::{AttributionError, AttributionSeries, check_weights};
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -29,6 +31,15 @@ pub enum ExportError {
     /// Invalid format error.
     #[error("Invalid format: {0}")]
     InvalidFormat(String),
+
+    /// Portfolio weights that are not finite or do not sum to one.
+    #[error("Invalid weights on {date}: {source}")]
+    Weights {
+        /// Date of the portfolio snapshot.
+        date: NaiveDate,
+        /// What is wrong with the weights.
+        source: AttributionError,
+    },
 }
 
 /// Export format options.
@@ -200,6 +211,279 @@ impl PortfolioExport {
     pub fn total_weight(&self) -> f64 {
         self.holdings.iter().map(|h| h.weight).sum()
     }
+
+    /// Get the weight of a symbol, zero if it is not held.
+    pub fn weight(&self, symbol: &str) -> f64 {
+        self.holdings
+            .iter()
+            .filter(|h| h.symbol == symbol)
+            .map(|h| h.weight)
+            .sum()
+    }
+
+    /// Active weights against a benchmark: portfolio minus benchmark weight
+    /// for every symbol held by either.
+    ///
+    /// Market values and shares are not carried over.
+    pub fn active_against(&self, benchmark: &Self) -> Self {
+        let mut symbols: Vec<&str> = self
+            .holdings
+            .iter()
+            .chain(&benchmark.holdings)
+            .map(|h| h.symbol.as_str())
+            .collect();
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        let holdings = symbols
+            .into_iter()
+            .map(|symbol| {
+                let active = self.weight(symbol) - benchmark.weight(symbol);
+                PortfolioHolding::new(symbol.to_string(), active, None, None)
+            })
+            .collect();
+
+        Self::new(
+            format!("{} vs {}", self.name, benchmark.name),
+            self.date,
+            holdings,
+        )
+    }
+
+    /// Check that the holdings' weights are finite and sum to one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ExportError::Weights`] naming the snapshot date.
+    pub fn validate_weights(&self) -> Result<(), ExportError> {
+        let weights: Vec<f64> = self.holdings.iter().map(|h| h.weight).collect();
+        check_weights(&weights).map_err(|source| ExportError::Weights {
+            date: self.date,
+            source,
+        })
+    }
+
+    /// Read dated holdings from a file, as JSON for a `.json` extension and
+    /// CSV otherwise, naming the portfolio after the file stem unless the
+    /// file states a name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, or a
+    /// snapshot has a weight that is not finite.
+    pub fn read_series(path: impl AsRef<Path>) -> Result<Vec<Self>, ExportError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("portfolio");
+        let format = if path.extension().is_some_and(|ext| ext == "json") {
+            ExportFormat::Json
+        } else {
+            ExportFormat::Csv
+        };
+        Self::parse_series(&contents, format, name)
+    }
+
+    /// Parse dated holdings into one snapshot per date, sorted by date.
+    ///
+    /// JSON holds one portfolio or an array of them. CSV has `symbol` and
+    /// `weight` columns, optional `market_value` and `shares` columns, and
+    /// either a `date` column or `# Date:` comment lines as written by the
+    /// CSV export, so exports of several dates can be concatenated. Without
+    /// a `weight` column, weights are each holding's share of the date's
+    /// market value. Weights are kept as given, so net-short, dollar-neutral
+    /// and partially invested books parse as held.
+    ///
+    /// ```
+    /// use perth_output::{ExportFormat, PortfolioExport};
+    ///
+    /// let csv = "date,symbol,weight\n\
+    ///            2024-01-31,AAPL,0.6\n\
+    ///            2024-01-31,MSFT,0.4\n\
+    ///            2024-02-29,AAPL,0.5\n\
+    ///            2024-02-29,MSFT,0.5\n";
+    /// let series = PortfolioExport::parse_series(csv, ExportFormat::Csv, "Tech").unwrap();
+    ///
+    /// assert_eq!(series.len(), 2);
+    /// assert_eq!(series[1].weight("AAPL"), 0.5);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ExportError::InvalidFormat`] for rows without a date,
+    /// symbol or weight, and [`ExportError::Weights`] if a snapshot has a
+    /// weight that is not finite.
+    pub fn parse_series(
+        contents: &str,
+        format: ExportFormat,
+        default_name: &str,
+    ) -> Result<Vec<Self>, ExportError> {
+        let mut series = match format {
+            ExportFormat::Json | ExportFormat::PrettyJson => {
+                match serde_json::from_str::<Vec<Self>>(contents) {
+                    Ok(series) => series,
+                    Err(_) => vec![serde_json::from_str::<Self>(contents)?],
+                }
+            }
+            ExportFormat::Csv => parse_holdings_csv(contents, default_name)?,
+        };
+        series.sort_by_key(|p| p.date);
+        for portfolio in &series {
+            if let Some(holding) = portfolio.holdings.iter().find(|h| !h.weight.is_finite()) {
+                return Err(ExportError::Weights {
+                    date: portfolio.date,
+                    source: AttributionError::NonFiniteWeight(holding.weight),
+                });
+            }
+        }
+        Ok(series)
+    }
+}
+
+/// Column positions of a holdings CSV.
+struct HoldingsColumns {
+    date: Option<usize>,
+    symbol: usize,
+    weight: Option<usize>,
+    market_value: Option<usize>,
+    shares: Option<usize>,
+}
+
+impl HoldingsColumns {
+    /// Recognize a header row, which needs a `symbol` column and a `weight`
+    /// or `market_value` column.
+    fn from_header(record: &csv::StringRecord) -> Option<Self> {
+        let find = |name: &str| {
+            record
+                .iter()
+                .position(|f| f.trim().eq_ignore_ascii_case(name))
+        };
+        let columns = Self {
+            date: find("date"),
+            symbol: find("symbol")?,
+            weight: find("weight"),
+            market_value: find("market_value"),
+            shares: find("shares"),
+        };
+        (columns.weight.is_some() || columns.market_value.is_some()).then_some(columns)
+    }
+}
+
+/// Parse a holdings CSV into one snapshot per date, in order of appearance.
+fn parse_holdings_csv(
+    contents: &str,
+    default_name: &str,
+) -> Result<Vec<PortfolioExport>, ExportError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(contents.trim_start_matches('\u{feff}').as_bytes());
+
+    let mut name = default_name.to_string();
+    let mut comment_date = None;
+    let mut columns: Option<HoldingsColumns> = None;
+    let mut series: Vec<PortfolioExport> = Vec::new();
+    let mut weighted_by_value = Vec::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |p| p.line());
+        // Fields are trimmed as read, so comments keep their commas and spaces
+        if record
+            .get(0)
+            .is_some_and(|f| f.trim_start().starts_with('#'))
+        {
+            let comment = record.iter().collect::<Vec<_>>().join(",");
+            let comment = comment.trim_start_matches('#').trim();
+            if let Some(value) = comment.strip_prefix("Portfolio:") {
+                name = value.trim().to_string();
+            } else if let Some(value) = comment.strip_prefix("Date:") {
+                comment_date = Some(parse_cell_date(value.trim(), line)?);
+            }
+            continue;
+        }
+        if record.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        if let Some(header) = HoldingsColumns::from_header(&record) {
+            columns = Some(header);
+            continue;
+        }
+        let Some(columns) = &columns else {
+            return Err(ExportError::InvalidFormat(format!(
+                "line {}: holdings before a header with symbol and weight columns",
+                line
+            )));
+        };
+
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+        };
+        let number = |index: Option<usize>| {
+            cell(index)
+                .map(|c| {
+                    c.parse::<f64>().map_err(|_| {
+                        ExportError::InvalidFormat(format!("line {}: invalid number {:?}", line, c))
+                    })
+                })
+                .transpose()
+        };
+        let date = match cell(columns.date) {
+            Some(date) => parse_cell_date(date, line)?,
+            None => comment_date.ok_or_else(|| {
+                ExportError::InvalidFormat(format!("line {}: holding without a date", line))
+            })?,
+        };
+        let symbol = cell(Some(columns.symbol)).ok_or_else(|| {
+            ExportError::InvalidFormat(format!("line {}: holding without a symbol", line))
+        })?;
+        let market_value = number(columns.market_value)?;
+        let weight = if columns.weight.is_some() {
+            number(columns.weight)?
+        } else {
+            weighted_by_value.push(date);
+            market_value
+        };
+        let Some(weight) = weight else {
+            return Err(ExportError::InvalidFormat(format!(
+                "line {}: {} has no weight",
+                line, symbol
+            )));
+        };
+        let holding = PortfolioHolding::new(
+            symbol.to_uppercase(),
+            weight,
+            market_value,
+            number(columns.shares)?,
+        );
+
+        match series.iter_mut().find(|p| p.date == date) {
+            Some(portfolio) => portfolio.holdings.push(holding),
+            None => series.push(PortfolioExport::new(name.clone(), date, vec![holding])),
+        }
+    }
+
+    // Weights from market values, over every holding of the date
+    for portfolio in &mut series {
+        if weighted_by_value.contains(&portfolio.date) {
+            let total = portfolio.total_weight();
+            for holding in &mut portfolio.holdings {
+                holding.weight /= total;
+            }
+        }
+    }
+    Ok(series)
+}
+
+/// Parse a `YYYY-MM-DD` date of a holdings file.
+fn parse_cell_date(cell: &str, line: u64) -> Result<NaiveDate, ExportError> {
+    NaiveDate::parse_from_str(cell, "%Y-%m-%d")
+        .map_err(|_| ExportError::InvalidFormat(format!("line {}: invalid date {:?}", line, cell)))
 }
 
 /// A single holding in a portfolio.
@@ -366,6 +650,381 @@ impl Exporter for PortfolioExport {
     }
 }
 
+impl Exporter for AttributionSeries {
+    /// CSV has one row per period, with an exposure and a contribution
+    /// column per factor, ready for plotting. The model fingerprint, if
+    /// known, is written as a comment above the header.
+    fn export_to_string(&self, format: ExportFormat) -> Result<String, ExportError> {
+        match format {
+            ExportFormat::Csv => {
+                let mut output = String::new();
+                if let Some(fingerprint) = &self.model_fingerprint {
+                    output.push_str(&format!("# Model: {}\n", fingerprint));
+                }
+
+                let factors = self.factor_names();
+                let mut header: Vec<String> = [
+                    "symbol",
+                    "period_start",
+                    "period_end",
+                    "days",
+                    "total_return",
+                    "factor_return",
+                    "specific_return",
+                    "cumulative_specific_return",
+                ]
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+                header.extend(factors.iter().map(|f| format!("{}_exposure", f)));
+                header.extend(factors.iter().map(|f| format!("{}_contribution", f)));
+
+                let mut wtr = csv::Writer::from_writer(vec![]);
+                wtr.write_record(&header)?;
+                for period in &self.periods {
+                    let factor = |name: &str| period.factors.iter().find(|f| f.factor_name == name);
+                    let mut record = vec![
+                        self.symbol.clone(),
+                        period.period_start.to_string(),
+                        period.period_end.to_string(),
+                        period.days.to_string(),
+                        period.total_return.to_string(),
+                        period.factor_return.to_string(),
+                        period.specific_return.to_string(),
+                        period.cumulative_specific_return.to_string(),
+                    ];
+                    record.extend(factors.iter().map(|name| {
+                        factor(name)
+                            .map(|f| f.exposure.to_string())
+                            .unwrap_or_default()
+                    }));
+                    record.extend(factors.iter().map(|name| {
+                        factor(name)
+                            .map(|f| f.contribution.to_string())
+                            .unwrap_or_default()
+                    }));
+                    wtr.write_record(&record)?;
+                }
+                let data =
+                    String::from_utf8(wtr.into_inner().map_err(|e| e.into_error())?).unwrap();
+                output.push_str(&data);
+                Ok(output)
+            }
+            ExportFormat::Json => Ok(serde_json::to_string(self)?),
+            ExportFormat::PrettyJson => Ok(serde_json::to_string_pretty(self)?),
+        }
+    }
+}
+
+/// A constituent added to or removed from a universe.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConstituentChange {
+    /// Security symbol.
+    pub symbol: String,
+
+    /// Sector on the side where the constituent is present.
+    pub sector: Option<String>,
+
+    /// Weight on the side where the constituent is present.
+    pub weight: f64,
+}
+
+impl ConstituentChange {
+    /// Create a new constituent change.
+    pub const fn new(symbol: String, sector: Option<String>, weight: f64) -> Self {
+        Self {
+            symbol,
+            sector,
+            weight,
+        }
+    }
+}
+
+/// A constituent present on both sides whose sector changed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SectorReclassification {
+    /// Security symbol.
+    pub symbol: String,
+
+    /// Sector before the change.
+    pub from_sector: Option<String>,
+
+    /// Sector after the change.
+    pub to_sector: Option<String>,
+}
+
+impl SectorReclassification {
+    /// Create a new sector reclassification.
+    pub const fn new(
+        symbol: String,
+        from_sector: Option<String>,
+        to_sector: Option<String>,
+    ) -> Self {
+        Self {
+            symbol,
+            from_sector,
+            to_sector,
+        }
+    }
+}
+
+/// A sector's share of a universe before and after a change.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SectorWeightChange {
+    /// Sector name.
+    pub sector: String,
+
+    /// Weight before the change (0.0 to 1.0).
+    pub from_weight: f64,
+
+    /// Weight after the change (0.0 to 1.0).
+    pub to_weight: f64,
+}
+
+impl SectorWeightChange {
+    /// Create a new sector weight change.
+    pub const fn new(sector: String, from_weight: f64, to_weight: f64) -> Self {
+        Self {
+            sector,
+            from_weight,
+            to_weight,
+        }
+    }
+
+    /// Change in weight.
+    pub fn change(&self) -> f64 {
+        self.to_weight - self.from_weight
+    }
+}
+
+/// Differences between two universes, or between two dates of one universe.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UniverseDiffExport {
+    /// Name of the earlier universe or snapshot.
+    pub from: String,
+
+    /// Name of the later universe or snapshot.
+    pub to: String,
+
+    /// Date the changes take effect, for dated membership changes.
+    pub date: Option<NaiveDate>,
+
+    /// Number of constituents before.
+    pub from_size: usize,
+
+    /// Number of constituents after.
+    pub to_size: usize,
+
+    /// Constituents only present after.
+    pub additions: Vec<ConstituentChange>,
+
+    /// Constituents only present before.
+    pub deletions: Vec<ConstituentChange>,
+
+    /// Constituents present on both sides with a different sector.
+    pub reclassifications: Vec<SectorReclassification>,
+
+    /// Sector weights on both sides, for every sector present on either.
+    pub sector_weights: Vec<SectorWeightChange>,
+}
+
+impl UniverseDiffExport {
+    /// Whether membership and sectors are unchanged.
+    pub const fn is_empty(&self) -> bool {
+        self.additions.is_empty() && self.deletions.is_empty() && self.reclassifications.is_empty()
+    }
+
+    /// Format as text for terminal display.
+    pub fn to_text(&self) -> String {
+        let sector = |s: &Option<String>| s.as_deref().unwrap_or("Unknown").to_string();
+        let mut output = String::new();
+
+        match self.date {
+            Some(date) => output.push_str(&format!(
+                "{}: {} -> {}
+",
+                date, self.from, self.to
+            )),
+            None => output.push_str(&format!(
+                "Universe diff: {} -> {}
+",
+                self.from, self.to
+            )),
+        }
+        output.push_str(&format!(
+            "Constituents: {} -> {} (+{} / -{})
+",
+            self.from_size,
+            self.to_size,
+            self.additions.len(),
+            self.deletions.len()
+        ));
+
+        if !self.additions.is_empty() {
+            output.push_str(&format!("\nAdditions ({}):\n", self.additions.len()));
+            for change in &self.additions {
+                output.push_str(&format!(
+                    "  + {:<10} {:<28} {:>7.2}%\n",
+                    change.symbol,
+                    sector(&change.sector),
+                    change.weight * 100.0
+                ));
+            }
+        }
+        if !self.deletions.is_empty() {
+            output.push_str(&format!("\nDeletions ({}):\n", self.deletions.len()));
+            for change in &self.deletions {
+                output.push_str(&format!(
+                    "  - {:<10} {:<28} {:>7.2}%\n",
+                    change.symbol,
+                    sector(&change.sector),
+                    change.weight * 100.0
+                ));
+            }
+        }
+        if !self.reclassifications.is_empty() {
+            output.push_str(&format!(
+                "\nReclassifications ({}):\n",
+                self.reclassifications.len()
+            ));
+            for change in &self.reclassifications {
+                output.push_str(&format!(
+                    "  ~ {:<10} {} -> {}\n",
+                    change.symbol,
+                    sector(&change.from_sector),
+                    sector(&change.to_sector)
+                ));
+            }
+        }
+
+        let moved: Vec<&SectorWeightChange> = self
+            .sector_weights
+            .iter()
+            .filter(|w| w.change().abs() >= 5e-5)
+            .collect();
+        if !moved.is_empty() {
+            output.push_str("\nSector weights:\n");
+            output.push_str(&format!(
+                "  {:<28} {:>8} {:>8} {:>8}\n",
+                "Sector", "Before", "After", "Change"
+            ));
+            for weight in moved {
+                output.push_str(&format!(
+                    "  {:<28} {:>7.2}% {:>7.2}% {:>+7.2}%\n",
+                    weight.sector,
+                    weight.from_weight * 100.0,
+                    weight.to_weight * 100.0,
+                    weight.change() * 100.0
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Convert to a flat structure suitable for CSV export.
+    fn to_flat_records(&self) -> Vec<UniverseChangeFlat> {
+        let record = |change: &str, symbol: Option<&str>| UniverseChangeFlat {
+            date: self.date,
+            change: change.to_string(),
+            symbol: symbol.map(str::to_string),
+            from_sector: None,
+            to_sector: None,
+            from_weight: None,
+            to_weight: None,
+        };
+
+        let mut records = Vec::new();
+        for c in &self.additions {
+            records.push(UniverseChangeFlat {
+                to_sector: c.sector.clone(),
+                to_weight: Some(c.weight),
+                ..record("added", Some(&c.symbol))
+            });
+        }
+        for c in &self.deletions {
+            records.push(UniverseChangeFlat {
+                from_sector: c.sector.clone(),
+                from_weight: Some(c.weight),
+                ..record("removed", Some(&c.symbol))
+            });
+        }
+        for c in &self.reclassifications {
+            records.push(UniverseChangeFlat {
+                from_sector: c.from_sector.clone(),
+                to_sector: c.to_sector.clone(),
+                ..record("reclassified", Some(&c.symbol))
+            });
+        }
+        for w in &self.sector_weights {
+            records.push(UniverseChangeFlat {
+                from_sector: Some(w.sector.clone()),
+                to_sector: Some(w.sector.clone()),
+                from_weight: Some(w.from_weight),
+                to_weight: Some(w.to_weight),
+                ..record("sector_weight", None)
+            });
+        }
+        records
+    }
+}
+
+impl std::fmt::Display for UniverseDiffExport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+/// Flattened universe change for CSV export.
+#[derive(Debug, Serialize, Deserialize)]
+struct UniverseChangeFlat {
+    date: Option<NaiveDate>,
+    change: String,
+    symbol: Option<String>,
+    from_sector: Option<String>,
+    to_sector: Option<String>,
+    from_weight: Option<f64>,
+    to_weight: Option<f64>,
+}
+
+impl Exporter for UniverseDiffExport {
+    fn export_to_string(&self, format: ExportFormat) -> Result<String, ExportError> {
+        match format {
+            ExportFormat::Csv => {
+                let mut wtr = csv::Writer::from_writer(vec![]);
+                for record in self.to_flat_records() {
+                    wtr.serialize(&record)?;
+                }
+                let data =
+                    String::from_utf8(wtr.into_inner().map_err(|e| e.into_error())?).unwrap();
+                Ok(data)
+            }
+            ExportFormat::Json => Ok(serde_json::to_string(self)?),
+            ExportFormat::PrettyJson => Ok(serde_json::to_string_pretty(self)?),
+        }
+    }
+}
+
+impl Exporter for Vec<UniverseDiffExport> {
+    fn export_to_string(&self, format: ExportFormat) -> Result<String, ExportError> {
+        match format {
+            ExportFormat::Csv => {
+                let mut wtr = csv::Writer::from_writer(vec![]);
+                for diff in self {
+                    for record in diff.to_flat_records() {
+                        wtr.serialize(&record)?;
+                    }
+                }
+                let data =
+                    String::from_utf8(wtr.into_inner().map_err(|e| e.into_error())?).unwrap();
+                Ok(data)
+            }
+            ExportFormat::Json => Ok(serde_json::to_string(self)?),
+            ExportFormat::PrettyJson => Ok(serde_json::to_string_pretty(self)?),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -539,6 +1198,67 @@ mod tests {
         assert_eq!(portfolio.total_weight(), 1.0);
     }
 
+    #[test]
+    fn test_portfolio_series_parse() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let january = PortfolioExport::new(
+            "Tech, Growth".to_string(),
+            date(1, 31),
+            vec![
+                PortfolioHolding::new("AAPL".to_string(), 0.6, Some(60000.0), None),
+                PortfolioHolding::new("MSFT".to_string(), 0.4, Some(40000.0), None),
+            ],
+        );
+        let february = PortfolioExport::new(
+            "Tech, Growth".to_string(),
+            date(2, 29),
+            vec![PortfolioHolding::new(
+                "AAPL".to_string(),
+                1.0,
+                None,
+                Some(10.0),
+            )],
+        );
+
+        // Concatenated CSV exports, later date first
+        let csv = [&february, &january]
+            .iter()
+            .map(|p| p.export_to_string(ExportFormat::Csv).unwrap())
+            .collect::<String>();
+        let series = PortfolioExport::parse_series(&csv, ExportFormat::Csv, "file").unwrap();
+        assert_eq!(series, vec![january.clone(), february.clone()]);
+
+        let json = serde_json::to_string(&[&january, &february]).unwrap();
+        let series = PortfolioExport::parse_series(&json, ExportFormat::Json, "file").unwrap();
+        assert_eq!(series.len(), 2);
+        let single = january.export_to_string(ExportFormat::Json).unwrap();
+        let series = PortfolioExport::parse_series(&single, ExportFormat::Json, "file").unwrap();
+        assert_eq!(series[0].name, "Tech, Growth");
+
+        // Weights from market values
+        let csv = "date,symbol,market_value\n2024-01-31,aapl,300\n2024-01-31,MSFT,100\n";
+        let series = PortfolioExport::parse_series(csv, ExportFormat::Csv, "file").unwrap();
+        assert_eq!(series[0].name, "file");
+        assert_eq!(series[0].weight("AAPL"), 0.75);
+
+        // Long-short books keep their weights
+        let csv = "date,symbol,weight\n2024-01-31,AAPL,0.6\n2024-01-31,MSFT,-0.6\n";
+        let series = PortfolioExport::parse_series(csv, ExportFormat::Csv, "file").unwrap();
+        assert_eq!(series[0].total_weight(), 0.0);
+
+        let csv = "date,symbol,weight\n2024-01-31,AAPL,0.6\n2024-01-31,MSFT,NaN\n";
+        let err = PortfolioExport::parse_series(csv, ExportFormat::Csv, "file").unwrap_err();
+        assert!(matches!(
+            err,
+            ExportError::Weights { date: d, source: AttributionError::NonFiniteWeight(_) } if d == date(1, 31)
+        ));
+        let csv = "symbol,weight\nAAPL,1.0\n";
+        assert!(matches!(
+            PortfolioExport::parse_series(csv, ExportFormat::Csv, "file"),
+            Err(ExportError::InvalidFormat(_))
+        ));
+    }
+
     #[test]
     fn test_export_to_file() {
         use std::io::Read;
@@ -611,6 +1331,34 @@ mod tests {
         assert!(json.contains("\"MSFT\""));
     }
 
+    #[test]
+    fn test_portfolio_active_weights() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let sector_fund = PortfolioExport::new(
+            "XLK".to_string(),
+            date,
+            vec![
+                PortfolioHolding::new("AAPL".to_string(), 0.6, None, None),
+                PortfolioHolding::new("MSFT".to_string(), 0.4, None, None),
+            ],
+        );
+        let benchmark = PortfolioExport::new(
+            "SPY".to_string(),
+            date,
+            vec![
+                PortfolioHolding::new("AAPL".to_string(), 0.1, None, None),
+                PortfolioHolding::new("XOM".to_string(), 0.9, None, None),
+            ],
+        );
+
+        let active = sector_fund.active_against(&benchmark);
+        assert_eq!(active.name, "XLK vs SPY");
+        assert_eq!(active.holdings.len(), 3);
+        assert!((active.weight("AAPL") - 0.5).abs() < 1e-12);
+        assert!((active.weight("XOM") + 0.9).abs() < 1e-12);
+        assert!(active.total_weight().abs() < 1e-12);
+    }
+
     #[test]
     fn test_portfolio_holding_creation() {
         let holding = PortfolioHolding::new("AAPL".to_string(), 0.25, Some(25000.0), Some(100.0));
@@ -656,4 +1404,113 @@ mod tests {
         assert_eq!(risk.specific_risk, 0.05);
         assert_eq!(risk.factor_contributions, contributions);
     }
+
+    fn sample_universe_diff() -> UniverseDiffExport {
+        UniverseDiffExport {
+            from: "old.csv".to_string(),
+            to: "new.csv".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 3, 18),
+            from_size: 2,
+            to_size: 2,
+            additions: vec![ConstituentChange::new(
+                "NVDA".to_string(),
+                Some("Information Technology".to_string()),
+                0.5,
+            )],
+            deletions: vec![ConstituentChange::new(
+                "XOM".to_string(),
+                Some("Energy".to_string()),
+                0.5,
+            )],
+            reclassifications: vec![SectorReclassification::new(
+                "GOOGL".to_string(),
+                Some("Information Technology".to_string()),
+                Some("Communication Services".to_string()),
+            )],
+            sector_weights: vec![SectorWeightChange::new("Energy".to_string(), 0.5, 0.0)],
+        }
+    }
+
+    #[test]
+    fn test_universe_diff_export_csv() {
+        let diff = sample_universe_diff();
+        let csv = diff.export_to_string(ExportFormat::Csv).unwrap();
+
+        assert!(csv.starts_with("date,change,symbol,from_sector,to_sector"));
+        assert!(csv.contains("2024-03-18,added,NVDA,,Information Technology,,0.5"));
+        assert!(csv.contains("removed,XOM,Energy,,0.5,"));
+        assert!(csv.contains("reclassified,GOOGL"));
+        assert!(csv.contains("sector_weight,,Energy,Energy,0.5,0.0"));
+    }
+
+    #[test]
+    fn test_universe_diff_export_json() {
+        let diffs = vec![sample_universe_diff()];
+        let json = diffs.export_to_string(ExportFormat::Json).unwrap();
+
+        let parsed: Vec<UniverseDiffExport> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, diffs);
+    }
+
+    #[test]
+    fn test_universe_diff_text() {
+        let diff = sample_universe_diff();
+        assert!(!diff.is_empty());
+        assert_eq!(diff.sector_weights[0].change(), -0.5);
+
+        let text = diff.to_text();
+        assert!(text.starts_with("2024-03-18: old.csv -> new.csv"));
+        assert!(text.contains("+ NVDA"));
+        assert!(text.contains("- XOM"));
+        assert!(text.contains("Information Technology -> Communication Services"));
+        assert!(text.contains("-50.00%"));
+    }
+
+    #[test]
+    fn test_attribution_series_export() {
+        use crate::attribution::{
+            AttributionFrequency, FactorAttribution, LinkingMethod, SecurityAttribution,
+        };
+
+        let day = |d: u32, factors: Vec<FactorAttribution>| {
+            let date = NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+            SecurityAttribution::new("AAPL".to_string(), date, date, 0.02, factors)
+        };
+        let market = FactorAttribution::new("market".to_string(), 1.0, 0.01, 0.02);
+        let size = FactorAttribution::new("size_score".to_string(), 0.5, 0.01, 0.02);
+        let series = AttributionSeries::new(
+            "AAPL".to_string(),
+            AttributionFrequency::Daily,
+            LinkingMethod::Carino,
+            &[day(2, vec![market.clone()]), day(3, vec![market, size])],
+        );
+
+        let csv = series.export_to_string(ExportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("symbol,period_start,period_end,days,total_return"));
+        assert!(lines[0].ends_with(
+            "market_exposure,size_score_exposure,market_contribution,size_score_contribution"
+        ));
+        assert!(lines[1].starts_with("AAPL,2024-01-02,2024-01-02,1,0.02,0.01,0.01"));
+        assert!(lines[1].ends_with(",1,,0.01,"));
+        assert!(lines[2].ends_with(",1,0.5,0.01,0.005"));
+
+        let json = series.export_to_string(ExportFormat::Json).unwrap();
+        let parsed: AttributionSeries = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.periods.len(), 2);
+        assert_eq!(parsed.factor_names(), series.factor_names());
+        assert!(json.contains("\"frequency\":\"daily\""));
+        assert!(json.contains("\"linking\":\"carino\""));
+        assert_eq!(parsed.model_fingerprint, None);
+
+        let series = AttributionSeries {
+            model_fingerprint: Some("0123456789abcdef".to_string()),
+            ..series
+        };
+        let csv = series.export_to_string(ExportFormat::Csv).unwrap();
+        assert!(csv.starts_with("# Model: 0123456789abcdef\nsymbol,period_start"));
+        let json = series.export_to_string(ExportFormat::Json).unwrap();
+        assert!(json.contains("\"model_fingerprint\":\"0123456789abcdef\""));
+    }
 }
@@ -9,10 +9,15 @@ pub mod export;
 pub mod report;
 pub mod summary;
 
-pub use attribution::{FactorAttribution, PortfolioAttribution, SecurityAttribution};
+pub use attribution::{
+    AttributionError, AttributionFrequency, AttributionPeriod, AttributionSeries,
+    FactorAttribution, LinkingMethod, PortfolioAttribution, SecurityAttribution,
+    WEIGHT_SUM_TOLERANCE,
+};
 pub use export::{
-    ExportError, ExportFormat, Exporter, FactorExposureExport, PortfolioExport, PortfolioHolding,
-    RiskDecompositionExport,
+    ConstituentChange, ExportError, ExportFormat, Exporter, FactorExposureExport, PortfolioExport,
+    PortfolioHolding, RiskDecompositionExport, SectorReclassification, SectorWeightChange,
+    UniverseDiffExport,
 };
 pub use report::{Report, ReportBuilder, ReportError};
 pub use summary::{FactorRiskContribution, RiskSummary, generate_risk_summary};
@@ -29,7 +29,8 @@ fn test_full_attribution_workflow() {
         "Test Portfolio".to_string(),
         vec![aapl, msft],
         vec![0.5, 0.5],
-    );
+    )
+    .unwrap();
 
     // Verify portfolio calculations
     assert_eq!(portfolio.securities.len(), 2);
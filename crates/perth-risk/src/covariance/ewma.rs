@@ -14,7 +14,8 @@ use ndarray::{Array1, Array2};
 use serde::{Deserialize, Serialize};
 
 /// EWMA covariance estimator configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct EwmaConfig {
     /// Decay factor λ (default: 0.95)
     /// Higher values = more weight on past, slower adaptation
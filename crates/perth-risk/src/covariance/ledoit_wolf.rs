@@ -35,7 +35,8 @@ pub enum ShrinkageTarget {
 }
 
 /// Ledoit-Wolf covariance estimator configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LedoitWolfConfig {
     /// Minimum number of observations required (default: 2)
     /// Note: Ledoit-Wolf works with small samples, but we need at least 2 observations
@@ -9,7 +9,7 @@ pub mod newey_west;
 pub mod regime;
 pub mod utils;
 
-pub use ewma::EwmaCovarianceEstimator;
+pub use ewma::{EwmaConfig, EwmaCovarianceEstimator};
 pub use ledoit_wolf::{LedoitWolfConfig, LedoitWolfEstimator, ShrinkageTarget};
 pub use newey_west::{NeweyWestConfig, NeweyWestEstimator};
 pub use regime::{VolatilityRegime, VolatilityRegimeConfig, VolatilityRegimeDetector};
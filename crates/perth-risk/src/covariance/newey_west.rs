@@ -24,7 +24,8 @@ use ndarray::{Array1, Array2};
 use serde::{Deserialize, Serialize};
 
 /// Newey-West covariance estimator configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NeweyWestConfig {
     /// Minimum number of observations required (default: 60)
     pub min_observations: usize,
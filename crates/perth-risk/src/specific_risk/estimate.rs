@@ -8,7 +8,8 @@ use ndarray::Array1;
 use serde::{Deserialize, Serialize};
 
 /// Configuration for specific risk estimation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SpecificRiskConfig {
     /// Method to use for variance estimation
     pub method: VarianceMethod,
@@ -24,7 +25,7 @@ pub struct SpecificRiskConfig {
 }
 
 /// Methods for estimating residual variance
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VarianceMethod {
     /// Simple historical standard deviation
     Historical,
@@ -8,7 +8,7 @@ pub mod bayesian;
 pub mod estimate;
 
 pub use bayesian::{BayesianShrinkageConfig, BayesianSpecificRisk};
-pub use estimate::SpecificRiskEstimator;
+pub use estimate::{SpecificRiskConfig, SpecificRiskEstimator, VarianceMethod};
 
 use thiserror::Error;
 
@@ -4,6 +4,9 @@
 #![warn(missing_docs)]
 #![forbid(unsafe_code)]
 
+pub mod regression;
+pub mod spec;
+pub mod style;
 pub mod universe;
 
 // Re-export main types from sub-crates
@@ -15,7 +18,8 @@ pub use perth_risk as risk;
 pub use factors;
 
 // Re-export common universe types
-pub use universe::{Universe, gics::GicsSector, sp500::SP500Universe};
+pub use spec::ModelSpec;
+pub use universe::{FileUniverse, Universe, gics::GicsSector, sp500::SP500Universe};
 
 /// Version information.
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,143 @@
+//! Identifying constraint between the market and sector factor returns.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Linear constraint on the sector returns that identifies the market
+/// intercept.
+///
+/// Every asset belongs to exactly one sector, so the intercept and the
+/// sector dummies are collinear and one restriction `Σₛ cₛ fₛ = 0` is needed.
+/// Its weights `cₛ` decide what the market return means: with cap weights it
+/// is the cap-weighted return of the sector-neutral market, and each sector
+/// return reads as that sector's return relative to it.
+///
+/// ```toml
+/// [regression]
+/// sector_constraint = "cap-weighted"    # equal-weighted or cap-weighted
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SectorConstraint {
+    /// Sector returns sum to zero, as in toraniko.
+    #[default]
+    EqualWeighted,
+    /// Sector returns weighted by each sector's share of the day's total
+    /// market cap sum to zero.
+    CapWeighted,
+}
+
+impl SectorConstraint {
+    /// Constraint weight of each sector on one date, summing to one.
+    ///
+    /// `sectors` holds one dummy column per sector and `market_caps` the
+    /// caps of the date's assets. A sector without members or market cap
+    /// gets weight zero under cap weighting, and a date without any market
+    /// cap falls back to equal weights.
+    pub(crate) fn weights(self, sectors: &[&[f64]], market_caps: &[f64]) -> Vec<f64> {
+        match self {
+            Self::EqualWeighted => vec![1.0 / sectors.len() as f64; sectors.len()],
+            Self::CapWeighted => {
+                let total: f64 = market_caps.iter().sum();
+                if total <= 0.0 || !total.is_finite() {
+                    return Self::EqualWeighted.weights(sectors, market_caps);
+                }
+                sectors
+                    .iter()
+                    .map(|dummies| {
+                        let cap: f64 = dummies.iter().zip(market_caps).map(|(d, m)| d * m).sum();
+                        cap / total
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl fmt::Display for SectorConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EqualWeighted => write!(f, "equal-weighted sector returns sum to zero"),
+            Self::CapWeighted => write!(f, "cap-weighted sector returns sum to zero"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regression::wls::constrained_wls;
+    use ndarray::Array2;
+
+    #[test]
+    fn test_constraint_weights() {
+        let tech = [1.0, 1.0, 0.0, 0.0];
+        let energy = [0.0, 0.0, 1.0, 0.0];
+        let utilities = [0.0, 0.0, 0.0, 1.0];
+        let sectors: [&[f64]; 3] = [&tech, &energy, &utilities];
+        let caps = [3.0, 1.0, 4.0, 2.0];
+
+        assert_eq!(
+            SectorConstraint::EqualWeighted.weights(&sectors, &caps),
+            [1.0 / 3.0; 3]
+        );
+        assert_eq!(
+            SectorConstraint::CapWeighted.weights(&sectors, &caps),
+            [0.4, 0.4, 0.2]
+        );
+    }
+
+    #[test]
+    fn test_cap_weighted_sector_returns_sum_to_zero() {
+        // Nine assets in three sectors, one style
+        let n = 9;
+        let sector_of = |i: usize| i % 3;
+        let dummies: Vec<Vec<f64>> = (0..3)
+            .map(|s| {
+                (0..n)
+                    .map(|i| f64::from(u8::from(sector_of(i) == s)))
+                    .collect()
+            })
+            .collect();
+        let sectors: Vec<&[f64]> = dummies.iter().map(Vec::as_slice).collect();
+        let matrix = Array2::from_shape_fn((n, 3), |(i, s)| dummies[s][i]);
+        let styles = Array2::from_shape_fn((n, 1), |(i, _)| (i as f64 * 0.9).sin());
+        let y: Vec<f64> = (0..n)
+            .map(|i| 0.001 * sector_of(i) as f64 + 0.002 * (i as f64 * 1.7).cos())
+            .collect();
+
+        for caps in [
+            vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0],
+            vec![50.0, 1.0, 1.0, 30.0, 1.0, 1.0, 20.0, 2.0, 3.0],
+        ] {
+            let constraint = SectorConstraint::CapWeighted.weights(&sectors, &caps);
+            assert!((constraint.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+            let fit = constrained_wls(&y, &caps, &matrix, &styles, &constraint).unwrap();
+            let weighted_sum: f64 = constraint
+                .iter()
+                .zip(&fit.factor_returns[1..4])
+                .map(|(c, f)| c * f)
+                .sum();
+            assert!(weighted_sum.abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_zero_cap_sector() {
+        let tech = [1.0, 1.0, 0.0, 0.0];
+        let energy = [0.0, 0.0, 1.0, 0.0];
+        let utilities = [0.0, 0.0, 0.0, 1.0];
+        let empty = [0.0; 4];
+        let sectors: [&[f64]; 4] = [&tech, &energy, &utilities, &empty];
+
+        // Utilities' only member has no market cap, and the last sector no
+        // members
+        let weights = SectorConstraint::CapWeighted.weights(&sectors, &[3.0, 1.0, 4.0, 0.0]);
+        assert_eq!(weights, [0.5, 0.5, 0.0, 0.0]);
+
+        // Without any market cap every sector counts equally
+        let weights = SectorConstraint::CapWeighted.weights(&sectors, &[0.0; 4]);
+        assert_eq!(weights, [0.25; 4]);
+    }
+}
@@ -0,0 +1,176 @@
+//! Fit statistics of each cross-sectional regression.
+
+use chrono::NaiveDate;
+use std::fmt;
+
+/// Effective assets per estimated parameter below which a cross-section is
+/// flagged as dominated by a few large weights.
+pub const MIN_EFFECTIVE_ASSETS_PER_FACTOR: f64 = 2.0;
+
+/// Absolute t-statistic above which a factor return counts as significant.
+pub const T_STAT_SIGNIFICANCE: f64 = 2.0;
+
+/// Why a cross-section's estimates are missing or unreliable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossSectionIssue {
+    /// Fewer assets than the regression needs; no factor returns were
+    /// estimated.
+    TooFewAssets {
+        /// Assets needed: the estimated parameters plus two
+        required: usize,
+    },
+    /// The exposures are linearly dependent, for example an industry with
+    /// no members; no factor returns were estimated.
+    Singular,
+    /// The effective number of assets is below
+    /// [`MIN_EFFECTIVE_ASSETS_PER_FACTOR`] per parameter, so a few large
+    /// caps drive the estimates.
+    ConcentratedWeights,
+}
+
+impl fmt::Display for CrossSectionIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFewAssets { required } => {
+                write!(f, "too few assets ({required} required), not estimated")
+            }
+            Self::Singular => write!(f, "singular exposures, not estimated"),
+            Self::ConcentratedWeights => write!(f, "weights concentrated in a few assets"),
+        }
+    }
+}
+
+/// Fit statistics of one date's cross-sectional regression.
+///
+/// Factor return standard errors and t-statistics are stored per factor in
+/// the `std_error` and `t_stat` columns of
+/// [`FactorReturns::factor_returns`](crate::regression::FactorReturns::factor_returns).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegressionDiagnostics {
+    /// Cross-section date
+    pub date: NaiveDate,
+    /// Assets in the regression
+    pub n_assets: usize,
+    /// Effective number of assets under the regression weights (market cap
+    /// times any robust weight), `(Σwᵢ)² / Σwᵢ²`
+    pub effective_n_assets: f64,
+    /// Estimated parameters: the market, all sectors but one (the
+    /// [`SectorConstraint`](crate::regression::SectorConstraint) fixes the
+    /// last) and the styles
+    pub n_parameters: usize,
+    /// Weighted R², NaN if not estimated
+    pub r_squared: f64,
+    /// R² adjusted for the parameters, NaN if not estimated
+    pub adjusted_r_squared: f64,
+    /// Problems with the cross-section, empty for a healthy one
+    pub issues: Vec<CrossSectionIssue>,
+    /// Assets whose robust weight fell below
+    /// [`DOWNWEIGHT_REPORT_THRESHOLD`](crate::regression::DOWNWEIGHT_REPORT_THRESHOLD), with that weight, lowest first;
+    /// empty without robust regression
+    pub downweighted: Vec<(String, f64)>,
+}
+
+impl RegressionDiagnostics {
+    /// Diagnostics of a cross-section that was not estimated.
+    pub(crate) fn skipped(
+        date: NaiveDate,
+        weights: &[f64],
+        n_parameters: usize,
+        issue: CrossSectionIssue,
+    ) -> Self {
+        Self {
+            date,
+            n_assets: weights.len(),
+            effective_n_assets: effective_n(weights),
+            n_parameters,
+            r_squared: f64::NAN,
+            adjusted_r_squared: f64::NAN,
+            issues: vec![issue],
+            downweighted: Vec::new(),
+        }
+    }
+
+    /// Diagnostics of an estimated cross-section.
+    pub(crate) fn estimated(
+        date: NaiveDate,
+        weights: &[f64],
+        n_parameters: usize,
+        r_squared: f64,
+        adjusted_r_squared: f64,
+        downweighted: Vec<(String, f64)>,
+    ) -> Self {
+        let effective_n_assets = effective_n(weights);
+        let issues = if effective_n_assets < MIN_EFFECTIVE_ASSETS_PER_FACTOR * n_parameters as f64 {
+            vec![CrossSectionIssue::ConcentratedWeights]
+        } else {
+            Vec::new()
+        };
+        Self {
+            date,
+            n_assets: weights.len(),
+            effective_n_assets,
+            n_parameters,
+            r_squared,
+            adjusted_r_squared,
+            issues,
+            downweighted,
+        }
+    }
+
+    /// Whether factor returns were estimated on this date.
+    pub fn is_estimated(&self) -> bool {
+        !self.issues.iter().any(|issue| {
+            matches!(
+                issue,
+                CrossSectionIssue::TooFewAssets { .. } | CrossSectionIssue::Singular
+            )
+        })
+    }
+
+    /// Whether the cross-section has any issue.
+    pub const fn is_degenerate(&self) -> bool {
+        !self.issues.is_empty()
+    }
+}
+
+/// `(Σwᵢ)² / Σwᵢ²` over the positive weights, 0 if there are none.
+fn effective_n(weights: &[f64]) -> f64 {
+    let (sum, sum_sq) = weights
+        .iter()
+        .map(|w| w.max(0.0))
+        .fold((0.0, 0.0), |(s, q), w| (s + w, q + w * w));
+    if sum_sq > 0.0 {
+        sum * sum / sum_sq
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_assets() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 28).unwrap();
+        let equal = RegressionDiagnostics::estimated(date, &[5.0; 40], 10, 0.3, 0.1, Vec::new());
+        assert!((equal.effective_n_assets - 40.0).abs() < 1e-9);
+        assert!(!equal.is_degenerate());
+
+        // One giant and many tiny caps make about one effective asset
+        let mut weights = vec![1.0; 40];
+        weights[0] = 1000.0;
+        let concentrated =
+            RegressionDiagnostics::estimated(date, &weights, 10, 0.3, 0.1, Vec::new());
+        assert!(concentrated.effective_n_assets < 2.0);
+        assert_eq!(
+            concentrated.issues,
+            [CrossSectionIssue::ConcentratedWeights]
+        );
+        assert!(concentrated.is_estimated());
+
+        let skipped =
+            RegressionDiagnostics::skipped(date, &[1.0; 5], 10, CrossSectionIssue::Singular);
+        assert!(!skipped.is_estimated() && skipped.r_squared.is_nan());
+    }
+}
@@ -0,0 +1,768 @@
+//! Cross-sectional regression of asset returns on factor exposures.
+//!
+//! [`CrossSectionalRegression`] estimates daily market, industry and style
+//! factor returns the way toraniko's `FactorReturnsEstimator` does: returns
+//! are winsorized, each cross-section is fit by weighted least squares with
+//! market-cap weights, and industry returns sum to zero so the market
+//! return is identified. Its frames have the same layout, so they feed
+//! `toraniko_model::compute_attribution` unchanged.
+//!
+//! toraniko passes `√cap` to its WLS, which scales each asset's row of the
+//! design matrix and returns by it, so squared residuals are weighted by
+//! `cap`. The fit here weighs squared residuals by `cap` directly, giving
+//! the same factor returns and residuals.
+//!
+//! [`SectorConstraint::CapWeighted`] instead makes the cap-weighted industry
+//! returns sum to zero, so the market factor is the cap-weighted market and
+//! each industry return is relative to it. The constraint in use is
+//! reported with the factor returns.
+//!
+//! Each date also gets [`RegressionDiagnostics`]: R², adjusted R², the
+//! number of assets and the effective number under the weights, and any
+//! [`CrossSectionIssue`] that makes the day's estimates missing or
+//! unreliable. Factor return standard errors and t-statistics are stored
+//! next to the returns.
+//!
+//! With `residualize_styles` set, each `*_score` column is first regressed
+//! on the sector dummies with market-cap weights and replaced by its
+//! residual, so style returns are estimated from sector-neutral scores and
+//! the part of a style's return shared with an industry goes to the
+//! industry.
+//!
+//! [`RobustWeighting`] optionally refits each cross-section by iteratively
+//! reweighted least squares with Huber or Tukey bisquare weights, so that
+//! earnings-day jumps and other outliers do not dominate factor returns.
+
+mod constraint;
+mod diagnostics;
+mod robust;
+mod wls;
+
+pub use constraint::SectorConstraint;
+pub use robust::{DOWNWEIGHT_REPORT_THRESHOLD, RobustMethod, RobustWeighting};
+
+pub use diagnostics::{
+    CrossSectionIssue, MIN_EFFECTIVE_ASSETS_PER_FACTOR, RegressionDiagnostics, T_STAT_SIGNIFICANCE,
+};
+
+use chrono::NaiveDate;
+use ndarray::{Array1, Array2};
+use polars::prelude::*;
+use thiserror::Error;
+use toraniko_model::EstimatorConfig;
+
+/// Errors that can occur while estimating factor returns.
+#[derive(Debug, Error)]
+pub enum RegressionError {
+    /// Polars error.
+    #[error("Polars error: {0}")]
+    Polars(#[from] PolarsError),
+
+    /// A required column is missing.
+    #[error("Missing column: {0}")]
+    MissingColumn(String),
+
+    /// No asset has returns, market cap and exposures on a common date.
+    #[error("No cross-section to estimate")]
+    NoData,
+}
+
+/// Result type for factor returns estimation.
+pub type Result<T> = std::result::Result<T, RegressionError>;
+
+/// Estimated factor returns with their fit statistics.
+#[derive(Debug, Clone)]
+pub struct FactorReturns {
+    /// Columns `date`, `factor`, `factor_return`, `std_error`, `t_stat` and
+    /// `constraint_weight`, one row per estimated date and factor. Factors
+    /// are `market`, the `sector_*` columns and the `*_score` columns.
+    /// `constraint_weight` is the sector's weight in the date's
+    /// [`SectorConstraint`], null for the market and styles.
+    pub factor_returns: DataFrame,
+    /// Columns `date`, `symbol` and `residual_return`.
+    pub residuals: DataFrame,
+    /// One entry per date, including dates that could not be estimated.
+    pub diagnostics: Vec<RegressionDiagnostics>,
+    /// Constraint identifying the market return.
+    pub sector_constraint: SectorConstraint,
+}
+
+/// How often a factor's return was significant over the estimated dates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactorSignificance {
+    /// Factor name, as in the `factor` column
+    pub factor: String,
+    /// Dates with a finite t-statistic
+    pub days: usize,
+    /// Mean absolute t-statistic
+    pub mean_abs_t: f64,
+    /// Share of dates with `|t|` above [`T_STAT_SIGNIFICANCE`]
+    pub significant_share: f64,
+}
+
+impl FactorReturns {
+    /// Dates whose cross-section has an issue.
+    pub fn degenerate(&self) -> impl Iterator<Item = &RegressionDiagnostics> {
+        self.diagnostics.iter().filter(|d| d.is_degenerate())
+    }
+
+    /// Per-factor t-statistic summary, in the order factors first appear.
+    pub fn significance(&self) -> Result<Vec<FactorSignificance>> {
+        let t = col("t_stat").abs();
+        let summary = self
+            .factor_returns
+            .clone()
+            .lazy()
+            .filter(col("t_stat").is_finite())
+            .group_by_stable([col("factor")])
+            .agg([
+                t.clone().count().alias("days"),
+                t.clone().mean().alias("mean_abs_t"),
+                t.gt(lit(T_STAT_SIGNIFICANCE))
+                    .cast(DataType::Float64)
+                    .mean()
+                    .alias("significant_share"),
+            ])
+            .collect()?;
+
+        let factors = summary.column("factor")?.str()?;
+        let days = summary.column("days")?.cast(&DataType::UInt64)?;
+        let days = days.u64()?;
+        let mean_abs_t = summary.column("mean_abs_t")?.f64()?;
+        let share = summary.column("significant_share")?.f64()?;
+        Ok((0..summary.height())
+            .map(|i| FactorSignificance {
+                factor: factors.get(i).unwrap_or_default().to_string(),
+                days: days.get(i).unwrap_or(0) as usize,
+                mean_abs_t: mean_abs_t.get(i).unwrap_or(f64::NAN),
+                significant_share: share.get(i).unwrap_or(f64::NAN),
+            })
+            .collect())
+    }
+}
+
+/// Daily cross-sectional WLS estimator of factor returns.
+#[derive(Debug, Clone)]
+pub struct CrossSectionalRegression {
+    config: EstimatorConfig,
+    robust: RobustWeighting,
+    sector_constraint: SectorConstraint,
+}
+
+impl Default for CrossSectionalRegression {
+    fn default() -> Self {
+        Self::with_config(EstimatorConfig::default())
+    }
+}
+
+impl CrossSectionalRegression {
+    /// Create an estimator with toraniko's default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an estimator with `config`.
+    ///
+    /// `winsor_factor` clips each day's returns before the fit and
+    /// `residualize_styles` removes the cap-weighted sector means from the
+    /// style scores, as toraniko's estimator does.
+    pub fn with_config(config: EstimatorConfig) -> Self {
+        Self {
+            config,
+            robust: RobustWeighting::default(),
+            sector_constraint: SectorConstraint::default(),
+        }
+    }
+
+    /// Refit each cross-section with robust weights.
+    pub const fn with_robust_weighting(mut self, robust: RobustWeighting) -> Self {
+        self.robust = robust;
+        self
+    }
+
+    /// Identify the market return with `constraint` on the sector returns.
+    pub const fn with_sector_constraint(mut self, constraint: SectorConstraint) -> Self {
+        self.sector_constraint = constraint;
+        self
+    }
+
+    /// Estimate factor returns for every date the inputs share.
+    ///
+    /// Takes toraniko's inputs: `returns` with `asset_returns`,
+    /// `market_caps` with `market_cap`, `sectors` with `sector_*` dummies and
+    /// `styles` with `*_score` exposures, each keyed by `date` and `symbol`.
+    /// Rows without a finite return or market cap are dropped, and missing
+    /// exposures count as zero.
+    pub fn estimate(
+        &self,
+        returns: LazyFrame,
+        market_caps: LazyFrame,
+        sectors: LazyFrame,
+        styles: LazyFrame,
+    ) -> Result<FactorReturns> {
+        let keys = [col("date"), col("symbol")];
+        let join = |left: LazyFrame, right: LazyFrame| {
+            left.join(
+                right,
+                keys.clone(),
+                keys.clone(),
+                JoinArgs::new(JoinType::Inner),
+            )
+        };
+        let joined = join(join(join(returns, market_caps), sectors), styles)
+            .with_columns([
+                col("date").cast(DataType::Date),
+                col("asset_returns").cast(DataType::Float64),
+                col("market_cap").cast(DataType::Float64),
+            ])
+            .filter(
+                col("asset_returns")
+                    .is_finite()
+                    .and(col("market_cap").is_finite()),
+            )
+            .sort(["date", "symbol"], SortMultipleOptions::default())
+            .collect()?;
+        if joined.height() == 0 {
+            return Err(RegressionError::NoData);
+        }
+
+        let names: Vec<String> = joined
+            .get_column_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let sector_columns: Vec<&String> =
+            names.iter().filter(|n| n.starts_with("sector_")).collect();
+        let style_columns: Vec<&String> = names.iter().filter(|n| n.ends_with("_score")).collect();
+        if sector_columns.is_empty() {
+            return Err(RegressionError::MissingColumn(
+                "sector_* columns".to_string(),
+            ));
+        }
+
+        let values = |name: &str| -> Result<Vec<f64>> {
+            Ok(joined
+                .column(name)?
+                .cast(&DataType::Float64)?
+                .f64()?
+                .into_iter()
+                .map(|v| v.unwrap_or(0.0))
+                .collect())
+        };
+        let dates: Vec<Option<NaiveDate>> = joined.column("date")?.date()?.as_date_iter().collect();
+        let symbols = joined.column("symbol")?.str()?;
+        let returns = values("asset_returns")?;
+        let market_caps = values("market_cap")?;
+        let sector_values = sector_columns
+            .iter()
+            .map(|name| values(name))
+            .collect::<Result<Vec<_>>>()?;
+        let style_values = style_columns
+            .iter()
+            .map(|name| values(name))
+            .collect::<Result<Vec<_>>>()?;
+
+        let factor_names: Vec<&str> = std::iter::once("market")
+            .chain(sector_columns.iter().map(|n| n.as_str()))
+            .chain(style_columns.iter().map(|n| n.as_str()))
+            .collect();
+        let n_parameters = sector_columns.len() + style_columns.len();
+
+        let mut factors = FactorRows::default();
+        let mut residuals = ResidualRows::default();
+        let mut diagnostics = Vec::new();
+        let mut start = 0;
+        while start < joined.height() {
+            let mut end = start + 1;
+            while end < joined.height() && dates[end] == dates[start] {
+                end += 1;
+            }
+            let range = start..end;
+            start = end;
+            let Some(date) = dates[range.start] else {
+                continue;
+            };
+
+            // Squared residuals are weighted by cap, as rows scaled by √cap
+            // are in toraniko
+            let weights = &market_caps[range.clone()];
+            let n = range.len();
+            if n < n_parameters + 2 {
+                diagnostics.push(RegressionDiagnostics::skipped(
+                    date,
+                    weights,
+                    n_parameters,
+                    CrossSectionIssue::TooFewAssets {
+                        required: n_parameters + 2,
+                    },
+                ));
+                continue;
+            }
+
+            let y = self.config.winsor_factor.map_or_else(
+                || returns[range.clone()].to_vec(),
+                |tail| winsorize(&returns[range.clone()], tail),
+            );
+            let exposures = |columns: &[Vec<f64>]| {
+                Array2::from_shape_fn((n, columns.len()), |(i, j)| columns[j][range.start + i])
+            };
+            let dummies: Vec<&[f64]> = sector_values
+                .iter()
+                .map(|values| &values[range.clone()])
+                .collect();
+            let constraint = self.sector_constraint.weights(&dummies, weights);
+            let mut styles = exposures(&style_values);
+            if self.config.residualize_styles {
+                for mut column in styles.columns_mut() {
+                    let scores = column.to_vec();
+                    column.assign(&Array1::from(residualize(&scores, weights, &dummies)));
+                }
+            }
+            let Some((fit, robust_weights)) = wls::robust_wls(
+                &y,
+                weights,
+                &exposures(&sector_values),
+                &styles,
+                &constraint,
+                &self.robust,
+            ) else {
+                diagnostics.push(RegressionDiagnostics::skipped(
+                    date,
+                    weights,
+                    n_parameters,
+                    CrossSectionIssue::Singular,
+                ));
+                continue;
+            };
+
+            for (k, name) in factor_names.iter().enumerate() {
+                let (value, se) = (fit.factor_returns[k], fit.std_errors[k]);
+                factors.dates.push(date);
+                factors.names.push(name);
+                factors.values.push(value);
+                factors.std_errors.push(se);
+                factors
+                    .t_stats
+                    .push(if se > 0.0 { value / se } else { f64::NAN });
+                factors.constraint_weights.push(
+                    (1..=constraint.len())
+                        .contains(&k)
+                        .then(|| constraint[k - 1]),
+                );
+            }
+            for (i, residual) in range.clone().zip(&fit.residuals) {
+                residuals.dates.push(date);
+                residuals
+                    .symbols
+                    .push(symbols.get(i).unwrap_or_default().to_string());
+                residuals.values.push(*residual);
+            }
+            let mut downweighted: Vec<(String, f64)> = range
+                .clone()
+                .zip(&robust_weights)
+                .filter(|(_, weight)| **weight < DOWNWEIGHT_REPORT_THRESHOLD)
+                .map(|(i, weight)| (symbols.get(i).unwrap_or_default().to_string(), *weight))
+                .collect();
+            downweighted.sort_by(|a, b| a.1.total_cmp(&b.1));
+            diagnostics.push(RegressionDiagnostics::estimated(
+                date,
+                &fit.weights,
+                n_parameters,
+                fit.r_squared,
+                fit.adjusted_r_squared,
+                downweighted,
+            ));
+        }
+
+        Ok(FactorReturns {
+            factor_returns: DataFrame::new(vec![
+                Column::new("date".into(), factors.dates),
+                Column::new("factor".into(), factors.names),
+                Column::new("factor_return".into(), factors.values),
+                Column::new("std_error".into(), factors.std_errors),
+                Column::new("t_stat".into(), factors.t_stats),
+                Column::new("constraint_weight".into(), factors.constraint_weights),
+            ])?,
+            residuals: DataFrame::new(vec![
+                Column::new("date".into(), residuals.dates),
+                Column::new("symbol".into(), residuals.symbols),
+                Column::new("residual_return".into(), residuals.values),
+            ])?,
+            diagnostics,
+            sector_constraint: self.sector_constraint,
+        })
+    }
+}
+
+/// Columns of the factor returns frame.
+#[derive(Default)]
+struct FactorRows<'a> {
+    dates: Vec<NaiveDate>,
+    names: Vec<&'a str>,
+    values: Vec<f64>,
+    std_errors: Vec<f64>,
+    t_stats: Vec<f64>,
+    constraint_weights: Vec<Option<f64>>,
+}
+
+/// Columns of the residuals frame.
+#[derive(Default)]
+struct ResidualRows {
+    dates: Vec<NaiveDate>,
+    symbols: Vec<String>,
+    values: Vec<f64>,
+}
+
+/// Clip values to the order statistics `tail` of the way in from each end,
+/// as toraniko does.
+fn winsorize(values: &[f64], tail: f64) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len();
+    if n == 0 {
+        return sorted;
+    }
+    let low = sorted[((n as f64 * tail).floor() as usize).min(n - 1)];
+    let high = sorted[((n as f64 * (1.0 - tail)).ceil() as usize).clamp(1, n) - 1];
+    values.iter().map(|v| v.clamp(low, high)).collect()
+}
+
+/// Residuals of `scores` regressed on the sector `dummies` with `weights`.
+///
+/// Each asset belongs to at most one sector, so the regression subtracts the
+/// weighted mean score of the asset's sector. Assets outside every sector,
+/// and sectors without positive weight, keep their scores.
+fn residualize(scores: &[f64], weights: &[f64], dummies: &[&[f64]]) -> Vec<f64> {
+    let means: Vec<f64> = dummies
+        .iter()
+        .map(|members| {
+            let (total, weighted) = members.iter().zip(weights).zip(scores).fold(
+                (0.0, 0.0),
+                |(total, weighted), ((d, w), s)| {
+                    let w = d * w.max(0.0);
+                    (total + w, weighted + w * s)
+                },
+            );
+            if total > 0.0 { weighted / total } else { 0.0 }
+        })
+        .collect();
+    scores
+        .iter()
+        .enumerate()
+        .map(|(i, score)| {
+            score
+                - dummies
+                    .iter()
+                    .zip(&means)
+                    .map(|(members, mean)| members[i] * mean)
+                    .sum::<f64>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Inputs with two sectors and one style for `n_assets` over `n_days`.
+    fn inputs(n_assets: usize, n_days: usize) -> [LazyFrame; 4] {
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut dates = Vec::new();
+        let mut symbols = Vec::new();
+        let mut returns = Vec::new();
+        let mut caps = Vec::new();
+        let mut tech = Vec::new();
+        let mut energy = Vec::new();
+        let mut size = Vec::new();
+        for day in 0..n_days {
+            let date = start + chrono::Days::new(day as u64);
+            for i in 0..n_assets {
+                let in_tech = i % 2 == 0;
+                let score = (i as f64 * 0.77).sin();
+                let noise = 0.001 * ((i * 7 + day * 3) as f64).cos();
+                dates.push(date);
+                symbols.push(format!("S{i:02}"));
+                returns.push(0.002 + if in_tech { 0.004 } else { -0.004 } + 0.01 * score + noise);
+                caps.push(1e9 * (1.0 + i as f64));
+                tech.push(f64::from(u8::from(in_tech)));
+                energy.push(f64::from(u8::from(!in_tech)));
+                size.push(score);
+            }
+        }
+        let frame = |columns: Vec<Column>| {
+            let mut all = vec![
+                Column::new("date".into(), dates.clone()),
+                Column::new("symbol".into(), symbols.clone()),
+            ];
+            all.extend(columns);
+            DataFrame::new(all).unwrap().lazy()
+        };
+        [
+            frame(vec![Column::new("asset_returns".into(), returns)]),
+            frame(vec![Column::new("market_cap".into(), caps)]),
+            frame(vec![
+                Column::new("sector_tech".into(), tech),
+                Column::new("sector_energy".into(), energy),
+            ]),
+            frame(vec![Column::new("size_score".into(), size)]),
+        ]
+    }
+
+    #[test]
+    fn test_estimate_with_diagnostics() {
+        let [returns, caps, sectors, styles] = inputs(40, 3);
+        let estimate = CrossSectionalRegression::with_config(EstimatorConfig {
+            winsor_factor: None,
+            residualize_styles: false,
+        })
+        .estimate(returns, caps, sectors, styles)
+        .unwrap();
+
+        assert_eq!(estimate.diagnostics.len(), 3);
+        assert_eq!(estimate.factor_returns.height(), 3 * 4);
+        assert_eq!(estimate.residuals.height(), 3 * 40);
+        assert_eq!(estimate.degenerate().count(), 0);
+
+        let day = &estimate.diagnostics[0];
+        assert_eq!((day.n_assets, day.n_parameters), (40, 3));
+        assert!(day.effective_n_assets < 40.0 && day.effective_n_assets > 20.0);
+        assert!(day.r_squared > 0.95 && day.adjusted_r_squared <= day.r_squared);
+
+        let returns = estimate.factor_returns.column("factor_return").unwrap();
+        let returns = returns.f64().unwrap();
+        assert!((returns.get(0).unwrap() - 0.002).abs() < 1e-3);
+        assert!((returns.get(1).unwrap() - 0.004).abs() < 1e-3);
+        assert!((returns.get(1).unwrap() + returns.get(2).unwrap()).abs() < 1e-12);
+        assert!((returns.get(3).unwrap() - 0.01).abs() < 1e-3);
+
+        let significance = estimate.significance().unwrap();
+        assert_eq!(significance.len(), 4);
+        assert_eq!(significance[3].factor, "size_score");
+        assert_eq!(significance[3].days, 3);
+        assert_eq!(significance[3].significant_share, 1.0);
+    }
+
+    #[test]
+    fn test_cap_weighted_sector_constraint() {
+        let config = EstimatorConfig {
+            winsor_factor: None,
+            residualize_styles: false,
+        };
+        let estimate = |constraint: SectorConstraint| {
+            let [returns, caps, sectors, styles] = inputs(40, 1);
+            CrossSectionalRegression::with_config(config.clone())
+                .with_sector_constraint(constraint)
+                .estimate(returns, caps, sectors, styles)
+                .unwrap()
+        };
+        let column = |estimate: &FactorReturns, name: &str| -> Vec<Option<f64>> {
+            let values = estimate.factor_returns.column(name).unwrap();
+            values.f64().unwrap().into_iter().collect()
+        };
+
+        let equal = estimate(SectorConstraint::EqualWeighted);
+        let capped = estimate(SectorConstraint::CapWeighted);
+        assert_eq!(capped.sector_constraint, SectorConstraint::CapWeighted);
+        assert_eq!(
+            column(&equal, "constraint_weight"),
+            [None, Some(0.5), Some(0.5), None]
+        );
+
+        // Caps 1..=40 with even indexes in tech: tech holds 400 of 820
+        let weights = column(&capped, "constraint_weight");
+        let (tech, energy) = (weights[1].unwrap(), weights[2].unwrap());
+        assert!((tech - 400.0 / 820.0).abs() < 1e-12);
+        assert!((tech + energy - 1.0).abs() < 1e-12);
+
+        let returns = column(&capped, "factor_return");
+        let sector_sum = tech * returns[1].unwrap() + energy * returns[2].unwrap();
+        assert!(sector_sum.abs() < 1e-12);
+
+        // The split between market and sectors moves, the style return does not
+        let equal_returns = column(&equal, "factor_return");
+        assert!((returns[0].unwrap() - equal_returns[0].unwrap()).abs() > 1e-6);
+        assert!((returns[3].unwrap() - equal_returns[3].unwrap()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_robust_reports_downweighted() {
+        let config = EstimatorConfig {
+            winsor_factor: None,
+            residualize_styles: false,
+        };
+        let first = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let estimate = |robust: RobustWeighting| {
+            let [returns, caps, sectors, styles] = inputs(40, 2);
+            let returns = returns.with_column(
+                when(col("symbol").eq(lit("S07")).and(col("date").eq(lit(first))))
+                    .then(col("asset_returns") + lit(0.3))
+                    .otherwise(col("asset_returns"))
+                    .alias("asset_returns"),
+            );
+            CrossSectionalRegression::with_config(config.clone())
+                .with_robust_weighting(robust)
+                .estimate(returns, caps, sectors, styles)
+                .unwrap()
+        };
+        let size_return = |estimate: &FactorReturns| {
+            let returns = estimate.factor_returns.column("factor_return").unwrap();
+            returns.f64().unwrap().get(3).unwrap()
+        };
+
+        let plain = estimate(RobustWeighting::default());
+        assert!(plain.diagnostics[0].downweighted.is_empty());
+        let robust = estimate(RobustWeighting::new(RobustMethod::Tukey));
+        let jump_day = &robust.diagnostics[0].downweighted;
+        assert_eq!(jump_day[0], ("S07".to_string(), 0.0));
+        let next_day = &robust.diagnostics[1].downweighted;
+        assert!(next_day.iter().all(|(symbol, _)| symbol != "S07"));
+
+        // The jump no longer moves the style return
+        assert!((size_return(&robust) - 0.01).abs() < (size_return(&plain) - 0.01).abs());
+    }
+
+    #[test]
+    fn test_residualized_styles() {
+        let estimate = |residualize_styles: bool| {
+            let [returns, caps, sectors, styles] = inputs(40, 1);
+            CrossSectionalRegression::with_config(EstimatorConfig {
+                winsor_factor: None,
+                residualize_styles,
+            })
+            .estimate(returns, caps, sectors, styles)
+            .unwrap()
+        };
+        let column = |frame: &DataFrame, name: &str| -> Vec<f64> {
+            let values = frame.column(name).unwrap();
+            values.f64().unwrap().into_no_null_iter().collect()
+        };
+
+        let raw = estimate(false);
+        let residualized = estimate(true);
+        let raw_returns = column(&raw.factor_returns, "factor_return");
+        let returns = column(&residualized.factor_returns, "factor_return");
+
+        // The style's sector means move to the sectors; the fit is unchanged
+        assert!((returns[1] - raw_returns[1]).abs() > 1e-6);
+        assert!((returns[3] - raw_returns[3]).abs() < 1e-12);
+        let raw_residuals = column(&raw.residuals, "residual_return");
+        let residuals = column(&residualized.residuals, "residual_return");
+        for (a, b) in residuals.iter().zip(&raw_residuals) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_winsorized_returns() {
+        let first = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let estimate = |winsor_factor: Option<f64>| {
+            let [returns, caps, sectors, styles] = inputs(40, 1);
+            let returns = returns.with_column(
+                when(col("symbol").eq(lit("S07")).and(col("date").eq(lit(first))))
+                    .then(col("asset_returns") + lit(0.3))
+                    .otherwise(col("asset_returns"))
+                    .alias("asset_returns"),
+            );
+            let estimate = CrossSectionalRegression::with_config(EstimatorConfig {
+                winsor_factor,
+                residualize_styles: false,
+            })
+            .estimate(returns, caps, sectors, styles)
+            .unwrap();
+            let returns = estimate.factor_returns.column("factor_return").unwrap();
+            returns.f64().unwrap().get(3).unwrap()
+        };
+
+        // Clipping the jump pulls the style return back towards its true value
+        assert!((estimate(Some(0.05)) - 0.01).abs() < (estimate(None) - 0.01).abs());
+    }
+
+    #[test]
+    fn test_matches_toraniko_estimator() {
+        use toraniko_model::FactorReturnsEstimator;
+        use toraniko_model::prelude::ReturnsEstimator;
+
+        // toraniko fits the raw scores, whatever `residualize_styles` says,
+        // and scales rows by √cap where the fit here weighs by cap
+        let config = EstimatorConfig {
+            winsor_factor: Some(0.05),
+            residualize_styles: false,
+        };
+        let [returns, caps, sectors, styles] = inputs(40, 2);
+        let estimate = CrossSectionalRegression::with_config(config.clone())
+            .estimate(
+                returns.clone(),
+                caps.clone(),
+                sectors.clone(),
+                styles.clone(),
+            )
+            .unwrap();
+        let (factor_returns, residuals) = FactorReturnsEstimator::with_config(config)
+            .estimate(returns, caps, sectors, styles)
+            .unwrap();
+
+        let sorted = |frame: &DataFrame, keys: [&str; 2], name: &str| -> Vec<f64> {
+            let frame = frame.sort(keys, SortMultipleOptions::default()).unwrap();
+            let values = frame.column(name).unwrap();
+            values.f64().unwrap().into_no_null_iter().collect()
+        };
+        for (frame, expected, keys, name) in [
+            (
+                &estimate.factor_returns,
+                &factor_returns,
+                ["date", "factor"],
+                "factor_return",
+            ),
+            (
+                &estimate.residuals,
+                &residuals,
+                ["date", "symbol"],
+                "residual_return",
+            ),
+        ] {
+            let (actual, expected) = (sorted(frame, keys, name), sorted(expected, keys, name));
+            assert_eq!(actual.len(), expected.len());
+            for (a, b) in actual.iter().zip(&expected) {
+                assert!((a - b).abs() < 1e-10, "{name}: {a} != {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_residualize() {
+        let scores = [1.0, 3.0, -2.0, 0.5, 4.0];
+        let weights = [3.0, 1.0, 2.0, 2.0, 5.0];
+        let tech = [1.0, 1.0, 0.0, 0.0, 0.0];
+        let energy = [0.0, 0.0, 1.0, 1.0, 0.0];
+        let residuals = residualize(&scores, &weights, &[&tech, &energy]);
+
+        // Cap-weighted means are 1.5 in tech and -0.75 in energy
+        assert_eq!(residuals, [-0.5, 1.5, -1.25, 1.25, 4.0]);
+    }
+
+    #[test]
+    fn test_degenerate_cross_section() {
+        let [returns, caps, sectors, styles] = inputs(4, 1);
+        let estimate = CrossSectionalRegression::new()
+            .estimate(returns, caps, sectors, styles)
+            .unwrap();
+
+        assert_eq!(estimate.factor_returns.height(), 0);
+        let degenerate: Vec<_> = estimate.degenerate().collect();
+        assert_eq!(degenerate.len(), 1);
+        assert_eq!(
+            degenerate[0].issues,
+            [CrossSectionIssue::TooFewAssets { required: 5 }]
+        );
+        assert!(!degenerate[0].is_estimated());
+    }
+
+    #[test]
+    fn test_winsorize() {
+        let values = [5.0, -3.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
+        let clipped = winsorize(&values, 0.1);
+        assert_eq!(clipped[0], 0.8);
+        assert_eq!(clipped[1], 0.1);
+        assert_eq!(&clipped[2..], &values[2..]);
+    }
+}
@@ -0,0 +1,278 @@
+//! Robust weights for iteratively reweighted least squares.
+
+use serde::{Deserialize, Serialize};
+
+/// Robust weight below which an asset is reported as down-weighted.
+pub const DOWNWEIGHT_REPORT_THRESHOLD: f64 = 0.5;
+
+/// Consistency factor turning the median absolute deviation of normal
+/// residuals into their standard deviation.
+const MAD_SCALE: f64 = 1.4826;
+
+/// Weight function applied to standardized residuals.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RobustMethod {
+    /// Plain WLS.
+    #[default]
+    None,
+    /// Huber: full weight within `c` residual scales, `c / |u|` beyond.
+    Huber,
+    /// Tukey bisquare: `(1 - (u / c)²)²` within `c` residual scales, zero
+    /// beyond, so gross outliers drop out entirely.
+    Tukey,
+}
+
+impl RobustMethod {
+    /// Tuning constant with 95% efficiency under normal residuals.
+    pub const fn default_tuning(self) -> f64 {
+        match self {
+            Self::None => f64::INFINITY,
+            Self::Huber => 1.345,
+            Self::Tukey => 4.685,
+        }
+    }
+}
+
+/// Iteratively reweighted least squares settings.
+///
+/// Each pass scales the residuals by `1.4826 · MAD`, turns them into robust
+/// weights with the [`RobustMethod`], and refits with squared residuals
+/// weighted by the product of the robust and market-cap weights, until no
+/// robust weight moves by more than `tolerance` or `max_iterations` refits
+/// have run.
+///
+/// ```toml
+/// [regression.robust]
+/// method = "huber"    # none, huber or tukey
+/// tuning = 1.345      # optional; 1.345 for huber, 4.685 for tukey
+/// max_iterations = 20
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RobustWeighting {
+    /// Weight function.
+    pub method: RobustMethod,
+    /// Tuning constant in residual scales, or `None` for the method's
+    /// default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tuning: Option<f64>,
+    /// Most refits after the initial WLS fit.
+    pub max_iterations: usize,
+    /// Largest robust weight change that counts as converged.
+    pub tolerance: f64,
+}
+
+impl Default for RobustWeighting {
+    fn default() -> Self {
+        Self {
+            method: RobustMethod::None,
+            tuning: None,
+            max_iterations: 20,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+impl RobustWeighting {
+    /// Settings for `method` with its default tuning constant.
+    pub fn new(method: RobustMethod) -> Self {
+        Self {
+            method,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the fit is reweighted at all.
+    pub fn is_enabled(&self) -> bool {
+        self.method != RobustMethod::None && self.max_iterations > 0
+    }
+
+    /// The tuning constant in use.
+    pub fn tuning_constant(&self) -> f64 {
+        self.tuning.unwrap_or_else(|| self.method.default_tuning())
+    }
+
+    /// Robust weights of `residuals`, all one if their scale is zero.
+    pub(crate) fn weights(&self, residuals: &[f64]) -> Vec<f64> {
+        let scale = MAD_SCALE * median_absolute_deviation(residuals);
+        if scale <= f64::EPSILON || !scale.is_finite() {
+            return vec![1.0; residuals.len()];
+        }
+        let c = self.tuning_constant();
+        residuals
+            .iter()
+            .map(|e| {
+                let u = (e / scale).abs();
+                match self.method {
+                    RobustMethod::None => 1.0,
+                    RobustMethod::Huber if u <= c => 1.0,
+                    RobustMethod::Huber => c / u,
+                    RobustMethod::Tukey if u < c => (1.0 - (u / c).powi(2)).powi(2),
+                    RobustMethod::Tukey => 0.0,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Median of `|x - median(x)|`.
+fn median_absolute_deviation(values: &[f64]) -> f64 {
+    let center = median(values.to_vec());
+    median(values.iter().map(|v| (v - center).abs()).collect())
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regression::wls::{constrained_wls, robust_wls};
+    use ndarray::Array2;
+
+    #[test]
+    fn test_robust_weights() {
+        // The median absolute deviation is 1, so the scale is 1.4826
+        let residuals = [-1.0, -0.5, 0.0, 0.5, 1.0, 30.0, -30.0];
+        let huber = RobustWeighting::new(RobustMethod::Huber).weights(&residuals);
+        assert_eq!(&huber[..5], &[1.0; 5]);
+        assert!((huber[5] - 1.345 * 1.4826 / 30.0).abs() < 1e-12);
+        assert_eq!(huber[5], huber[6]);
+
+        let tukey = RobustWeighting::new(RobustMethod::Tukey).weights(&residuals);
+        assert_eq!(tukey[2], 1.0);
+        assert!(tukey[1] < 1.0 && tukey[1] > 0.9);
+        assert_eq!(tukey[5], 0.0);
+
+        let flat = RobustWeighting::new(RobustMethod::Tukey).weights(&[0.0, 0.0, 0.0, 5.0]);
+        assert_eq!(flat, [1.0; 4]);
+        assert!(!RobustWeighting::default().is_enabled());
+    }
+
+    /// Thirty assets on one style factor, with a 50% jump added to asset 7.
+    fn cross_section() -> (Vec<f64>, Array2<f64>, Array2<f64>) {
+        let x: Vec<f64> = (0..30).map(|i| (f64::from(i) * 0.7).sin()).collect();
+        let mut y: Vec<f64> = x
+            .iter()
+            .enumerate()
+            .map(|(i, x)| 0.01 + 0.02 * x + 0.001 * (i as f64 * 2.3).cos())
+            .collect();
+        y[7] += 0.5;
+        let styles = Array2::from_shape_vec((30, 1), x).unwrap();
+        (y, Array2::ones((30, 1)), styles)
+    }
+
+    #[test]
+    fn test_outlier_is_downweighted() {
+        let (y, sectors, styles) = cross_section();
+        let plain = constrained_wls(&y, &[1.0; 30], &sectors, &styles, &[1.0]).unwrap();
+
+        let huber = RobustWeighting::new(RobustMethod::Huber).weights(&plain.residuals);
+        assert!(huber[7] > 0.0 && huber[7] < DOWNWEIGHT_REPORT_THRESHOLD);
+        assert!(huber.iter().all(|w| *w >= huber[7]));
+
+        let tukey = RobustWeighting::new(RobustMethod::Tukey).weights(&plain.residuals);
+        assert_eq!(tukey[7], 0.0);
+        assert!(
+            tukey
+                .iter()
+                .enumerate()
+                .all(|(i, w)| i == 7 || *w > DOWNWEIGHT_REPORT_THRESHOLD)
+        );
+
+        let none = RobustWeighting::new(RobustMethod::None).weights(&plain.residuals);
+        assert_eq!(none, vec![1.0; 30]);
+    }
+
+    #[test]
+    fn test_reweighting_converges() {
+        let (y, sectors, styles) = cross_section();
+        for method in [RobustMethod::Huber, RobustMethod::Tukey] {
+            let robust = RobustWeighting::new(method);
+            let (fit, weights) =
+                robust_wls(&y, &[1.0; 30], &sectors, &styles, &[1.0], &robust).unwrap();
+
+            // The weights are a fixed point of the last fit's residuals
+            let next = robust.weights(&fit.residuals);
+            let change = next
+                .iter()
+                .zip(&weights)
+                .fold(0.0_f64, |max, (a, b)| max.max((a - b).abs()));
+            assert!(change <= robust.tolerance, "{method:?}");
+            assert!(weights[7] < DOWNWEIGHT_REPORT_THRESHOLD, "{method:?}");
+        }
+    }
+
+    #[test]
+    fn test_max_iterations_bounds_refits() {
+        let (y, sectors, styles) = cross_section();
+        let plain = constrained_wls(&y, &[1.0; 30], &sectors, &styles, &[1.0]).unwrap();
+
+        // One refit, with the weights of the plain fit's residuals
+        let once = RobustWeighting {
+            max_iterations: 1,
+            ..RobustWeighting::new(RobustMethod::Huber)
+        };
+        let (fit, weights) = robust_wls(&y, &[1.0; 30], &sectors, &styles, &[1.0], &once).unwrap();
+        assert_eq!(weights, once.weights(&plain.residuals));
+        assert_eq!(
+            Some(fit),
+            constrained_wls(&y, &weights, &sectors, &styles, &[1.0])
+        );
+
+        // No refits, or a tolerance every change is within, keep the WLS fit
+        for robust in [
+            RobustWeighting {
+                max_iterations: 0,
+                ..RobustWeighting::new(RobustMethod::Huber)
+            },
+            RobustWeighting {
+                tolerance: 1.0,
+                ..RobustWeighting::new(RobustMethod::Huber)
+            },
+        ] {
+            let (fit, weights) =
+                robust_wls(&y, &[1.0; 30], &sectors, &styles, &[1.0], &robust).unwrap();
+            assert_eq!(fit, plain);
+            assert_eq!(weights, vec![1.0; 30]);
+        }
+    }
+
+    #[test]
+    fn test_all_weights_zero() {
+        let (y, sectors, styles) = cross_section();
+        let robust = RobustWeighting::new(RobustMethod::Tukey);
+        assert!(robust_wls(&y, &[0.0; 30], &sectors, &styles, &[1.0], &robust).is_none());
+
+        // A tuning constant this small zeroes every weight but the median
+        // residual's, so the refit is singular and the WLS fit is kept
+        let zeroing = RobustWeighting {
+            tuning: Some(1e-12),
+            ..robust
+        };
+        let plain = constrained_wls(&y, &[1.0; 30], &sectors, &styles, &[1.0]).unwrap();
+        assert!(
+            zeroing
+                .weights(&plain.residuals)
+                .iter()
+                .filter(|w| **w > 0.0)
+                .count()
+                <= 1
+        );
+        let (fit, weights) =
+            robust_wls(&y, &[1.0; 30], &sectors, &styles, &[1.0], &zeroing).unwrap();
+        assert_eq!(fit, plain);
+        assert_eq!(weights, vec![1.0; 30]);
+    }
+}
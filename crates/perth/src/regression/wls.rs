@@ -0,0 +1,370 @@
+//! Weighted least squares with a linear constraint on the sector returns.
+
+use crate::regression::RobustWeighting;
+use ndarray::{Array1, Array2};
+
+/// One fitted cross-section.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Fit {
+    /// Market, sector then style returns
+    pub(crate) factor_returns: Vec<f64>,
+    /// Standard errors of `factor_returns`
+    pub(crate) std_errors: Vec<f64>,
+    /// Residual returns, one per asset
+    pub(crate) residuals: Vec<f64>,
+    /// Weighted R² around the weighted mean return
+    pub(crate) r_squared: f64,
+    /// R² adjusted for the estimated parameters
+    pub(crate) adjusted_r_squared: f64,
+    /// Weights of the squared residuals, one per asset
+    pub(crate) weights: Vec<f64>,
+}
+
+/// Regress `y` on a market intercept, the sector dummies and the style
+/// scores, minimizing `Σ wᵢ eᵢ²` subject to `Σₛ cₛ fₛ = 0` over the sector
+/// returns `fₛ`, with `constraint` holding the `cₛ`.
+///
+/// The constraint is imposed by substitution: the sector with the largest
+/// `cₛ` is expressed through the others, so the system has
+/// `n_sectors + n_styles` free parameters. Standard errors are the usual
+/// WLS ones, `σ² (XᵀWX)⁻¹` with `σ² = Σ wᵢ eᵢ² / (n - p)`, mapped back to
+/// the constrained sector returns. `None` if there are no sectors, no more
+/// assets than parameters, no positive weight or constraint weight, or the
+/// normal equations are singular.
+pub(crate) fn constrained_wls(
+    y: &[f64],
+    weights: &[f64],
+    sectors: &Array2<f64>,
+    styles: &Array2<f64>,
+    constraint: &[f64],
+) -> Option<Fit> {
+    let n = y.len();
+    let n_sectors = sectors.ncols();
+    let n_styles = styles.ncols();
+    let p = n_sectors + n_styles;
+    if n_sectors == 0 || n <= p {
+        return None;
+    }
+
+    // The pivot sector's return is -Σ (cₛ / c_pivot) fₛ over the others
+    let pivot = (0..n_sectors)
+        .rev()
+        .max_by(|&a, &b| constraint[a].total_cmp(&constraint[b]))?;
+    if constraint[pivot] <= 0.0 {
+        return None;
+    }
+    let free: Vec<usize> = (0..n_sectors).filter(|&j| j != pivot).collect();
+    let ratios: Vec<f64> = free
+        .iter()
+        .map(|&j| constraint[j] / constraint[pivot])
+        .collect();
+
+    let mut x = Array2::zeros((n, p));
+    for i in 0..n {
+        x[[i, 0]] = 1.0;
+        for (k, &j) in free.iter().enumerate() {
+            x[[i, 1 + k]] = sectors[[i, j]] - ratios[k] * sectors[[i, pivot]];
+        }
+        for j in 0..n_styles {
+            x[[i, n_sectors + j]] = styles[[i, j]];
+        }
+    }
+
+    // Weights are rescaled to mean one, which leaves the fit unchanged
+    let scale = weights.iter().map(|w| w.max(0.0)).sum::<f64>() / n as f64;
+    if scale <= 0.0 || !scale.is_finite() {
+        return None;
+    }
+    let w: Array1<f64> = weights.iter().map(|w| w.max(0.0) / scale).collect();
+    let y = Array1::from(y.to_vec());
+
+    let xtw = &x.t() * &w;
+    let inverse = invert(&xtw.dot(&x))?;
+    let coefficients = inverse.dot(&xtw.dot(&y));
+    let residuals = &y - &x.dot(&coefficients);
+
+    let total_weight = w.sum();
+    let mean = w.dot(&y) / total_weight;
+    let sse: f64 = w.iter().zip(&residuals).map(|(w, e)| w * e * e).sum();
+    let sst: f64 = w.iter().zip(&y).map(|(w, y)| w * (y - mean).powi(2)).sum();
+    let r_squared = if sst > 0.0 { 1.0 - sse / sst } else { f64::NAN };
+    let adjusted_r_squared = 1.0 - (1.0 - r_squared) * (n - 1) as f64 / (n - p) as f64;
+
+    // Map the free parameters to market, sector and style returns
+    let mut transform = Array2::zeros((1 + n_sectors + n_styles, p));
+    transform[[0, 0]] = 1.0;
+    for (k, &j) in free.iter().enumerate() {
+        transform[[1 + j, 1 + k]] = 1.0;
+        transform[[1 + pivot, 1 + k]] = -ratios[k];
+    }
+    for j in 0..n_styles {
+        transform[[1 + n_sectors + j, n_sectors + j]] = 1.0;
+    }
+    let sigma2 = sse / (n - p) as f64;
+    let covariance = transform.dot(&inverse).dot(&transform.t()) * sigma2;
+
+    Some(Fit {
+        factor_returns: transform.dot(&coefficients).to_vec(),
+        std_errors: covariance
+            .diag()
+            .iter()
+            .map(|v| v.max(0.0).sqrt())
+            .collect(),
+        residuals: residuals.to_vec(),
+        r_squared,
+        adjusted_r_squared,
+        weights: weights.iter().map(|w| w.max(0.0)).collect(),
+    })
+}
+
+/// [`constrained_wls`] iteratively reweighted by `robust`, returning the last
+/// fit and the robust weights it used.
+///
+/// Each refit weighs an asset's squared residual by its cap weight times its
+/// robust weight, the usual IRLS objective. In toraniko's terms, where rows
+/// are scaled by `√cap`, the robust weight scales the row by `√weight`.
+///
+/// A refit that turns singular, such as Tukey weights zeroing every member
+/// of a sector, ends the iteration at the previous fit.
+pub(crate) fn robust_wls(
+    y: &[f64],
+    weights: &[f64],
+    sectors: &Array2<f64>,
+    styles: &Array2<f64>,
+    constraint: &[f64],
+    robust: &RobustWeighting,
+) -> Option<(Fit, Vec<f64>)> {
+    let mut fit = constrained_wls(y, weights, sectors, styles, constraint)?;
+    let mut robust_weights = vec![1.0; y.len()];
+    if !robust.is_enabled() {
+        return Some((fit, robust_weights));
+    }
+
+    for _ in 0..robust.max_iterations {
+        let next = robust.weights(&fit.residuals);
+        let change = next
+            .iter()
+            .zip(&robust_weights)
+            .fold(0.0_f64, |max, (a, b)| max.max((a - b).abs()));
+        if change <= robust.tolerance {
+            break;
+        }
+        let combined: Vec<f64> = weights
+            .iter()
+            .zip(&next)
+            .map(|(w, r)| w.max(0.0) * r)
+            .collect();
+        let Some(refit) = constrained_wls(y, &combined, sectors, styles, constraint) else {
+            break;
+        };
+        fit = refit;
+        robust_weights = next;
+    }
+    Some((fit, robust_weights))
+}
+
+/// Gauss-Jordan inverse with partial pivoting, or `None` if the matrix is
+/// singular relative to its largest diagonal entry.
+fn invert(a: &Array2<f64>) -> Option<Array2<f64>> {
+    let n = a.nrows();
+    let tolerance = 1e-12 * a.diag().iter().fold(0.0_f64, |m, x| m.max(x.abs()));
+    let mut m = a.clone();
+    let mut inverse = Array2::eye(n);
+
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| m[[i, col]].abs().total_cmp(&m[[j, col]].abs()))?;
+        if m[[pivot, col]].abs() <= tolerance {
+            return None;
+        }
+        if pivot != col {
+            for j in 0..n {
+                m.swap([col, j], [pivot, j]);
+                inverse.swap([col, j], [pivot, j]);
+            }
+        }
+
+        let d = m[[col, col]];
+        m.row_mut(col).mapv_inplace(|x| x / d);
+        inverse.row_mut(col).mapv_inplace(|x| x / d);
+        for row in 0..n {
+            let factor = m[[row, col]];
+            if row == col || factor == 0.0 {
+                continue;
+            }
+            for j in 0..n {
+                m[[row, j]] -= factor * m[[col, j]];
+                inverse[[row, j]] -= factor * inverse[[col, j]];
+            }
+        }
+    }
+    Some(inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_sector_matches_simple_regression() {
+        let x = [-2.0, -1.0, 0.0, 1.0, 2.0, 3.0];
+        let y = [0.9, 2.1, 2.9, 4.2, 4.8, 6.1];
+        let sectors = Array2::ones((6, 1));
+        let styles = Array2::from_shape_vec((6, 1), x.to_vec()).unwrap();
+        let fit = constrained_wls(&y, &[1.0; 6], &sectors, &styles, &[1.0]).unwrap();
+
+        // Market and sector share the intercept; the lone sector gets none
+        let mean_x = x.iter().sum::<f64>() / 6.0;
+        let mean_y = y.iter().sum::<f64>() / 6.0;
+        let sxx: f64 = x.iter().map(|x| (x - mean_x).powi(2)).sum();
+        let sxy: f64 = x
+            .iter()
+            .zip(&y)
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let slope = sxy / sxx;
+        assert!((fit.factor_returns[0] - (mean_y - slope * mean_x)).abs() < 1e-12);
+        assert_eq!(fit.factor_returns[1], 0.0);
+        assert!((fit.factor_returns[2] - slope).abs() < 1e-12);
+
+        let sse: f64 = fit.residuals.iter().map(|e| e * e).sum();
+        let se = (sse / 4.0 / sxx).sqrt();
+        assert!((fit.std_errors[2] - se).abs() < 1e-12);
+        assert!(fit.r_squared > 0.99 && fit.adjusted_r_squared < fit.r_squared);
+    }
+
+    #[test]
+    fn test_sector_returns_sum_to_zero() {
+        let y = [0.01, 0.02, 0.015, 0.025, 0.03, 0.01, -0.01];
+        let weights = [1.0, 2.0, 1.5, 2.5, 3.0, 1.0, 0.5];
+        let sectors = Array2::from_shape_fn((7, 3), |(i, j)| f64::from(u8::from(i % 3 == j)));
+        let styles =
+            Array2::from_shape_vec((7, 1), vec![0.5, 0.3, 0.2, -0.2, -0.3, -0.5, 0.1]).unwrap();
+        let fit = constrained_wls(&y, &weights, &sectors, &styles, &[1.0; 3]).unwrap();
+
+        assert_eq!(fit.factor_returns.len(), 5);
+        assert!(fit.factor_returns[1..4].iter().sum::<f64>().abs() < 1e-12);
+        assert!(fit.std_errors.iter().all(|se| se.is_finite() && *se > 0.0));
+
+        // Weighted residuals are orthogonal to the intercept
+        let weighted: f64 = fit.residuals.iter().zip(&weights).map(|(e, w)| e * w).sum();
+        assert!(weighted.abs() < 1e-12);
+
+        // A cap-weighted constraint changes how the return is split between
+        // the market and the sectors, but not the fit
+        let caps = [0.5, 0.3, 0.2];
+        let capped = constrained_wls(&y, &weights, &sectors, &styles, &caps).unwrap();
+        let weighted_sum: f64 = caps
+            .iter()
+            .zip(&capped.factor_returns[1..4])
+            .map(|(c, f)| c * f)
+            .sum();
+        assert!(weighted_sum.abs() < 1e-12);
+        assert!((capped.factor_returns[0] - fit.factor_returns[0]).abs() > 1e-6);
+        for (a, b) in capped.residuals.iter().zip(&fit.residuals) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert!((capped.factor_returns[4] - fit.factor_returns[4]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_robust_fit_resists_outlier() {
+        use crate::regression::RobustMethod;
+
+        let x: Vec<f64> = (0..30).map(|i| (i as f64 * 0.7).sin()).collect();
+        let mut y: Vec<f64> = x
+            .iter()
+            .enumerate()
+            .map(|(i, x)| 0.01 + 0.02 * x + 0.001 * (i as f64 * 2.3).cos())
+            .collect();
+        // An earnings-day jump on the asset with the largest exposure
+        let jump = x
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap()
+            .0;
+        y[jump] += 0.5;
+        let sectors = Array2::ones((30, 1));
+        let styles = Array2::from_shape_vec((30, 1), x).unwrap();
+
+        let plain = constrained_wls(&y, &[1.0; 30], &sectors, &styles, &[1.0]).unwrap();
+        for method in [RobustMethod::Huber, RobustMethod::Tukey] {
+            let robust = RobustWeighting::new(method);
+            let (fit, weights) =
+                robust_wls(&y, &[1.0; 30], &sectors, &styles, &[1.0], &robust).unwrap();
+            assert!((fit.factor_returns[2] - 0.02).abs() < (plain.factor_returns[2] - 0.02).abs());
+            assert!((fit.factor_returns[2] - 0.02).abs() < 0.005, "{method:?}");
+            let lowest = weights
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap()
+                .0;
+            assert_eq!(lowest, jump);
+        }
+    }
+
+    #[test]
+    fn test_robust_weights_multiply_cap_weights() {
+        use crate::regression::RobustMethod;
+
+        let x: Vec<f64> = (0..30).map(|i| (i as f64 * 0.7).sin()).collect();
+        let caps: Vec<f64> = (0..30).map(|i| 1e9 * (1.0 + i as f64)).collect();
+        let mut y: Vec<f64> = x
+            .iter()
+            .enumerate()
+            .map(|(i, x)| 0.01 + 0.02 * x + 0.001 * (i as f64 * 2.3).cos())
+            .collect();
+        y[7] += 0.5;
+        let sectors = Array2::ones((30, 1));
+        let styles = Array2::from_shape_vec((30, 1), x).unwrap();
+
+        let robust = RobustWeighting::new(RobustMethod::Huber);
+        let (fit, robust_weights) =
+            robust_wls(&y, &caps, &sectors, &styles, &[1.0], &robust).unwrap();
+        for ((weight, cap), r) in fit.weights.iter().zip(&caps).zip(&robust_weights) {
+            assert!((weight - cap * r).abs() <= 1e-12 * cap);
+        }
+
+        // The outlier's weight is its cap times Huber's c / |u|, with u its
+        // residual over 1.4826 · MAD
+        let mut residuals = fit.residuals.clone();
+        residuals.sort_by(f64::total_cmp);
+        let median = (residuals[14] + residuals[15]) / 2.0;
+        let mut deviations: Vec<f64> = residuals.iter().map(|e| (e - median).abs()).collect();
+        deviations.sort_by(f64::total_cmp);
+        let scale = 1.4826 * (deviations[14] + deviations[15]) / 2.0;
+        let huber = 1.345 * scale / fit.residuals[7].abs();
+        assert!(huber < 0.1);
+        // Refits stop once the weights move by less than the tolerance
+        assert!((fit.weights[7] / caps[7] - huber).abs() < robust.tolerance);
+    }
+
+    #[test]
+    fn test_singular_and_underdetermined() {
+        let sectors = Array2::ones((4, 1));
+        let constant = Array2::from_elem((4, 1), 2.0);
+        assert!(
+            constrained_wls(
+                &[0.1, 0.2, 0.3, 0.4],
+                &[1.0; 4],
+                &sectors,
+                &constant,
+                &[1.0]
+            )
+            .is_none()
+        );
+
+        let styles = Array2::from_shape_vec((2, 1), vec![1.0, 2.0]).unwrap();
+        assert!(
+            constrained_wls(
+                &[0.1, 0.2],
+                &[1.0; 2],
+                &Array2::ones((2, 1)),
+                &styles,
+                &[1.0]
+            )
+            .is_none()
+        );
+    }
+}
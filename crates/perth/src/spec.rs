@@ -0,0 +1,783 @@
+//! Model specification files.
+//!
+//! A [`ModelSpec`] pins down everything a model run depends on beyond the
+//! data: the universe, the style factors and their parameters, the industry
+//! scheme, the regression options and the risk estimators. Specs are written
+//! in TOML, where every section and key is optional and falls back to the
+//! defaults the CLI uses:
+//!
+//! ```toml
+//! name = "us-daily"
+//!
+//! [universe]
+//! path = "russell1000.csv"    # omit for the built-in S&P 500
+//!
+//! [factors.momentum]
+//! lookback = 252
+//! skip_days = 21
+//!
+//! [factors.illiquidity]
+//! enabled = false
+//!
+//! [industries]
+//! level = "industry-group"
+//!
+//! [regression]
+//! winsor_factor = 0.01        # 0 disables winsorization
+//! residualize_styles = false  # fit styles without removing sector means
+//! sector_constraint = "cap-weighted"
+//!
+//! [risk.covariance]
+//! method = "ewma"             # ewma, ledoit-wolf or newey-west
+//! ewma = { decay = 0.97 }
+//! ```
+//!
+//! The spec is echoed with every output, together with a
+//! [`fingerprint`](ModelSpec::fingerprint) of its canonical form, so a run can
+//! be reproduced from its output alone.
+
+use crate::regression::{CrossSectionalRegression, RobustWeighting, SectorConstraint};
+use crate::style::{FactorEngine, OrthogonalizationMethod, Standardization};
+use crate::universe::GicsLevel;
+use perth_risk::covariance::{
+    CovarianceError, CovarianceEstimator, EwmaConfig, EwmaCovarianceEstimator, LedoitWolfConfig,
+    LedoitWolfEstimator, NeweyWestConfig, NeweyWestEstimator,
+};
+use perth_risk::specific_risk::{SpecificRiskConfig, SpecificRiskEstimator, VarianceMethod};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use toraniko_model::EstimatorConfig;
+
+/// Errors that can occur while loading a model specification.
+#[derive(Debug, Error)]
+pub enum ModelSpecError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// TOML parsing error.
+    #[error("TOML parsing error: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    /// TOML serialization error.
+    #[error("TOML serialization error: {0}")]
+    TomlSer(#[from] toml::ser::Error),
+
+    /// A parameter is out of range.
+    #[error("Invalid model spec: {0}")]
+    Invalid(String),
+}
+
+/// Result type for model specifications.
+pub type Result<T> = std::result::Result<T, ModelSpecError>;
+
+/// Full specification of a model run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModelSpec {
+    /// Name of the model, shown with its outputs.
+    pub name: String,
+    /// Estimation universe.
+    pub universe: UniverseSpec,
+    /// Style factors and their parameters.
+    pub factors: FactorSpec,
+    /// Industry factor scheme.
+    pub industries: IndustrySpec,
+    /// Cross-sectional regression options.
+    pub regression: RegressionSpec,
+    /// Factor covariance and specific risk estimators.
+    pub risk: RiskSpec,
+}
+
+impl Default for ModelSpec {
+    fn default() -> Self {
+        Self {
+            name: "perth".to_string(),
+            universe: UniverseSpec::default(),
+            factors: FactorSpec::default(),
+            industries: IndustrySpec::default(),
+            regression: RegressionSpec::default(),
+            risk: RiskSpec::default(),
+        }
+    }
+}
+
+impl ModelSpec {
+    /// Parse and validate a spec from TOML.
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        let spec: Self = toml::from_str(contents)?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Load a spec file.
+    ///
+    /// A relative universe path is resolved against the spec's directory, so
+    /// a spec and its universe file can be moved together.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let mut spec = Self::from_toml_str(&std::fs::read_to_string(path)?)?;
+        if let Some(universe) = &spec.universe.path
+            && universe.is_relative()
+            && let Some(dir) = path.parent()
+        {
+            spec.universe.path = Some(dir.join(universe));
+        }
+        Ok(spec)
+    }
+
+    /// The spec as TOML, with every parameter written out.
+    pub fn to_toml_string(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Check that parameters are within their valid ranges.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| Err(ModelSpecError::Invalid(message));
+        let factors = &self.factors;
+        let windows = [
+            ("beta", factors.beta.lookback, factors.beta.min_periods),
+            (
+                "volatility",
+                factors.volatility.lookback,
+                factors.volatility.min_periods,
+            ),
+            (
+                "illiquidity",
+                factors.illiquidity.lookback,
+                factors.illiquidity.min_periods,
+            ),
+            (
+                "residual_volatility",
+                factors.residual_volatility.lookback,
+                factors.residual_volatility.min_periods,
+            ),
+        ];
+
+        for (name, lookback) in [
+            ("momentum", factors.momentum.lookback),
+            ("reversal", factors.reversal.lookback),
+            ("long_term_momentum", factors.long_term_momentum.lookback),
+        ] {
+            if lookback == 0 {
+                return invalid(format!("factors.{name}.lookback must be positive"));
+            }
+        }
+        for (name, lookback, min_periods) in windows {
+            if lookback == 0 {
+                return invalid(format!("factors.{name}.lookback must be positive"));
+            }
+            if min_periods > lookback {
+                return invalid(format!(
+                    "factors.{name}.min_periods ({min_periods}) exceeds its lookback ({lookback})"
+                ));
+            }
+        }
+        let enabled = self.factor_engine();
+        let enabled = enabled.factor_names();
+        let order = &factors.orthogonalization.order;
+        for (i, name) in order.iter().enumerate() {
+            if !enabled.contains(&name.as_str()) {
+                return invalid(format!(
+                    "factors.orthogonalization.order names {name}, which is not an enabled factor"
+                ));
+            }
+            if order[..i].contains(name) {
+                return invalid(format!(
+                    "factors.orthogonalization.order lists {name} twice"
+                ));
+            }
+        }
+        let sigma = factors.standardization.winsorize_sigma;
+        if !(sigma == 0.0 || sigma >= 1.0) {
+            return invalid(format!(
+                "factors.standardization.winsorize_sigma must be 0 (off) or at least 1, got {sigma}"
+            ));
+        }
+        if !(0.0..0.5).contains(&self.regression.winsor_factor) {
+            return invalid(format!(
+                "regression.winsor_factor must be in [0, 0.5), got {}",
+                self.regression.winsor_factor
+            ));
+        }
+        let robust = &self.regression.robust;
+        if let Some(tuning) = robust.tuning
+            && !(tuning.is_finite() && tuning > 0.0)
+        {
+            return invalid(format!(
+                "regression.robust.tuning must be positive, got {tuning}"
+            ));
+        }
+        if robust.tolerance <= 0.0 {
+            return invalid(format!(
+                "regression.robust.tolerance must be positive, got {}",
+                robust.tolerance
+            ));
+        }
+        for (name, decay) in [
+            (
+                "risk.covariance.ewma.decay",
+                self.risk.covariance.ewma.decay,
+            ),
+            ("risk.specific.ewma_decay", self.risk.specific.ewma_decay),
+        ] {
+            if decay <= 0.0 || decay >= 1.0 {
+                return invalid(format!("{name} must be between 0 and 1, got {decay}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Stable fingerprint of the spec: FNV-1a over its canonical TOML.
+    ///
+    /// Two runs with the same fingerprint used the same parameters, however
+    /// their spec files were laid out.
+    pub fn fingerprint(&self) -> String {
+        let canonical = self.to_toml_string().unwrap_or_default();
+        let hash = canonical
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        format!("{hash:016x}")
+    }
+
+    /// Factor engine computing the enabled style factors.
+    pub fn factor_engine(&self) -> FactorEngine {
+        FactorEngine::from_spec(&self.factors)
+    }
+
+    /// Options for the cross-sectional factor returns estimator.
+    pub fn estimator_config(&self) -> EstimatorConfig {
+        self.regression.estimator_config()
+    }
+
+    /// Cross-sectional regression estimating the daily factor returns.
+    pub fn regression_estimator(&self) -> CrossSectionalRegression {
+        self.regression.estimator()
+    }
+}
+
+/// Estimation universe.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UniverseSpec {
+    /// Universe file (CSV, JSON or TOML), or `None` for the S&P 500.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+impl fmt::Display for UniverseSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}", path.display()),
+            None => write!(f, "sp500"),
+        }
+    }
+}
+
+/// Style factors and their parameters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FactorSpec {
+    /// Medium-term price momentum.
+    pub momentum: MomentumSpec,
+    /// Log market cap.
+    pub size: SizeSpec,
+    /// Beta against the market benchmark.
+    pub beta: BetaSpec,
+    /// Historical volatility of daily returns.
+    pub volatility: VolatilitySpec,
+    /// Amihud illiquidity.
+    pub illiquidity: IlliquiditySpec,
+    /// Short-term reversal.
+    pub reversal: ReversalSpec,
+    /// Long-term (12-1) momentum.
+    pub long_term_momentum: LongTermMomentumSpec,
+    /// Residual volatility.
+    pub residual_volatility: ResidualVolatilitySpec,
+    /// Trailing dividend yield.
+    pub dividend_yield: DividendYieldSpec,
+    /// Earnings yield.
+    pub earnings_yield: EarningsYieldSpec,
+    /// Winsorization, standardization and imputation of raw values.
+    pub standardization: Standardization,
+    /// Orthogonalization of the standardized scores.
+    pub orthogonalization: OrthogonalizationSpec,
+}
+
+/// Medium-term momentum parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MomentumSpec {
+    /// Whether the factor is part of the model.
+    pub enabled: bool,
+    /// Trading days of returns accumulated.
+    pub lookback: usize,
+    /// Most recent trading days left out, to avoid short-term reversal.
+    pub skip_days: usize,
+}
+
+impl Default for MomentumSpec {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lookback: 126,
+            skip_days: 21,
+        }
+    }
+}
+
+/// Size parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SizeSpec {
+    /// Whether the factor is part of the model.
+    pub enabled: bool,
+}
+
+impl Default for SizeSpec {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Market beta parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BetaSpec {
+    /// Whether the factor is part of the model.
+    pub enabled: bool,
+    /// Trading days in the regression window.
+    pub lookback: usize,
+    /// Fewest observations for a beta estimate.
+    pub min_periods: usize,
+}
+
+impl Default for BetaSpec {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lookback: 126,
+            min_periods: 40,
+        }
+    }
+}
+
+/// Historical volatility parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VolatilitySpec {
+    /// Whether the factor is part of the model.
+    pub enabled: bool,
+    /// Trading days in the volatility window.
+    pub lookback: usize,
+    /// Fewest observations for a volatility estimate.
+    pub min_periods: usize,
+}
+
+impl Default for VolatilitySpec {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lookback: 63,
+            min_periods: 63,
+        }
+    }
+}
+
+/// Amihud illiquidity parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IlliquiditySpec {
+    /// Whether the factor is part of the model.
+    pub enabled: bool,
+    /// Trading days illiquidity is averaged over.
+    pub lookback: usize,
+    /// Fewest observations for an illiquidity estimate.
+    pub min_periods: usize,
+}
+
+impl Default for IlliquiditySpec {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lookback: 21,
+            min_periods: 21,
+        }
+    }
+}
+
+/// Short-term reversal parameters.
+///
+/// Off by default, so models fitted before the factor existed keep their
+/// factor set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReversalSpec {
+    /// Whether the factor is part of the model.
+    pub enabled: bool,
+    /// Trading days of returns reversed.
+    pub lookback: usize,
+}
+
+impl Default for ReversalSpec {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lookback: 21,
+        }
+    }
+}
+
+/// Long-term momentum parameters.
+///
+/// Off by default: the 252 days of history it needs would drop symbols from
+/// short-window runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LongTermMomentumSpec {
+    /// Whether the factor is part of the model.
+    pub enabled: bool,
+    /// Trading days of returns accumulated.
+    pub lookback: usize,
+    /// Most recent trading days left out.
+    pub skip_days: usize,
+}
+
+impl Default for LongTermMomentumSpec {
+    /// 12-1 momentum: the return from 252 to 21 trading days ago.
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lookback: 231,
+            skip_days: 21,
+        }
+    }
+}
+
+/// Residual volatility parameters.
+///
+/// Off by default, so models fitted before the factor existed keep their
+/// factor set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResidualVolatilitySpec {
+    /// Whether the factor is part of the model.
+    pub enabled: bool,
+    /// Trading days in the market-model regression window.
+    pub lookback: usize,
+    /// Fewest observations for an estimate.
+    pub min_periods: usize,
+    /// Factors the scores are orthogonalized against.
+    pub orthogonal_to: Vec<String>,
+}
+
+impl Default for ResidualVolatilitySpec {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lookback: 126,
+            min_periods: 40,
+            orthogonal_to: vec!["beta".to_string(), "size".to_string()],
+        }
+    }
+}
+
+/// Dividend yield parameters.
+///
+/// Off by default: the factor needs the `dividend_ttm` panel column, which
+/// only a dividend-aware data pipeline provides.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DividendYieldSpec {
+    /// Whether the factor is part of the model.
+    pub enabled: bool,
+}
+
+/// Earnings yield parameters.
+///
+/// Off by default: the factor needs the `eps_ttm` panel column, built from
+/// EDGAR annual reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EarningsYieldSpec {
+    /// Whether the factor is part of the model.
+    pub enabled: bool,
+    /// Days after a fiscal year end before its annual report is treated as
+    /// public.
+    pub report_lag_days: u64,
+}
+
+impl Default for EarningsYieldSpec {
+    /// 90 days, the latest a 10-K is due after the fiscal year end.
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            report_lag_days: 90,
+        }
+    }
+}
+
+/// Orthogonalization of style scores.
+///
+/// ```toml
+/// [factors.orthogonalization]
+/// method = "gram-schmidt"   # none, gram-schmidt or residualize
+/// order = ["size", "beta", "volatility", "illiquidity"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrthogonalizationSpec {
+    /// Orthogonalization method.
+    pub method: OrthogonalizationMethod,
+    /// Factors orthogonalized, in order.
+    pub order: Vec<String>,
+}
+
+/// Industry factor scheme.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndustrySpec {
+    /// GICS level with one dummy factor per classification.
+    pub level: GicsLevel,
+}
+
+impl Default for IndustrySpec {
+    fn default() -> Self {
+        Self {
+            level: GicsLevel::Sector,
+        }
+    }
+}
+
+/// Cross-sectional regression options.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegressionSpec {
+    /// Tail fraction of returns winsorized on each side; 0 disables it.
+    pub winsor_factor: f64,
+    /// Whether style scores are residualized on the sector dummies, with
+    /// market-cap weights, before each cross-section is fit.
+    pub residualize_styles: bool,
+    /// Constraint on the sector returns identifying the market return.
+    pub sector_constraint: SectorConstraint,
+    /// Iteratively reweighted least squares against outliers.
+    pub robust: RobustWeighting,
+}
+
+impl Default for RegressionSpec {
+    fn default() -> Self {
+        Self {
+            winsor_factor: 0.05,
+            residualize_styles: true,
+            sector_constraint: SectorConstraint::default(),
+            robust: RobustWeighting::default(),
+        }
+    }
+}
+
+impl RegressionSpec {
+    /// Options for the cross-sectional factor returns estimator.
+    pub fn estimator_config(&self) -> EstimatorConfig {
+        EstimatorConfig {
+            winsor_factor: (self.winsor_factor > 0.0).then_some(self.winsor_factor),
+            residualize_styles: self.residualize_styles,
+        }
+    }
+
+    /// The cross-sectional regression these options describe.
+    pub fn estimator(&self) -> CrossSectionalRegression {
+        CrossSectionalRegression::with_config(self.estimator_config())
+            .with_robust_weighting(self.robust.clone())
+            .with_sector_constraint(self.sector_constraint)
+    }
+}
+
+/// Risk estimators.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RiskSpec {
+    /// Factor covariance estimator.
+    pub covariance: CovarianceSpec,
+    /// Specific risk estimator.
+    pub specific: SpecificRiskConfig,
+}
+
+impl RiskSpec {
+    /// Specific risk estimator configured by the spec.
+    pub fn specific_estimator(&self) -> SpecificRiskEstimator {
+        SpecificRiskEstimator::new(self.specific.clone())
+    }
+
+    /// Short description of the specific risk estimator.
+    pub fn specific_description(&self) -> String {
+        match self.specific.method {
+            VarianceMethod::Ewma => format!("EWMA (λ={})", self.specific.ewma_decay),
+            VarianceMethod::Historical => "Historical".to_string(),
+        }
+    }
+}
+
+/// Factor covariance estimation method.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CovarianceMethod {
+    /// Exponentially weighted moving average.
+    Ewma,
+    /// Ledoit-Wolf shrinkage of the sample covariance.
+    #[default]
+    LedoitWolf,
+    /// Newey-West autocorrelation-consistent covariance.
+    NeweyWest,
+}
+
+/// Factor covariance estimator and the parameters of each method.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CovarianceSpec {
+    /// Estimation method.
+    pub method: CovarianceMethod,
+    /// EWMA parameters.
+    pub ewma: EwmaConfig,
+    /// Ledoit-Wolf parameters.
+    pub ledoit_wolf: LedoitWolfConfig,
+    /// Newey-West parameters.
+    pub newey_west: NeweyWestConfig,
+}
+
+impl CovarianceSpec {
+    /// Covariance estimator for the selected method.
+    pub fn estimator(&self) -> std::result::Result<Box<dyn CovarianceEstimator>, CovarianceError> {
+        Ok(match self.method {
+            CovarianceMethod::Ewma => Box::new(EwmaCovarianceEstimator::new(self.ewma.clone())?),
+            CovarianceMethod::LedoitWolf => {
+                Box::new(LedoitWolfEstimator::new(self.ledoit_wolf.clone()))
+            }
+            CovarianceMethod::NeweyWest => {
+                Box::new(NeweyWestEstimator::new(self.newey_west.clone()))
+            }
+        })
+    }
+
+    /// Short description of the selected method and its main parameter.
+    pub fn description(&self) -> String {
+        match self.method {
+            CovarianceMethod::Ewma => format!("EWMA (λ={})", self.ewma.decay),
+            CovarianceMethod::LedoitWolf => {
+                format!("Ledoit-Wolf ({:?} target)", self.ledoit_wolf.target)
+            }
+            CovarianceMethod::NeweyWest => self.newey_west.lags.map_or_else(
+                || "Newey-West (automatic lags)".to_string(),
+                |lags| format!("Newey-West ({lags} lags)"),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regression::RobustMethod;
+    use crate::style::Imputation;
+
+    #[test]
+    fn test_defaults_match_engine() {
+        let spec = ModelSpec::from_toml_str("").unwrap();
+        assert_eq!(spec, ModelSpec::default());
+        assert_eq!(
+            spec.factor_engine().factor_names(),
+            FactorEngine::new().factor_names()
+        );
+        assert_eq!(spec.estimator_config().winsor_factor, Some(0.05));
+        assert!(spec.estimator_config().residualize_styles);
+    }
+
+    #[test]
+    fn test_partial_sections() {
+        let spec = ModelSpec::from_toml_str(
+            r#"
+            name = "test"
+
+            [factors.momentum]
+            lookback = 252
+
+            [factors.illiquidity]
+            enabled = false
+
+            [factors.standardization]
+            imputation = "regression"
+
+            [industries]
+            level = "industry-group"
+
+            [regression]
+            winsor_factor = 0
+            sector_constraint = "cap-weighted"
+            robust = { method = "tukey" }
+
+            [risk.covariance]
+            method = "ewma"
+            ewma = { decay = 0.97 }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(spec.factors.momentum.lookback, 252);
+        assert_eq!(spec.factors.momentum.skip_days, 21);
+        assert_eq!(
+            spec.factors.standardization.imputation,
+            Imputation::Regression
+        );
+        assert_eq!(spec.factors.standardization.winsorize_sigma, 3.0);
+        assert_eq!(spec.industries.level, GicsLevel::IndustryGroup);
+        assert_eq!(spec.estimator_config().winsor_factor, None);
+        assert_eq!(
+            spec.regression.sector_constraint,
+            SectorConstraint::CapWeighted
+        );
+        assert_eq!(spec.regression.robust.method, RobustMethod::Tukey);
+        assert_eq!(spec.regression.robust.tuning_constant(), 4.685);
+        assert_eq!(spec.risk.covariance.ewma.decay, 0.97);
+        assert_eq!(spec.risk.covariance.ewma.min_observations, 60);
+        assert_eq!(spec.risk.covariance.description(), "EWMA (λ=0.97)");
+        assert!(!spec.factor_engine().factor_names().contains(&"illiquidity"));
+    }
+
+    #[test]
+    fn test_round_trip_and_fingerprint() {
+        let mut spec = ModelSpec::default();
+        spec.universe.path = Some(PathBuf::from("universe.csv"));
+        spec.risk.covariance.method = CovarianceMethod::NeweyWest;
+
+        let toml = spec.to_toml_string().unwrap();
+        let parsed = ModelSpec::from_toml_str(&toml).unwrap();
+        assert_eq!(parsed, spec);
+        assert_eq!(parsed.fingerprint(), spec.fingerprint());
+        assert_ne!(spec.fingerprint(), ModelSpec::default().fingerprint());
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        for contents in [
+            "[factors.beta]\nlookback = 20\nmin_periods = 40",
+            "[regression]\nwinsor_factor = 0.6",
+            "[risk.covariance.ewma]\ndecay = 1.0",
+            "[factors.orthogonalization]\nmethod = \"gram-schmidt\"\norder = [\"size\", \"value\"]",
+            "[factors.illiquidity]\nenabled = false\n[factors.orthogonalization]\norder = [\"illiquidity\"]",
+            "[factors.orthogonalization]\norder = [\"size\", \"beta\", \"size\"]",
+            "[factors.standardization]\nwinsorize_sigma = 0.5",
+            "[regression.robust]\nmethod = \"huber\"\ntuning = -1.0",
+        ] {
+            assert!(matches!(
+                ModelSpec::from_toml_str(contents),
+                Err(ModelSpecError::Invalid(_))
+            ));
+        }
+        assert!(matches!(
+            ModelSpec::from_toml_str("[factors.momentum]\nlookbak = 252"),
+            Err(ModelSpecError::Toml(_))
+        ));
+    }
+}
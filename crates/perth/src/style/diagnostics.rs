@@ -0,0 +1,252 @@
+//! Collinearity diagnostics for style exposures.
+
+use crate::style::orthogonal::residualize;
+use factors::Result as FactorResult;
+use ndarray::Array2;
+use perth_risk::covariance::condition_number;
+use polars::prelude::*;
+
+/// Variance inflation factor above which a style factor is flagged.
+///
+/// A VIF of 10 means the other style factors explain 90% of the factor's
+/// cross-sectional variance.
+pub const VIF_WARNING_THRESHOLD: f64 = 10.0;
+
+/// Collinearity of the style exposures in one cross-section.
+///
+/// Highly collinear exposures make the regression split a common return
+/// between factors arbitrarily, which shows up as large offsetting factor
+/// returns and contributions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollinearityDiagnostics {
+    /// Cross-section date
+    pub date: String,
+    /// Assets with a score for every factor on the date
+    pub n_assets: usize,
+    /// Style factors, in score column order
+    pub factors: Vec<String>,
+    /// Pairwise correlations of the exposures, indexed like `factors`
+    pub correlations: Vec<Vec<f64>>,
+    /// Variance inflation factor of each factor, `1 / (1 - R²)` of an OLS
+    /// regression of its exposures on the others'
+    pub vif: Vec<f64>,
+    /// Ratio of the largest to the smallest eigenvalue of the correlation
+    /// matrix
+    pub condition_number: f64,
+}
+
+impl CollinearityDiagnostics {
+    /// Diagnostics for each date of a frame of `{name}_score` columns, such
+    /// as the output of [`FactorEngine::compute_all_scores`].
+    ///
+    /// Rows missing any score are left out of their date's cross-section.
+    ///
+    /// [`FactorEngine::compute_all_scores`]: crate::style::FactorEngine::compute_all_scores
+    pub fn by_date(scores: &DataFrame) -> FactorResult<Vec<Self>> {
+        let columns: Vec<String> = scores
+            .get_column_names()
+            .into_iter()
+            .filter(|name| name.ends_with("_score"))
+            .map(|name| name.to_string())
+            .collect();
+        let factors: Vec<String> = columns
+            .iter()
+            .map(|name| name.trim_end_matches("_score").to_string())
+            .collect();
+
+        let mut selection = vec![col("date").cast(DataType::String)];
+        selection.extend(
+            columns
+                .iter()
+                .map(|name| col(name.as_str()).cast(DataType::Float64)),
+        );
+        let frame = scores
+            .clone()
+            .lazy()
+            .select(selection)
+            .drop_nulls(None)
+            .sort(["date"], SortMultipleOptions::default())
+            .collect()?;
+
+        let dates = frame.column("date")?.str()?;
+        let values = columns
+            .iter()
+            .map(|name| {
+                Ok(frame
+                    .column(name)?
+                    .f64()?
+                    .into_no_null_iter()
+                    .collect::<Vec<f64>>())
+            })
+            .collect::<FactorResult<Vec<_>>>()?;
+
+        let mut diagnostics = Vec::new();
+        let mut start = 0;
+        while start < frame.height() {
+            let date = dates.get(start).unwrap_or_default();
+            let mut end = start + 1;
+            while end < frame.height() && dates.get(end) == Some(date) {
+                end += 1;
+            }
+            let exposures: Vec<Vec<f64>> = values.iter().map(|v| v[start..end].to_vec()).collect();
+            diagnostics.push(Self::from_exposures(date, factors.clone(), &exposures));
+            start = end;
+        }
+        Ok(diagnostics)
+    }
+
+    /// Diagnostics of one cross-section, with one exposure vector per factor.
+    fn from_exposures(date: &str, factors: Vec<String>, exposures: &[Vec<f64>]) -> Self {
+        let k = exposures.len();
+        let n_assets = exposures.first().map_or(0, Vec::len);
+
+        let mut correlations = vec![vec![1.0; k]; k];
+        for i in 0..k {
+            for j in 0..i {
+                let rho = correlation(&exposures[i], &exposures[j]);
+                correlations[i][j] = rho;
+                correlations[j][i] = rho;
+            }
+        }
+
+        let vif = (0..k)
+            .map(|i| {
+                let others: Vec<Vec<f64>> = exposures
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, x)| x.clone())
+                    .collect();
+                variance_inflation(&exposures[i], &others)
+            })
+            .collect();
+
+        let matrix = Array2::from_shape_fn((k, k), |(i, j)| correlations[i][j]);
+        let condition_number = if k == 0 || matrix.iter().any(|x| !x.is_finite()) {
+            f64::INFINITY
+        } else {
+            condition_number(&matrix)
+        };
+
+        Self {
+            date: date.to_string(),
+            n_assets,
+            factors,
+            correlations,
+            vif,
+            condition_number,
+        }
+    }
+
+    /// Factors whose VIF exceeds `threshold`, largest first.
+    pub fn high_vif(&self, threshold: f64) -> Vec<(&str, f64)> {
+        let mut high: Vec<(&str, f64)> = self
+            .factors
+            .iter()
+            .zip(&self.vif)
+            .filter(|(_, vif)| **vif > threshold)
+            .map(|(name, vif)| (name.as_str(), *vif))
+            .collect();
+        high.sort_by(|a, b| b.1.total_cmp(&a.1));
+        high
+    }
+
+    /// Factor pairs whose absolute correlation is at least `min_abs`,
+    /// strongest first.
+    pub fn correlated_pairs(&self, min_abs: f64) -> Vec<(&str, &str, f64)> {
+        let mut pairs = Vec::new();
+        for i in 0..self.factors.len() {
+            for j in (i + 1)..self.factors.len() {
+                let rho = self.correlations[i][j];
+                if rho.abs() >= min_abs {
+                    pairs.push((self.factors[i].as_str(), self.factors[j].as_str(), rho));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| b.2.abs().total_cmp(&a.2.abs()));
+        pairs
+    }
+}
+
+/// Pearson correlation, or NaN if either vector is constant.
+fn correlation(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y) {
+        sxy += (a - mean_x) * (b - mean_y);
+        sxx += (a - mean_x).powi(2);
+        syy += (b - mean_y).powi(2);
+    }
+    if sxx <= 0.0 || syy <= 0.0 {
+        return f64::NAN;
+    }
+    sxy / (sxx * syy).sqrt()
+}
+
+/// `1 / (1 - R²)` of `y` regressed on an intercept and `others`, infinite
+/// when the others explain `y` exactly or there are too few observations.
+fn variance_inflation(y: &[f64], others: &[Vec<f64>]) -> f64 {
+    if y.len() <= others.len() + 1 {
+        return f64::INFINITY;
+    }
+    let mean = y.iter().sum::<f64>() / y.len() as f64;
+    let total: f64 = y.iter().map(|v| (v - mean).powi(2)).sum();
+    let residual: f64 = residualize(y, others).iter().map(|e| e * e).sum();
+    if residual <= total * 1e-12 {
+        f64::INFINITY
+    } else {
+        total / residual
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collinear_exposures() {
+        let n = 50;
+        let size: Vec<f64> = (0..n).map(|i| (i as f64 * 0.37).sin()).collect();
+        let noise: Vec<f64> = (0..n).map(|i| (i as f64 * 1.91).cos()).collect();
+        let momentum: Vec<f64> = (0..n).map(|i| (i as f64 * 2.71).sin()).collect();
+        // Illiquidity is mostly small size
+        let illiquidity: Vec<f64> = size
+            .iter()
+            .zip(&noise)
+            .map(|(s, e)| -s + 0.05 * e)
+            .collect();
+
+        let scores = df! {
+            "symbol" => (0..n).map(|i| format!("S{i}")).collect::<Vec<_>>(),
+            "date" => vec!["2024-06-28"; n],
+            "size_score" => size,
+            "momentum_score" => momentum,
+            "illiquidity_score" => illiquidity,
+        }
+        .unwrap();
+
+        let diagnostics = CollinearityDiagnostics::by_date(&scores).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let day = &diagnostics[0];
+        assert_eq!(day.n_assets, n);
+        assert_eq!(day.factors, ["size", "momentum", "illiquidity"]);
+        assert!(day.correlations[0][2] < -0.99);
+        assert_eq!(day.correlations[0][2], day.correlations[2][0]);
+
+        let high: Vec<&str> = day
+            .high_vif(VIF_WARNING_THRESHOLD)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(high.len(), 2);
+        assert!(high.contains(&"size") && high.contains(&"illiquidity"));
+        assert!(day.vif[1] < 2.0);
+        assert!(day.condition_number > 100.0);
+
+        let pairs = day.correlated_pairs(0.9);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].0, pairs[0].1), ("size", "illiquidity"));
+    }
+}
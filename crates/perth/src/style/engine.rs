@@ -0,0 +1,770 @@
+//! Factor computation engine.
+//!
+//! Computes the registered style factors for every security in the panel.
+//! The default factors can all be computed from Yahoo Finance data alone,
+//! with lookback windows shortened to preserve more data for analysis.
+
+use crate::spec::FactorSpec;
+use crate::style::orthogonal::residualize;
+use crate::style::{
+    FactorPlugin, FnFactor, OrthogonalizationMethod, ResidualVolatility, Standardization,
+    StyleFactor,
+};
+use chrono::NaiveDate;
+use factors::{
+    ConfigurableFactor, Factor, FactorError, Result as FactorResult,
+    liquidity::{AmihudIlliquidity, AmihudIlliquidityConfig},
+    momentum::{
+        LongTermMomentum, LongTermMomentumConfig, MediumTermMomentum, MediumTermMomentumConfig,
+        ShortTermMomentum, ShortTermMomentumConfig,
+    },
+    volatility::{HistoricalVolatility, HistoricalVolatilityConfig, MarketBeta, MarketBetaConfig},
+};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// Symbols of a cross-section with their market caps and sectors.
+type CrossSection = (Vec<String>, Vec<Option<f64>>, Vec<Option<String>>);
+
+/// Factor input columns served from differently named panel columns.
+const COLUMN_ALIASES: &[(&str, &str)] =
+    &[("close", "adjusted_close"), ("unadjusted_close", "close")];
+
+/// Engine computing the registered style factors.
+///
+/// [`new`](Self::new) registers the default factors:
+/// - Momentum: medium-term momentum (6-month lookback, 21-day skip)
+/// - Size: log market cap, from the market cap proxy
+/// - Beta: systematic risk against the market return (126-day window)
+/// - Volatility: historical volatility (63-day window)
+/// - Illiquidity: Amihud illiquidity (21-day window)
+///
+/// [`from_spec`](Self::from_spec) builds the same factors with the windows
+/// of a [model spec](crate::spec::ModelSpec), and can add opt-in price
+/// factors:
+/// - Reversal: negated 1-month return
+/// - Long-term momentum: 12-1 month momentum
+/// - Residual volatility: volatility of market-model residuals (126-day
+///   window), orthogonalized against beta and size
+///
+/// and two optional factors read from fundamental panel columns:
+/// - Dividend yield: trailing 12-month dividends over price, from
+///   `dividend_ttm`
+/// - Earnings yield: trailing diluted EPS over price, from `eps_ttm`
+#[derive(Debug)]
+pub struct FactorEngine {
+    factors: Vec<Box<dyn StyleFactor>>,
+    standardization: Standardization,
+    orthogonalization: OrthogonalizationMethod,
+    orthogonal_order: Vec<String>,
+}
+
+impl Default for FactorEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FactorEngine {
+    /// Create an engine with the default factors.
+    ///
+    /// Uses shorter lookback windows than the `factors` defaults to preserve
+    /// more data:
+    /// - Momentum: 126 days (6 months) + 21-day skip = 147 days required
+    /// - Beta: 126-day window with 40 min periods = 40 days required
+    /// - Volatility: 63-day window with 63 min periods = 63 days required
+    /// - Illiquidity: 21-day window with 21 min periods = 21 days required
+    ///
+    /// Reversal, long-term momentum and residual volatility are opt-in; enable
+    /// them in a [`FactorSpec`] and build the engine with
+    /// [`from_spec`](Self::from_spec).
+    pub fn new() -> Self {
+        Self::from_spec(&FactorSpec::default())
+    }
+
+    /// Create an engine with the factors enabled in a model spec.
+    pub fn from_spec(spec: &FactorSpec) -> Self {
+        let mut engine = Self::empty();
+        if spec.momentum.enabled {
+            let momentum = MediumTermMomentum::with_config(MediumTermMomentumConfig {
+                lookback: spec.momentum.lookback,
+                skip_days: spec.momentum.skip_days,
+            });
+            engine = engine.with_factor(FactorPlugin::new("momentum", momentum));
+        }
+        if spec.size.enabled {
+            engine = engine.with_factor(size_factor());
+        }
+        if spec.beta.enabled {
+            let beta = MarketBeta::with_config(MarketBetaConfig {
+                lookback: spec.beta.lookback,
+                min_periods: spec.beta.min_periods,
+            });
+            engine = engine.with_factor(FactorPlugin::new("beta", beta));
+        }
+        if spec.volatility.enabled {
+            let volatility = HistoricalVolatility::with_config(HistoricalVolatilityConfig {
+                lookback: spec.volatility.lookback,
+                min_periods: spec.volatility.min_periods,
+            });
+            engine = engine.with_factor(FactorPlugin::new("volatility", volatility));
+        }
+        if spec.illiquidity.enabled {
+            let illiquidity = AmihudIlliquidity::with_config(AmihudIlliquidityConfig {
+                lookback: spec.illiquidity.lookback,
+                min_periods: spec.illiquidity.min_periods,
+            });
+            engine = engine.with_factor(FactorPlugin::new("illiquidity", illiquidity));
+        }
+        if spec.reversal.enabled {
+            engine = engine.with_factor(reversal_factor(spec.reversal.lookback));
+        }
+        if spec.long_term_momentum.enabled {
+            let momentum = LongTermMomentum::with_config(LongTermMomentumConfig {
+                lookback: spec.long_term_momentum.lookback,
+                skip_days: spec.long_term_momentum.skip_days,
+            });
+            engine = engine.with_factor(FactorPlugin::new("long_term_momentum", momentum));
+        }
+        if spec.residual_volatility.enabled {
+            let residual_volatility = ResidualVolatility::new(
+                spec.residual_volatility.lookback,
+                spec.residual_volatility.min_periods,
+            )
+            .with_orthogonal_to(&spec.residual_volatility.orthogonal_to);
+            engine = engine.with_factor(residual_volatility);
+        }
+        if spec.dividend_yield.enabled {
+            engine = engine.with_factor(yield_factor("dividend_yield", "dividend_ttm"));
+        }
+        if spec.earnings_yield.enabled {
+            engine = engine.with_factor(yield_factor("earnings_yield", "eps_ttm"));
+        }
+        engine.with_orthogonalization(spec.orthogonalization.method, &spec.orthogonalization.order)
+    }
+
+    /// Create an engine without any factors.
+    pub fn empty() -> Self {
+        Self {
+            factors: Vec::new(),
+            standardization: Standardization::default(),
+            orthogonalization: OrthogonalizationMethod::None,
+            orthogonal_order: Vec::new(),
+        }
+    }
+
+    /// Standardize and impute raw values with `standardization`.
+    pub const fn with_standardization(mut self, standardization: Standardization) -> Self {
+        self.standardization = standardization;
+        self
+    }
+
+    /// Orthogonalize the scores of the factors in `order` with `method`.
+    ///
+    /// Runs after each factor's own [`orthogonal_to`](StyleFactor::orthogonal_to)
+    /// step. Names of factors that are not registered are ignored.
+    pub fn with_orthogonalization<I, S>(mut self, method: OrthogonalizationMethod, order: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.orthogonalization = method;
+        self.orthogonal_order = order.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Register a factor, replacing any registered under the same name.
+    pub fn with_factor(mut self, factor: impl StyleFactor + 'static) -> Self {
+        self.register(Box::new(factor));
+        self
+    }
+
+    /// Register a boxed factor, replacing any registered under the same name.
+    pub fn register(&mut self, factor: Box<dyn StyleFactor>) {
+        match self.factors.iter().position(|f| f.name() == factor.name()) {
+            Some(i) => self.factors[i] = factor,
+            None => self.factors.push(factor),
+        }
+    }
+
+    /// Remove a factor by name, returning whether it was registered.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.factors.len();
+        self.factors.retain(|f| f.name() != name);
+        self.factors.len() != before
+    }
+
+    /// Names of the registered factors, in output order.
+    pub fn factor_names(&self) -> Vec<&str> {
+        self.factors.iter().map(|f| f.name()).collect()
+    }
+
+    /// Longest lookback of the registered factors, in trading days.
+    pub fn lookback(&self) -> usize {
+        self.factors.iter().map(|f| f.lookback()).max().unwrap_or(0)
+    }
+
+    /// Compute all factor scores on `date`.
+    ///
+    /// # Arguments
+    /// * `data` - Panel with columns: date, symbol, adjusted_close, volume,
+    ///   market_return, market_cap and any columns custom factors read
+    /// * `date` - The target date for factor computation
+    ///
+    /// # Returns
+    /// DataFrame with columns: symbol, date (as a string) and one
+    /// `{name}_score` column per factor, for the symbols every factor scores.
+    /// Scores of factors with [`orthogonal_to`](StyleFactor::orthogonal_to)
+    /// set are residualized last, in registration order, followed by the
+    /// [orthogonalization](Self::with_orthogonalization) step.
+    pub fn compute_all_scores(&self, data: &DataFrame, date: NaiveDate) -> FactorResult<DataFrame> {
+        let view = input_view(data);
+        let available: Vec<String> = view
+            .clone()
+            .collect_schema()?
+            .iter_names()
+            .map(|n| n.to_string())
+            .collect();
+
+        let (symbols, weights, sectors) = cross_section(&view, &available, date)?;
+        let index: HashMap<&str, usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(i, symbol)| (symbol.as_str(), i))
+            .collect();
+
+        let mut exposures = Vec::with_capacity(self.factors.len());
+        for factor in &self.factors {
+            let required = factor.required_columns();
+            if let Some(missing) = required.iter().find(|c| !available.iter().any(|a| a == *c)) {
+                return Err(FactorError::MissingColumn(format!(
+                    "{} (required by {})",
+                    missing,
+                    factor.name()
+                )));
+            }
+
+            let mut columns = vec![col("symbol"), col("date")];
+            columns.extend(required.iter().map(|c| col(*c)));
+            let raw = factor.compute_raw(&view.clone().select(columns), date)?;
+
+            let mut values = vec![None; symbols.len()];
+            for (symbol, value) in raw_values(raw, factor.name(), date)? {
+                if let Some(&i) = index.get(symbol.as_str()) {
+                    values[i] = Some(value);
+                }
+            }
+            self.standardization.standardize(&mut values, &weights);
+            exposures.push(values);
+        }
+
+        // Keep the rows that are complete after imputation
+        let rows = self.standardization.impute(&exposures, &sectors);
+        let keep: Vec<usize> = (0..rows.len()).filter(|&i| rows[i].is_some()).collect();
+        let weights: Vec<Option<f64>> = keep.iter().map(|&i| weights[i]).collect();
+        let mut columns: Vec<Column> = vec![
+            Column::new(
+                "symbol".into(),
+                keep.iter()
+                    .map(|&i| symbols[i].as_str())
+                    .collect::<Vec<_>>(),
+            ),
+            Column::new("date".into(), vec![date.to_string(); keep.len()]),
+        ];
+        for (k, factor) in self.factors.iter().enumerate() {
+            let values: Vec<f64> = keep
+                .iter()
+                .filter_map(|&i| rows[i].as_ref().map(|row| row[k]))
+                .collect();
+            columns.push(Column::new(score_column(factor.name()).into(), values));
+        }
+        let mut scores = DataFrame::new(columns)?;
+
+        let rescale = |values: &mut [f64]| self.standardization.rescale(values, &weights);
+        for factor in &self.factors {
+            let against: Vec<&str> = factor
+                .orthogonal_to()
+                .into_iter()
+                .filter(|name| *name != factor.name() && self.factor_names().contains(name))
+                .collect();
+            if !against.is_empty() {
+                orthogonalize(&mut scores, factor.name(), &against, &rescale)?;
+            }
+        }
+        self.orthogonalize_ordered(&mut scores, &rescale)?;
+        Ok(scores)
+    }
+
+    /// Apply the configured orthogonalization to the ordered factors.
+    fn orthogonalize_ordered(
+        &self,
+        scores: &mut DataFrame,
+        rescale: &dyn Fn(&mut [f64]),
+    ) -> FactorResult<()> {
+        let names = self.factor_names();
+        let order: Vec<&str> = self
+            .orthogonal_order
+            .iter()
+            .map(String::as_str)
+            .filter(|name| names.contains(name))
+            .collect();
+
+        match self.orthogonalization {
+            OrthogonalizationMethod::None => {}
+            OrthogonalizationMethod::GramSchmidt => {
+                for (i, name) in order.iter().enumerate().skip(1) {
+                    orthogonalize(scores, name, &order[..i], rescale)?;
+                }
+            }
+            OrthogonalizationMethod::Residualize => {
+                let original = scores.clone();
+                for name in &order {
+                    let regressors = names
+                        .iter()
+                        .filter(|other| *other != name)
+                        .map(|other| score_values(&original, other))
+                        .collect::<FactorResult<Vec<_>>>()?;
+                    let mut residual = residualize(&score_values(&original, name)?, &regressors);
+                    rescale(&mut residual);
+                    replace_scores(scores, name, residual)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replace a factor's scores with their rescaled residuals on the scores of
+/// the `against` factors.
+fn orthogonalize(
+    scores: &mut DataFrame,
+    name: &str,
+    against: &[&str],
+    rescale: &dyn Fn(&mut [f64]),
+) -> FactorResult<()> {
+    let regressors = against
+        .iter()
+        .map(|factor| score_values(scores, factor))
+        .collect::<FactorResult<Vec<_>>>()?;
+    let mut residual = residualize(&score_values(scores, name)?, &regressors);
+    rescale(&mut residual);
+    replace_scores(scores, name, residual)
+}
+
+/// A factor's scores, in row order.
+fn score_values(scores: &DataFrame, name: &str) -> FactorResult<Vec<f64>> {
+    Ok(scores
+        .column(&score_column(name))?
+        .f64()?
+        .into_no_null_iter()
+        .collect())
+}
+
+/// Overwrite a factor's score column.
+fn replace_scores(scores: &mut DataFrame, name: &str, values: Vec<f64>) -> FactorResult<()> {
+    let column = score_column(name);
+    scores.replace(&column, Series::new(column.as_str().into(), values))?;
+    Ok(())
+}
+
+/// Score column name of a factor.
+fn score_column(name: &str) -> String {
+    format!("{name}_score")
+}
+
+/// The panel as factors see it: string dates, `close` replaced by the
+/// adjusted close and float volumes.
+fn input_view(data: &DataFrame) -> LazyFrame {
+    let names: Vec<&str> = data
+        .get_column_names()
+        .into_iter()
+        .map(|n| n.as_str())
+        .collect();
+
+    let mut columns = vec![col("date").cast(DataType::String)];
+    if names.contains(&"volume") {
+        columns.push(col("volume").cast(DataType::Float64));
+    }
+    for (alias, source) in COLUMN_ALIASES {
+        if names.contains(source) {
+            columns.push(col(*source).alias(*alias));
+        }
+    }
+    data.clone().lazy().with_columns(columns)
+}
+
+/// Symbols with a row on `date`, sorted, with their market caps and
+/// sectors where the panel has them.
+fn cross_section(
+    view: &LazyFrame,
+    available: &[String],
+    date: NaiveDate,
+) -> FactorResult<CrossSection> {
+    let has = |name: &str| available.iter().any(|a| a == name);
+    let optional = |name: &str, dtype: DataType| {
+        if has(name) {
+            col(name).cast(dtype)
+        } else {
+            lit(NULL).cast(dtype).alias(name)
+        }
+    };
+    let frame = view
+        .clone()
+        .filter(col("date").eq(lit(date.to_string())))
+        .select([
+            col("symbol"),
+            optional("market_cap", DataType::Float64),
+            optional("sector", DataType::String),
+        ])
+        .unique_stable(Some(vec!["symbol".into()]), UniqueKeepStrategy::First)
+        .sort(["symbol"], SortMultipleOptions::default())
+        .collect()?;
+
+    let symbols = frame
+        .column("symbol")?
+        .str()?
+        .into_iter()
+        .map(|s| s.unwrap_or_default().to_string())
+        .collect();
+    let weights = frame.column("market_cap")?.f64()?.into_iter().collect();
+    let sectors = frame
+        .column("sector")?
+        .str()?
+        .into_iter()
+        .map(|s| s.map(str::to_string))
+        .collect();
+    Ok((symbols, weights, sectors))
+}
+
+/// A factor's finite raw values on `date`, by symbol.
+fn raw_values(raw: DataFrame, name: &str, date: NaiveDate) -> FactorResult<Vec<(String, f64)>> {
+    let mut raw = raw.lazy();
+    if raw.collect_schema()?.contains("date") {
+        raw = raw.filter(col("date").cast(DataType::String).eq(lit(date.to_string())));
+    }
+    let raw = raw
+        .select([col("symbol"), col(name).cast(DataType::Float64)])
+        .filter(col(name).is_not_null().and(col(name).is_finite()))
+        .collect()?;
+
+    let symbols = raw.column("symbol")?.str()?;
+    let values = raw.column(name)?.f64()?;
+    Ok(symbols
+        .into_iter()
+        .zip(values)
+        .filter_map(|(symbol, value)| Some((symbol?.to_string(), value?)))
+        .collect())
+}
+
+/// Reversal: the negated return over the last `lookback` trading days,
+/// `-(P_t / P_{t-lookback} - 1)`, so that recent losers score high.
+fn reversal_factor(lookback: usize) -> FnFactor {
+    let momentum = ShortTermMomentum::with_config(ShortTermMomentumConfig {
+        lookback,
+        skip_days: 0,
+    });
+    FnFactor::new("reversal", ["close"], lookback + 1, move |panel, date| {
+        let raw = momentum.compute_raw(panel, date)?;
+        Ok(raw
+            .lazy()
+            .select([
+                col("symbol"),
+                col("date"),
+                (lit(0.0) - col(momentum.name())).alias("reversal"),
+            ])
+            .collect()?)
+    })
+}
+
+/// A per-share amount over the unadjusted close on the target date.
+///
+/// A null amount means the fundamentals are unknown and the symbol gets no
+/// score; a zero amount, such as a company known to pay no dividends, scores
+/// a zero yield.
+fn yield_factor(name: &'static str, per_share: &'static str) -> FnFactor {
+    FnFactor::new(
+        name,
+        ["unadjusted_close", per_share],
+        1,
+        move |panel, date| {
+            Ok(panel
+                .clone()
+                .filter(col("date").eq(lit(date.to_string())))
+                .select([
+                    col("symbol"),
+                    col("date"),
+                    (col(per_share).cast(DataType::Float64)
+                        / col("unadjusted_close").cast(DataType::Float64))
+                    .alias(name),
+                ])
+                .collect()?)
+        },
+    )
+}
+
+/// Size: log market cap on the target date.
+///
+/// Yahoo data lacks shares outstanding, so this reads the market cap proxy
+/// rather than using a `factors` size factor.
+fn size_factor() -> FnFactor {
+    FnFactor::new("size", ["market_cap"], 1, |panel, date| {
+        Ok(panel
+            .clone()
+            .filter(col("date").eq(lit(date.to_string())))
+            .select([
+                col("symbol"),
+                col("market_cap").log(std::f64::consts::E).alias("size"),
+            ])
+            .collect()?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Days;
+
+    /// A panel of `n_days` daily rows for three symbols.
+    fn panel(n_days: u64) -> DataFrame {
+        wide_panel(3, n_days)
+    }
+
+    /// A panel of `n_days` daily rows for up to eight symbols.
+    fn wide_panel(n_symbols: usize, n_days: u64) -> DataFrame {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut dates = Vec::new();
+        let mut symbols = Vec::new();
+        let mut closes = Vec::new();
+        let mut volumes = Vec::new();
+        let mut market_returns = Vec::new();
+        let mut market_caps = Vec::new();
+        let names = ["AAA", "BBB", "CCC", "DDD", "EEE", "FFF", "GGG", "HHH"];
+        for (i, symbol) in names.iter().take(n_symbols).enumerate() {
+            let drift = 0.001 * (i as f64 + 1.0);
+            for day in 0..n_days {
+                let t = day as f64;
+                dates.push(start.checked_add_days(Days::new(day)).unwrap());
+                symbols.push(*symbol);
+                closes.push(
+                    100.0 * (1.0 + drift).powf(t) * (1.0 + 0.01 * (t * (i as f64 + 1.0)).sin()),
+                );
+                volumes.push(1_000_000 + 1000 * i as i64 + day as i64);
+                market_returns.push(0.002 * (t * 0.7).sin());
+                market_caps.push(1e9 * (i as f64 + 1.0) * (1.0 + 0.001 * t));
+            }
+        }
+
+        df! {
+            "date" => dates,
+            "symbol" => symbols,
+            "adjusted_close" => closes,
+            "volume" => volumes,
+            "market_return" => market_returns,
+            "market_cap" => market_caps,
+        }
+        .unwrap()
+    }
+
+    fn last_date(n_days: u64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .checked_add_days(Days::new(n_days - 1))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_default_factors() {
+        // Reversal, long-term momentum and residual volatility are opt-in
+        let engine = FactorEngine::new();
+        assert_eq!(
+            engine.factor_names(),
+            vec!["momentum", "size", "beta", "volatility", "illiquidity"]
+        );
+        assert!(engine.lookback() < 231);
+
+        let mut spec = FactorSpec::default();
+        spec.reversal.enabled = true;
+        spec.long_term_momentum.enabled = true;
+        spec.residual_volatility.enabled = true;
+        let engine = FactorEngine::from_spec(&spec);
+        assert_eq!(
+            engine.factor_names()[5..],
+            ["reversal", "long_term_momentum", "residual_volatility"]
+        );
+        assert!(engine.lookback() >= 231);
+
+        let scores = engine
+            .compute_all_scores(&panel(300), last_date(300))
+            .unwrap();
+        assert_eq!(scores.height(), 3);
+        // Market caps on the last day are in the ratio 1:2:3, and the
+        // cap-weighted market portfolio has no style exposure
+        for name in engine.factor_names() {
+            let values = score_values(&scores, name).unwrap();
+            let mean = (values[0] + 2.0 * values[1] + 3.0 * values[2]) / 6.0;
+            assert!(mean.abs() < 1e-9, "{name} scores are not cap-centered");
+        }
+    }
+
+    #[test]
+    fn test_custom_factor() {
+        let custom = FnFactor::new("dollar_volume", ["close", "volume"], 1, |panel, date| {
+            Ok(panel
+                .clone()
+                .filter(col("date").eq(lit(date.to_string())))
+                .select([
+                    col("symbol"),
+                    (col("close") * col("volume")).alias("dollar_volume"),
+                ])
+                .collect()?)
+        });
+        let engine = FactorEngine::empty()
+            .with_factor(size_factor())
+            .with_factor(custom);
+
+        let scores = engine
+            .compute_all_scores(&panel(30), last_date(30))
+            .unwrap();
+        assert_eq!(
+            scores.get_column_names(),
+            ["symbol", "date", "size_score", "dollar_volume_score"]
+        );
+        let std = scores
+            .column("dollar_volume_score")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .std(1)
+            .unwrap();
+        assert!((std - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_register_replaces_and_removes() {
+        let mut engine = FactorEngine::new();
+        engine.register(Box::new(FactorPlugin::new(
+            "momentum",
+            MediumTermMomentum::default(),
+        )));
+        assert_eq!(engine.factor_names().len(), 5);
+        assert!(engine.remove("illiquidity"));
+        assert!(!engine.remove("illiquidity"));
+        assert_eq!(engine.factor_names().len(), 4);
+    }
+
+    #[test]
+    fn test_yield_factors() {
+        let mut spec = FactorSpec::default();
+        spec.dividend_yield.enabled = true;
+        spec.earnings_yield.enabled = true;
+        let names = FactorEngine::from_spec(&spec).factor_names().len();
+        assert_eq!(names, 7);
+
+        // A known non-payer (BBB) scores below the payer, and unknown
+        // dividends (AAA) are imputed from the cross-sectional median
+        let data = panel(5)
+            .lazy()
+            .with_columns([
+                col("adjusted_close").alias("close"),
+                when(col("symbol").eq(lit("AAA")))
+                    .then(lit(NULL).cast(DataType::Float64))
+                    .when(col("symbol").eq(lit("BBB")))
+                    .then(lit(0.0))
+                    .otherwise(lit(2.0))
+                    .alias("dividend_ttm"),
+                when(col("symbol").eq(lit("CCC")))
+                    .then(lit(-1.0))
+                    .otherwise(lit(5.0))
+                    .alias("eps_ttm"),
+            ])
+            .collect()
+            .unwrap();
+        let engine = FactorEngine::empty()
+            .with_factor(yield_factor("dividend_yield", "dividend_ttm"))
+            .with_factor(yield_factor("earnings_yield", "eps_ttm"));
+        let scores = engine.compute_all_scores(&data, last_date(5)).unwrap();
+
+        let symbols: Vec<&str> = scores
+            .column("symbol")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(symbols, ["AAA", "BBB", "CCC"]);
+        let dividend = score_values(&scores, "dividend_yield").unwrap();
+        assert!(dividend[1] < dividend[2]);
+        assert!((dividend[0] - (dividend[1] + dividend[2]) / 2.0).abs() < 1e-12);
+        let earnings = score_values(&scores, "earnings_yield").unwrap();
+        assert!(earnings[1] > earnings[2]);
+
+        // Without imputation the unknown symbol drops out
+        let scores = engine
+            .with_standardization(Standardization::equal_weighted())
+            .compute_all_scores(&data, last_date(5))
+            .unwrap();
+        assert_eq!(scores.height(), 2);
+    }
+
+    #[test]
+    fn test_orthogonalization() {
+        let data = wide_panel(8, 300);
+        let date = last_date(300);
+        let correlation = |scores: &DataFrame, a: &str, b: &str| {
+            let a = score_values(scores, a).unwrap();
+            let b = score_values(scores, b).unwrap();
+            let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+            let (mean_a, mean_b) = (mean(&a), mean(&b));
+            a.iter()
+                .zip(&b)
+                .map(|(x, y)| (x - mean_a) * (y - mean_b))
+                .sum::<f64>()
+                / (a.len() as f64 - 1.0)
+        };
+
+        let plain = FactorEngine::new().compute_all_scores(&data, date).unwrap();
+        let order = ["size", "beta", "volatility"];
+        let gram_schmidt = FactorEngine::new()
+            .with_orthogonalization(OrthogonalizationMethod::GramSchmidt, order)
+            .compute_all_scores(&data, date)
+            .unwrap();
+        assert_eq!(
+            score_values(&plain, "size").unwrap(),
+            score_values(&gram_schmidt, "size").unwrap()
+        );
+        for (a, b) in [
+            ("size", "beta"),
+            ("size", "volatility"),
+            ("beta", "volatility"),
+        ] {
+            assert!(correlation(&gram_schmidt, a, b).abs() < 1e-9, "{a}/{b}");
+        }
+
+        let residualized = FactorEngine::new()
+            .with_orthogonalization(OrthogonalizationMethod::Residualize, ["momentum"])
+            .compute_all_scores(&data, date)
+            .unwrap();
+        assert_eq!(
+            score_values(&plain, "beta").unwrap(),
+            score_values(&residualized, "beta").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_missing_column() {
+        let engine = FactorEngine::empty().with_factor(FnFactor::new(
+            "earnings_yield",
+            ["earnings"],
+            1,
+            |_, _| unreachable!(),
+        ));
+
+        let err = engine
+            .compute_all_scores(&panel(5), last_date(5))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("earnings (required by earnings_yield)")
+        );
+    }
+}
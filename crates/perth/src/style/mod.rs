@@ -0,0 +1,189 @@
+//! Style factors for the cross-sectional model.
+//!
+//! A [`StyleFactor`] turns the prepared price panel into one raw value per
+//! symbol on a date. The [`FactorEngine`] runs each registered factor on the
+//! columns it asks for, standardizes the values cross-sectionally and joins
+//! them into `{name}_score` columns, the naming the estimator uses to find
+//! style exposures.
+//!
+//! Any [`factors::Factor`] can be registered through [`FactorPlugin`], and a
+//! closure over the panel through [`FnFactor`]; proprietary signals can also
+//! implement [`StyleFactor`] directly.
+//!
+//! ```
+//! use perth::style::{FactorEngine, FnFactor};
+//! use polars::prelude::*;
+//!
+//! // Dollar volume on the target date, as a custom liquidity factor
+//! let dollar_volume = FnFactor::new("dollar_volume", ["close", "volume"], 1, |panel, date| {
+//!     Ok(panel
+//!         .clone()
+//!         .filter(col("date").eq(lit(date.to_string())))
+//!         .select([
+//!             col("symbol"),
+//!             (col("close") * col("volume")).log(std::f64::consts::E).alias("dollar_volume"),
+//!         ])
+//!         .collect()?)
+//! });
+//!
+//! let engine = FactorEngine::new().with_factor(dollar_volume);
+//! assert!(engine.factor_names().contains(&"dollar_volume"));
+//! ```
+
+mod diagnostics;
+mod engine;
+mod orthogonal;
+mod residual_volatility;
+mod standardize;
+
+pub use diagnostics::{CollinearityDiagnostics, VIF_WARNING_THRESHOLD};
+pub use engine::FactorEngine;
+pub use orthogonal::OrthogonalizationMethod;
+pub use residual_volatility::ResidualVolatility;
+pub use standardize::{Imputation, MeanWeighting, Standardization};
+
+use chrono::NaiveDate;
+use factors::{Factor, Result as FactorResult};
+use polars::prelude::*;
+use std::fmt;
+
+/// A style factor the [`FactorEngine`] can compute.
+pub trait StyleFactor: Send + Sync + fmt::Debug {
+    /// Name of the factor; its scores are output as `{name}_score`.
+    fn name(&self) -> &str;
+
+    /// Panel columns the factor reads, besides `symbol` and `date`.
+    ///
+    /// The engine serves `close` as the adjusted close and `volume` as a
+    /// float; other names are taken from the panel as they are.
+    fn required_columns(&self) -> Vec<&str>;
+
+    /// Trading days of history needed before the target date.
+    fn lookback(&self) -> usize;
+
+    /// Factors this factor's scores are made orthogonal to.
+    ///
+    /// The engine regresses the standardized scores cross-sectionally on an
+    /// intercept and the scores of these factors, and keeps the standardized
+    /// residuals. Names of factors that are not registered are ignored.
+    fn orthogonal_to(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    /// Compute raw, unstandardized values on `date`.
+    ///
+    /// `input` holds `symbol`, `date` (as a `YYYY-MM-DD` string) and the
+    /// required columns for every date up to the target. The result has a
+    /// `symbol` column and a column named after the factor; rows of a `date`
+    /// column other than the target are dropped by the engine.
+    fn compute_raw(&self, input: &LazyFrame, date: NaiveDate) -> FactorResult<DataFrame>;
+}
+
+/// A [`factors::Factor`] registered under a style factor name.
+#[derive(Debug, Clone)]
+pub struct FactorPlugin<F> {
+    name: String,
+    factor: F,
+}
+
+impl<F: Factor> FactorPlugin<F> {
+    /// Register `factor` with its scores named `{name}_score`.
+    pub fn new(name: impl Into<String>, factor: F) -> Self {
+        Self {
+            name: name.into(),
+            factor,
+        }
+    }
+
+    /// The wrapped factor.
+    pub const fn factor(&self) -> &F {
+        &self.factor
+    }
+}
+
+impl<F: Factor> StyleFactor for FactorPlugin<F> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn required_columns(&self) -> Vec<&str> {
+        self.factor
+            .required_columns()
+            .iter()
+            .copied()
+            .filter(|c| !matches!(*c, "symbol" | "date"))
+            .collect()
+    }
+
+    fn lookback(&self) -> usize {
+        self.factor.lookback()
+    }
+
+    fn compute_raw(&self, input: &LazyFrame, date: NaiveDate) -> FactorResult<DataFrame> {
+        let raw = self.factor.compute_raw(input, date)?;
+        if self.factor.name() == self.name {
+            return Ok(raw);
+        }
+        Ok(raw
+            .lazy()
+            .rename([self.factor.name()], [self.name.as_str()], true)
+            .collect()?)
+    }
+}
+
+/// Signature of a closure computing raw factor values.
+type ComputeFn = dyn Fn(&LazyFrame, NaiveDate) -> FactorResult<DataFrame> + Send + Sync;
+
+/// A style factor computed by a closure over the panel.
+pub struct FnFactor {
+    name: String,
+    columns: Vec<String>,
+    lookback: usize,
+    compute: Box<ComputeFn>,
+}
+
+impl FnFactor {
+    /// Create a factor from its name, required columns, lookback and a
+    /// closure computing raw values (see [`StyleFactor::compute_raw`]).
+    pub fn new<I, S, C>(name: impl Into<String>, columns: I, lookback: usize, compute: C) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+        C: Fn(&LazyFrame, NaiveDate) -> FactorResult<DataFrame> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            columns: columns.into_iter().map(Into::into).collect(),
+            lookback,
+            compute: Box::new(compute),
+        }
+    }
+}
+
+impl fmt::Debug for FnFactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnFactor")
+            .field("name", &self.name)
+            .field("columns", &self.columns)
+            .field("lookback", &self.lookback)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StyleFactor for FnFactor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn required_columns(&self) -> Vec<&str> {
+        self.columns.iter().map(String::as_str).collect()
+    }
+
+    fn lookback(&self) -> usize {
+        self.lookback
+    }
+
+    fn compute_raw(&self, input: &LazyFrame, date: NaiveDate) -> FactorResult<DataFrame> {
+        (self.compute)(input, date)
+    }
+}
@@ -0,0 +1,77 @@
+//! Cross-sectional orthogonalization of factor scores.
+
+use serde::{Deserialize, Serialize};
+
+/// How the engine orthogonalizes style scores after standardizing them.
+///
+/// Both methods act on an ordered list of factors and re-standardize each
+/// residual with the engine's [`Standardization`] mean weighting.
+///
+/// [`Standardization`]: crate::style::Standardization
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OrthogonalizationMethod {
+    /// Leave the scores as computed.
+    #[default]
+    None,
+    /// Sequential Gram-Schmidt: each factor loses its projection on the
+    /// factors before it in the order, so the first is unchanged and the
+    /// ordered scores end up mutually uncorrelated.
+    GramSchmidt,
+    /// Regression residualization: each listed factor is replaced by its
+    /// residual on the original scores of every other registered factor,
+    /// keeping only the part no other factor explains.
+    Residualize,
+}
+
+/// Residuals of an OLS regression of `y` on an intercept and `regressors`.
+///
+/// Uses modified Gram-Schmidt, so regressors that are collinear with earlier
+/// ones are skipped rather than making the fit singular.
+pub(crate) fn residualize(y: &[f64], regressors: &[Vec<f64>]) -> Vec<f64> {
+    let n = y.len();
+    let mut basis: Vec<Vec<f64>> = Vec::with_capacity(regressors.len() + 1);
+    for column in std::iter::once(vec![1.0; n]).chain(regressors.iter().cloned()) {
+        let mut v = column;
+        for q in &basis {
+            let projection = dot(&v, q);
+            v.iter_mut().zip(q).for_each(|(x, q)| *x -= projection * q);
+        }
+        let norm = dot(&v, &v).sqrt();
+        if norm > 1e-10 * (n as f64).sqrt() {
+            v.iter_mut().for_each(|x| *x /= norm);
+            basis.push(v);
+        }
+    }
+
+    let mut residual = y.to_vec();
+    for q in &basis {
+        let projection = dot(&residual, q);
+        residual
+            .iter_mut()
+            .zip(q)
+            .for_each(|(x, q)| *x -= projection * q);
+    }
+    residual
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_residuals_are_orthogonal() {
+        let x1 = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let x2 = vec![0.5, -1.0, 2.0, 0.0, 1.5, -0.5];
+        let y = vec![3.1, 4.9, 8.2, 9.0, 12.1, 12.8];
+
+        let residual = residualize(&y, &[x1.clone(), x2.clone(), x1.clone()]);
+        assert!(residual.iter().sum::<f64>().abs() < 1e-9);
+        assert!(dot(&residual, &x1).abs() < 1e-9);
+        assert!(dot(&residual, &x2).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,233 @@
+//! Residual volatility: volatility of market-model residuals.
+
+use crate::style::StyleFactor;
+use chrono::NaiveDate;
+use factors::Result as FactorResult;
+use perth_data::calendar::TRADING_DAYS_PER_YEAR;
+use polars::prelude::*;
+use std::collections::BTreeMap;
+
+/// Date of a symbol's last return and its paired (stock, market) returns.
+type ReturnHistory<'a> = (&'a str, Vec<(f64, f64)>);
+
+/// Residual volatility style factor.
+///
+/// For each symbol, the daily returns `r_t = P_t / P_{t-1} - 1` over the last
+/// `lookback` trading days up to the target date are regressed on the market
+/// return by OLS, `r_t = α + β m_t + ε_t`. The raw value is the annualized
+/// standard deviation of the residuals,
+/// `σ_ε = sqrt(252 · Σ ε_t² / (n - 2))`, for symbols with at least
+/// `min_periods` paired observations.
+///
+/// Residual volatility is mechanically correlated with beta and size, so by
+/// default its standardized scores are orthogonalized against the `beta` and
+/// `size` scores cross-sectionally (see [`StyleFactor::orthogonal_to`]),
+/// leaving the part of idiosyncratic risk those factors do not explain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResidualVolatility {
+    lookback: usize,
+    min_periods: usize,
+    orthogonal_to: Vec<String>,
+}
+
+impl Default for ResidualVolatility {
+    /// The beta factor's 126-day window with 40 minimum observations,
+    /// orthogonalized against beta and size.
+    fn default() -> Self {
+        Self::new(126, 40)
+    }
+}
+
+impl ResidualVolatility {
+    /// Create the factor with a regression window and the fewest
+    /// observations a symbol needs, orthogonalized against beta and size.
+    pub fn new(lookback: usize, min_periods: usize) -> Self {
+        Self {
+            lookback,
+            min_periods: min_periods.max(3),
+            orthogonal_to: vec!["beta".to_string(), "size".to_string()],
+        }
+    }
+
+    /// Set the factors the scores are orthogonalized against.
+    pub fn with_orthogonal_to<I, S>(mut self, factors: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.orthogonal_to = factors.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl StyleFactor for ResidualVolatility {
+    fn name(&self) -> &str {
+        "residual_volatility"
+    }
+
+    fn required_columns(&self) -> Vec<&str> {
+        vec!["close", "market_return"]
+    }
+
+    fn lookback(&self) -> usize {
+        self.lookback + 1
+    }
+
+    fn orthogonal_to(&self) -> Vec<&str> {
+        self.orthogonal_to.iter().map(String::as_str).collect()
+    }
+
+    fn compute_raw(&self, input: &LazyFrame, date: NaiveDate) -> FactorResult<DataFrame> {
+        let panel = input
+            .clone()
+            .filter(col("date").lt_eq(lit(date.to_string())))
+            .select([
+                col("symbol"),
+                col("date"),
+                col("close").cast(DataType::Float64),
+                col("market_return").cast(DataType::Float64),
+            ])
+            .sort(["symbol", "date"], SortMultipleOptions::default())
+            .collect()?;
+
+        let symbols = panel.column("symbol")?.str()?;
+        let dates = panel.column("date")?.str()?;
+        let closes = panel.column("close")?.f64()?;
+        let market = panel.column("market_return")?.f64()?;
+
+        // Paired (stock, market) returns per symbol, oldest first
+        let mut returns: BTreeMap<&str, ReturnHistory<'_>> = BTreeMap::new();
+        let mut previous: Option<(&str, f64)> = None;
+        for i in 0..panel.height() {
+            let (Some(symbol), Some(day)) = (symbols.get(i), dates.get(i)) else {
+                continue;
+            };
+            let close = closes.get(i).filter(|c| c.is_finite() && *c > 0.0);
+            if let (Some((last_symbol, last_close)), Some(close), Some(m)) =
+                (previous, close, market.get(i))
+                && last_symbol == symbol
+                && m.is_finite()
+            {
+                let entry = returns.entry(symbol).or_insert((day, Vec::new()));
+                entry.0 = day;
+                entry.1.push((close / last_close - 1.0, m));
+            }
+            previous = close
+                .map(|c| (symbol, c))
+                .or(previous.filter(|p| p.0 == symbol));
+        }
+
+        let mut out_symbols = Vec::new();
+        let mut out_dates = Vec::new();
+        let mut values = Vec::new();
+        for (symbol, (day, pairs)) in returns {
+            let window = &pairs[pairs.len().saturating_sub(self.lookback)..];
+            if let Some(vol) = residual_volatility(window, self.min_periods) {
+                out_symbols.push(symbol);
+                out_dates.push(day);
+                values.push(vol);
+            }
+        }
+
+        Ok(df! {
+            "symbol" => out_symbols,
+            "date" => out_dates,
+            "residual_volatility" => values,
+        }?)
+    }
+}
+
+/// Annualized residual standard deviation of an OLS fit of stock on market
+/// returns, or `None` with too few observations or a flat market.
+fn residual_volatility(pairs: &[(f64, f64)], min_periods: usize) -> Option<f64> {
+    let n = pairs.len();
+    if n < min_periods.max(3) {
+        return None;
+    }
+
+    let count = n as f64;
+    let mean_r = pairs.iter().map(|p| p.0).sum::<f64>() / count;
+    let mean_m = pairs.iter().map(|p| p.1).sum::<f64>() / count;
+    let (mut s_mm, mut s_rm) = (0.0, 0.0);
+    for (r, m) in pairs {
+        let dm = m - mean_m;
+        s_mm += dm * dm;
+        s_rm += (r - mean_r) * dm;
+    }
+    if s_mm <= f64::EPSILON {
+        return None;
+    }
+
+    let beta = s_rm / s_mm;
+    let ssr: f64 = pairs
+        .iter()
+        .map(|(r, m)| (r - mean_r - beta * (m - mean_m)).powi(2))
+        .sum();
+    Some((ssr / (count - 2.0) * f64::from(TRADING_DAYS_PER_YEAR)).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_residual_volatility_excludes_market_risk() {
+        let market: Vec<f64> = (0..60).map(|t| 0.01 * (t as f64 * 0.9).sin()).collect();
+        let noise: Vec<f64> = (0..60).map(|t| 0.002 * (t as f64 * 2.3).cos()).collect();
+
+        // Pure market exposure has no residual risk, whatever the beta
+        let levered: Vec<(f64, f64)> = market.iter().map(|m| (0.001 + 1.8 * m, *m)).collect();
+        assert!(residual_volatility(&levered, 20).unwrap() < 1e-6);
+
+        let noisy: Vec<(f64, f64)> = market
+            .iter()
+            .zip(&noise)
+            .map(|(m, e)| (1.2 * m + e, *m))
+            .collect();
+        let vol = residual_volatility(&noisy, 20).unwrap();
+        assert!(vol > 0.0 && vol < 0.002 * 252.0_f64.sqrt());
+
+        assert_eq!(residual_volatility(&noisy[..10], 20), None);
+    }
+
+    #[test]
+    fn test_compute_raw_uses_window() {
+        let dates: Vec<String> = (1..=31).map(|d| format!("2024-01-{d:02}")).collect();
+        let mut symbols = Vec::new();
+        let mut all_dates = Vec::new();
+        let mut closes = Vec::new();
+        let mut market = Vec::new();
+        for symbol in ["AAA", "BBB"] {
+            let mut close = 100.0;
+            for (t, day) in dates.iter().enumerate() {
+                let m = 0.01 * (t as f64 * 0.7).sin();
+                let idio = if symbol == "BBB" {
+                    0.02 * (t as f64 * 1.9).cos()
+                } else {
+                    0.0
+                };
+                close *= 1.0 + m + idio;
+                symbols.push(symbol);
+                all_dates.push(day.clone());
+                closes.push(close);
+                market.push(m);
+            }
+        }
+        let panel = df! {
+            "symbol" => symbols,
+            "date" => all_dates,
+            "close" => closes,
+            "market_return" => market,
+        }
+        .unwrap();
+
+        let factor = ResidualVolatility::new(20, 10);
+        let target = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let raw = factor.compute_raw(&panel.lazy(), target).unwrap();
+        let values = raw.column("residual_volatility").unwrap().f64().unwrap();
+        assert_eq!(raw.height(), 2);
+        assert!(values.get(0).unwrap() < 1e-6);
+        assert!(values.get(1).unwrap() > 0.1);
+        assert_eq!(factor.orthogonal_to(), ["beta", "size"]);
+    }
+}
@@ -0,0 +1,396 @@
+//! Robust cross-sectional standardization and imputation of exposures.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Mean that exposures are centered on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MeanWeighting {
+    /// Equal-weighted mean.
+    Equal,
+    /// Market-cap-weighted mean, so the market portfolio has zero exposure.
+    #[default]
+    CapWeighted,
+}
+
+/// How missing exposures are filled in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Imputation {
+    /// Drop symbols missing any exposure.
+    None,
+    /// The median exposure of the symbol's sector, or of the whole cross
+    /// section when the sector has none.
+    #[default]
+    SectorMedian,
+    /// The fitted value of an OLS regression of the factor on the other
+    /// factors over the symbols with every exposure. Other exposures the
+    /// symbol lacks enter the fit at their sector median.
+    Regression,
+}
+
+/// Cross-sectional standardization of raw factor values.
+///
+/// Raw values are winsorized iteratively: values beyond `winsorize_sigma`
+/// standard deviations of the mean are clipped to that bound, and the
+/// bounds recomputed, until nothing is clipped or `max_iterations` passes
+/// have run. The winsorized values are centered on the [`MeanWeighting`]
+/// mean and divided by their equal-weighted standard deviation.
+///
+/// ```toml
+/// [factors.standardization]
+/// mean = "cap-weighted"        # equal or cap-weighted
+/// winsorize_sigma = 3.0
+/// max_iterations = 10
+/// imputation = "sector-median" # none, sector-median or regression
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Standardization {
+    /// Mean exposures are centered on.
+    pub mean: MeanWeighting,
+    /// Winsorization bound in standard deviations; 0 disables it.
+    pub winsorize_sigma: f64,
+    /// Most winsorization passes.
+    pub max_iterations: usize,
+    /// Treatment of missing exposures.
+    pub imputation: Imputation,
+}
+
+impl Default for Standardization {
+    /// Cap-weighted mean, ±3σ winsorization over up to 10 passes, and
+    /// sector-median imputation.
+    fn default() -> Self {
+        Self {
+            mean: MeanWeighting::CapWeighted,
+            winsorize_sigma: 3.0,
+            max_iterations: 10,
+            imputation: Imputation::SectorMedian,
+        }
+    }
+}
+
+impl Standardization {
+    /// The plain z-score: equal-weighted mean and standard deviation, no
+    /// winsorization, and symbols missing any exposure dropped.
+    pub const fn equal_weighted() -> Self {
+        Self {
+            mean: MeanWeighting::Equal,
+            winsorize_sigma: 0.0,
+            max_iterations: 0,
+            imputation: Imputation::None,
+        }
+    }
+
+    /// Winsorize and standardize the present values in place.
+    ///
+    /// `weights` are market caps aligned with `values`; symbols without a
+    /// positive one do not count toward a cap-weighted mean.
+    pub(crate) fn standardize(&self, values: &mut [Option<f64>], weights: &[Option<f64>]) {
+        if self.winsorize_sigma > 0.0 {
+            for _ in 0..self.max_iterations {
+                let present: Vec<f64> = values.iter().flatten().copied().collect();
+                let Some((mean, std)) = moments(&present) else {
+                    break;
+                };
+                let (low, high) = (
+                    mean - self.winsorize_sigma * std,
+                    mean + self.winsorize_sigma * std,
+                );
+                let mut clipped = false;
+                for value in values.iter_mut().flatten() {
+                    if *value < low || *value > high {
+                        *value = value.clamp(low, high);
+                        clipped = true;
+                    }
+                }
+                if !clipped {
+                    break;
+                }
+            }
+        }
+
+        let present: Vec<f64> = values.iter().flatten().copied().collect();
+        let Some((equal_mean, std)) = moments(&present) else {
+            return;
+        };
+        let mean = match self.mean {
+            MeanWeighting::Equal => equal_mean,
+            MeanWeighting::CapWeighted => weighted_mean(values, weights).unwrap_or(equal_mean),
+        };
+        let std = if std > 1e-10 { std } else { 1.0 };
+        for value in values.iter_mut().flatten() {
+            *value = (*value - mean) / std;
+        }
+    }
+
+    /// Rescale complete scores, such as orthogonalization residuals, without
+    /// winsorizing them.
+    pub(crate) fn rescale(&self, values: &mut [f64], weights: &[Option<f64>]) {
+        let mut optional: Vec<Option<f64>> = values.iter().copied().map(Some).collect();
+        Self {
+            winsorize_sigma: 0.0,
+            ..self.clone()
+        }
+        .standardize(&mut optional, weights);
+        for (value, scaled) in values.iter_mut().zip(optional) {
+            *value = scaled.unwrap_or(*value);
+        }
+    }
+
+    /// Fill the missing entries of `exposures`, one column per factor.
+    ///
+    /// Returns `None` for rows that stay incomplete, which only happens
+    /// under [`Imputation::None`].
+    pub(crate) fn impute(
+        &self,
+        exposures: &[Vec<Option<f64>>],
+        sectors: &[Option<String>],
+    ) -> Vec<Option<Vec<f64>>> {
+        let n = sectors.len();
+        if self.imputation == Imputation::None {
+            return (0..n)
+                .map(|i| exposures.iter().map(|column| column[i]).collect())
+                .collect();
+        }
+
+        let medians: Vec<Vec<f64>> = exposures
+            .iter()
+            .map(|column| sector_medians(column, sectors))
+            .collect();
+        let filled = |k: usize, i: usize| exposures[k][i].unwrap_or(medians[k][i]);
+
+        let mut rows: Vec<Vec<f64>> = (0..n)
+            .map(|i| (0..exposures.len()).map(|k| filled(k, i)).collect())
+            .collect();
+        if self.imputation == Imputation::Regression {
+            let complete: Vec<usize> = (0..n)
+                .filter(|&i| exposures.iter().all(|column| column[i].is_some()))
+                .collect();
+            for (k, column) in exposures.iter().enumerate() {
+                if column.iter().all(Option::is_some) {
+                    continue;
+                }
+                let others: Vec<usize> = (0..exposures.len()).filter(|&j| j != k).collect();
+                let design: Vec<Vec<f64>> = complete
+                    .iter()
+                    .map(|&i| others.iter().map(|&j| filled(j, i)).collect())
+                    .collect();
+                let target: Vec<f64> = complete.iter().map(|&i| filled(k, i)).collect();
+                let Some(beta) = least_squares(&design, &target) else {
+                    continue;
+                };
+                for (i, row) in rows.iter_mut().enumerate() {
+                    if column[i].is_none() {
+                        row[k] = beta[0]
+                            + others
+                                .iter()
+                                .zip(&beta[1..])
+                                .map(|(&j, b)| b * filled(j, i))
+                                .sum::<f64>();
+                    }
+                }
+            }
+        }
+        rows.into_iter().map(Some).collect()
+    }
+}
+
+/// Equal-weighted mean and sample standard deviation, if there are at least
+/// two values.
+fn moments(values: &[f64]) -> Option<(f64, f64)> {
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Some((mean, variance.sqrt()))
+}
+
+/// Mean of the present values weighted by their positive weights.
+fn weighted_mean(values: &[Option<f64>], weights: &[Option<f64>]) -> Option<f64> {
+    let (total, weight) = values
+        .iter()
+        .zip(weights)
+        .filter_map(|(value, weight)| Some((value.as_ref()?, weight.filter(|w| *w > 0.0)?)))
+        .fold((0.0, 0.0), |(total, sum), (value, weight)| {
+            (total + value * weight, sum + weight)
+        });
+    (weight > 0.0).then(|| total / weight)
+}
+
+/// Each row's sector median of the present values, falling back to the
+/// cross-sectional median and then to zero.
+fn sector_medians(values: &[Option<f64>], sectors: &[Option<String>]) -> Vec<f64> {
+    let mut by_sector: HashMap<&str, Vec<f64>> = HashMap::new();
+    for (value, sector) in values.iter().zip(sectors) {
+        if let (Some(value), Some(sector)) = (value, sector) {
+            by_sector.entry(sector).or_default().push(*value);
+        }
+    }
+    let overall = median(values.iter().flatten().copied().collect()).unwrap_or(0.0);
+    let medians: HashMap<&str, f64> = by_sector
+        .into_iter()
+        .filter_map(|(sector, values)| Some((sector, median(values)?)))
+        .collect();
+    sectors
+        .iter()
+        .map(|sector| {
+            sector
+                .as_deref()
+                .and_then(|s| medians.get(s).copied())
+                .unwrap_or(overall)
+        })
+        .collect()
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+/// OLS coefficients of `y` on an intercept and the columns of `x` (one row
+/// per observation), intercept first. `None` if the normal equations are
+/// singular or there are too few observations.
+fn least_squares(x: &[Vec<f64>], y: &[f64]) -> Option<Vec<f64>> {
+    let k = x.first().map_or(0, Vec::len) + 1;
+    if y.len() <= k {
+        return None;
+    }
+
+    // Normal equations [XᵀX | Xᵀy], with a leading column of ones
+    let mut system = vec![vec![0.0; k + 1]; k];
+    for (row, target) in x.iter().zip(y) {
+        let row: Vec<f64> = std::iter::once(1.0).chain(row.iter().copied()).collect();
+        for i in 0..k {
+            for j in 0..k {
+                system[i][j] += row[i] * row[j];
+            }
+            system[i][k] += row[i] * target;
+        }
+    }
+
+    // Gaussian elimination with partial pivoting
+    for col in 0..k {
+        let pivot =
+            (col..k).max_by(|&a, &b| system[a][col].abs().total_cmp(&system[b][col].abs()))?;
+        if system[pivot][col].abs() < 1e-10 {
+            return None;
+        }
+        system.swap(col, pivot);
+        let (upper, lower) = system.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for row in lower {
+            let factor = row[col] / pivot_row[col];
+            row.iter_mut()
+                .zip(pivot_row)
+                .skip(col)
+                .for_each(|(x, p)| *x -= factor * p);
+        }
+    }
+    let mut beta = vec![0.0; k];
+    for i in (0..k).rev() {
+        let known: f64 = ((i + 1)..k).map(|j| system[i][j] * beta[j]).sum();
+        beta[i] = (system[i][k] - known) / system[i][i];
+    }
+    Some(beta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cap_weighted_standardization() {
+        let mut values = vec![Some(1.0), Some(2.0), Some(3.0), None, Some(4.0)];
+        let weights = vec![Some(10.0), Some(1.0), Some(1.0), Some(5.0), None];
+        Standardization::default().standardize(&mut values, &weights);
+
+        // Zero cap-weighted mean over the symbols with both
+        let cap_mean: f64 = values
+            .iter()
+            .zip(&weights)
+            .filter_map(|(v, w)| Some(v.as_ref()? * w.as_ref()?))
+            .sum::<f64>()
+            / 12.0;
+        assert!(cap_mean.abs() < 1e-12);
+        assert_eq!(values[3], None);
+
+        // Unit equal-weighted standard deviation
+        let present: Vec<f64> = values.iter().flatten().copied().collect();
+        let (_, std) = moments(&present).unwrap();
+        assert!((std - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_iterative_winsorization() {
+        let mut values: Vec<Option<f64>> = (0..50).map(|i| Some(f64::from(i % 5))).collect();
+        values.push(Some(1000.0));
+        let weights = vec![None; values.len()];
+        let config = Standardization {
+            mean: MeanWeighting::Equal,
+            ..Standardization::default()
+        };
+        config.standardize(&mut values, &weights);
+
+        let present: Vec<f64> = values.iter().flatten().copied().collect();
+        let max = present.iter().copied().fold(f64::MIN, f64::max);
+        assert!(max < 4.0, "outlier still at {max}");
+        let (mean, std) = moments(&present).unwrap();
+        assert!(mean.abs() < 1e-12 && (std - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_imputation() {
+        let sectors: Vec<Option<String>> = ["Tech", "Tech", "Tech", "Energy", "Energy", "Tech"]
+            .iter()
+            .map(|s| Some(s.to_string()))
+            .chain([None])
+            .collect();
+        let size = vec![
+            Some(1.0),
+            Some(2.0),
+            Some(3.0),
+            Some(-1.0),
+            Some(-2.0),
+            None,
+            None,
+        ];
+        let value = vec![
+            Some(2.0),
+            Some(4.0),
+            Some(6.0),
+            Some(-2.0),
+            Some(-4.0),
+            Some(5.0),
+            Some(0.0),
+        ];
+        let exposures = vec![size, value];
+
+        let dropped = Standardization::equal_weighted().impute(&exposures, &sectors);
+        assert_eq!(dropped.iter().flatten().count(), 5);
+
+        let medians = Standardization::default().impute(&exposures, &sectors);
+        assert_eq!(medians[5].as_ref().unwrap()[0], 2.0);
+        assert_eq!(medians[6].as_ref().unwrap()[0], 1.0);
+
+        // value = 2 · size exactly, so the fit recovers size = value / 2
+        let regression = Standardization {
+            imputation: Imputation::Regression,
+            ..Standardization::default()
+        }
+        .impute(&exposures, &sectors);
+        assert!((regression[5].as_ref().unwrap()[0] - 2.5).abs() < 1e-9);
+        assert!(regression[6].as_ref().unwrap()[0].abs() < 1e-9);
+    }
+}
@@ -0,0 +1,312 @@
+//! Country of listing.
+//!
+//! A [`Country`] carries what the pipeline needs to put a non-US listing on
+//! the same footing as a US one: its quote currency, the scale of its quoted
+//! prices and a market benchmark. Benchmarks are the US-listed iShares MSCI
+//! country ETFs, so every benchmark return is already in US dollars.
+//!
+//! Symbols without an explicit country are placed by their Yahoo Finance
+//! exchange suffix (`7203.T` is Japanese, `SAP.DE` German); everything else
+//! is treated as a US listing.
+
+use crate::universe::error::UniverseError;
+use crate::universe::gics::normalize;
+use perth_data::fx::Currency;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Countries of listing.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub enum Country {
+    /// United States
+    #[default]
+    UnitedStates,
+    /// Canada
+    Canada,
+    /// United Kingdom
+    UnitedKingdom,
+    /// Ireland
+    Ireland,
+    /// Germany
+    Germany,
+    /// France
+    France,
+    /// Netherlands
+    Netherlands,
+    /// Belgium
+    Belgium,
+    /// Italy
+    Italy,
+    /// Spain
+    Spain,
+    /// Finland
+    Finland,
+    /// Switzerland
+    Switzerland,
+    /// Sweden
+    Sweden,
+    /// Denmark
+    Denmark,
+    /// Norway
+    Norway,
+    /// Japan
+    Japan,
+    /// Hong Kong
+    HongKong,
+    /// Australia
+    Australia,
+}
+
+impl Country {
+    /// Returns all supported countries.
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::UnitedStates,
+            Self::Canada,
+            Self::UnitedKingdom,
+            Self::Ireland,
+            Self::Germany,
+            Self::France,
+            Self::Netherlands,
+            Self::Belgium,
+            Self::Italy,
+            Self::Spain,
+            Self::Finland,
+            Self::Switzerland,
+            Self::Sweden,
+            Self::Denmark,
+            Self::Norway,
+            Self::Japan,
+            Self::HongKong,
+            Self::Australia,
+        ]
+    }
+
+    /// ISO 3166-1 alpha-2 code.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::UnitedStates => "US",
+            Self::Canada => "CA",
+            Self::UnitedKingdom => "GB",
+            Self::Ireland => "IE",
+            Self::Germany => "DE",
+            Self::France => "FR",
+            Self::Netherlands => "NL",
+            Self::Belgium => "BE",
+            Self::Italy => "IT",
+            Self::Spain => "ES",
+            Self::Finland => "FI",
+            Self::Switzerland => "CH",
+            Self::Sweden => "SE",
+            Self::Denmark => "DK",
+            Self::Norway => "NO",
+            Self::Japan => "JP",
+            Self::HongKong => "HK",
+            Self::Australia => "AU",
+        }
+    }
+
+    /// Returns the full country name.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::UnitedStates => "United States",
+            Self::Canada => "Canada",
+            Self::UnitedKingdom => "United Kingdom",
+            Self::Ireland => "Ireland",
+            Self::Germany => "Germany",
+            Self::France => "France",
+            Self::Netherlands => "Netherlands",
+            Self::Belgium => "Belgium",
+            Self::Italy => "Italy",
+            Self::Spain => "Spain",
+            Self::Finland => "Finland",
+            Self::Switzerland => "Switzerland",
+            Self::Sweden => "Sweden",
+            Self::Denmark => "Denmark",
+            Self::Norway => "Norway",
+            Self::Japan => "Japan",
+            Self::HongKong => "Hong Kong",
+            Self::Australia => "Australia",
+        }
+    }
+
+    /// Currency local prices are quoted in.
+    pub const fn currency(&self) -> Currency {
+        match self {
+            Self::UnitedStates => Currency::Usd,
+            Self::Canada => Currency::Cad,
+            Self::UnitedKingdom => Currency::Gbp,
+            Self::Ireland
+            | Self::Germany
+            | Self::France
+            | Self::Netherlands
+            | Self::Belgium
+            | Self::Italy
+            | Self::Spain
+            | Self::Finland => Currency::Eur,
+            Self::Switzerland => Currency::Chf,
+            Self::Sweden => Currency::Sek,
+            Self::Denmark => Currency::Dkk,
+            Self::Norway => Currency::Nok,
+            Self::Japan => Currency::Jpy,
+            Self::HongKong => Currency::Hkd,
+            Self::Australia => Currency::Aud,
+        }
+    }
+
+    /// Currency units per quoted price unit.
+    ///
+    /// London quotes are in pence, so their prices are scaled by 0.01.
+    pub const fn price_scale(&self) -> f64 {
+        match self {
+            Self::UnitedKingdom => 0.01,
+            _ => 1.0,
+        }
+    }
+
+    /// Symbol of the USD-priced benchmark for the country's market.
+    pub const fn benchmark(&self) -> &'static str {
+        match self {
+            Self::UnitedStates => "SPY",
+            Self::Canada => "EWC",
+            Self::UnitedKingdom => "EWU",
+            Self::Ireland => "EIRL",
+            Self::Germany => "EWG",
+            Self::France => "EWQ",
+            Self::Netherlands => "EWN",
+            Self::Belgium => "EWK",
+            Self::Italy => "EWI",
+            Self::Spain => "EWP",
+            Self::Finland => "EFNL",
+            Self::Switzerland => "EWL",
+            Self::Sweden => "EWD",
+            Self::Denmark => "EDEN",
+            Self::Norway => "ENOR",
+            Self::Japan => "EWJ",
+            Self::HongKong => "EWH",
+            Self::Australia => "EWA",
+        }
+    }
+
+    /// Yahoo Finance exchange suffixes of the country's listings.
+    pub const fn suffixes(&self) -> &'static [&'static str] {
+        match self {
+            Self::UnitedStates => &[],
+            Self::Canada => &["TO", "V", "NE"],
+            Self::UnitedKingdom => &["L"],
+            Self::Ireland => &["IR"],
+            Self::Germany => &["DE", "F"],
+            Self::France => &["PA"],
+            Self::Netherlands => &["AS"],
+            Self::Belgium => &["BR"],
+            Self::Italy => &["MI"],
+            Self::Spain => &["MC"],
+            Self::Finland => &["HE"],
+            Self::Switzerland => &["SW"],
+            Self::Sweden => &["ST"],
+            Self::Denmark => &["CO"],
+            Self::Norway => &["OL"],
+            Self::Japan => &["T"],
+            Self::HongKong => &["HK"],
+            Self::Australia => &["AX"],
+        }
+    }
+
+    /// Country of a symbol from its exchange suffix, defaulting to the US.
+    ///
+    /// US share classes such as `BRK.B` have no known suffix and stay US.
+    pub fn from_symbol(symbol: &str) -> Self {
+        symbol
+            .rsplit_once('.')
+            .and_then(|(_, suffix)| {
+                Self::all()
+                    .into_iter()
+                    .find(|c| c.suffixes().iter().any(|s| s.eq_ignore_ascii_case(suffix)))
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Country {
+    type Err = UniverseError;
+
+    /// Parse a country from its ISO code, name or a common alias.
+    ///
+    /// Matching ignores case, spaces, underscores and hyphens, so `"JP"`,
+    /// `"japan"` and `"Hong Kong"` all parse.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = normalize(s);
+        let alias = match normalized.as_str() {
+            "usa" | "unitedstatesofamerica" => Some(Self::UnitedStates),
+            "uk" | "britain" | "greatbritain" => Some(Self::UnitedKingdom),
+            _ => None,
+        };
+
+        alias
+            .or_else(|| {
+                Self::all().into_iter().find(|c| {
+                    normalize(c.code()) == normalized || normalize(c.name()) == normalized
+                })
+            })
+            .ok_or_else(|| UniverseError::UnknownCountry(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for Country {
+    type Error = UniverseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Country> for String {
+    fn from(country: Country) -> Self {
+        country.code().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let parse = |s: &str| s.parse::<Country>().ok();
+
+        assert_eq!(parse("JP"), Some(Country::Japan));
+        assert_eq!(parse("hong kong"), Some(Country::HongKong));
+        assert_eq!(parse("UK"), Some(Country::UnitedKingdom));
+        assert_eq!(parse("gb"), Some(Country::UnitedKingdom));
+        assert_eq!(parse("Atlantis"), None);
+    }
+
+    #[test]
+    fn test_from_symbol() {
+        assert_eq!(Country::from_symbol("AAPL"), Country::UnitedStates);
+        assert_eq!(Country::from_symbol("BRK.B"), Country::UnitedStates);
+        assert_eq!(Country::from_symbol("7203.T"), Country::Japan);
+        assert_eq!(Country::from_symbol("SAP.DE"), Country::Germany);
+        assert_eq!(Country::from_symbol("VOD.L"), Country::UnitedKingdom);
+        assert_eq!(Country::from_symbol("SHOP.TO"), Country::Canada);
+    }
+
+    #[test]
+    fn test_currency_and_benchmark() {
+        assert_eq!(Country::Germany.currency(), Currency::Eur);
+        assert_eq!(Country::Japan.currency(), Currency::Jpy);
+        assert_eq!(Country::UnitedStates.benchmark(), "SPY");
+        assert_eq!(Country::Japan.benchmark(), "EWJ");
+        assert_eq!(Country::UnitedKingdom.price_scale(), 0.01);
+    }
+}
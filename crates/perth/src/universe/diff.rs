@@ -0,0 +1,317 @@
+//! Universe changes.
+//!
+//! A [`UniverseSnapshot`] freezes a universe's members with their sectors and
+//! weights, either as the universe lists them on a date or as a membership
+//! history records them. Diffing two snapshots gives the additions, deletions,
+//! sector reclassifications and sector weight shifts between them, and
+//! [`history_diffs`] does so at every date the membership changed.
+//!
+//! Universes without benchmark weights are treated as equal-weighted, so
+//! their sector weights are the share of members in each sector.
+
+use crate::universe::{MembershipHistory, Universe};
+use chrono::NaiveDate;
+use perth_data::calendar::{ExchangeCalendar, NyseCalendar};
+use perth_output::{
+    ConstituentChange, SectorReclassification, SectorWeightChange, UniverseDiffExport,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A member's sector label and weight within a snapshot.
+#[derive(Debug, Clone, PartialEq)]
+struct Member {
+    sector: Option<String>,
+    weight: f64,
+}
+
+/// A universe's members, sectors and weights at one point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniverseSnapshot {
+    name: String,
+    members: BTreeMap<String, Member>,
+}
+
+impl UniverseSnapshot {
+    /// Snapshot the constituents of a universe on `date`.
+    ///
+    /// Universes without a membership history return every constituent.
+    pub fn of(universe: &dyn Universe, date: NaiveDate) -> Self {
+        let members = universe
+            .symbols_on(date)
+            .into_iter()
+            .map(|symbol| {
+                let sector = universe.sector(&symbol).map(|s| s.name().to_string());
+                (symbol, sector)
+            })
+            .collect();
+
+        Self::weighted(universe.name().to_string(), members, universe)
+    }
+
+    /// Snapshot the members recorded in `history` on `date`.
+    ///
+    /// Sectors come from the spell covering `date`, so reclassifications
+    /// recorded in the history show up, falling back to the universe's
+    /// current sector for spells without a label.
+    pub fn on(universe: &dyn Universe, history: &MembershipHistory, date: NaiveDate) -> Self {
+        let members = history
+            .symbols_on(date)
+            .into_iter()
+            .map(|symbol| {
+                let sector = history
+                    .spells(&symbol)
+                    .iter()
+                    .find(|m| m.contains(date))
+                    .and_then(|m| m.sector.clone())
+                    .or_else(|| universe.sector(&symbol).map(|s| s.name().to_string()));
+                (symbol, sector)
+            })
+            .collect();
+
+        Self::weighted(format!("{} {}", universe.name(), date), members, universe)
+    }
+
+    /// Attach normalized weights, equal unless every member is weighted.
+    fn weighted(
+        name: String,
+        sectors: BTreeMap<String, Option<String>>,
+        universe: &dyn Universe,
+    ) -> Self {
+        let weights: Option<Vec<f64>> = sectors.keys().map(|s| universe.weight(s)).collect();
+        let weights = weights
+            .filter(|w| w.iter().sum::<f64>() > 0.0)
+            .unwrap_or_else(|| vec![1.0; sectors.len()]);
+        let total: f64 = weights.iter().sum();
+
+        let members = sectors
+            .into_iter()
+            .zip(weights)
+            .map(|((symbol, sector), weight)| {
+                (
+                    symbol,
+                    Member {
+                        sector,
+                        weight: weight / total,
+                    },
+                )
+            })
+            .collect();
+
+        Self { name, members }
+    }
+
+    /// Display name of the snapshot.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of members.
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Member symbols, sorted.
+    pub fn symbols(&self) -> Vec<String> {
+        self.members.keys().cloned().collect()
+    }
+
+    /// Weight of each sector, with unclassified members under "Unknown".
+    pub fn sector_weights(&self) -> BTreeMap<String, f64> {
+        let mut weights = BTreeMap::new();
+        for member in self.members.values() {
+            let sector = member.sector.as_deref().unwrap_or("Unknown");
+            *weights.entry(sector.to_string()).or_insert(0.0) += member.weight;
+        }
+        weights
+    }
+
+    /// Changes from this snapshot to `other`.
+    pub fn diff(&self, other: &Self) -> UniverseDiffExport {
+        let change = |(symbol, member): (&String, &Member)| {
+            ConstituentChange::new(symbol.clone(), member.sector.clone(), member.weight)
+        };
+
+        let additions = other
+            .members
+            .iter()
+            .filter(|(symbol, _)| !self.members.contains_key(*symbol))
+            .map(change)
+            .collect();
+        let deletions = self
+            .members
+            .iter()
+            .filter(|(symbol, _)| !other.members.contains_key(*symbol))
+            .map(change)
+            .collect();
+        let reclassifications = self
+            .members
+            .iter()
+            .filter_map(|(symbol, before)| {
+                let after = other.members.get(symbol)?;
+                (before.sector != after.sector).then(|| {
+                    SectorReclassification::new(
+                        symbol.clone(),
+                        before.sector.clone(),
+                        after.sector.clone(),
+                    )
+                })
+            })
+            .collect();
+
+        let from_weights = self.sector_weights();
+        let to_weights = other.sector_weights();
+        let sectors: BTreeSet<&String> = from_weights.keys().chain(to_weights.keys()).collect();
+        let sector_weights = sectors
+            .into_iter()
+            .map(|sector| {
+                SectorWeightChange::new(
+                    sector.clone(),
+                    from_weights.get(sector).copied().unwrap_or(0.0),
+                    to_weights.get(sector).copied().unwrap_or(0.0),
+                )
+            })
+            .collect();
+
+        UniverseDiffExport {
+            from: self.name.clone(),
+            to: other.name.clone(),
+            date: None,
+            from_size: self.size(),
+            to_size: other.size(),
+            additions,
+            deletions,
+            reclassifications,
+            sector_weights,
+        }
+    }
+}
+
+/// Dates on which a membership history changes, sorted.
+///
+/// A spell's start is the first day of membership; a removal takes effect on
+/// the first trading day after the spell's last.
+pub fn change_dates(history: &MembershipHistory) -> Vec<NaiveDate> {
+    let calendar = NyseCalendar;
+    let dates: BTreeSet<NaiveDate> = history
+        .memberships()
+        .flat_map(|m| [m.start, m.end.map(|end| calendar.next_trading_day(end))])
+        .flatten()
+        .collect();
+    dates.into_iter().collect()
+}
+
+/// The changes at each date the membership history changed.
+///
+/// Each date is compared with the trading day before it. Dates outside
+/// `since..=until` are skipped, as are dates on which nothing visible changed
+/// (a spell split without a new sector label, say).
+pub fn history_diffs(
+    universe: &dyn Universe,
+    history: &MembershipHistory,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+) -> Vec<UniverseDiffExport> {
+    let calendar = NyseCalendar;
+    change_dates(history)
+        .into_iter()
+        .filter(|date| since.is_none_or(|s| s <= *date) && until.is_none_or(|u| *date <= u))
+        .filter_map(|date| {
+            let previous = calendar.previous_trading_day(date);
+            let before = UniverseSnapshot::on(universe, history, previous);
+            let after = UniverseSnapshot::on(universe, history, date);
+            let diff = UniverseDiffExport {
+                date: Some(date),
+                ..before.diff(&after)
+            };
+            (!diff.is_empty()).then_some(diff)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universe::{FileUniverse, UniverseFormat};
+    use perth_data::cache::UniverseMembership;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn universe(contents: &str, name: &str) -> FileUniverse {
+        FileUniverse::parse(contents, UniverseFormat::Csv, name).unwrap()
+    }
+
+    #[test]
+    fn test_diff() {
+        let today = date(2024, 6, 3);
+        let old = universe(
+            "symbol,sector,weight\nAAPL,Information Technology,0.5\nGOOGL,Information Technology,0.3\nXOM,Energy,0.2\n",
+            "old",
+        );
+        let new = universe(
+            "symbol,sector,weight\nAAPL,Information Technology,0.5\nGOOGL,Communication Services,0.3\nNVDA,Information Technology,0.2\n",
+            "new",
+        );
+
+        let diff = UniverseSnapshot::of(&old, today).diff(&UniverseSnapshot::of(&new, today));
+        assert_eq!((diff.from.as_str(), diff.to.as_str()), ("old", "new"));
+        assert_eq!(diff.additions.len(), 1);
+        assert_eq!(diff.additions[0].symbol, "NVDA");
+        assert_eq!(diff.deletions[0].symbol, "XOM");
+        assert_eq!(diff.deletions[0].weight, 0.2);
+        assert_eq!(diff.reclassifications.len(), 1);
+        assert_eq!(
+            diff.reclassifications[0].to_sector.as_deref(),
+            Some("Communication Services")
+        );
+
+        let weight = |sector: &str| {
+            diff.sector_weights
+                .iter()
+                .find(|w| w.sector == sector)
+                .unwrap()
+                .clone()
+        };
+        assert!((weight("Information Technology").change() + 0.1).abs() < 1e-12);
+        assert!((weight("Communication Services").to_weight - 0.3).abs() < 1e-12);
+        assert_eq!(weight("Energy").to_weight, 0.0);
+    }
+
+    #[test]
+    fn test_equal_weights_without_weight_column() {
+        let members = universe(
+            "symbol,sector\nAAPL,Information Technology\nMSFT,Information Technology\nXOM,Energy\nCVX,Energy\n",
+            "members",
+        );
+
+        let snapshot = UniverseSnapshot::of(&members, date(2024, 6, 3));
+        assert_eq!(snapshot.size(), 4);
+        assert_eq!(snapshot.sector_weights()["Energy"], 0.5);
+        assert!(snapshot.diff(&snapshot).is_empty());
+    }
+
+    #[test]
+    fn test_history_diffs() {
+        let members = universe(
+            "symbol,sector\nAAPL,Information Technology\nXOM,Energy\nNVDA,Information Technology\n",
+            "members",
+        );
+        let mut xom = UniverseMembership::new("XOM", None);
+        xom.end = Some(date(2024, 3, 15));
+        let history = MembershipHistory::new([
+            UniverseMembership::new("AAPL", None),
+            xom,
+            UniverseMembership::new("NVDA", Some(date(2024, 3, 18))),
+        ]);
+
+        // XOM's last day is a Friday, so both changes land on Monday
+        let diffs = history_diffs(&members, &history, None, None);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].date, Some(date(2024, 3, 18)));
+        assert_eq!(diffs[0].additions[0].symbol, "NVDA");
+        assert_eq!(diffs[0].deletions[0].symbol, "XOM");
+
+        assert!(history_diffs(&members, &history, Some(date(2024, 4, 1)), None).is_empty());
+    }
+}
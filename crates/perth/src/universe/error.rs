@@ -0,0 +1,68 @@
+//! Error types for universe definitions.
+
+use thiserror::Error;
+
+/// Errors that can occur while loading or building a universe.
+#[derive(Debug, Error)]
+pub enum UniverseError {
+    /// IO error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// CSV parsing error.
+    #[error("CSV parsing error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// JSON parsing error.
+    #[error("JSON parsing error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// TOML parsing error.
+    #[error("TOML parsing error: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    /// File extension is not a supported universe format.
+    #[error("Unsupported universe format: {0} (expected .csv, .json or .toml)")]
+    UnsupportedFormat(String),
+
+    /// Cached data could not be read.
+    #[error("Data error: {0}")]
+    Data(#[from] perth_data::error::DataError),
+
+    /// DataFrame operation failed.
+    #[error("Polars error: {0}")]
+    Polars(#[from] polars::prelude::PolarsError),
+
+    /// Universe rules are inconsistent.
+    #[error("Invalid universe rules: {0}")]
+    InvalidRules(String),
+
+    /// ETF holdings file could not be read.
+    #[error("Invalid holdings file: {0}")]
+    InvalidHoldings(String),
+
+    /// Sector name or code could not be parsed.
+    #[error("Unknown sector: {0}")]
+    UnknownSector(String),
+
+    /// GICS code, name or level could not be parsed.
+    #[error("Unknown GICS code: {0}")]
+    UnknownGicsCode(String),
+
+    /// Country name or code could not be parsed.
+    #[error("Unknown country: {0}")]
+    UnknownCountry(String),
+
+    /// Invalid constituent definition.
+    #[error("Invalid constituent {symbol}: {reason}")]
+    InvalidConstituent {
+        /// Symbol of the offending row
+        symbol: String,
+        /// What is wrong with it
+        reason: String,
+    },
+
+    /// The universe has no constituents.
+    #[error("Universe has no constituents")]
+    Empty,
+}
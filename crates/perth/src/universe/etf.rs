@@ -0,0 +1,437 @@
+//! ETF holdings files.
+//!
+//! Issuers publish fund holdings as CSV files with a few lines of fund
+//! details above the holdings table and a disclaimer below it. The column
+//! names differ between issuers (`Ticker` or `Symbol`, `Weight (%)` or
+//! `% of Net Assets`), so [`EtfHoldings`] finds the table by its header row
+//! and recognises the common spellings of each column.
+//!
+//! Cash, futures and other non-equity lines are dropped and the remaining
+//! weights normalized to sum to one. The holdings can then be used as a
+//! weighted [`FileUniverse`] or as a benchmark [`PortfolioExport`].
+//!
+//! Holdings files do not always carry a GICS sector, and some issuers use
+//! their own labels, so sectors that do not parse are taken from a base
+//! universe such as the S&P 500. Share-class separators (`BRK/B`, `BRK B`)
+//! are mapped to a dot to match the base universe's tickers.
+
+use crate::universe::Universe;
+use crate::universe::error::UniverseError;
+use crate::universe::file::FileUniverse;
+use crate::universe::gics::{GicsSector, normalize};
+use crate::universe::sp500::Constituent;
+use chrono::NaiveDate;
+use perth_output::{PortfolioExport, PortfolioHolding};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Header spellings of each holdings column, after normalization.
+const SYMBOL_COLUMNS: &[&str] = &["ticker", "symbol", "tickersymbol"];
+const NAME_COLUMNS: &[&str] = &["name", "securityname", "holding", "holdings", "description"];
+const SECTOR_COLUMNS: &[&str] = &["sector", "gicssector"];
+const WEIGHT_COLUMNS: &[&str] = &[
+    "weight",
+    "weighting",
+    "portfolioweight",
+    "ofnetassets",
+    "offund",
+    "percentofnetassets",
+    "percentoffund",
+];
+const SHARES_COLUMNS: &[&str] = &["shares", "sharesheld", "quantity", "position"];
+const MARKET_VALUE_COLUMNS: &[&str] = &["marketvalue", "marketvaluebase", "notionalvalue"];
+const ASSET_CLASS_COLUMNS: &[&str] = &["assetclass", "securitytype", "assettype"];
+
+/// Date formats seen in holdings file preambles.
+const DATE_FORMATS: &[&str] = &["%b %d, %Y", "%d-%b-%Y", "%Y-%m-%d", "%m/%d/%Y", "%d %b %Y"];
+
+/// One equity line of an ETF holdings file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EtfHolding {
+    /// Ticker, with share-class separators mapped to a dot.
+    pub symbol: String,
+    /// Security name, if listed.
+    pub name: Option<String>,
+    /// Sector label as written by the issuer, if listed.
+    pub sector: Option<String>,
+    /// Weight within the fund's equity holdings (0.0 to 1.0).
+    pub weight: f64,
+    /// Number of shares held, if listed.
+    pub shares: Option<f64>,
+    /// Market value of the position, if listed.
+    pub market_value: Option<f64>,
+}
+
+/// Equity holdings of an ETF.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EtfHoldings {
+    /// Fund name or ticker.
+    pub name: String,
+    /// Date the holdings are as of, if the file states it.
+    pub date: Option<NaiveDate>,
+    /// Equity holdings, largest weight first.
+    pub holdings: Vec<EtfHolding>,
+}
+
+/// Positions of the recognised columns within the header row.
+#[derive(Debug, Default)]
+struct Columns {
+    symbol: usize,
+    name: Option<usize>,
+    sector: Option<usize>,
+    weight: Option<usize>,
+    shares: Option<usize>,
+    market_value: Option<usize>,
+    asset_class: Option<usize>,
+}
+
+impl Columns {
+    /// Recognise a header row, which needs a ticker and a weight or value.
+    fn from_header(record: &csv::StringRecord) -> Option<Self> {
+        let find = |names: &[&str]| {
+            record
+                .iter()
+                .position(|cell| names.contains(&header_key(cell).as_str()))
+        };
+
+        let columns = Self {
+            symbol: find(SYMBOL_COLUMNS)?,
+            name: find(NAME_COLUMNS),
+            sector: find(SECTOR_COLUMNS),
+            weight: find(WEIGHT_COLUMNS),
+            shares: find(SHARES_COLUMNS),
+            market_value: find(MARKET_VALUE_COLUMNS),
+            asset_class: find(ASSET_CLASS_COLUMNS),
+        };
+        (columns.weight.is_some() || columns.market_value.is_some()).then_some(columns)
+    }
+}
+
+impl EtfHoldings {
+    /// Load a holdings file, naming the fund after the file stem unless the
+    /// file states a fund name.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, UniverseError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        let default_name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("etf");
+
+        Self::parse(&contents, default_name)
+    }
+
+    /// Parse an issuer holdings CSV.
+    pub fn parse(contents: &str, name: &str) -> Result<Self, UniverseError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(contents.trim_start_matches('\u{feff}').as_bytes());
+
+        let mut fund_name = None;
+        let mut date = None;
+        let mut columns = None;
+        let mut rows = Vec::new();
+
+        for record in reader.records() {
+            let record = record?;
+            let Some(columns) = &columns else {
+                // Fund details above the table
+                let label = record.get(0).map(header_key).unwrap_or_default();
+                let value = record.iter().skip(1).find(|v| !v.is_empty());
+                if fund_name.is_none() && label == "fundname" {
+                    fund_name = value.map(str::to_string);
+                }
+                if date.is_none() {
+                    date = record.iter().find_map(parse_date);
+                }
+                columns = Columns::from_header(&record);
+                continue;
+            };
+
+            let symbol = record.get(columns.symbol).unwrap_or_default();
+            if record.iter().all(str::is_empty) || (record.len() == 1 && !rows.is_empty()) {
+                // A blank line or a disclaimer ends the table
+                break;
+            }
+            if let Some(holding) = parse_holding(&record, columns, symbol) {
+                rows.push(holding);
+            }
+        }
+
+        if columns.is_none() {
+            return Err(UniverseError::InvalidHoldings(
+                "no header row with a ticker and a weight or market value column".to_string(),
+            ));
+        }
+
+        let total: f64 = rows.iter().map(|h| h.weight).sum();
+        if rows.is_empty() || total <= 0.0 {
+            return Err(UniverseError::Empty);
+        }
+        for holding in &mut rows {
+            holding.weight /= total;
+        }
+        rows.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+
+        Ok(Self {
+            name: fund_name.unwrap_or_else(|| name.to_string()),
+            date,
+            holdings: rows,
+        })
+    }
+
+    /// Number of equity holdings.
+    pub const fn len(&self) -> usize {
+        self.holdings.len()
+    }
+
+    /// Whether the fund holds no equities.
+    pub const fn is_empty(&self) -> bool {
+        self.holdings.is_empty()
+    }
+
+    /// Symbols whose sector could not be resolved, largest weight first.
+    pub fn unmapped(&self, base: &dyn Universe) -> Vec<String> {
+        self.holdings
+            .iter()
+            .filter(|h| resolve(h, base).is_none())
+            .map(|h| h.symbol.clone())
+            .collect()
+    }
+
+    /// Weight of each sector, with unmapped holdings under "Unknown".
+    pub fn sector_weights(&self, base: &dyn Universe) -> BTreeMap<String, f64> {
+        let mut weights = BTreeMap::new();
+        for holding in &self.holdings {
+            let sector = resolve(holding, base).map_or("Unknown", |c| c.sector.name());
+            *weights.entry(sector.to_string()).or_insert(0.0) += holding.weight;
+        }
+        weights
+    }
+
+    /// Weighted universe of the holdings whose sector resolves.
+    ///
+    /// Unmapped holdings are left out (see [`unmapped`](Self::unmapped)),
+    /// so the weights can sum to less than one.
+    pub fn to_universe(&self, base: &dyn Universe) -> Result<FileUniverse, UniverseError> {
+        let constituents = self
+            .holdings
+            .iter()
+            .filter_map(|h| resolve(h, base))
+            .collect();
+        FileUniverse::from_constituents(self.name.clone(), constituents)
+    }
+
+    /// The holdings as a benchmark portfolio.
+    ///
+    /// Dated as of the holdings file, or `date` when it states none.
+    pub fn to_portfolio(&self, date: NaiveDate) -> PortfolioExport {
+        let holdings = self
+            .holdings
+            .iter()
+            .map(|h| PortfolioHolding::new(h.symbol.clone(), h.weight, h.market_value, h.shares))
+            .collect();
+        PortfolioExport::new(self.name.clone(), self.date.unwrap_or(date), holdings)
+    }
+}
+
+/// Resolve a holding's sector, from the issuer's label when it parses and
+/// from the base universe otherwise.
+fn resolve(holding: &EtfHolding, base: &dyn Universe) -> Option<Constituent> {
+    let symbol = holding.symbol.as_str();
+    let sector = holding
+        .sector
+        .as_deref()
+        .and_then(|s| s.parse::<GicsSector>().ok())
+        .or_else(|| base.sector(symbol))?;
+
+    let mut constituent = match base.sub_industry(symbol) {
+        Some(code) if code.sector() == sector => {
+            Constituent::new(symbol, sector).with_sub_industry(code)
+        }
+        _ => Constituent::new(symbol, sector),
+    };
+    constituent.weight = Some(holding.weight);
+    Some(constituent)
+}
+
+/// Normalize a header cell: lowercase alphanumerics only.
+fn header_key(cell: &str) -> String {
+    normalize(cell)
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect()
+}
+
+/// Parse a date from a preamble cell such as `"As of Oct 15, 2024"`.
+fn parse_date(cell: &str) -> Option<NaiveDate> {
+    let cell = cell.trim();
+    let cell = cell
+        .strip_prefix("As of")
+        .or_else(|| cell.strip_prefix("as of"))
+        .unwrap_or(cell)
+        .trim();
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(cell, format).ok())
+}
+
+/// Parse a number written with thousands separators, currency or percent signs.
+fn parse_number(cell: &str) -> Option<f64> {
+    let cleaned: String = cell
+        .chars()
+        .filter(|c| !matches!(c, ',' | '$' | '%' | ' '))
+        .collect();
+    cleaned.parse().ok()
+}
+
+/// Map share-class separators to a dot: `BRK/B` and `BRK B` become `BRK.B`.
+fn clean_symbol(symbol: &str) -> String {
+    symbol
+        .trim()
+        .to_uppercase()
+        .split([' ', '/'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Parse one holdings row, skipping cash and other non-equity lines.
+fn parse_holding(
+    record: &csv::StringRecord,
+    columns: &Columns,
+    symbol: &str,
+) -> Option<EtfHolding> {
+    let cell = |index: Option<usize>| {
+        index
+            .and_then(|i| record.get(i))
+            .filter(|v| !v.is_empty() && *v != "-")
+    };
+
+    if let Some(asset_class) = cell(columns.asset_class)
+        && !asset_class.to_lowercase().contains("equit")
+    {
+        return None;
+    }
+    let symbol = clean_symbol(symbol);
+    if symbol.is_empty()
+        || !symbol.chars().any(|c| c.is_ascii_alphabetic())
+        || matches!(symbol.as_str(), "CASH" | "USD" | "-" | "--")
+    {
+        return None;
+    }
+
+    let market_value = cell(columns.market_value).and_then(parse_number);
+    let weight = match columns.weight {
+        Some(_) => cell(columns.weight).and_then(parse_number),
+        None => market_value,
+    }
+    .filter(|w| w.is_finite() && *w > 0.0)?;
+
+    Some(EtfHolding {
+        symbol,
+        name: cell(columns.name).map(str::to_string),
+        sector: cell(columns.sector).map(str::to_string),
+        weight,
+        shares: cell(columns.shares).and_then(parse_number),
+        market_value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universe::SP500Universe;
+
+    const ISHARES: &str = "\
+iShares Core S&P 500 ETF
+Fund Holdings as of,\"Oct 15, 2024\"
+Inception Date,\"May 15, 2000\"
+\u{a0}
+Ticker,Name,Sector,Asset Class,Market Value,Weight (%),Notional Value,Quantity,Price
+\"AAPL\",\"APPLE INC\",\"Information Technology\",\"Equity\",\"40,000.00\",\"6.00\",\"40,000.00\",\"170.00\",\"235.00\"
+\"MSFT\",\"MICROSOFT CORP\",\"Information Technology\",\"Equity\",\"30,000.00\",\"4.00\",\"30,000.00\",\"72.00\",\"415.00\"
+\"BRK.B\",\"BERKSHIRE HATHAWAY INC CLASS B\",\"Financials\",\"Equity\",\"10,000.00\",\"2.00\",\"10,000.00\",\"21.00\",\"470.00\"
+\"XTSLA\",\"BLK CSH FND TREASURY SL AGENCY\",\"Cash and/or Derivatives\",\"Money Market\",\"500.00\",\"0.10\",\"500.00\",\"500.00\",\"1.00\"
+\"ESZ4\",\"S&P500 EMINI DEC 24\",\"Cash and/or Derivatives\",\"Futures\",\"0.00\",\"0.00\",\"600.00\",\"1.00\",\"5800.00\"
+
+\"The content contained herein is owned or licensed by BlackRock\"
+";
+
+    const SPDR: &str = "\
+Fund Name:,The Technology Select Sector SPDR Fund
+Ticker Symbol:,XLK
+Holdings:,As of 15-Oct-2024
+,
+Name,Ticker,Identifier,SEDOL,Weight,Sector,Shares Held,Local Currency
+APPLE INC,AAPL,037833100,2046251,15.00,-,\"1,000\",USD
+NVIDIA CORP,NVDA,67066G104,2379504,15.00,-,\"2,000\",USD
+MICROSOFT CORP,MSFT,594918104,2588173,12.00,-,\"500\",USD
+ACME WIDGETS,ACMEX,000000000,0000000,8.00,-,\"100\",USD
+";
+
+    #[test]
+    fn test_parse_ishares() {
+        let etf = EtfHoldings::parse(ISHARES, "IVV").unwrap();
+
+        assert_eq!(etf.name, "IVV");
+        assert_eq!(etf.date, NaiveDate::from_ymd_opt(2024, 10, 15));
+        assert_eq!(etf.len(), 3);
+        assert_eq!(etf.holdings[0].symbol, "AAPL");
+        assert!((etf.holdings[0].weight - 0.5).abs() < 1e-12);
+        assert_eq!(etf.holdings[2].market_value, Some(10_000.0));
+        assert_eq!(etf.holdings[2].shares, Some(21.0));
+    }
+
+    #[test]
+    fn test_parse_spdr() {
+        let etf = EtfHoldings::parse(SPDR, "xlk").unwrap();
+
+        assert_eq!(etf.name, "The Technology Select Sector SPDR Fund");
+        assert_eq!(etf.date, NaiveDate::from_ymd_opt(2024, 10, 15));
+        assert_eq!(etf.len(), 4);
+        assert_eq!(etf.holdings[0].sector, None);
+        assert_eq!(etf.holdings[0].shares, Some(1000.0));
+    }
+
+    #[test]
+    fn test_universe_and_portfolio() {
+        let base = SP500Universe::new();
+        let etf = EtfHoldings::parse(SPDR, "xlk").unwrap();
+
+        assert_eq!(etf.unmapped(&base), vec!["ACMEX"]);
+        let sectors = etf.sector_weights(&base);
+        assert!((sectors["Information Technology"] - 0.84).abs() < 1e-12);
+        assert!((sectors["Unknown"] - 0.16).abs() < 1e-12);
+
+        let universe = etf.to_universe(&base).unwrap();
+        assert_eq!(universe.size(), 3);
+        assert_eq!(
+            universe.sector("NVDA"),
+            Some(GicsSector::InformationTechnology)
+        );
+        assert!((universe.weight("MSFT").unwrap() - 0.24).abs() < 1e-12);
+
+        let portfolio = etf.to_portfolio(NaiveDate::from_ymd_opt(2024, 12, 31).unwrap());
+        assert_eq!(
+            portfolio.date,
+            NaiveDate::from_ymd_opt(2024, 10, 15).unwrap()
+        );
+        assert_eq!(portfolio.holdings.len(), 4);
+        assert!((portfolio.total_weight() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_share_class_symbols() {
+        assert_eq!(clean_symbol("BRK/B"), "BRK.B");
+        assert_eq!(clean_symbol("brk b"), "BRK.B");
+        assert_eq!(clean_symbol(" AAPL "), "AAPL");
+    }
+
+    #[test]
+    fn test_missing_header() {
+        assert!(matches!(
+            EtfHoldings::parse("a,b\n1,2\n", "x"),
+            Err(UniverseError::InvalidHoldings(_))
+        ));
+    }
+}
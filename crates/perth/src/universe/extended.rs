@@ -0,0 +1,144 @@
+//! Universes extended with extra constituents.
+
+use crate::universe::Universe;
+use crate::universe::country::Country;
+use crate::universe::gics::{GicsCode, GicsSector};
+use crate::universe::sp500::Constituent;
+use chrono::NaiveDate;
+use std::fmt;
+
+/// A universe plus constituents it does not contain, such as a target
+/// symbol classified from EDGAR.
+///
+/// Extra constituents are members on every date and carry no weight.
+/// Symbols already in the base universe keep their base classification.
+pub struct ExtendedUniverse<'a> {
+    base: &'a dyn Universe,
+    extra: Vec<Constituent>,
+}
+
+impl<'a> ExtendedUniverse<'a> {
+    /// Wrap a universe without extra constituents.
+    pub fn new(base: &'a dyn Universe) -> Self {
+        Self {
+            base,
+            extra: Vec::new(),
+        }
+    }
+
+    /// Add a constituent unless the base universe already contains it.
+    pub fn with_constituent(mut self, constituent: Constituent) -> Self {
+        if !self.contains(&constituent.symbol) {
+            self.extra.push(constituent);
+        }
+        self
+    }
+
+    /// The constituents added on top of the base universe.
+    pub fn extra(&self) -> &[Constituent] {
+        &self.extra
+    }
+
+    fn get_extra(&self, symbol: &str) -> Option<&Constituent> {
+        self.extra.iter().find(|c| c.symbol == symbol)
+    }
+}
+
+impl fmt::Debug for ExtendedUniverse<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtendedUniverse")
+            .field("base", &self.base.name())
+            .field("extra", &self.extra)
+            .finish()
+    }
+}
+
+impl Universe for ExtendedUniverse<'_> {
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+
+    fn symbols(&self) -> Vec<String> {
+        let mut symbols = self.base.symbols();
+        symbols.extend(self.extra.iter().map(|c| c.symbol.clone()));
+        symbols
+    }
+
+    fn sector(&self, symbol: &str) -> Option<GicsSector> {
+        self.base
+            .sector(symbol)
+            .or_else(|| self.get_extra(symbol).map(|c| c.sector))
+    }
+
+    fn sub_industry(&self, symbol: &str) -> Option<GicsCode> {
+        if self.base.contains(symbol) {
+            return self.base.sub_industry(symbol);
+        }
+        self.get_extra(symbol).and_then(|c| c.sub_industry)
+    }
+
+    fn country(&self, symbol: &str) -> Country {
+        if self.base.contains(symbol) {
+            return self.base.country(symbol);
+        }
+        self.get_extra(symbol)
+            .map_or_else(|| Country::from_symbol(symbol), Constituent::country)
+    }
+
+    fn weight(&self, symbol: &str) -> Option<f64> {
+        self.base.weight(symbol)
+    }
+
+    fn contains(&self, symbol: &str) -> bool {
+        self.base.contains(symbol) || self.get_extra(symbol).is_some()
+    }
+
+    fn size(&self) -> usize {
+        self.base.size() + self.extra.len()
+    }
+
+    fn symbols_on(&self, date: NaiveDate) -> Vec<String> {
+        let mut symbols = self.base.symbols_on(date);
+        symbols.extend(self.extra.iter().map(|c| c.symbol.clone()));
+        symbols
+    }
+
+    fn contains_on(&self, symbol: &str, date: NaiveDate) -> bool {
+        self.base.contains_on(symbol, date) || self.get_extra(symbol).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universe::SP500Universe;
+
+    #[test]
+    fn test_extra_constituents() {
+        let base = SP500Universe::new();
+        let semis = GicsCode::new(45301020).unwrap();
+        let universe = ExtendedUniverse::new(&base)
+            .with_constituent(
+                Constituent::new("SMCI", GicsSector::InformationTechnology)
+                    .with_sub_industry(GicsCode::new(45202030).unwrap()),
+            )
+            .with_constituent(Constituent::new("NVDA", GicsSector::Energy));
+
+        assert_eq!(universe.size(), base.size() + 1);
+        assert_eq!(universe.extra().len(), 1);
+        assert!(universe.contains("SMCI"));
+        assert_eq!(
+            universe.sector("SMCI"),
+            Some(GicsSector::InformationTechnology)
+        );
+        assert_eq!(
+            universe.sector("NVDA"),
+            Some(GicsSector::InformationTechnology)
+        );
+        assert_eq!(universe.sub_industry("NVDA"), Some(semis));
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        assert!(universe.contains_on("SMCI", date));
+        assert!(universe.symbols_on(date).contains(&"SMCI".to_string()));
+    }
+}
@@ -0,0 +1,589 @@
+//! Universes loaded from CSV, JSON or TOML files.
+//!
+//! Every format describes the same records: a `symbol`, a GICS `sector`
+//! (name, alias or 2-digit code) and optional `industry`, `country` (ISO code
+//! or name) and `weight`. Without a `country`, it follows from the symbol's
+//! exchange suffix (`7203.T` is Japanese) and defaults to the US.
+//! An optional `sub_industry` (8-digit GICS code or name) enables industry
+//! factors; when it is given, `sector` may be left out.
+//! Optional `start_date` and `end_date` columns (`YYYY-MM-DD`, inclusive)
+//! give point-in-time membership; a symbol may then appear on several rows,
+//! one per membership spell. In TOML, quote the dates.
+//!
+//! CSV files have a header row:
+//!
+//! ```text
+//! symbol,sector,industry,weight
+//! AAPL,Information Technology,Technology Hardware,0.07
+//! XOM,Energy,,0.01
+//! ```
+//!
+//! JSON files hold either a list of records or an object with an optional
+//! `name` and a `constituents` list. TOML files use the object form:
+//!
+//! ```toml
+//! name = "Watchlist"
+//!
+//! [[constituents]]
+//! symbol = "AAPL"
+//! sector = "Information Technology"
+//! weight = 0.6
+//! ```
+
+use crate::universe::Universe;
+use crate::universe::country::Country;
+use crate::universe::error::UniverseError;
+use crate::universe::gics::{GicsCode, GicsLevel, GicsSector};
+use crate::universe::membership::MembershipHistory;
+use crate::universe::sp500::Constituent;
+use chrono::NaiveDate;
+use perth_data::cache::UniverseMembership;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// File formats a universe can be loaded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniverseFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// JSON list of records, or an object with a `constituents` list.
+    Json,
+    /// TOML with a `[[constituents]]` array of tables.
+    Toml,
+}
+
+impl UniverseFormat {
+    /// Detect the format from a file extension.
+    pub fn from_path(path: &Path) -> Result<Self, UniverseError> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+
+        match extension.as_deref() {
+            Some("csv") => Ok(Self::Csv),
+            Some("json") => Ok(Self::Json),
+            Some("toml") => Ok(Self::Toml),
+            _ => Err(UniverseError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+}
+
+/// One constituent as written in a universe file.
+#[derive(Debug, Deserialize)]
+struct ConstituentRecord {
+    symbol: String,
+    #[serde(default)]
+    sector: Option<String>,
+    #[serde(default)]
+    sub_industry: Option<GicsCodeField>,
+    #[serde(default)]
+    industry: Option<String>,
+    #[serde(default)]
+    country: Option<String>,
+    #[serde(default)]
+    weight: Option<f64>,
+    #[serde(default)]
+    start_date: Option<String>,
+    #[serde(default)]
+    end_date: Option<String>,
+}
+
+/// A GICS code written as a number or as a string (code or name).
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum GicsCodeField {
+    Code(u32),
+    Text(String),
+}
+
+/// Object form of a JSON or TOML universe file.
+#[derive(Debug, Deserialize)]
+struct UniverseDocument {
+    #[serde(default)]
+    name: Option<String>,
+    constituents: Vec<ConstituentRecord>,
+}
+
+/// JSON universes may be a bare list or an object.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonUniverse {
+    List(Vec<ConstituentRecord>),
+    Document(UniverseDocument),
+}
+
+/// Universe defined by a user-supplied list of constituents.
+#[derive(Debug, Clone)]
+pub struct FileUniverse {
+    name: String,
+    constituents: Vec<Constituent>,
+    index: HashMap<String, usize>,
+    history: MembershipHistory,
+}
+
+impl FileUniverse {
+    /// Load a universe from a file, picking the format from its extension.
+    ///
+    /// The universe is named after the file stem unless the file sets a
+    /// `name`.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, UniverseError> {
+        let path = path.as_ref();
+        let format = UniverseFormat::from_path(path)?;
+        let contents = std::fs::read_to_string(path)?;
+        let default_name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("custom");
+
+        Self::parse(&contents, format, default_name)
+    }
+
+    /// Parse a universe from a string in the given format.
+    pub fn parse(
+        contents: &str,
+        format: UniverseFormat,
+        name: &str,
+    ) -> Result<Self, UniverseError> {
+        let (file_name, records) = match format {
+            UniverseFormat::Csv => {
+                let mut reader = csv::ReaderBuilder::new()
+                    .trim(csv::Trim::All)
+                    .from_reader(contents.as_bytes());
+                let records = reader
+                    .deserialize()
+                    .collect::<Result<Vec<ConstituentRecord>, _>>()?;
+                (None, records)
+            }
+            UniverseFormat::Json => match serde_json::from_str(contents)? {
+                JsonUniverse::List(records) => (None, records),
+                JsonUniverse::Document(doc) => (doc.name, doc.constituents),
+            },
+            UniverseFormat::Toml => {
+                let doc: UniverseDocument = toml::from_str(contents)?;
+                (doc.name, doc.constituents)
+            }
+        };
+
+        let mut constituents: Vec<Constituent> = Vec::with_capacity(records.len());
+        let mut memberships = Vec::with_capacity(records.len());
+        for record in records {
+            let symbol = record.symbol.trim().to_uppercase();
+            let (sector, sub_industry) =
+                classify(&symbol, record.sector.as_deref(), record.sub_industry)?;
+            let industry = record.industry.filter(|i| !i.trim().is_empty());
+            let country = record
+                .country
+                .as_deref()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(str::parse::<Country>)
+                .transpose()?;
+            let start = parse_date(&symbol, "start_date", record.start_date.as_deref())?;
+            let end = parse_date(&symbol, "end_date", record.end_date.as_deref())?;
+            let dated = start.is_some() || end.is_some();
+
+            // Further dated rows for a symbol only add membership spells.
+            let seen = constituents.iter().any(|c| c.symbol == symbol);
+            if !seen || !dated {
+                let mut constituent = Constituent::new(symbol.clone(), sector);
+                constituent.sub_industry = sub_industry;
+                constituent.industry.clone_from(&industry);
+                constituent.country = country;
+                constituent.weight = record.weight;
+                constituents.push(constituent);
+            }
+
+            memberships.push(UniverseMembership {
+                symbol,
+                start,
+                end,
+                sector: Some(sector.name().to_string()),
+                industry,
+            });
+        }
+
+        Ok(
+            Self::from_constituents(file_name.as_deref().unwrap_or(name), constituents)?
+                .with_history(MembershipHistory::new(memberships)),
+        )
+    }
+
+    /// Build a universe from constituents.
+    ///
+    /// Symbols must be non-empty and unique, and weights finite and
+    /// non-negative.
+    pub fn from_constituents(
+        name: impl Into<String>,
+        constituents: Vec<Constituent>,
+    ) -> Result<Self, UniverseError> {
+        if constituents.is_empty() {
+            return Err(UniverseError::Empty);
+        }
+
+        let mut index = HashMap::with_capacity(constituents.len());
+        for (i, constituent) in constituents.iter().enumerate() {
+            let invalid = |reason: &str| UniverseError::InvalidConstituent {
+                symbol: constituent.symbol.clone(),
+                reason: reason.to_string(),
+            };
+
+            if constituent.symbol.is_empty() {
+                return Err(invalid("empty symbol"));
+            }
+            if constituent
+                .weight
+                .is_some_and(|w| !w.is_finite() || w < 0.0)
+            {
+                return Err(invalid("weight must be finite and non-negative"));
+            }
+            if index.insert(constituent.symbol.clone(), i).is_some() {
+                return Err(invalid("listed more than once"));
+            }
+        }
+
+        let history = MembershipHistory::new(constituents.iter().map(|c| UniverseMembership {
+            sector: Some(c.sector.name().to_string()),
+            industry: c.industry.clone(),
+            ..UniverseMembership::new(c.symbol.clone(), None)
+        }));
+
+        Ok(Self {
+            name: name.into(),
+            constituents,
+            index,
+            history,
+        })
+    }
+
+    /// Replace the membership history used for point-in-time lookups.
+    ///
+    /// Spells for symbols that are not constituents are ignored.
+    pub fn with_history(mut self, history: MembershipHistory) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// The membership history.
+    pub const fn history(&self) -> &MembershipHistory {
+        &self.history
+    }
+
+    /// Get all constituents, in file order.
+    pub fn constituents(&self) -> &[Constituent] {
+        &self.constituents
+    }
+
+    /// Get the constituent for a symbol.
+    pub fn get(&self, symbol: &str) -> Option<&Constituent> {
+        self.index.get(symbol).map(|&i| &self.constituents[i])
+    }
+
+    /// Whether every constituent carries a weight.
+    pub fn is_weighted(&self) -> bool {
+        self.constituents.iter().all(|c| c.weight.is_some())
+    }
+}
+
+impl Universe for FileUniverse {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn symbols(&self) -> Vec<String> {
+        self.constituents.iter().map(|c| c.symbol.clone()).collect()
+    }
+
+    fn contains(&self, symbol: &str) -> bool {
+        self.index.contains_key(symbol)
+    }
+
+    fn size(&self) -> usize {
+        self.constituents.len()
+    }
+
+    fn symbols_on(&self, date: NaiveDate) -> Vec<String> {
+        self.constituents
+            .iter()
+            .filter(|c| self.history.contains_on(&c.symbol, date))
+            .map(|c| c.symbol.clone())
+            .collect()
+    }
+
+    fn contains_on(&self, symbol: &str, date: NaiveDate) -> bool {
+        self.contains(symbol) && self.history.contains_on(symbol, date)
+    }
+
+    fn sector(&self, symbol: &str) -> Option<GicsSector> {
+        self.get(symbol).map(|c| c.sector)
+    }
+
+    fn sub_industry(&self, symbol: &str) -> Option<GicsCode> {
+        self.get(symbol).and_then(|c| c.sub_industry)
+    }
+
+    fn country(&self, symbol: &str) -> Country {
+        self.get(symbol)
+            .map_or_else(|| Country::from_symbol(symbol), Constituent::country)
+    }
+
+    fn weight(&self, symbol: &str) -> Option<f64> {
+        self.get(symbol).and_then(|c| c.weight)
+    }
+}
+
+/// Resolve a record's sector and sub-industry, checking they agree.
+fn classify(
+    symbol: &str,
+    sector: Option<&str>,
+    sub_industry: Option<GicsCodeField>,
+) -> Result<(GicsSector, Option<GicsCode>), UniverseError> {
+    let invalid = |reason: String| UniverseError::InvalidConstituent {
+        symbol: symbol.to_string(),
+        reason,
+    };
+
+    let sub_industry = match sub_industry {
+        Some(GicsCodeField::Code(code)) => Some(GicsCode::try_from(code)?),
+        Some(GicsCodeField::Text(text)) if !text.trim().is_empty() => Some(text.parse()?),
+        _ => None,
+    };
+    if let Some(code) = sub_industry
+        && code.level() != GicsLevel::SubIndustry
+    {
+        return Err(invalid(format!("{code} is a GICS {}", code.level())));
+    }
+
+    let sector = sector
+        .filter(|s| !s.trim().is_empty())
+        .map(str::parse::<GicsSector>)
+        .transpose()?;
+    match (sector, sub_industry) {
+        (Some(sector), Some(code)) if code.sector() != sector => Err(invalid(format!(
+            "sub-industry {} is not in sector {sector}",
+            code.name()
+        ))),
+        (Some(sector), code) => Ok((sector, code)),
+        (None, Some(code)) => Ok((code.sector(), Some(code))),
+        (None, None) => Err(invalid("missing sector".to_string())),
+    }
+}
+
+/// Parse an optional `YYYY-MM-DD` field, treating blanks as missing.
+fn parse_date(
+    symbol: &str,
+    field: &str,
+    value: Option<&str>,
+) -> Result<Option<NaiveDate>, UniverseError> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            NaiveDate::parse_from_str(v, "%Y-%m-%d").map_err(|_| {
+                UniverseError::InvalidConstituent {
+                    symbol: symbol.to_string(),
+                    reason: format!("invalid {field} {v:?}, expected YYYY-MM-DD"),
+                }
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "symbol,sector,industry,weight\n\
+                   aapl,Information Technology,Technology Hardware,0.6\n\
+                   XOM,10,,0.4\n";
+        let universe = FileUniverse::parse(csv, UniverseFormat::Csv, "watchlist").unwrap();
+
+        assert_eq!(universe.name(), "watchlist");
+        assert_eq!(universe.symbols(), vec!["AAPL", "XOM"]);
+        assert_eq!(universe.sector("XOM"), Some(GicsSector::Energy));
+        assert_eq!(universe.weight("AAPL"), Some(0.6));
+        assert_eq!(
+            universe.get("AAPL").unwrap().industry.as_deref(),
+            Some("Technology Hardware")
+        );
+        assert_eq!(universe.get("XOM").unwrap().industry, None);
+        assert!(universe.is_weighted());
+    }
+
+    #[test]
+    fn test_parse_csv_without_optional_columns() {
+        let csv = "symbol,sector\nMSFT,IT\nJPM,Financials\n";
+        let universe = FileUniverse::parse(csv, UniverseFormat::Csv, "plain").unwrap();
+
+        assert_eq!(universe.size(), 2);
+        assert_eq!(universe.weight("MSFT"), None);
+        assert!(!universe.is_weighted());
+    }
+
+    #[test]
+    fn test_parse_json_list_and_document() {
+        let list = r#"[{"symbol": "AAPL", "sector": "Information Technology"}]"#;
+        let universe = FileUniverse::parse(list, UniverseFormat::Json, "list").unwrap();
+        assert_eq!(universe.name(), "list");
+        assert!(universe.contains("AAPL"));
+
+        let doc = r#"{"name": "Russell 1000", "constituents": [
+            {"symbol": "AAPL", "sector": "45", "weight": 0.07}
+        ]}"#;
+        let universe = FileUniverse::parse(doc, UniverseFormat::Json, "file").unwrap();
+        assert_eq!(universe.name(), "Russell 1000");
+        assert_eq!(universe.weight("AAPL"), Some(0.07));
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        let toml = r#"
+            name = "Watchlist"
+
+            [[constituents]]
+            symbol = "AAPL"
+            sector = "Information Technology"
+            industry = "Technology Hardware"
+
+            [[constituents]]
+            symbol = "UNH"
+            sector = "Health Care"
+        "#;
+        let universe = FileUniverse::parse(toml, UniverseFormat::Toml, "file").unwrap();
+
+        assert_eq!(universe.name(), "Watchlist");
+        assert_eq!(universe.sector("UNH"), Some(GicsSector::HealthCare));
+        assert_eq!(
+            universe.symbols_in_sector(GicsSector::InformationTechnology),
+            vec!["AAPL"]
+        );
+    }
+
+    #[test]
+    fn test_sub_industries() {
+        let csv = "symbol,sector,sub_industry\n\
+                   NVDA,,45301020\n\
+                   ACN,IT,IT Consulting & Other Services\n\
+                   XOM,Energy,\n";
+        let universe = FileUniverse::parse(csv, UniverseFormat::Csv, "gics").unwrap();
+
+        assert_eq!(
+            universe.sector("NVDA"),
+            Some(GicsSector::InformationTechnology)
+        );
+        assert_eq!(
+            universe
+                .classification("NVDA", GicsLevel::Industry)
+                .map(|c| c.value()),
+            Some(453010)
+        );
+        assert_eq!(
+            universe.sub_industry("ACN").map(|c| c.value()),
+            Some(45102010)
+        );
+        assert_eq!(universe.sub_industry("XOM"), None);
+
+        let toml = r#"
+            [[constituents]]
+            symbol = "AMD"
+            sub_industry = 45301020
+        "#;
+        let universe = FileUniverse::parse(toml, UniverseFormat::Toml, "gics").unwrap();
+        assert_eq!(
+            universe.sector("AMD"),
+            Some(GicsSector::InformationTechnology)
+        );
+
+        let invalid = |csv: &str| FileUniverse::parse(csv, UniverseFormat::Csv, "bad").is_err();
+        assert!(invalid(
+            "symbol,sector,sub_industry\nNVDA,Energy,45301020\n"
+        ));
+        assert!(invalid("symbol,sector,sub_industry\nNVDA,,453010\n"));
+        assert!(invalid("symbol,sector,sub_industry\nNVDA,,\n"));
+    }
+
+    #[test]
+    fn test_countries() {
+        let csv = "symbol,sector,country\n\
+                   AAPL,IT,\n\
+                   7203.T,Consumer Discretionary,\n\
+                   ASML,IT,NL\n";
+        let universe = FileUniverse::parse(csv, UniverseFormat::Csv, "global").unwrap();
+
+        assert_eq!(universe.country("AAPL"), Country::UnitedStates);
+        assert_eq!(universe.country("7203.T"), Country::Japan);
+        assert_eq!(universe.country("ASML"), Country::Netherlands);
+        assert!(
+            FileUniverse::parse(
+                "symbol,sector,country\nAAPL,IT,Atlantis\n",
+                UniverseFormat::Csv,
+                "bad"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_dated_memberships() {
+        let csv = "symbol,sector,start_date,end_date\n\
+                   AAPL,IT,,\n\
+                   XOM,Energy,2010-01-04,2020-08-28\n\
+                   XOM,Energy,2021-03-22,\n\
+                   TSLA,Consumer Discretionary,2020-12-21,\n";
+        let universe = FileUniverse::parse(csv, UniverseFormat::Csv, "pit").unwrap();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        assert_eq!(universe.symbols(), vec!["AAPL", "XOM", "TSLA"]);
+        assert_eq!(universe.symbols_on(date(2019, 6, 3)), vec!["AAPL", "XOM"]);
+        assert_eq!(universe.symbols_on(date(2021, 1, 4)), vec!["AAPL", "TSLA"]);
+        assert!(universe.contains_on("XOM", date(2022, 1, 3)));
+        assert!(!universe.contains_on("XOM", date(2020, 9, 1)));
+        assert_eq!(universe.history().spells("XOM").len(), 2);
+
+        assert!(matches!(
+            FileUniverse::parse(
+                "symbol,sector,start_date\nAAPL,IT,01/02/2020\n",
+                UniverseFormat::Csv,
+                "bad"
+            ),
+            Err(UniverseError::InvalidConstituent { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_universes() {
+        let parse = |csv: &str| FileUniverse::parse(csv, UniverseFormat::Csv, "bad");
+
+        assert!(matches!(
+            parse("symbol,sector\nAAPL,Crypto\n"),
+            Err(UniverseError::UnknownSector(_))
+        ));
+        assert!(matches!(
+            parse("symbol,sector\nAAPL,IT\naapl,IT\n"),
+            Err(UniverseError::InvalidConstituent { .. })
+        ));
+        assert!(matches!(
+            parse("symbol,sector,weight\nAAPL,IT,-0.1\n"),
+            Err(UniverseError::InvalidConstituent { .. })
+        ));
+        assert!(matches!(
+            parse("symbol,sector\n"),
+            Err(UniverseError::Empty)
+        ));
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            UniverseFormat::from_path(Path::new("r1000.CSV")).unwrap(),
+            UniverseFormat::Csv
+        );
+        assert!(matches!(
+            UniverseFormat::from_path(Path::new("universe.xlsx")),
+            Err(UniverseError::UnsupportedFormat(_))
+        ));
+    }
+}
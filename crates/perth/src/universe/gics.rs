@@ -1,7 +1,14 @@
-//! GICS (Global Industry Classification Standard) sector definitions.
+//! GICS (Global Industry Classification Standard) classifications.
+//!
+//! GICS has four levels, each adding two digits to its parent's code: 11
+//! sectors (`45`), 25 industry groups (`4530`), 74 industries (`453010`) and
+//! 163 sub-industries (`45301020`). [`GicsSector`] names the top level and
+//! [`GicsCode`] any level, following the structure in effect since March 2023.
 
+use crate::universe::error::UniverseError;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
 /// GICS Level 1 sectors (11 sectors).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -111,12 +118,580 @@ impl GicsSector {
     }
 }
 
+impl From<GicsSector> for GicsCode {
+    fn from(sector: GicsSector) -> Self {
+        Self(u32::from(sector.code()))
+    }
+}
+
 impl fmt::Display for GicsSector {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.name())
     }
 }
 
+impl FromStr for GicsSector {
+    type Err = UniverseError;
+
+    /// Parse a sector from its name, a common alias or its 2-digit code.
+    ///
+    /// Matching ignores case, spaces, underscores and hyphens, so
+    /// `"Health Care"`, `"health_care"` and `"35"` all parse.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = normalize(s);
+
+        if let Ok(code) = normalized.parse::<u8>() {
+            return Self::from_code(code)
+                .ok_or_else(|| UniverseError::UnknownSector(s.to_string()));
+        }
+
+        let sector = match normalized.as_str() {
+            "informationtechnology" | "it" | "tech" | "technology" => Self::InformationTechnology,
+            "healthcare" | "health" => Self::HealthCare,
+            "financials" | "finance" | "financial" => Self::Financials,
+            "consumerdiscretionary" | "discretionary" => Self::ConsumerDiscretionary,
+            "communicationservices" | "communication" | "comms" => Self::CommunicationServices,
+            "industrials" | "industrial" => Self::Industrials,
+            "consumerstaples" | "staples" => Self::ConsumerStaples,
+            "energy" => Self::Energy,
+            "utilities" | "utility" => Self::Utilities,
+            "realestate" | "estate" => Self::RealEstate,
+            "materials" => Self::Materials,
+            _ => return Err(UniverseError::UnknownSector(s.to_string())),
+        };
+
+        Ok(sector)
+    }
+}
+
+/// Level of the GICS hierarchy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GicsLevel {
+    /// Sector (2 digits)
+    Sector,
+    /// Industry group (4 digits)
+    IndustryGroup,
+    /// Industry (6 digits)
+    Industry,
+    /// Sub-industry (8 digits)
+    SubIndustry,
+}
+
+impl GicsLevel {
+    /// Returns all levels, from coarsest to finest.
+    pub const fn all() -> [Self; 4] {
+        [
+            Self::Sector,
+            Self::IndustryGroup,
+            Self::Industry,
+            Self::SubIndustry,
+        ]
+    }
+
+    /// Number of digits in codes at this level.
+    pub const fn digits(&self) -> u32 {
+        match self {
+            Self::Sector => 2,
+            Self::IndustryGroup => 4,
+            Self::Industry => 6,
+            Self::SubIndustry => 8,
+        }
+    }
+
+    /// The next finer level, or `None` for sub-industries.
+    pub const fn finer(&self) -> Option<Self> {
+        match self {
+            Self::Sector => Some(Self::IndustryGroup),
+            Self::IndustryGroup => Some(Self::Industry),
+            Self::Industry => Some(Self::SubIndustry),
+            Self::SubIndustry => None,
+        }
+    }
+
+    /// Returns the level name.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Sector => "sector",
+            Self::IndustryGroup => "industry group",
+            Self::Industry => "industry",
+            Self::SubIndustry => "sub-industry",
+        }
+    }
+}
+
+impl fmt::Display for GicsLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for GicsLevel {
+    type Err = UniverseError;
+
+    /// Parse a level name such as `"sector"`, `"industry-group"` or
+    /// `"sub_industry"`, ignoring case and separators.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalize(s).as_str() {
+            "sector" => Ok(Self::Sector),
+            "industrygroup" | "group" => Ok(Self::IndustryGroup),
+            "industry" => Ok(Self::Industry),
+            "subindustry" => Ok(Self::SubIndustry),
+            _ => Err(UniverseError::UnknownGicsCode(s.to_string())),
+        }
+    }
+}
+
+/// A GICS code at any level of the hierarchy, e.g. `45301020` (Semiconductors).
+///
+/// Only codes in the current GICS structure can be constructed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "u32", into = "u32")]
+pub struct GicsCode(u32);
+
+impl GicsCode {
+    /// Look up a code, returning `None` if it is not a GICS code.
+    pub fn new(code: u32) -> Option<Self> {
+        let code = Self(code);
+        code.lookup().map(|_| code)
+    }
+
+    /// The numeric code.
+    pub const fn value(&self) -> u32 {
+        self.0
+    }
+
+    /// Level of the hierarchy this code belongs to.
+    pub const fn level(&self) -> GicsLevel {
+        match self.0 {
+            0..100 => GicsLevel::Sector,
+            100..10_000 => GicsLevel::IndustryGroup,
+            10_000..1_000_000 => GicsLevel::Industry,
+            _ => GicsLevel::SubIndustry,
+        }
+    }
+
+    /// Returns the name of the sector, industry group, industry or sub-industry.
+    pub fn name(&self) -> &'static str {
+        self.lookup().expect("GicsCode holds a known code")
+    }
+
+    /// The code one level up, or `None` for a sector.
+    pub const fn parent(&self) -> Option<Self> {
+        match self.level() {
+            GicsLevel::Sector => None,
+            _ => Some(Self(self.0 / 100)),
+        }
+    }
+
+    /// The ancestor at `level`, or `None` if `level` is finer than this code.
+    ///
+    /// A code is its own ancestor at its own level.
+    pub fn at_level(&self, level: GicsLevel) -> Option<Self> {
+        let finer_by = self.level().digits().checked_sub(level.digits())?;
+        Some(Self(self.0 / 10u32.pow(finer_by)))
+    }
+
+    /// The sector this code belongs to.
+    pub fn sector(&self) -> GicsSector {
+        let sector = self
+            .at_level(GicsLevel::Sector)
+            .expect("every code has a sector");
+        GicsSector::from_code(sector.0 as u8).expect("GicsCode holds a known code")
+    }
+
+    /// Codes one level down, in code order.
+    pub fn children(&self) -> Vec<Self> {
+        self.level()
+            .finer()
+            .map(|level| {
+                Self::all(level)
+                    .into_iter()
+                    .filter(|child| child.parent() == Some(*self))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// All codes at `level`, in code order.
+    pub fn all(level: GicsLevel) -> Vec<Self> {
+        match level {
+            GicsLevel::Sector => {
+                let mut sectors: Vec<Self> =
+                    GicsSector::all().into_iter().map(Self::from).collect();
+                sectors.sort();
+                sectors
+            }
+            GicsLevel::IndustryGroup => table_codes(INDUSTRY_GROUPS),
+            GicsLevel::Industry => table_codes(INDUSTRIES),
+            GicsLevel::SubIndustry => table_codes(SUB_INDUSTRIES),
+        }
+    }
+
+    fn lookup(&self) -> Option<&'static str> {
+        let table = match self.level() {
+            GicsLevel::Sector => {
+                return u8::try_from(self.0)
+                    .ok()
+                    .and_then(GicsSector::from_code)
+                    .map(|sector| sector.name());
+            }
+            GicsLevel::IndustryGroup => INDUSTRY_GROUPS,
+            GicsLevel::Industry => INDUSTRIES,
+            GicsLevel::SubIndustry => SUB_INDUSTRIES,
+        };
+        table
+            .binary_search_by_key(&self.0, |(code, _)| *code)
+            .ok()
+            .map(|i| table[i].1)
+    }
+}
+
+impl TryFrom<u32> for GicsCode {
+    type Error = UniverseError;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        Self::new(code).ok_or_else(|| UniverseError::UnknownGicsCode(code.to_string()))
+    }
+}
+
+impl From<GicsCode> for u32 {
+    fn from(code: GicsCode) -> Self {
+        code.0
+    }
+}
+
+impl fmt::Display for GicsCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for GicsCode {
+    type Err = UniverseError;
+
+    /// Parse a 2, 4, 6 or 8-digit code, or the name of a sub-industry,
+    /// industry, industry group or sector (finest level wins on a tie).
+    ///
+    /// Names match ignoring case, spaces, underscores and hyphens.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let unknown = || UniverseError::UnknownGicsCode(s.to_string());
+
+        if !trimmed.is_empty() && trimmed.chars().all(|c| c.is_ascii_digit()) {
+            if !matches!(trimmed.len(), 2 | 4 | 6 | 8) || trimmed.starts_with('0') {
+                return Err(unknown());
+            }
+            return trimmed
+                .parse::<u32>()
+                .ok()
+                .and_then(Self::new)
+                .ok_or_else(unknown);
+        }
+
+        let name = normalize(trimmed);
+        GicsLevel::all()
+            .into_iter()
+            .rev()
+            .flat_map(Self::all)
+            .find(|code| normalize(code.name()) == name)
+            .ok_or_else(unknown)
+    }
+}
+
+/// Codes of a level table.
+fn table_codes(table: &[(u32, &str)]) -> Vec<GicsCode> {
+    table.iter().map(|(code, _)| GicsCode(*code)).collect()
+}
+
+/// Lowercase and strip spaces, underscores and hyphens for lenient matching.
+pub(crate) fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// GICS industry groups (level 2).
+const INDUSTRY_GROUPS: &[(u32, &str)] = &[
+    (1010, "Energy"),
+    (1510, "Materials"),
+    (2010, "Capital Goods"),
+    (2020, "Commercial & Professional Services"),
+    (2030, "Transportation"),
+    (2510, "Automobiles & Components"),
+    (2520, "Consumer Durables & Apparel"),
+    (2530, "Consumer Services"),
+    (2550, "Consumer Discretionary Distribution & Retail"),
+    (3010, "Consumer Staples Distribution & Retail"),
+    (3020, "Food, Beverage & Tobacco"),
+    (3030, "Household & Personal Products"),
+    (3510, "Health Care Equipment & Services"),
+    (3520, "Pharmaceuticals, Biotechnology & Life Sciences"),
+    (4010, "Banks"),
+    (4020, "Financial Services"),
+    (4030, "Insurance"),
+    (4510, "Software & Services"),
+    (4520, "Technology Hardware & Equipment"),
+    (4530, "Semiconductors & Semiconductor Equipment"),
+    (5010, "Telecommunication Services"),
+    (5020, "Media & Entertainment"),
+    (5510, "Utilities"),
+    (6010, "Equity Real Estate Investment Trusts (REITs)"),
+    (6020, "Real Estate Management & Development"),
+];
+
+/// GICS industries (level 3).
+const INDUSTRIES: &[(u32, &str)] = &[
+    (101010, "Energy Equipment & Services"),
+    (101020, "Oil, Gas & Consumable Fuels"),
+    (151010, "Chemicals"),
+    (151020, "Construction Materials"),
+    (151030, "Containers & Packaging"),
+    (151040, "Metals & Mining"),
+    (151050, "Paper & Forest Products"),
+    (201010, "Aerospace & Defense"),
+    (201020, "Building Products"),
+    (201030, "Construction & Engineering"),
+    (201040, "Electrical Equipment"),
+    (201050, "Industrial Conglomerates"),
+    (201060, "Machinery"),
+    (201070, "Trading Companies & Distributors"),
+    (202010, "Commercial Services & Supplies"),
+    (202020, "Professional Services"),
+    (203010, "Air Freight & Logistics"),
+    (203020, "Passenger Airlines"),
+    (203030, "Marine Transportation"),
+    (203040, "Ground Transportation"),
+    (203050, "Transportation Infrastructure"),
+    (251010, "Automobile Components"),
+    (251020, "Automobiles"),
+    (252010, "Household Durables"),
+    (252020, "Leisure Products"),
+    (252030, "Textiles, Apparel & Luxury Goods"),
+    (253010, "Hotels, Restaurants & Leisure"),
+    (253020, "Diversified Consumer Services"),
+    (255010, "Distributors"),
+    (255030, "Broadline Retail"),
+    (255040, "Specialty Retail"),
+    (301010, "Consumer Staples Distribution & Retail"),
+    (302010, "Beverages"),
+    (302020, "Food Products"),
+    (302030, "Tobacco"),
+    (303010, "Household Products"),
+    (303020, "Personal Care Products"),
+    (351010, "Health Care Equipment & Supplies"),
+    (351020, "Health Care Providers & Services"),
+    (351030, "Health Care Technology"),
+    (352010, "Biotechnology"),
+    (352020, "Pharmaceuticals"),
+    (352030, "Life Sciences Tools & Services"),
+    (401010, "Banks"),
+    (402010, "Financial Services"),
+    (402020, "Consumer Finance"),
+    (402030, "Capital Markets"),
+    (402040, "Mortgage Real Estate Investment Trusts (REITs)"),
+    (403010, "Insurance"),
+    (451020, "IT Services"),
+    (451030, "Software"),
+    (452010, "Communications Equipment"),
+    (452020, "Technology Hardware, Storage & Peripherals"),
+    (452030, "Electronic Equipment, Instruments & Components"),
+    (453010, "Semiconductors & Semiconductor Equipment"),
+    (501010, "Diversified Telecommunication Services"),
+    (501020, "Wireless Telecommunication Services"),
+    (502010, "Media"),
+    (502020, "Entertainment"),
+    (502030, "Interactive Media & Services"),
+    (551010, "Electric Utilities"),
+    (551020, "Gas Utilities"),
+    (551030, "Multi-Utilities"),
+    (551040, "Water Utilities"),
+    (
+        551050,
+        "Independent Power and Renewable Electricity Producers",
+    ),
+    (601010, "Diversified REITs"),
+    (601025, "Industrial REITs"),
+    (601030, "Hotel & Resort REITs"),
+    (601040, "Office REITs"),
+    (601050, "Health Care REITs"),
+    (601060, "Residential REITs"),
+    (601070, "Retail REITs"),
+    (601080, "Specialized REITs"),
+    (602010, "Real Estate Management & Development"),
+];
+
+/// GICS sub-industries (level 4).
+const SUB_INDUSTRIES: &[(u32, &str)] = &[
+    (10101010, "Oil & Gas Drilling"),
+    (10101020, "Oil & Gas Equipment & Services"),
+    (10102010, "Integrated Oil & Gas"),
+    (10102020, "Oil & Gas Exploration & Production"),
+    (10102030, "Oil & Gas Refining & Marketing"),
+    (10102040, "Oil & Gas Storage & Transportation"),
+    (10102050, "Coal & Consumable Fuels"),
+    (15101010, "Commodity Chemicals"),
+    (15101020, "Diversified Chemicals"),
+    (15101030, "Fertilizers & Agricultural Chemicals"),
+    (15101040, "Industrial Gases"),
+    (15101050, "Specialty Chemicals"),
+    (15102010, "Construction Materials"),
+    (15103010, "Metal, Glass & Plastic Containers"),
+    (15103020, "Paper & Plastic Packaging Products & Materials"),
+    (15104010, "Aluminum"),
+    (15104020, "Diversified Metals & Mining"),
+    (15104025, "Copper"),
+    (15104030, "Gold"),
+    (15104040, "Precious Metals & Minerals"),
+    (15104045, "Silver"),
+    (15104050, "Steel"),
+    (15105010, "Forest Products"),
+    (15105020, "Paper Products"),
+    (20101010, "Aerospace & Defense"),
+    (20102010, "Building Products"),
+    (20103010, "Construction & Engineering"),
+    (20104010, "Electrical Components & Equipment"),
+    (20104020, "Heavy Electrical Equipment"),
+    (20105010, "Industrial Conglomerates"),
+    (
+        20106010,
+        "Construction Machinery & Heavy Transportation Equipment",
+    ),
+    (20106015, "Agricultural & Farm Machinery"),
+    (20106020, "Industrial Machinery & Supplies & Components"),
+    (20107010, "Trading Companies & Distributors"),
+    (20201010, "Commercial Printing"),
+    (20201050, "Environmental & Facilities Services"),
+    (20201060, "Office Services & Supplies"),
+    (20201070, "Diversified Support Services"),
+    (20201080, "Security & Alarm Services"),
+    (20202010, "Human Resource & Employment Services"),
+    (20202020, "Research & Consulting Services"),
+    (20202030, "Data Processing & Outsourced Services"),
+    (20301010, "Air Freight & Logistics"),
+    (20302010, "Passenger Airlines"),
+    (20303010, "Marine Transportation"),
+    (20304010, "Rail Transportation"),
+    (20304030, "Cargo Ground Transportation"),
+    (20304040, "Passenger Ground Transportation"),
+    (20305010, "Airport Services"),
+    (20305020, "Highways & Railtracks"),
+    (20305030, "Marine Ports & Services"),
+    (25101010, "Automotive Parts & Equipment"),
+    (25101020, "Tires & Rubber"),
+    (25102010, "Automobile Manufacturers"),
+    (25102020, "Motorcycle Manufacturers"),
+    (25201010, "Consumer Electronics"),
+    (25201020, "Home Furnishings"),
+    (25201030, "Homebuilding"),
+    (25201040, "Household Appliances"),
+    (25201050, "Housewares & Specialties"),
+    (25202010, "Leisure Products"),
+    (25203010, "Apparel, Accessories & Luxury Goods"),
+    (25203020, "Footwear"),
+    (25203030, "Textiles"),
+    (25301010, "Casinos & Gaming"),
+    (25301020, "Hotels, Resorts & Cruise Lines"),
+    (25301030, "Leisure Facilities"),
+    (25301040, "Restaurants"),
+    (25302010, "Education Services"),
+    (25302020, "Specialized Consumer Services"),
+    (25501010, "Distributors"),
+    (25503030, "Broadline Retail"),
+    (25504010, "Apparel Retail"),
+    (25504020, "Computer & Electronics Retail"),
+    (25504030, "Home Improvement Retail"),
+    (25504040, "Other Specialty Retail"),
+    (25504050, "Automotive Retail"),
+    (25504060, "Homefurnishing Retail"),
+    (30101010, "Drug Retail"),
+    (30101020, "Food Distributors"),
+    (30101030, "Food Retail"),
+    (30101040, "Consumer Staples Merchandise Retail"),
+    (30201010, "Brewers"),
+    (30201020, "Distillers & Vintners"),
+    (30201030, "Soft Drinks & Non-alcoholic Beverages"),
+    (30202010, "Agricultural Products & Services"),
+    (30202030, "Packaged Foods & Meats"),
+    (30203010, "Tobacco"),
+    (30301010, "Household Products"),
+    (30302010, "Personal Care Products"),
+    (35101010, "Health Care Equipment"),
+    (35101020, "Health Care Supplies"),
+    (35102010, "Health Care Distributors"),
+    (35102015, "Health Care Services"),
+    (35102020, "Health Care Facilities"),
+    (35102030, "Managed Health Care"),
+    (35103010, "Health Care Technology"),
+    (35201010, "Biotechnology"),
+    (35202010, "Pharmaceuticals"),
+    (35203010, "Life Sciences Tools & Services"),
+    (40101010, "Diversified Banks"),
+    (40101015, "Regional Banks"),
+    (40201020, "Diversified Financial Services"),
+    (40201030, "Multi-Sector Holdings"),
+    (40201040, "Specialized Finance"),
+    (40201050, "Commercial & Residential Mortgage Finance"),
+    (40201060, "Transaction & Payment Processing Services"),
+    (40202010, "Consumer Finance"),
+    (40203010, "Asset Management & Custody Banks"),
+    (40203020, "Investment Banking & Brokerage"),
+    (40203030, "Diversified Capital Markets"),
+    (40203040, "Financial Exchanges & Data"),
+    (40204010, "Mortgage REITs"),
+    (40301010, "Insurance Brokers"),
+    (40301020, "Life & Health Insurance"),
+    (40301030, "Multi-line Insurance"),
+    (40301040, "Property & Casualty Insurance"),
+    (40301050, "Reinsurance"),
+    (45102010, "IT Consulting & Other Services"),
+    (45102030, "Internet Services & Infrastructure"),
+    (45103010, "Application Software"),
+    (45103020, "Systems Software"),
+    (45201020, "Communications Equipment"),
+    (45202030, "Technology Hardware, Storage & Peripherals"),
+    (45203010, "Electronic Equipment & Instruments"),
+    (45203015, "Electronic Components"),
+    (45203020, "Electronic Manufacturing Services"),
+    (45203030, "Technology Distributors"),
+    (45301010, "Semiconductor Materials & Equipment"),
+    (45301020, "Semiconductors"),
+    (50101010, "Alternative Carriers"),
+    (50101020, "Integrated Telecommunication Services"),
+    (50102010, "Wireless Telecommunication Services"),
+    (50201010, "Advertising"),
+    (50201020, "Broadcasting"),
+    (50201030, "Cable & Satellite"),
+    (50201040, "Publishing"),
+    (50202010, "Movies & Entertainment"),
+    (50202020, "Interactive Home Entertainment"),
+    (50203010, "Interactive Media & Services"),
+    (55101010, "Electric Utilities"),
+    (55102010, "Gas Utilities"),
+    (55103010, "Multi-Utilities"),
+    (55104010, "Water Utilities"),
+    (55105010, "Independent Power Producers & Energy Traders"),
+    (55105020, "Renewable Electricity"),
+    (60101010, "Diversified REITs"),
+    (60102510, "Industrial REITs"),
+    (60103010, "Hotel & Resort REITs"),
+    (60104010, "Office REITs"),
+    (60105010, "Health Care REITs"),
+    (60106010, "Multi-Family Residential REITs"),
+    (60106020, "Single-Family Residential REITs"),
+    (60107010, "Retail REITs"),
+    (60108010, "Other Specialized REITs"),
+    (60108020, "Self-Storage REITs"),
+    (60108030, "Telecom Tower REITs"),
+    (60108040, "Timber REITs"),
+    (60108050, "Data Center REITs"),
+    (60201010, "Diversified Real Estate Activities"),
+    (60201020, "Real Estate Operating Companies"),
+    (60201030, "Real Estate Development"),
+    (60201040, "Real Estate Services"),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,6 +719,17 @@ mod tests {
         assert_eq!(GicsSector::from_code(99), None);
     }
 
+    #[test]
+    fn test_from_str() {
+        let parse = |s: &str| s.parse::<GicsSector>().ok();
+        assert_eq!(parse("Health Care"), Some(GicsSector::HealthCare));
+        assert_eq!(parse("health_care"), Some(GicsSector::HealthCare));
+        assert_eq!(parse("IT"), Some(GicsSector::InformationTechnology));
+        assert_eq!(parse("60"), Some(GicsSector::RealEstate));
+        assert_eq!(parse("99"), None);
+        assert_eq!(parse("Crypto"), None);
+    }
+
     #[test]
     fn test_display() {
         assert_eq!(
@@ -152,4 +738,83 @@ mod tests {
         );
         assert_eq!(format!("{}", GicsSector::Energy), "Energy");
     }
+
+    #[test]
+    fn test_gics_code_levels() {
+        let semis = GicsCode::new(45301020).unwrap();
+        assert_eq!(semis.level(), GicsLevel::SubIndustry);
+        assert_eq!(semis.name(), "Semiconductors");
+        assert_eq!(semis.sector(), GicsSector::InformationTechnology);
+
+        let industry = semis.parent().unwrap();
+        assert_eq!(industry.value(), 453010);
+        assert_eq!(industry.level(), GicsLevel::Industry);
+        assert_eq!(
+            semis.at_level(GicsLevel::IndustryGroup).map(|c| c.value()),
+            Some(4530)
+        );
+        assert_eq!(
+            semis.at_level(GicsLevel::Sector),
+            Some(GicsCode::from(GicsSector::InformationTechnology))
+        );
+        assert_eq!(industry.at_level(GicsLevel::SubIndustry), None);
+        assert_eq!(GicsCode::from(GicsSector::Energy).parent(), None);
+
+        assert_eq!(GicsCode::new(45301099), None);
+        assert_eq!(GicsCode::new(99), None);
+    }
+
+    #[test]
+    fn test_gics_hierarchy_is_complete() {
+        assert_eq!(GicsCode::all(GicsLevel::Sector).len(), 11);
+        assert_eq!(GicsCode::all(GicsLevel::IndustryGroup).len(), 25);
+        assert_eq!(GicsCode::all(GicsLevel::Industry).len(), 74);
+        assert_eq!(GicsCode::all(GicsLevel::SubIndustry).len(), 163);
+
+        for level in [
+            GicsLevel::IndustryGroup,
+            GicsLevel::Industry,
+            GicsLevel::SubIndustry,
+        ] {
+            for code in GicsCode::all(level) {
+                let parent = code.parent().unwrap();
+                assert!(
+                    GicsCode::new(parent.value()).is_some(),
+                    "{code} has no parent"
+                );
+            }
+        }
+
+        let it_services = GicsCode::new(451020).unwrap();
+        assert_eq!(
+            it_services
+                .children()
+                .iter()
+                .map(|c| c.value())
+                .collect::<Vec<_>>(),
+            vec![45102010, 45102030]
+        );
+    }
+
+    #[test]
+    fn test_gics_code_from_str() {
+        let parse = |s: &str| s.parse::<GicsCode>().ok().map(|c| c.value());
+        assert_eq!(parse("45301020"), Some(45301020));
+        assert_eq!(parse(" 4530 "), Some(4530));
+        assert_eq!(parse("semiconductors"), Some(45301020));
+        assert_eq!(parse("IT Services"), Some(451020));
+        assert_eq!(parse("Health Care"), Some(35));
+        assert_eq!(parse("0045"), None);
+        assert_eq!(parse("453"), None);
+        assert_eq!(parse("Crypto Mining"), None);
+
+        assert_eq!(
+            "sub_industry".parse::<GicsLevel>().ok(),
+            Some(GicsLevel::SubIndustry)
+        );
+        assert_eq!(
+            "Industry-Group".parse::<GicsLevel>().ok(),
+            Some(GicsLevel::IndustryGroup)
+        );
+    }
 }
@@ -0,0 +1,138 @@
+//! Point-in-time universe membership.
+//!
+//! A [`MembershipHistory`] records when each symbol joined and left a
+//! universe, so a cross-section on any date contains only the names that were
+//! members then. Estimating over today's constituents instead silently drops
+//! every company that was removed or went bankrupt along the way.
+
+use chrono::NaiveDate;
+use perth_data::cache::{SqliteCache, UniverseMembership};
+use std::collections::BTreeMap;
+
+/// Dated membership spells per symbol.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MembershipHistory {
+    spells: BTreeMap<String, Vec<UniverseMembership>>,
+}
+
+impl MembershipHistory {
+    /// Build a history from membership spells.
+    pub fn new(memberships: impl IntoIterator<Item = UniverseMembership>) -> Self {
+        let mut spells: BTreeMap<String, Vec<UniverseMembership>> = BTreeMap::new();
+        for membership in memberships {
+            spells
+                .entry(membership.symbol.clone())
+                .or_default()
+                .push(membership);
+        }
+        for symbol_spells in spells.values_mut() {
+            symbol_spells.sort_by_key(|m| m.start);
+        }
+        Self { spells }
+    }
+
+    /// Load the memberships stored in the cache's universe table.
+    pub fn load(cache: &SqliteCache) -> perth_data::Result<Self> {
+        Ok(Self::new(cache.get_universe_memberships()?))
+    }
+
+    /// Store the memberships in the cache's universe table.
+    pub fn save(&self, cache: &SqliteCache) -> perth_data::Result<()> {
+        let memberships: Vec<_> = self.memberships().cloned().collect();
+        cache.put_universe_memberships(&memberships)
+    }
+
+    /// Whether no spells are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.spells.is_empty()
+    }
+
+    /// Whether any spell has a start or end date.
+    pub fn is_dated(&self) -> bool {
+        self.memberships()
+            .any(|m| m.start.is_some() || m.end.is_some())
+    }
+
+    /// All spells, ordered by symbol and start date.
+    pub fn memberships(&self) -> impl Iterator<Item = &UniverseMembership> {
+        self.spells.values().flatten()
+    }
+
+    /// Spells recorded for one symbol.
+    pub fn spells(&self, symbol: &str) -> &[UniverseMembership] {
+        self.spells.get(symbol).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every symbol that was ever a member, sorted.
+    pub fn symbols(&self) -> Vec<String> {
+        self.spells.keys().cloned().collect()
+    }
+
+    /// Symbols that were members on `date`, sorted.
+    pub fn symbols_on(&self, date: NaiveDate) -> Vec<String> {
+        self.spells
+            .iter()
+            .filter(|(_, spells)| spells.iter().any(|m| m.contains(date)))
+            .map(|(symbol, _)| symbol.clone())
+            .collect()
+    }
+
+    /// Whether `symbol` was a member on `date`.
+    pub fn contains_on(&self, symbol: &str, date: NaiveDate) -> bool {
+        self.spells(symbol).iter().any(|m| m.contains(date))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn history() -> MembershipHistory {
+        MembershipHistory::new([
+            UniverseMembership::new("AAPL", None),
+            UniverseMembership {
+                end: Some(date(2020, 9, 18)),
+                ..UniverseMembership::new("XOM", Some(date(2010, 1, 4)))
+            },
+            UniverseMembership::new("XOM", Some(date(2021, 3, 22))),
+            UniverseMembership::new("TSLA", Some(date(2020, 12, 21))),
+        ])
+    }
+
+    #[test]
+    fn test_symbols_on() {
+        let history = history();
+
+        assert_eq!(history.symbols_on(date(2019, 1, 2)), vec!["AAPL", "XOM"]);
+        assert_eq!(history.symbols_on(date(2021, 1, 4)), vec!["AAPL", "TSLA"]);
+        assert_eq!(
+            history.symbols_on(date(2022, 1, 3)),
+            vec!["AAPL", "TSLA", "XOM"]
+        );
+        assert_eq!(history.symbols(), vec!["AAPL", "TSLA", "XOM"]);
+    }
+
+    #[test]
+    fn test_contains_on() {
+        let history = history();
+
+        assert!(history.contains_on("XOM", date(2020, 9, 18)));
+        assert!(!history.contains_on("XOM", date(2020, 9, 21)));
+        assert!(!history.contains_on("TSLA", date(2020, 12, 18)));
+        assert!(!history.contains_on("MSFT", date(2020, 12, 18)));
+        assert!(history.is_dated());
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let history = history();
+
+        history.save(&cache).unwrap();
+        assert_eq!(MembershipHistory::load(&cache).unwrap(), history);
+    }
+}
@@ -1,19 +1,95 @@
 //! Universe management for Perth factor model.
 //!
 //! This module provides functionality for managing stock universes,
-//! including the S&P 500 and GICS sector classifications.
+//! including the S&P 500, universes loaded from files, ETF holdings or built
+//! from liquidity rules, GICS classifications down to the sub-industry, the
+//! country each symbol is listed in and the changes between universes.
 
+pub mod country;
+pub mod diff;
+pub mod error;
+pub mod etf;
+pub mod extended;
+pub mod file;
 pub mod gics;
+pub mod membership;
+pub mod rules;
+pub mod sic;
 pub mod sp500;
 
-pub use gics::GicsSector;
+use chrono::NaiveDate;
+pub use country::Country;
+pub use diff::UniverseSnapshot;
+pub use error::UniverseError;
+pub use etf::{EtfHolding, EtfHoldings};
+pub use extended::ExtendedUniverse;
+pub use file::{FileUniverse, UniverseFormat};
+pub use gics::{GicsCode, GicsLevel, GicsSector};
+pub use membership::MembershipHistory;
+pub use rules::{RankBy, Reconstitution, SecurityMetrics, UniverseRules};
+pub use sic::{SicClassification, SicClassifier};
 pub use sp500::{Constituent, SP500Universe};
+use std::collections::HashMap;
 
 /// Trait for stock universes.
 pub trait Universe {
+    /// Display name of the universe.
+    fn name(&self) -> &str;
+
     /// Get all symbols in the universe.
     fn symbols(&self) -> Vec<String>;
 
+    /// Get the GICS sector for a symbol.
+    fn sector(&self, symbol: &str) -> Option<GicsSector>;
+
+    /// Get the GICS sub-industry for a symbol, if the universe records one.
+    fn sub_industry(&self, _symbol: &str) -> Option<GicsCode> {
+        None
+    }
+
+    /// Get a symbol's GICS classification at `level`.
+    ///
+    /// Levels below the sector need the sub-industry, so this is `None` for
+    /// symbols without one.
+    fn classification(&self, symbol: &str, level: GicsLevel) -> Option<GicsCode> {
+        match level {
+            GicsLevel::Sector => self.sector(symbol).map(GicsCode::from),
+            _ => self.sub_industry(symbol)?.at_level(level),
+        }
+    }
+
+    /// Get the country a symbol is listed in.
+    ///
+    /// Defaults to the country implied by the symbol's exchange suffix, which
+    /// is the US for plain tickers.
+    fn country(&self, symbol: &str) -> Country {
+        Country::from_symbol(symbol)
+    }
+
+    /// Get the benchmark weight for a symbol, if the universe is weighted.
+    fn weight(&self, _symbol: &str) -> Option<f64> {
+        None
+    }
+
+    /// Get all symbols in a specific sector.
+    fn symbols_in_sector(&self, sector: GicsSector) -> Vec<String> {
+        self.symbols()
+            .into_iter()
+            .filter(|s| self.sector(s) == Some(sector))
+            .collect()
+    }
+
+    /// Get the count of constituents per sector.
+    fn sector_counts(&self) -> HashMap<GicsSector, usize> {
+        let mut counts = HashMap::new();
+        for symbol in self.symbols() {
+            if let Some(sector) = self.sector(&symbol) {
+                *counts.entry(sector).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
     /// Check if a symbol is in the universe.
     fn contains(&self, symbol: &str) -> bool {
         self.symbols().contains(&symbol.to_string())
@@ -23,12 +99,53 @@ pub trait Universe {
     fn size(&self) -> usize {
         self.symbols().len()
     }
+
+    /// Get the symbols that were members on `date`.
+    ///
+    /// Universes without membership history treat every symbol as a member
+    /// on every date.
+    fn symbols_on(&self, _date: NaiveDate) -> Vec<String> {
+        self.symbols()
+    }
+
+    /// Check if a symbol was a member on `date`.
+    fn contains_on(&self, symbol: &str, _date: NaiveDate) -> bool {
+        self.contains(symbol)
+    }
 }
 
 impl Universe for SP500Universe {
+    fn name(&self) -> &str {
+        "S&P 500"
+    }
+
     fn symbols(&self) -> Vec<String> {
         self.symbols()
     }
+
+    fn symbols_on(&self, date: NaiveDate) -> Vec<String> {
+        self.symbols_on(date)
+    }
+
+    fn contains_on(&self, symbol: &str, date: NaiveDate) -> bool {
+        self.contains_on(symbol, date)
+    }
+
+    fn sector(&self, symbol: &str) -> Option<GicsSector> {
+        self.sector(symbol)
+    }
+
+    fn sub_industry(&self, symbol: &str) -> Option<GicsCode> {
+        self.sub_industry(symbol)
+    }
+
+    fn symbols_in_sector(&self, sector: GicsSector) -> Vec<String> {
+        self.symbols_in_sector(sector)
+    }
+
+    fn sector_counts(&self) -> HashMap<GicsSector, usize> {
+        self.sector_counts()
+    }
 }
 
 #[cfg(test)]
@@ -43,4 +160,16 @@ mod tests {
         assert!(!universe.contains("NOTREAL"));
         assert!(universe.size() >= 100);
     }
+
+    #[test]
+    fn test_classification_levels() {
+        let universe: &dyn Universe = &SP500Universe::new();
+        let code = |level| universe.classification("AMD", level).map(|c| c.value());
+
+        assert_eq!(code(GicsLevel::Sector), Some(45));
+        assert_eq!(code(GicsLevel::IndustryGroup), Some(4530));
+        assert_eq!(code(GicsLevel::Industry), Some(453010));
+        assert_eq!(code(GicsLevel::SubIndustry), Some(45301020));
+        assert_eq!(universe.classification("NOTREAL", GicsLevel::Sector), None);
+    }
 }
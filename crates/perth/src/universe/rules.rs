@@ -0,0 +1,608 @@
+//! Rule-based universes.
+//!
+//! Instead of a fixed list, a universe can be defined by rules such as "the
+//! top 1000 stocks by 3-month median dollar volume, priced above $5".
+//! [`UniverseRules`] evaluates those rules point in time against cached
+//! quotes and market caps on a reconstitution schedule, and records the
+//! result as the dated [`MembershipHistory`] the rest of the pipeline uses.
+//!
+//! Each reconstitution only looks at data up to the previous session, so a
+//! membership never depends on prices from the day it takes effect. A buffer
+//! zone around the size cutoff lets incumbents that slip just below it stay
+//! in, which keeps turnover down when names hover around the boundary.
+//!
+//! Rules can be written in TOML:
+//!
+//! ```toml
+//! name = "US Liquid 1000"
+//! size = 1000
+//! rank_by = "dollar-volume"
+//! lookback_days = 63
+//! min_price = 5.0
+//! buffer = 0.2
+//! reconstitution = "quarterly"
+//! ```
+
+use crate::universe::error::UniverseError;
+use crate::universe::membership::MembershipHistory;
+use chrono::{Datelike, NaiveDate};
+use perth_data::cache::{SqliteCache, UniverseMembership};
+use perth_data::calendar::{ExchangeCalendar, NyseCalendar};
+use perth_data::error::DataError;
+use polars::prelude::*;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Quantity used to rank eligible securities.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RankBy {
+    /// Median daily dollar volume over the lookback window.
+    #[default]
+    DollarVolume,
+    /// Latest market capitalization.
+    MarketCap,
+}
+
+/// How often the universe is rebuilt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Reconstitution {
+    /// First trading day of every month.
+    Monthly,
+    /// First trading day of January, April, July and October.
+    #[default]
+    Quarterly,
+    /// First trading day of January and July.
+    SemiAnnual,
+    /// First trading day of January.
+    Annual,
+}
+
+impl Reconstitution {
+    /// Months between reconstitutions.
+    pub const fn months(self) -> u32 {
+        match self {
+            Self::Monthly => 1,
+            Self::Quarterly => 3,
+            Self::SemiAnnual => 6,
+            Self::Annual => 12,
+        }
+    }
+
+    /// Reconstitution dates in `[start, end]`.
+    ///
+    /// The first trading day on or after `start` always starts the schedule,
+    /// so the universe is defined from the beginning of the range.
+    pub fn dates(self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        let first = if NyseCalendar.is_trading_day(start) {
+            start
+        } else {
+            NyseCalendar.next_trading_day(start)
+        };
+        if first > end {
+            return Vec::new();
+        }
+
+        let mut dates = vec![first];
+        let mut month = start.with_day(1).expect("first of month");
+        loop {
+            month = month
+                .checked_add_months(chrono::Months::new(1))
+                .expect("date in range");
+            if !(month.month() - 1).is_multiple_of(self.months()) {
+                continue;
+            }
+            let day = NyseCalendar.n_trading_days_forward(month.pred_opt().expect("date"), 1);
+            if day > end {
+                break;
+            }
+            if day > first {
+                dates.push(day);
+            }
+        }
+        dates
+    }
+}
+
+/// Liquidity and size measures for one security on one date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecurityMetrics {
+    /// Ticker symbol
+    pub symbol: String,
+    /// Latest close
+    pub price: f64,
+    /// Median daily close times volume over the lookback window
+    pub median_dollar_volume: f64,
+    /// Latest market capitalization, if cached
+    pub market_cap: Option<f64>,
+}
+
+impl SecurityMetrics {
+    /// Compute metrics from quotes covering the lookback window.
+    ///
+    /// Uses the unadjusted `close`, since price floors and dollar volume are
+    /// about what traded at the time. Returns `None` for an empty window.
+    pub fn from_quotes(
+        symbol: impl Into<String>,
+        quotes: &DataFrame,
+        market_cap: Option<f64>,
+    ) -> Result<Option<Self>, PolarsError> {
+        let quotes = quotes.sort(["date"], SortMultipleOptions::default())?;
+        let closes = quotes.column("close")?.cast(&DataType::Float64)?;
+        let volumes = quotes.column("volume")?.cast(&DataType::Float64)?;
+
+        let mut dollar_volumes: Vec<f64> = closes
+            .f64()?
+            .into_iter()
+            .zip(volumes.f64()?)
+            .filter_map(|(close, volume)| Some(close? * volume?))
+            .collect();
+        let Some(price) = closes.f64()?.into_iter().flatten().last() else {
+            return Ok(None);
+        };
+        if dollar_volumes.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            symbol: symbol.into(),
+            price,
+            median_dollar_volume: median(&mut dollar_volumes),
+            market_cap,
+        }))
+    }
+
+    /// Value of the ranking measure, if available.
+    pub const fn rank_value(&self, rank_by: RankBy) -> Option<f64> {
+        match rank_by {
+            RankBy::DollarVolume => Some(self.median_dollar_volume),
+            RankBy::MarketCap => self.market_cap,
+        }
+    }
+}
+
+/// Median of a non-empty slice.
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Rules defining a universe by liquidity, price and size.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UniverseRules {
+    /// Display name of the universe
+    pub name: String,
+    /// Number of members to keep (`None` keeps every eligible security)
+    pub size: Option<usize>,
+    /// Quantity used to rank eligible securities
+    pub rank_by: RankBy,
+    /// Trading days in the liquidity window (63 is about three months)
+    pub lookback_days: u32,
+    /// Minimum latest close
+    pub min_price: Option<f64>,
+    /// Minimum median daily dollar volume
+    pub min_dollar_volume: Option<f64>,
+    /// Minimum market capitalization
+    pub min_market_cap: Option<f64>,
+    /// Fraction of `size` past the cutoff within which incumbents stay
+    pub buffer: f64,
+    /// How often the universe is rebuilt
+    pub reconstitution: Reconstitution,
+    /// Symbols to consider (empty means every symbol with cached quotes)
+    pub candidates: Vec<String>,
+}
+
+impl Default for UniverseRules {
+    fn default() -> Self {
+        Self {
+            name: "Rule-based universe".to_string(),
+            size: None,
+            rank_by: RankBy::default(),
+            lookback_days: 63,
+            min_price: None,
+            min_dollar_volume: None,
+            min_market_cap: None,
+            buffer: 0.0,
+            reconstitution: Reconstitution::default(),
+            candidates: Vec::new(),
+        }
+    }
+}
+
+impl UniverseRules {
+    /// Rules with no filters, rebuilt quarterly.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Parse rules from a TOML string.
+    pub fn from_toml_str(content: &str) -> Result<Self, UniverseError> {
+        let rules: Self = toml::from_str(content)?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// Load rules from a TOML file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, UniverseError> {
+        Self::from_toml_str(&std::fs::read_to_string(path)?)
+    }
+
+    /// Keep the top `size` securities by the ranking measure.
+    pub const fn with_size(mut self, size: usize) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Set the ranking measure.
+    pub const fn with_rank_by(mut self, rank_by: RankBy) -> Self {
+        self.rank_by = rank_by;
+        self
+    }
+
+    /// Set the liquidity window in trading days.
+    pub const fn with_lookback_days(mut self, days: u32) -> Self {
+        self.lookback_days = days;
+        self
+    }
+
+    /// Require a minimum latest close.
+    pub const fn with_min_price(mut self, price: f64) -> Self {
+        self.min_price = Some(price);
+        self
+    }
+
+    /// Require a minimum median daily dollar volume.
+    pub const fn with_min_dollar_volume(mut self, dollar_volume: f64) -> Self {
+        self.min_dollar_volume = Some(dollar_volume);
+        self
+    }
+
+    /// Require a minimum market capitalization.
+    pub const fn with_min_market_cap(mut self, market_cap: f64) -> Self {
+        self.min_market_cap = Some(market_cap);
+        self
+    }
+
+    /// Let incumbents stay while ranked within `size * (1 + buffer)`.
+    pub const fn with_buffer(mut self, buffer: f64) -> Self {
+        self.buffer = buffer;
+        self
+    }
+
+    /// Set the reconstitution schedule.
+    pub const fn with_reconstitution(mut self, reconstitution: Reconstitution) -> Self {
+        self.reconstitution = reconstitution;
+        self
+    }
+
+    /// Restrict the symbols considered.
+    pub fn with_candidates(mut self, candidates: impl IntoIterator<Item = String>) -> Self {
+        self.candidates = candidates.into_iter().collect();
+        self
+    }
+
+    /// Check that the rules are usable.
+    pub fn validate(&self) -> Result<(), UniverseError> {
+        let invalid = |reason: &str| Err(UniverseError::InvalidRules(reason.to_string()));
+        if self.size == Some(0) {
+            return invalid("size must be positive");
+        }
+        if self.lookback_days == 0 {
+            return invalid("lookback_days must be positive");
+        }
+        if !(self.buffer >= 0.0 && self.buffer.is_finite()) {
+            return invalid("buffer must be a non-negative fraction");
+        }
+        Ok(())
+    }
+
+    /// Whether a security passes the filters and can be ranked.
+    pub fn is_eligible(&self, metrics: &SecurityMetrics) -> bool {
+        self.min_price.is_none_or(|min| metrics.price >= min)
+            && self
+                .min_dollar_volume
+                .is_none_or(|min| metrics.median_dollar_volume >= min)
+            && self
+                .min_market_cap
+                .is_none_or(|min| metrics.market_cap.is_some_and(|cap| cap >= min))
+            && metrics.rank_value(self.rank_by).is_some()
+    }
+
+    /// Pick the members from one date's metrics, sorted by symbol.
+    ///
+    /// Incumbents ranked within `size * (1 + buffer)` are kept first, best
+    /// ranked first; the remaining slots go to the best-ranked newcomers.
+    pub fn select(&self, metrics: &[SecurityMetrics], incumbents: &HashSet<String>) -> Vec<String> {
+        let mut ranked: Vec<(&str, f64)> = metrics
+            .iter()
+            .filter(|m| self.is_eligible(m))
+            .filter_map(|m| Some((m.symbol.as_str(), m.rank_value(self.rank_by)?)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        let ranked: Vec<&str> = ranked.into_iter().map(|(s, _)| s).collect();
+        let mut selected: Vec<String> = self
+            .size
+            .map_or_else(
+                || ranked.clone(),
+                |size| self.cut(&ranked, size, incumbents),
+            )
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        selected.sort();
+        selected
+    }
+
+    /// Apply the size cutoff and buffer zone to symbols in rank order.
+    fn cut<'a>(
+        &self,
+        ranked: &[&'a str],
+        size: usize,
+        incumbents: &HashSet<String>,
+    ) -> Vec<&'a str> {
+        let buffered = (size as f64 * (1.0 + self.buffer)).floor() as usize;
+        let mut members: Vec<&str> = ranked
+            .iter()
+            .take(buffered)
+            .filter(|s| incumbents.contains(**s))
+            .take(size)
+            .copied()
+            .collect();
+        let kept: HashSet<&str> = members.iter().copied().collect();
+        let open = size - members.len();
+        members.extend(
+            ranked
+                .iter()
+                .filter(|s| !kept.contains(**s))
+                .take(open)
+                .copied(),
+        );
+        members
+    }
+
+    /// Metrics for every candidate with data before `date`.
+    ///
+    /// The window ends on the session before `date`, so a reconstitution
+    /// effective on `date` only uses data known at that morning's open.
+    /// Candidates with fewer than half the window's sessions cached are
+    /// skipped, which keeps fresh listings out until they have a history.
+    pub fn evaluate(
+        &self,
+        cache: &SqliteCache,
+        date: NaiveDate,
+    ) -> Result<Vec<SecurityMetrics>, UniverseError> {
+        let candidates = if self.candidates.is_empty() {
+            cache.get_quote_symbols()?
+        } else {
+            self.candidates.clone()
+        };
+        let end = NyseCalendar.previous_trading_day(date);
+        let start = NyseCalendar.n_trading_days_back(end, self.lookback_days - 1);
+        let min_sessions = (self.lookback_days as usize).div_ceil(2);
+
+        let mut metrics = Vec::new();
+        for symbol in candidates {
+            let quotes = match cache.get_quotes(&symbol, start, end) {
+                Ok(quotes) => quotes,
+                Err(DataError::MissingData { .. }) => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if quotes.height() < min_sessions {
+                continue;
+            }
+            let market_cap = cache.get_market_cap_on_or_before(&symbol, end)?;
+            if let Some(m) = SecurityMetrics::from_quotes(symbol, &quotes, market_cap)? {
+                metrics.push(m);
+            }
+        }
+        Ok(metrics)
+    }
+
+    /// Build dated memberships over `[start, end]`.
+    ///
+    /// Selections take effect on each reconstitution date and last until the
+    /// session before the next one; the final spells are left open. Sector
+    /// and industry labels come from classifications recorded in the cache.
+    pub fn build(
+        &self,
+        cache: &SqliteCache,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<MembershipHistory, UniverseError> {
+        self.validate()?;
+
+        let mut open: BTreeMap<String, UniverseMembership> = BTreeMap::new();
+        let mut closed = Vec::new();
+        for date in self.reconstitution.dates(start, end) {
+            let incumbents: HashSet<String> = open.keys().cloned().collect();
+            let selected = self.select(&self.evaluate(cache, date)?, &incumbents);
+            let selected_set: HashSet<&String> = selected.iter().collect();
+
+            let leaving: Vec<String> = incumbents
+                .iter()
+                .filter(|s| !selected_set.contains(s))
+                .cloned()
+                .collect();
+            for symbol in leaving {
+                if let Some(mut spell) = open.remove(&symbol) {
+                    spell.end = Some(NyseCalendar.previous_trading_day(date));
+                    closed.push(spell);
+                }
+            }
+
+            for symbol in selected {
+                if open.contains_key(&symbol) {
+                    continue;
+                }
+                let classification = cache.get_classification(&symbol)?;
+                let spell = UniverseMembership {
+                    sector: classification.as_ref().map(|c| c.sector.clone()),
+                    industry: classification.and_then(|c| c.industry),
+                    ..UniverseMembership::new(symbol.clone(), Some(date))
+                };
+                open.insert(symbol, spell);
+            }
+        }
+
+        Ok(MembershipHistory::new(
+            closed.into_iter().chain(open.into_values()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn metrics(symbol: &str, price: f64, dollar_volume: f64) -> SecurityMetrics {
+        SecurityMetrics {
+            symbol: symbol.to_string(),
+            price,
+            median_dollar_volume: dollar_volume,
+            market_cap: None,
+        }
+    }
+
+    #[test]
+    fn test_reconstitution_dates() {
+        assert_eq!(
+            Reconstitution::Quarterly.dates(date(2023, 12, 15), date(2024, 7, 31)),
+            vec![
+                date(2023, 12, 15),
+                date(2024, 1, 2),
+                date(2024, 4, 1),
+                date(2024, 7, 1)
+            ]
+        );
+        // Starting on a weekend moves to the next session.
+        assert_eq!(
+            Reconstitution::Annual.dates(date(2023, 12, 30), date(2024, 12, 31)),
+            vec![date(2024, 1, 2)]
+        );
+        assert_eq!(
+            Reconstitution::Monthly
+                .dates(date(2024, 1, 2), date(2024, 3, 31))
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_select_with_filters() {
+        let rules = UniverseRules::new("Liquid")
+            .with_size(2)
+            .with_min_price(5.0);
+        let metrics = [
+            metrics("AAA", 10.0, 300.0),
+            metrics("BBB", 4.0, 900.0),
+            metrics("CCC", 20.0, 200.0),
+            metrics("DDD", 30.0, 100.0),
+        ];
+
+        // BBB is the most liquid but priced below the floor.
+        assert_eq!(rules.select(&metrics, &HashSet::new()), vec!["AAA", "CCC"]);
+    }
+
+    #[test]
+    fn test_buffer_keeps_incumbents() {
+        let metrics = [
+            metrics("AAA", 10.0, 400.0),
+            metrics("BBB", 10.0, 300.0),
+            metrics("CCC", 10.0, 200.0),
+            metrics("DDD", 10.0, 100.0),
+        ];
+        let incumbents: HashSet<String> = ["AAA".to_string(), "CCC".to_string()].into();
+
+        // Without a buffer CCC (rank 3) drops out for BBB.
+        let rules = UniverseRules::new("Liquid").with_size(2);
+        assert_eq!(rules.select(&metrics, &incumbents), vec!["AAA", "BBB"]);
+
+        // With a 50% buffer incumbents ranked up to 3 stay.
+        let rules = rules.with_buffer(0.5);
+        assert_eq!(rules.select(&metrics, &incumbents), vec!["AAA", "CCC"]);
+
+        // DDD (rank 4) is outside the buffer.
+        let incumbents: HashSet<String> = ["AAA".to_string(), "DDD".to_string()].into();
+        assert_eq!(rules.select(&metrics, &incumbents), vec!["AAA", "BBB"]);
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let rules = UniverseRules::from_toml_str(
+            r#"
+            name = "US Liquid 1000"
+            size = 1000
+            min_price = 5.0
+            buffer = 0.2
+            reconstitution = "monthly"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(rules.size, Some(1000));
+        assert_eq!(rules.rank_by, RankBy::DollarVolume);
+        assert_eq!(rules.lookback_days, 63);
+        assert_eq!(rules.reconstitution, Reconstitution::Monthly);
+
+        assert!(UniverseRules::from_toml_str("size = 0").is_err());
+        assert!(UniverseRules::from_toml_str("sise = 10").is_err());
+    }
+
+    #[test]
+    fn test_build_from_cache() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let sessions: Vec<String> = NyseCalendar
+            .trading_days(date(2024, 1, 2), date(2024, 6, 28))
+            .into_iter()
+            .map(|d| d.to_string())
+            .collect();
+        // AAA and BBB trade steadily; BBB's volume jumps in the second
+        // quarter, overtaking AAA by the July reconstitution.
+        let quotes = |symbol: &str, volume: &dyn Fn(&str) -> u64| {
+            let n = sessions.len();
+            df! {
+                "symbol" => vec![symbol; n],
+                "date" => &sessions,
+                "open" => vec![10.0; n],
+                "high" => vec![10.0; n],
+                "low" => vec![10.0; n],
+                "close" => vec![10.0; n],
+                "volume" => sessions.iter().map(|d| volume(d)).collect::<Vec<_>>(),
+                "adjusted_close" => vec![10.0; n],
+            }
+            .unwrap()
+        };
+        cache.put_quotes(&quotes("AAA", &|_| 1_000)).unwrap();
+        cache
+            .put_quotes(&quotes("BBB", &|d| {
+                if d < "2024-04-01" { 500 } else { 5_000 }
+            }))
+            .unwrap();
+
+        let rules = UniverseRules::new("Top 1").with_size(1);
+        let history = rules
+            .build(&cache, date(2024, 4, 1), date(2024, 7, 31))
+            .unwrap();
+
+        assert_eq!(history.symbols_on(date(2024, 4, 1)), vec!["AAA"]);
+        assert_eq!(history.symbols_on(date(2024, 6, 28)), vec!["AAA"]);
+        assert_eq!(history.symbols_on(date(2024, 7, 1)), vec!["BBB"]);
+        assert_eq!(history.spells("AAA")[0].end, Some(date(2024, 6, 28)));
+    }
+}
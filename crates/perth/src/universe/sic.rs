@@ -0,0 +1,402 @@
+//! Sector classification from SEC Standard Industrial Classification codes.
+//!
+//! Every EDGAR filer carries a 4-digit SIC code in its submissions record.
+//! [`gics_for_sic`] maps SIC codes onto the closest GICS sub-industry, which
+//! is enough to give symbols outside a universe a sector and industry
+//! exposure. The mapping is approximate: SIC describes what an establishment
+//! produces rather than how the market groups the business, so conglomerates
+//! and platform companies can land in a neighbouring industry.
+//!
+//! [`SicClassifier`] looks symbols up on EDGAR and caches the result in the
+//! cache's `universe` table so each filer is fetched once.
+
+use crate::universe::gics::{GicsCode, GicsLevel, GicsSector};
+use crate::universe::sp500::Constituent;
+use perth_data::cache::{SqliteCache, SymbolClassification};
+use perth_data::edgar::{EdgarClient, EdgarCompanyFilings};
+use perth_data::error::DataError;
+
+/// SIC ranges and the GICS sub-industry they map to.
+///
+/// Ranges are inclusive and searched in order, so specific codes precede
+/// the major group that contains them.
+const SIC_TO_GICS: &[(u16, u16, u32)] = &[
+    // Agriculture, forestry and fishing
+    (800, 899, 15105010),
+    (100, 999, 30202010),
+    // Mining
+    (1040, 1049, 15104030),
+    (1000, 1099, 15104020),
+    (1200, 1299, 10102050),
+    (1381, 1381, 10101010),
+    (1382, 1389, 10101020),
+    (1300, 1399, 10102020),
+    (1400, 1499, 15102010),
+    // Construction
+    (1520, 1531, 25201030),
+    (1500, 1799, 20103010),
+    // Food, beverages and tobacco
+    (2082, 2082, 30201010),
+    (2083, 2085, 30201020),
+    (2086, 2087, 30201030),
+    (2000, 2099, 30202030),
+    (2100, 2199, 30203010),
+    // Textiles, apparel, lumber, furniture and paper
+    (2200, 2299, 25203030),
+    (2300, 2399, 25203010),
+    (2451, 2452, 25201030),
+    (2400, 2499, 15105010),
+    (2500, 2599, 25201020),
+    (2650, 2679, 15103020),
+    (2600, 2699, 15105020),
+    // Printing and publishing
+    (2750, 2759, 20201010),
+    (2700, 2799, 50201040),
+    // Chemicals and pharmaceuticals
+    (2836, 2836, 35201010),
+    (2830, 2839, 35202010),
+    (2840, 2843, 30301010),
+    (2844, 2844, 30302010),
+    (2850, 2859, 15101050),
+    (2870, 2879, 15101030),
+    (2890, 2899, 15101050),
+    (2800, 2899, 15101010),
+    (2900, 2999, 10102030),
+    // Rubber, leather, stone, clay and glass
+    (3010, 3011, 25101020),
+    (3000, 3099, 15101050),
+    (3140, 3149, 25203020),
+    (3100, 3199, 25203010),
+    (3220, 3229, 15103010),
+    (3200, 3299, 15102010),
+    // Metals
+    (3310, 3329, 15104050),
+    (3334, 3334, 15104010),
+    (3300, 3399, 15104020),
+    (3410, 3412, 15103010),
+    (3480, 3489, 20101010),
+    (3400, 3499, 20106020),
+    // Machinery and computers
+    (3520, 3524, 20106015),
+    (3530, 3537, 20106010),
+    (3576, 3576, 45201020),
+    (3570, 3579, 45202030),
+    (3500, 3599, 20106020),
+    // Electrical and electronic equipment
+    (3630, 3639, 25201040),
+    (3651, 3651, 25201010),
+    (3652, 3652, 50202010),
+    (3660, 3669, 45201020),
+    (3674, 3674, 45301020),
+    (3670, 3679, 45203015),
+    (3600, 3699, 20104010),
+    // Transportation equipment
+    (3711, 3711, 25102010),
+    (3714, 3714, 25101010),
+    (3720, 3739, 20101010),
+    (3751, 3751, 25102020),
+    (3760, 3769, 20101010),
+    (3700, 3799, 20106010),
+    // Instruments
+    (3812, 3812, 20101010),
+    (3826, 3826, 35203010),
+    (3810, 3829, 45203010),
+    (3842, 3843, 35101020),
+    (3840, 3849, 35101010),
+    (3850, 3851, 35101020),
+    (3860, 3861, 45202030),
+    (3870, 3873, 25203010),
+    (3800, 3899, 45203010),
+    // Miscellaneous manufacturing
+    (3910, 3915, 25203010),
+    (3940, 3949, 25202010),
+    (3900, 3999, 25201050),
+    // Transportation
+    (4000, 4099, 20304010),
+    (4100, 4199, 20304040),
+    (4200, 4299, 20304030),
+    (4400, 4499, 20303010),
+    (4513, 4513, 20301010),
+    (4580, 4581, 20305010),
+    (4500, 4599, 20302010),
+    (4600, 4699, 10102040),
+    (4700, 4729, 25301020),
+    (4700, 4799, 20301010),
+    // Communications
+    (4812, 4812, 50102010),
+    (4822, 4822, 50101010),
+    (4830, 4833, 50201020),
+    (4841, 4841, 50201030),
+    (4800, 4899, 50101020),
+    // Utilities
+    (4922, 4923, 10102040),
+    (4924, 4925, 55102010),
+    (4930, 4939, 55103010),
+    (4941, 4941, 55104010),
+    (4950, 4959, 20201050),
+    (4960, 4961, 55103010),
+    (4991, 4991, 55105010),
+    (4900, 4999, 55101010),
+    // Wholesale trade
+    (5045, 5045, 45203030),
+    (5047, 5047, 35102010),
+    (5065, 5065, 45203030),
+    (5122, 5122, 35102010),
+    (5140, 5149, 30101020),
+    (5170, 5172, 10102030),
+    (5000, 5199, 20107010),
+    // Retail trade
+    (5200, 5299, 25504030),
+    (5311, 5311, 25503030),
+    (5300, 5399, 30101040),
+    (5400, 5499, 30101030),
+    (5500, 5599, 25504050),
+    (5600, 5699, 25504010),
+    (5731, 5734, 25504020),
+    (5700, 5799, 25504060),
+    (5800, 5899, 25301040),
+    (5912, 5912, 30101010),
+    (5961, 5961, 25503030),
+    (5900, 5999, 25504040),
+    // Banking and credit
+    (6021, 6021, 40101010),
+    (6035, 6036, 40201050),
+    (6000, 6099, 40101015),
+    (6160, 6163, 40201050),
+    (6100, 6199, 40202010),
+    (6282, 6282, 40203010),
+    (6200, 6299, 40203020),
+    // Insurance
+    (6324, 6324, 35102030),
+    (6310, 6329, 40301020),
+    (6330, 6359, 40301040),
+    (6300, 6399, 40301030),
+    (6400, 6499, 40301010),
+    // Real estate and holding companies
+    (6531, 6531, 60201040),
+    (6552, 6552, 60201030),
+    (6500, 6599, 60201020),
+    (6798, 6798, 60101010),
+    (6700, 6799, 40201030),
+    // Services
+    (7000, 7099, 25301020),
+    (7200, 7299, 25302020),
+    (7310, 7319, 50201010),
+    (7320, 7323, 20202020),
+    (7340, 7349, 20201050),
+    (7350, 7359, 20107010),
+    (7360, 7369, 20202010),
+    (7370, 7370, 50203010),
+    (7372, 7372, 45103010),
+    (7374, 7374, 20202030),
+    (7370, 7379, 45102010),
+    (7381, 7381, 20201080),
+    (7300, 7399, 20201070),
+    (7500, 7599, 20304040),
+    (7800, 7899, 50202010),
+    (7993, 7993, 25301010),
+    (7900, 7999, 25301030),
+    // Health, legal, education and professional services
+    (8050, 8069, 35102020),
+    (8000, 8099, 35102015),
+    (8100, 8199, 20202020),
+    (8200, 8299, 25302010),
+    (8300, 8399, 25302020),
+    (8600, 8699, 25302020),
+    (8711, 8711, 20103010),
+    (8731, 8731, 35201010),
+    (8700, 8799, 20202020),
+];
+
+/// Map a SIC code to the closest GICS sub-industry.
+///
+/// Returns `None` for public administration and non-classifiable
+/// establishments (`9xxx`) and for codes outside the SIC structure.
+pub fn gics_for_sic(sic: u16) -> Option<GicsCode> {
+    SIC_TO_GICS
+        .iter()
+        .find(|(low, high, _)| (*low..=*high).contains(&sic))
+        .and_then(|(_, _, code)| GicsCode::new(*code))
+}
+
+/// A filer classified from its SIC code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SicClassification {
+    /// Ticker symbol
+    pub symbol: String,
+    /// Company name from EDGAR
+    pub name: Option<String>,
+    /// SIC code, when the classification came from EDGAR
+    pub sic: Option<u16>,
+    /// GICS sub-industry the filer maps to
+    pub sub_industry: GicsCode,
+}
+
+impl SicClassification {
+    /// Classify a filer from its submissions record.
+    pub fn from_filings(symbol: &str, filings: &EdgarCompanyFilings) -> Option<Self> {
+        let sic = filings.sic_code()?;
+        Some(Self {
+            symbol: symbol.to_uppercase(),
+            name: Some(filings.name.clone()),
+            sic: Some(sic),
+            sub_industry: gics_for_sic(sic)?,
+        })
+    }
+
+    /// Rebuild a classification cached in the universe table.
+    ///
+    /// Returns `None` unless the industry label is a GICS sub-industry in the
+    /// cached sector.
+    pub fn from_cached(cached: &SymbolClassification) -> Option<Self> {
+        let sector = cached.sector.parse::<GicsSector>().ok()?;
+        let sub_industry = cached.industry.as_deref()?.parse::<GicsCode>().ok()?;
+        (sub_industry.level() == GicsLevel::SubIndustry && sub_industry.sector() == sector).then(
+            || Self {
+                symbol: cached.symbol.clone(),
+                name: cached.name.clone(),
+                sic: None,
+                sub_industry,
+            },
+        )
+    }
+
+    /// The GICS sector.
+    pub fn sector(&self) -> GicsSector {
+        self.sub_industry.sector()
+    }
+
+    /// The classification as cached in the universe table.
+    pub fn to_cached(&self) -> SymbolClassification {
+        SymbolClassification {
+            symbol: self.symbol.clone(),
+            name: self.name.clone(),
+            sector: self.sector().name().to_string(),
+            industry: Some(self.sub_industry.to_string()),
+        }
+    }
+
+    /// A universe constituent with this classification.
+    pub fn constituent(&self) -> Constituent {
+        Constituent::new(self.symbol.clone(), self.sector())
+            .with_sub_industry(self.sub_industry)
+            .with_industry(self.sub_industry.name())
+    }
+}
+
+/// Classifies symbols from their EDGAR SIC codes, with optional caching.
+#[derive(Debug)]
+pub struct SicClassifier<'a> {
+    client: EdgarClient,
+    cache: Option<&'a SqliteCache>,
+}
+
+impl<'a> SicClassifier<'a> {
+    /// Create a classifier that queries EDGAR through `client`.
+    pub const fn new(client: EdgarClient) -> Self {
+        Self {
+            client,
+            cache: None,
+        }
+    }
+
+    /// Read and store classifications in the cache's universe table.
+    pub const fn with_cache(mut self, cache: &'a SqliteCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Classify a symbol, consulting the cache before EDGAR.
+    ///
+    /// Returns `Ok(None)` when the filer has no SIC code or one that does not
+    /// map to GICS. Symbols unknown to EDGAR are an error.
+    pub async fn classify(&self, symbol: &str) -> Result<Option<SicClassification>, DataError> {
+        let symbol = symbol.to_uppercase();
+
+        if let Some(cache) = self.cache
+            && let Some(cached) = cache.get_classification(&symbol)?
+            && let Some(classification) = SicClassification::from_cached(&cached)
+        {
+            return Ok(Some(classification));
+        }
+
+        let cik = self.client.get_company_cik(&symbol).await?;
+        let filings = self.client.get_company_filings(&cik).await?;
+        let classification = SicClassification::from_filings(&symbol, &filings);
+
+        if let (Some(cache), Some(classification)) = (self.cache, &classification) {
+            cache.put_classification(&classification.to_cached())?;
+        }
+
+        Ok(classification)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gics_for_sic() {
+        let sub_industry = |sic| gics_for_sic(sic).map(|c| c.value());
+
+        // Specific codes win over their major group.
+        assert_eq!(sub_industry(3674), Some(45301020));
+        assert_eq!(sub_industry(3672), Some(45203015));
+        assert_eq!(sub_industry(3571), Some(45202030));
+        assert_eq!(sub_industry(7372), Some(45103010));
+        assert_eq!(sub_industry(6324), Some(35102030));
+        assert_eq!(sub_industry(6021), Some(40101010));
+        assert_eq!(sub_industry(2834), Some(35202010));
+        assert_eq!(sub_industry(1311), Some(10102020));
+        assert_eq!(sub_industry(4911), Some(55101010));
+        assert_eq!(sub_industry(6798), Some(60101010));
+
+        assert_eq!(sub_industry(9995), None);
+        assert_eq!(sub_industry(0), None);
+    }
+
+    #[test]
+    fn test_every_mapping_is_a_sub_industry() {
+        for (low, high, code) in SIC_TO_GICS {
+            assert!(low <= high, "{low}-{high}");
+            let gics = GicsCode::new(*code).unwrap_or_else(|| panic!("{code} is not GICS"));
+            assert_eq!(gics.level(), GicsLevel::SubIndustry);
+        }
+    }
+
+    #[test]
+    fn test_cached_round_trip() {
+        let cache = SqliteCache::in_memory().unwrap();
+        let classification = SicClassification {
+            symbol: "SMCI".to_string(),
+            name: Some("Super Micro Computer, Inc.".to_string()),
+            sic: Some(3571),
+            sub_industry: gics_for_sic(3571).unwrap(),
+        };
+
+        cache
+            .put_classification(&classification.to_cached())
+            .unwrap();
+        let cached = cache.get_classification("SMCI").unwrap().unwrap();
+        assert_eq!(cached.sector, "Information Technology");
+
+        let restored = SicClassification::from_cached(&cached).unwrap();
+        assert_eq!(restored.sub_industry, classification.sub_industry);
+        assert_eq!(restored.sic, None);
+
+        let constituent = restored.constituent();
+        assert_eq!(constituent.sector, GicsSector::InformationTechnology);
+        assert_eq!(
+            constituent.industry.as_deref(),
+            Some("Technology Hardware, Storage & Peripherals")
+        );
+
+        // Free-text industries from other sources are not reused.
+        let free_text = SymbolClassification {
+            industry: Some("Computers".to_string()),
+            ..cached
+        };
+        assert_eq!(SicClassification::from_cached(&free_text), None);
+    }
+}
@@ -1,15 +1,28 @@
 //! S&P 500 universe with GICS sector classifications.
 
-use crate::universe::gics::GicsSector;
+use crate::universe::country::Country;
+use crate::universe::gics::{GicsCode, GicsSector};
+use crate::universe::membership::MembershipHistory;
+use chrono::NaiveDate;
+use perth_data::security::known_ticker_changes;
 use std::collections::HashMap;
 
-/// S&P 500 constituent with GICS sector.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Universe constituent with GICS sector and optional sub-industry,
+/// industry, country and weight.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Constituent {
     /// Stock symbol.
     pub symbol: String,
     /// GICS sector.
     pub sector: GicsSector,
+    /// GICS sub-industry, if known.
+    pub sub_industry: Option<GicsCode>,
+    /// Industry name, if known.
+    pub industry: Option<String>,
+    /// Country of listing, if not implied by the symbol.
+    pub country: Option<Country>,
+    /// Benchmark weight, if the universe is weighted.
+    pub weight: Option<f64>,
 }
 
 impl Constituent {
@@ -18,15 +31,55 @@ impl Constituent {
         Self {
             symbol: symbol.into(),
             sector,
+            sub_industry: None,
+            industry: None,
+            country: None,
+            weight: None,
         }
     }
+
+    /// Set the GICS sub-industry, taking the sector from the code.
+    pub fn with_sub_industry(mut self, sub_industry: GicsCode) -> Self {
+        self.sector = sub_industry.sector();
+        self.sub_industry = Some(sub_industry);
+        self
+    }
+
+    /// Set the industry name.
+    pub fn with_industry(mut self, industry: impl Into<String>) -> Self {
+        self.industry = Some(industry.into());
+        self
+    }
+
+    /// Set the country of listing.
+    pub const fn with_country(mut self, country: Country) -> Self {
+        self.country = Some(country);
+        self
+    }
+
+    /// Country of listing, falling back to the symbol's exchange suffix.
+    pub fn country(&self) -> Country {
+        self.country
+            .unwrap_or_else(|| Country::from_symbol(&self.symbol))
+    }
+
+    /// Set the benchmark weight.
+    pub const fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
 }
 
 /// S&P 500 universe.
+///
+/// The default constituents are today's names. Attach a
+/// [`MembershipHistory`] with [`with_history`](Self::with_history) to get
+/// point-in-time cross-sections instead.
 #[derive(Debug, Clone)]
 pub struct SP500Universe {
     constituents: Vec<Constituent>,
     symbol_to_sector: HashMap<String, GicsSector>,
+    history: Option<MembershipHistory>,
 }
 
 impl SP500Universe {
@@ -41,7 +94,64 @@ impl SP500Universe {
         Self {
             constituents,
             symbol_to_sector,
+            history: None,
+        }
+    }
+
+    /// Use dated index memberships for [`symbols_on`](Self::symbols_on) and
+    /// [`contains_on`](Self::contains_on).
+    ///
+    /// Former members missing from the default list are added when their
+    /// recorded sector label parses as a GICS sector; the rest cannot be
+    /// classified and are left out.
+    pub fn with_history(mut self, history: MembershipHistory) -> Self {
+        for membership in history.memberships() {
+            if self.symbol_to_sector.contains_key(&membership.symbol) {
+                continue;
+            }
+            if let Some(sector) = membership
+                .sector
+                .as_deref()
+                .and_then(|s| s.parse::<GicsSector>().ok())
+            {
+                self.symbol_to_sector
+                    .insert(membership.symbol.clone(), sector);
+                self.constituents
+                    .push(Constituent::new(membership.symbol.clone(), sector));
+            }
         }
+        self.history = Some(history);
+        self
+    }
+
+    /// The membership history, if one is attached.
+    pub const fn history(&self) -> Option<&MembershipHistory> {
+        self.history.as_ref()
+    }
+
+    /// Get the symbols that were index members on `date`.
+    ///
+    /// Without a membership history this is every constituent.
+    pub fn symbols_on(&self, date: NaiveDate) -> Vec<String> {
+        self.history.as_ref().map_or_else(
+            || self.symbols(),
+            |history| {
+                history
+                    .symbols_on(date)
+                    .into_iter()
+                    .filter(|s| self.symbol_to_sector.contains_key(s))
+                    .collect()
+            },
+        )
+    }
+
+    /// Check if a symbol was an index member on `date`.
+    pub fn contains_on(&self, symbol: &str, date: NaiveDate) -> bool {
+        self.symbol_to_sector.contains_key(symbol)
+            && self
+                .history
+                .as_ref()
+                .is_none_or(|h| h.contains_on(symbol, date))
     }
 
     /// Get all constituents.
@@ -55,8 +165,39 @@ impl SP500Universe {
     }
 
     /// Get the GICS sector for a symbol.
+    ///
+    /// Former tickers of a constituent (e.g. `FB` for `META`) resolve to the
+    /// constituent's sector.
     pub fn sector(&self, symbol: &str) -> Option<GicsSector> {
-        self.symbol_to_sector.get(symbol).copied()
+        let symbol = self.current_symbol(symbol)?;
+        self.symbol_to_sector.get(&symbol).copied()
+    }
+
+    /// Get the GICS sub-industry for a symbol, following former tickers.
+    pub fn sub_industry(&self, symbol: &str) -> Option<GicsCode> {
+        let symbol = self.current_symbol(symbol)?;
+        self.constituents
+            .iter()
+            .find(|c| c.symbol == symbol)
+            .and_then(|c| c.sub_industry)
+    }
+
+    /// Resolve a symbol to the ticker the constituent trades under today.
+    ///
+    /// Follows known ticker changes, so `FB` resolves to `META`. Returns
+    /// `None` when the symbol is not (or no longer) a constituent.
+    pub fn current_symbol(&self, symbol: &str) -> Option<String> {
+        let changes = known_ticker_changes();
+        let mut current = symbol.to_string();
+        // Each step moves to a newer ticker, so the chain is bounded.
+        for _ in 0..=changes.len() {
+            if self.symbol_to_sector.contains_key(&current) {
+                return Some(current);
+            }
+            let next = changes.iter().find(|c| c.old_ticker == current)?;
+            current = next.new_ticker.to_string();
+        }
+        None
     }
 
     /// Get all symbols in a specific sector.
@@ -79,139 +220,142 @@ impl SP500Universe {
 
     /// Default S&P 500 constituents (100+ stocks across all 11 GICS sectors).
     fn default_constituents() -> Vec<Constituent> {
-        use GicsSector::*;
+        let stock = |symbol: &str, sub_industry: u32| {
+            let code = GicsCode::new(sub_industry).expect("valid GICS sub-industry");
+            Constituent::new(symbol, code.sector()).with_sub_industry(code)
+        };
 
         vec![
             // Information Technology (45) - 15 stocks
-            Constituent::new("AAPL", InformationTechnology),
-            Constituent::new("MSFT", InformationTechnology),
-            Constituent::new("NVDA", InformationTechnology),
-            Constituent::new("AVGO", InformationTechnology),
-            Constituent::new("ORCL", InformationTechnology),
-            Constituent::new("CSCO", InformationTechnology),
-            Constituent::new("ACN", InformationTechnology),
-            Constituent::new("AMD", InformationTechnology),
-            Constituent::new("IBM", InformationTechnology),
-            Constituent::new("INTC", InformationTechnology),
-            Constituent::new("TXN", InformationTechnology),
-            Constituent::new("QCOM", InformationTechnology),
-            Constituent::new("ADBE", InformationTechnology),
-            Constituent::new("CRM", InformationTechnology),
-            Constituent::new("NOW", InformationTechnology),
+            stock("AAPL", 45202030),
+            stock("MSFT", 45103020),
+            stock("NVDA", 45301020),
+            stock("AVGO", 45301020),
+            stock("ORCL", 45103020),
+            stock("CSCO", 45201020),
+            stock("ACN", 45102010),
+            stock("AMD", 45301020),
+            stock("IBM", 45102010),
+            stock("INTC", 45301020),
+            stock("TXN", 45301020),
+            stock("QCOM", 45301020),
+            stock("ADBE", 45103010),
+            stock("CRM", 45103010),
+            stock("NOW", 45103020),
             // Health Care (35) - 12 stocks
-            Constituent::new("LLY", HealthCare),
-            Constituent::new("UNH", HealthCare),
-            Constituent::new("JNJ", HealthCare),
-            Constituent::new("ABBV", HealthCare),
-            Constituent::new("MRK", HealthCare),
-            Constituent::new("TMO", HealthCare),
-            Constituent::new("ABT", HealthCare),
-            Constituent::new("DHR", HealthCare),
-            Constituent::new("PFE", HealthCare),
-            Constituent::new("BMY", HealthCare),
-            Constituent::new("AMGN", HealthCare),
-            Constituent::new("GILD", HealthCare),
+            stock("LLY", 35202010),
+            stock("UNH", 35102030),
+            stock("JNJ", 35202010),
+            stock("ABBV", 35201010),
+            stock("MRK", 35202010),
+            stock("TMO", 35203010),
+            stock("ABT", 35101010),
+            stock("DHR", 35203010),
+            stock("PFE", 35202010),
+            stock("BMY", 35202010),
+            stock("AMGN", 35201010),
+            stock("GILD", 35201010),
             // Financials (40) - 12 stocks
-            Constituent::new("BRK.B", Financials),
-            Constituent::new("JPM", Financials),
-            Constituent::new("V", Financials),
-            Constituent::new("MA", Financials),
-            Constituent::new("BAC", Financials),
-            Constituent::new("WFC", Financials),
-            Constituent::new("MS", Financials),
-            Constituent::new("GS", Financials),
-            Constituent::new("BLK", Financials),
-            Constituent::new("C", Financials),
-            Constituent::new("AXP", Financials),
-            Constituent::new("SCHW", Financials),
+            stock("BRK.B", 40201030),
+            stock("JPM", 40101010),
+            stock("V", 40201060),
+            stock("MA", 40201060),
+            stock("BAC", 40101010),
+            stock("WFC", 40101010),
+            stock("MS", 40203020),
+            stock("GS", 40203020),
+            stock("BLK", 40203010),
+            stock("C", 40101010),
+            stock("AXP", 40202010),
+            stock("SCHW", 40203020),
             // Consumer Discretionary (25) - 12 stocks
-            Constituent::new("AMZN", ConsumerDiscretionary),
-            Constituent::new("TSLA", ConsumerDiscretionary),
-            Constituent::new("HD", ConsumerDiscretionary),
-            Constituent::new("MCD", ConsumerDiscretionary),
-            Constituent::new("NKE", ConsumerDiscretionary),
-            Constituent::new("SBUX", ConsumerDiscretionary),
-            Constituent::new("LOW", ConsumerDiscretionary),
-            Constituent::new("TJX", ConsumerDiscretionary),
-            Constituent::new("BKNG", ConsumerDiscretionary),
-            Constituent::new("CMG", ConsumerDiscretionary),
-            Constituent::new("F", ConsumerDiscretionary),
-            Constituent::new("GM", ConsumerDiscretionary),
+            stock("AMZN", 25503030),
+            stock("TSLA", 25102010),
+            stock("HD", 25504030),
+            stock("MCD", 25301040),
+            stock("NKE", 25203010),
+            stock("SBUX", 25301040),
+            stock("LOW", 25504030),
+            stock("TJX", 25504010),
+            stock("BKNG", 25301020),
+            stock("CMG", 25301040),
+            stock("F", 25102010),
+            stock("GM", 25102010),
             // Communication Services (50) - 10 stocks
-            Constituent::new("GOOGL", CommunicationServices),
-            Constituent::new("GOOG", CommunicationServices),
-            Constituent::new("META", CommunicationServices),
-            Constituent::new("NFLX", CommunicationServices),
-            Constituent::new("DIS", CommunicationServices),
-            Constituent::new("CMCSA", CommunicationServices),
-            Constituent::new("T", CommunicationServices),
-            Constituent::new("VZ", CommunicationServices),
-            Constituent::new("TMUS", CommunicationServices),
-            Constituent::new("EA", CommunicationServices),
+            stock("GOOGL", 50203010),
+            stock("GOOG", 50203010),
+            stock("META", 50203010),
+            stock("NFLX", 50202010),
+            stock("DIS", 50202010),
+            stock("CMCSA", 50201030),
+            stock("T", 50101020),
+            stock("VZ", 50101020),
+            stock("TMUS", 50102010),
+            stock("EA", 50202020),
             // Industrials (20) - 12 stocks
-            Constituent::new("CAT", Industrials),
-            Constituent::new("UNP", Industrials),
-            Constituent::new("RTX", Industrials),
-            Constituent::new("HON", Industrials),
-            Constituent::new("UPS", Industrials),
-            Constituent::new("BA", Industrials),
-            Constituent::new("DE", Industrials),
-            Constituent::new("LMT", Industrials),
-            Constituent::new("GE", Industrials),
-            Constituent::new("MMM", Industrials),
-            Constituent::new("FDX", Industrials),
-            Constituent::new("NSC", Industrials),
+            stock("CAT", 20106010),
+            stock("UNP", 20304010),
+            stock("RTX", 20101010),
+            stock("HON", 20105010),
+            stock("UPS", 20301010),
+            stock("BA", 20101010),
+            stock("DE", 20106015),
+            stock("LMT", 20101010),
+            stock("GE", 20101010),
+            stock("MMM", 20105010),
+            stock("FDX", 20301010),
+            stock("NSC", 20304010),
             // Consumer Staples (30) - 10 stocks
-            Constituent::new("WMT", ConsumerStaples),
-            Constituent::new("PG", ConsumerStaples),
-            Constituent::new("COST", ConsumerStaples),
-            Constituent::new("KO", ConsumerStaples),
-            Constituent::new("PEP", ConsumerStaples),
-            Constituent::new("PM", ConsumerStaples),
-            Constituent::new("MO", ConsumerStaples),
-            Constituent::new("CL", ConsumerStaples),
-            Constituent::new("MDLZ", ConsumerStaples),
-            Constituent::new("KHC", ConsumerStaples),
+            stock("WMT", 30101040),
+            stock("PG", 30301010),
+            stock("COST", 30101040),
+            stock("KO", 30201030),
+            stock("PEP", 30201030),
+            stock("PM", 30203010),
+            stock("MO", 30203010),
+            stock("CL", 30301010),
+            stock("MDLZ", 30202030),
+            stock("KHC", 30202030),
             // Energy (10) - 10 stocks
-            Constituent::new("XOM", Energy),
-            Constituent::new("CVX", Energy),
-            Constituent::new("COP", Energy),
-            Constituent::new("SLB", Energy),
-            Constituent::new("EOG", Energy),
-            Constituent::new("MPC", Energy),
-            Constituent::new("PSX", Energy),
-            Constituent::new("VLO", Energy),
-            Constituent::new("OXY", Energy),
-            Constituent::new("HAL", Energy),
+            stock("XOM", 10102010),
+            stock("CVX", 10102010),
+            stock("COP", 10102020),
+            stock("SLB", 10101020),
+            stock("EOG", 10102020),
+            stock("MPC", 10102030),
+            stock("PSX", 10102030),
+            stock("VLO", 10102030),
+            stock("OXY", 10102020),
+            stock("HAL", 10101020),
             // Utilities (55) - 8 stocks
-            Constituent::new("NEE", Utilities),
-            Constituent::new("SO", Utilities),
-            Constituent::new("DUK", Utilities),
-            Constituent::new("CEG", Utilities),
-            Constituent::new("AEP", Utilities),
-            Constituent::new("EXC", Utilities),
-            Constituent::new("XEL", Utilities),
-            Constituent::new("D", Utilities),
+            stock("NEE", 55101010),
+            stock("SO", 55101010),
+            stock("DUK", 55101010),
+            stock("CEG", 55101010),
+            stock("AEP", 55101010),
+            stock("EXC", 55101010),
+            stock("XEL", 55101010),
+            stock("D", 55103010),
             // Real Estate (60) - 8 stocks
-            Constituent::new("PLD", RealEstate),
-            Constituent::new("AMT", RealEstate),
-            Constituent::new("EQIX", RealEstate),
-            Constituent::new("CCI", RealEstate),
-            Constituent::new("PSA", RealEstate),
-            Constituent::new("SPG", RealEstate),
-            Constituent::new("O", RealEstate),
-            Constituent::new("WELL", RealEstate),
+            stock("PLD", 60102510),
+            stock("AMT", 60108030),
+            stock("EQIX", 60108050),
+            stock("CCI", 60108030),
+            stock("PSA", 60108020),
+            stock("SPG", 60107010),
+            stock("O", 60107010),
+            stock("WELL", 60105010),
             // Materials (15) - 10 stocks
-            Constituent::new("LIN", Materials),
-            Constituent::new("APD", Materials),
-            Constituent::new("SHW", Materials),
-            Constituent::new("FCX", Materials),
-            Constituent::new("NEM", Materials),
-            Constituent::new("ECL", Materials),
-            Constituent::new("DD", Materials),
-            Constituent::new("DOW", Materials),
-            Constituent::new("PPG", Materials),
-            Constituent::new("NUE", Materials),
+            stock("LIN", 15101040),
+            stock("APD", 15101040),
+            stock("SHW", 15101050),
+            stock("FCX", 15104025),
+            stock("NEM", 15104030),
+            stock("ECL", 15101050),
+            stock("DD", 15101050),
+            stock("DOW", 15101010),
+            stock("PPG", 15101050),
+            stock("NUE", 15104050),
         ]
     }
 }
@@ -260,6 +404,72 @@ mod tests {
         assert_eq!(universe.sector("INVALID"), None);
     }
 
+    #[test]
+    fn test_sub_industries() {
+        let universe = SP500Universe::new();
+
+        assert!(
+            universe
+                .constituents()
+                .iter()
+                .all(|c| c.sub_industry.is_some())
+        );
+        assert_eq!(
+            universe.sub_industry("NVDA").map(|c| c.name()),
+            Some("Semiconductors")
+        );
+        assert_eq!(
+            universe.sub_industry("FB").map(|c| c.value()),
+            Some(50203010)
+        );
+        assert_eq!(universe.sub_industry("INVALID"), None);
+    }
+
+    #[test]
+    fn test_former_tickers_resolve() {
+        let universe = SP500Universe::new();
+
+        assert_eq!(universe.current_symbol("FB").as_deref(), Some("META"));
+        assert_eq!(universe.current_symbol("AAPL").as_deref(), Some("AAPL"));
+        assert_eq!(universe.current_symbol("INVALID"), None);
+        assert_eq!(
+            universe.sector("FB"),
+            Some(GicsSector::CommunicationServices)
+        );
+    }
+
+    #[test]
+    fn test_point_in_time_membership() {
+        use perth_data::cache::UniverseMembership;
+
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let universe = SP500Universe::new().with_history(MembershipHistory::new([
+            UniverseMembership::new("AAPL", None),
+            UniverseMembership {
+                end: Some(date(2021, 12, 31)),
+                sector: Some("Information Technology".to_string()),
+                ..UniverseMembership::new("XLNX", None)
+            },
+            UniverseMembership {
+                sector: Some("Unclassified".to_string()),
+                ..UniverseMembership::new("ZZZZ", None)
+            },
+        ]));
+
+        assert_eq!(universe.symbols_on(date(2021, 6, 1)), vec!["AAPL", "XLNX"]);
+        assert_eq!(universe.symbols_on(date(2022, 6, 1)), vec!["AAPL"]);
+        assert!(universe.contains_on("XLNX", date(2021, 6, 1)));
+        assert!(!universe.contains_on("MSFT", date(2021, 6, 1)));
+        assert_eq!(
+            universe.sector("XLNX"),
+            Some(GicsSector::InformationTechnology)
+        );
+        assert_eq!(universe.sector("ZZZZ"), None);
+
+        // Without history every constituent is always a member.
+        assert!(SP500Universe::new().contains_on("MSFT", date(2000, 1, 3)));
+    }
+
     #[test]
     fn test_symbols_in_sector() {
         let universe = SP500Universe::new();